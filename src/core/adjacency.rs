@@ -0,0 +1,132 @@
+use std::ops::Range;
+use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
+
+use crate::core::units::Length;
+
+/// The mean length of the edges of the graph, or 1.0 if the graph has no edges.
+pub(crate) fn mean_edge_length(graph: &EdgeAttributedUndirectedGraph<Length>) -> Length {
+    let (sum, count) = (0..graph.order())
+        .flat_map(|i| graph.neighbors_of(i).iter())
+        .fold((0.0, 0), |(sum, count), ja| (sum + ja.1, count + 1));
+    if count > 0 {
+        sum / count as f64
+    } else {
+        1.0
+    }
+}
+
+/// A compact adjacency of the sites stored as half-edges in the compressed sparse row layout.
+///
+/// Each undirected edge of the graph is split into two half-edges, one from each end.
+/// The half-edges from a site are contiguous and keep the order of the neighbors in the graph,
+/// so iterating over the neighbors touches a single slice instead of a separate allocation per site.
+/// The twin of each half-edge is the one in the opposite direction.
+#[derive(Debug, Clone, Default)]
+pub struct Adjacency {
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    lengths: Vec<Length>,
+    twins: Vec<usize>,
+}
+
+impl Adjacency {
+    /// Build the adjacency from the graph.
+    pub fn from_graph(graph: &EdgeAttributedUndirectedGraph<Length>) -> Self {
+        let num = graph.order();
+        let mut offsets = Vec::with_capacity(num + 1);
+        let mut targets = Vec::new();
+        let mut lengths = Vec::new();
+        offsets.push(0);
+        (0..num).for_each(|i| {
+            graph.neighbors_of(i).iter().for_each(|ja| {
+                targets.push(ja.0);
+                lengths.push(ja.1);
+            });
+            offsets.push(targets.len());
+        });
+
+        let mut adjacency = Self {
+            offsets,
+            targets,
+            lengths,
+            twins: Vec::new(),
+        };
+        adjacency.twins = (0..num)
+            .flat_map(|i| adjacency.half_edges_of(i).map(move |k| (i, k)))
+            .map(|(i, k)| adjacency.find(adjacency.targets[k], i).unwrap_or(k))
+            .collect();
+        adjacency
+    }
+
+    /// The number of the sites.
+    pub fn num_sites(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    /// The number of the half-edges, twice the number of the edges.
+    pub fn num_half_edges(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// The range of the half-edges from the site.
+    pub fn half_edges_of(&self, i: usize) -> Range<usize> {
+        self.offsets[i]..self.offsets[i + 1]
+    }
+
+    /// The neighbors of the site.
+    pub fn neighbors_of(&self, i: usize) -> &[usize] {
+        &self.targets[self.half_edges_of(i)]
+    }
+
+    /// The lengths of the edges to the neighbors of the site, in the order of [Adjacency::neighbors_of].
+    pub fn lengths_of(&self, i: usize) -> &[Length] {
+        &self.lengths[self.half_edges_of(i)]
+    }
+
+    /// Iterate over the neighbors of the site with the lengths of the edges.
+    pub fn iter_neighbors(&self, i: usize) -> impl Iterator<Item = (usize, Length)> + '_ {
+        self.neighbors_of(i)
+            .iter()
+            .copied()
+            .zip(self.lengths_of(i).iter().copied())
+    }
+
+    /// The mean length of the edges, or 1.0 if there are no edges.
+    pub fn mean_length(&self) -> Length {
+        if self.lengths.is_empty() {
+            1.0
+        } else {
+            self.lengths.iter().sum::<Length>() / self.lengths.len() as f64
+        }
+    }
+
+    /// The site at the end of the half-edge.
+    pub fn target(&self, k: usize) -> usize {
+        self.targets[k]
+    }
+
+    /// The length of the half-edge.
+    pub fn length(&self, k: usize) -> Length {
+        self.lengths[k]
+    }
+
+    /// The half-edge in the opposite direction.
+    pub fn twin(&self, k: usize) -> usize {
+        self.twins[k]
+    }
+
+    /// Find the half-edge from the site `i` to the site `j`.
+    pub fn find(&self, i: usize, j: usize) -> Option<usize> {
+        let range = self.half_edges_of(i);
+        let start = range.start;
+        self.targets[range]
+            .iter()
+            .position(|&target| target == j)
+            .map(|position| start + position)
+    }
+
+    /// The length of the edge between the sites `i` and `j`, if it exists.
+    pub fn length_between(&self, i: usize, j: usize) -> Option<Length> {
+        self.find(i, j).map(|k| self.lengths[k])
+    }
+}
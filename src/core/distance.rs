@@ -0,0 +1,106 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
+
+use crate::core::units::Length;
+
+/// An item of the priority queue ordered by the shortest distance first.
+pub(crate) struct QueueItem(pub(crate) usize, pub(crate) Length);
+
+impl PartialEq for QueueItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.1 == other.1
+    }
+}
+
+impl Eq for QueueItem {}
+
+impl PartialOrd for QueueItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.1.total_cmp(&self.1)
+    }
+}
+
+/// The shortest distances along the edges of the graph from the nearest of a set of seed sites (Dijkstra's algorithm).
+///
+/// The seeds are any set of the sites, such as the coast, the rivers or the roads,
+/// so the field serves the analyses measuring the distance from them and the placement of the features away from (or near) them.
+///
+/// ### Properties
+///  - `distances` is the distance of each site from the nearest seed (unit: L).
+///    This is infinity if the site is unreachable or farther than the maximum distance.
+///  - `nearest_seeds` is the seed nearest to each site, or `None` if the site is not reached.
+///  - `reached` is the reached sites with their distances in the order of the distance, starting from the seeds.
+#[derive(Debug, Clone)]
+pub struct DistanceField {
+    distances: Vec<Length>,
+    nearest_seeds: Vec<Option<usize>>,
+    reached: Vec<(usize, Length)>,
+}
+
+impl DistanceField {
+    /// Calculate the distances from `seeds` up to `max_distance` (use infinity for no limit).
+    ///
+    /// The seeds out of the range of the sites of the graph are ignored, and the repeated seeds are regarded as one.
+    pub fn new(
+        graph: &EdgeAttributedUndirectedGraph<Length>,
+        seeds: &[usize],
+        max_distance: Length,
+    ) -> Self {
+        let num = graph.order();
+        let mut distances = vec![f64::INFINITY; num];
+        let mut nearest_seeds = vec![None; num];
+        let mut reached = Vec::new();
+        let mut queue = BinaryHeap::new();
+        // the repeated seeds are queued only once, so that each site is reached once
+        seeds.iter().filter(|&&seed| seed < num).for_each(|&seed| {
+            if nearest_seeds[seed].is_some() {
+                return;
+            }
+            distances[seed] = 0.0;
+            nearest_seeds[seed] = Some(seed);
+            queue.push(QueueItem(seed, 0.0));
+        });
+        while let Some(QueueItem(i, distance)) = queue.pop() {
+            if distance > distances[i] {
+                continue;
+            }
+            reached.push((i, distance));
+            graph.neighbors_of(i).iter().for_each(|ja| {
+                let next_distance = distance + ja.1;
+                if next_distance <= max_distance && next_distance < distances[ja.0] {
+                    distances[ja.0] = next_distance;
+                    nearest_seeds[ja.0] = nearest_seeds[i];
+                    queue.push(QueueItem(ja.0, next_distance));
+                }
+            });
+        }
+        Self {
+            distances,
+            nearest_seeds,
+            reached,
+        }
+    }
+
+    pub fn distances(&self) -> &[Length] {
+        &self.distances
+    }
+
+    pub fn nearest_seeds(&self) -> &[Option<usize>] {
+        &self.nearest_seeds
+    }
+
+    pub fn reached(&self) -> &[(usize, Length)] {
+        &self.reached
+    }
+
+    /// Take the reached sites with their distances, dropping the rest of the field.
+    pub fn into_reached(self) -> Vec<(usize, Length)> {
+        self.reached
+    }
+}
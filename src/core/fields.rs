@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+/// The name of the field of the thickness of the soil (regolith) layer (unit: L).
+pub const SOIL_THICKNESS: &str = "soil_thickness";
+
+/// The name of the field of the fraction of vegetation cover (0.0 to 1.0).
+pub const VEGETATION_COVER: &str = "vegetation_cover";
+
+/// The name of the field of the factor multiplied to the diffusivity of hillslope processes.
+/// If the field does not exist, the factor is regarded as 1.0.
+pub const DIFFUSIVITY_FACTOR: &str = "diffusivity_factor";
+
+/// The name of the field of the drainage area excluding the flow lost into the subsurface of soluble sites (unit: L^2).
+pub const SURFACE_DRAINAGE_AREA: &str = "surface_drainage_area";
+
+/// The name of the field of the effective discharge after the losses by infiltration and evaporation, as drainage area (unit: L^2).
+pub const DISCHARGE: &str = "discharge";
+
+/// The name of the field of the inflow infiltrated into the ground at each site, as drainage area (unit: L^2).
+pub const INFILTRATION: &str = "infiltration";
+
+/// The name of the field of the groundwater flowing out of each site through the aquifer, as drainage area (unit: L^2).
+pub const GROUNDWATER_FLOW: &str = "groundwater_flow";
+
+/// The name of the field of the groundwater emerging as baseflow at each site, as drainage area (unit: L^2).
+/// The sites with the positive baseflow are the springs.
+pub const BASEFLOW: &str = "baseflow";
+
+/// The name of the field of the flow passing through the subsurface of soluble sites, as drainage area (unit: L^2).
+pub const UNDERGROUND_FLOW: &str = "underground_flow";
+
+/// The name of the field of the flow emerging at springs, as drainage area (unit: L^2). This is 0.0 except at springs.
+pub const SPRING_DISCHARGE: &str = "spring_discharge";
+
+/// The name of the field marking sinkholes with 1.0 (0.0 elsewhere).
+pub const SINKHOLE: &str = "sinkhole";
+
+/// The name of the field marking the sites covered by lava flows with 1.0 (0.0 elsewhere).
+pub const LAVA_FLOW: &str = "lava_flow";
+
+/// The name of the field of the accumulated vertical slip of faults (unit: L).
+pub const FAULT_THROW: &str = "fault_throw";
+
+/// The name of the field of the accumulated thickness of the deposited sediment (unit: L).
+pub const DEPOSIT_THICKNESS: &str = "deposit_thickness";
+
+/// The name of the field of the volume of the sediment carried out of each site in the last iteration (unit: L^3).
+pub const SEDIMENT_FLUX: &str = "sediment_flux";
+
+/// The name of the field marking the active floodplain with 1.0 (0.0 elsewhere).
+pub const FLOODPLAIN: &str = "floodplain";
+
+/// The name of the field of the elevation of each site when it was last a part of the floodplain (unit: L).
+pub const FLOODPLAIN_LEVEL: &str = "floodplain_level";
+
+/// The name of the field of the elevation of the abandoned floodplain on river terraces (unit: L). This is 0.0 except on terraces.
+pub const TERRACE_LEVEL: &str = "terrace_level";
+
+/// The name of the field of the time elapsed since the abandonment of river terraces (unit: T). This is 0.0 except on terraces.
+pub const TERRACE_AGE: &str = "terrace_age";
+
+/// The name of the field of the time elapsed since each site last experienced significant erosion or deposition (unit: T).
+pub const SURFACE_AGE: &str = "surface_age";
+
+/// The name of the field of the elevation of each site when its surface was last renewed, raised by the uplift since then (unit: L).
+pub const SURFACE_LEVEL: &str = "surface_level";
+
+/// The name of the field of the perturbation added to the uplift rate of each site by the stochastic forcing (unit: L/T).
+pub const UPLIFT_PERTURBATION: &str = "uplift_perturbation";
+
+/// The name of the field of the uplift rate added to each site by the convergence of the plates (unit: L/T).
+pub const TECTONIC_UPLIFT: &str = "tectonic_uplift";
+
+/// The name of the field of the index of the plate which each site belongs to.
+pub const PLATE: &str = "plate";
+
+/// The name of the field marking the sites covered by permanent snow and ice with 1.0 (0.0 elsewhere).
+pub const ICE: &str = "ice";
+
+/// The name of the field of the response time of each site, the time for a change of the base level to propagate
+/// from the outlet up to the site along the channels (unit: T).
+pub const RESPONSE_TIME: &str = "response_time";
+
+/// The name of the field of the celerity of each site in the last iteration, the speed at which a knickpoint migrates
+/// upstream through the site along the channel, `K * A^m` (unit: L/T). The knickpoints move from the receiver toward the site,
+/// so the field drives the animations of how the landscape is evolving. This is 0.0 on the outlets and the non-erodible sites.
+pub const CELERITY: &str = "celerity";
+
+/// The name of the field of the channel steepness index `S * A^m` relative to its maximum, from 0.0 to 1.0.
+/// This is 0.0 on the outlets.
+pub const CHANNEL_STEEPNESS: &str = "channel_steepness";
+
+/// The name of the field of the distance from the coast along the edges, where the coast is the sites adjacent to the outlets (unit: L).
+/// This is 0.0 on the outlets.
+pub const COAST_DISTANCE: &str = "coast_distance";
+
+/// The name of the field of the distance from the coast normalized by its maximum, from 0.0 (coast) to 1.0 (the most inland site).
+pub const CONTINENTALITY: &str = "continentality";
+
+/// The name of the field of the relative annual insolation, where 1.0 is the insolation of a flat surface on the equator.
+pub const SOLAR_EXPOSURE: &str = "solar_exposure";
+
+/// The name of the field of the suitability of each site for settlements, from 0.0 (unsuitable) to 1.0.
+pub const SETTLEMENT_SUITABILITY: &str = "settlement_suitability";
+
+/// A set of named per-site values produced by the simulation in addition to the elevations.
+///
+/// Each field has the same length as the number of sites and the indices correspond to the sites.
+#[derive(Debug, Clone, Default)]
+pub struct SiteFields {
+    fields: BTreeMap<String, Vec<f64>>,
+}
+
+impl SiteFields {
+    /// Get the field of the given name.
+    pub fn get(&self, name: &str) -> Option<&[f64]> {
+        self.fields.get(name).map(|field| field.as_slice())
+    }
+
+    /// Get the mutable field of the given name.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Vec<f64>> {
+        self.fields.get_mut(name)
+    }
+
+    /// Get the mutable field of the given name, creating it with `num` zeros if it does not exist.
+    pub fn get_or_insert(&mut self, name: &str, num: usize) -> &mut Vec<f64> {
+        self.fields
+            .entry(name.to_string())
+            .or_insert_with(|| vec![0.0; num])
+    }
+
+    /// Insert a field, replacing the existing one of the same name.
+    pub fn insert(&mut self, name: &str, field: Vec<f64>) {
+        self.fields.insert(name.to_string(), field);
+    }
+
+    /// Iterate over the names of the fields.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.fields.keys().map(|name| name.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
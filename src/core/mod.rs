@@ -1,5 +1,10 @@
 //! Module `core` collects the fundamental objects, traits and type aliases.
 
+pub mod adjacency;
+pub mod distance;
+pub mod fields;
+pub mod network;
 pub mod parameters;
+pub mod scale;
 pub mod traits;
 pub mod units;
@@ -0,0 +1,110 @@
+use super::units::Area;
+
+/// The drainage network of the generated terrain, which is the flow of water in the last iteration of the simulation.
+///
+/// ### Properties
+///  - `receivers` is the next site of each site in the flow. Outlets are their own receivers.
+///  - `drainage_areas` is the drainage area of each site (unit: L^2).
+///  - `order` is the sites in the order from the outlets upstream, where each site comes after its receiver.
+///    The sites on a cycle of the receivers, which the simulation never produces, are not included.
+#[derive(Debug, Clone, Default)]
+pub struct DrainageNetwork {
+    receivers: Vec<usize>,
+    drainage_areas: Vec<Area>,
+    order: Vec<usize>,
+}
+
+impl DrainageNetwork {
+    pub fn new(receivers: Vec<usize>, drainage_areas: Vec<Area>) -> Self {
+        let mut order = Vec::new();
+        upstream_order(&receivers, &mut Vec::new(), &mut Vec::new(), &mut order);
+        Self::from_parts(receivers, drainage_areas, order)
+    }
+
+    /// Create the network with the order already computed, such as the one of the stream tree of the simulation.
+    pub(crate) fn from_parts(
+        receivers: Vec<usize>,
+        drainage_areas: Vec<Area>,
+        order: Vec<usize>,
+    ) -> Self {
+        Self {
+            receivers,
+            drainage_areas,
+            order,
+        }
+    }
+
+    pub fn receivers(&self) -> &[usize] {
+        &self.receivers
+    }
+
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    pub fn drainage_areas(&self) -> &[Area] {
+        &self.drainage_areas
+    }
+
+    /// Whether the site is an outlet.
+    pub fn is_outlet(&self, i: usize) -> bool {
+        self.receivers[i] == i
+    }
+
+    /// The sites draining directly into each site.
+    pub fn donors(&self) -> Vec<Vec<usize>> {
+        let mut donors = vec![Vec::new(); self.receivers.len()];
+        self.receivers.iter().enumerate().for_each(|(i, &j)| {
+            if j != i {
+                donors[j].push(i);
+            }
+        });
+        donors
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.receivers.is_empty()
+    }
+}
+
+/// Compute the order of the sites from the roots of `receivers` upstream into `order`,
+/// where each site comes after its receiver. The sites draining into each site are listed in `donors`
+/// from `donor_starts[i]` to `donor_starts[i + 1]`. The buffers are cleared and reused.
+pub(crate) fn upstream_order(
+    receivers: &[usize],
+    donor_starts: &mut Vec<usize>,
+    donors: &mut Vec<usize>,
+    order: &mut Vec<usize>,
+) {
+    let num = receivers.len();
+
+    // count the donors and take the cumulative sums as the ends of the ranges of the donors
+    donor_starts.clear();
+    donor_starts.resize(num + 1, 0);
+    receivers.iter().enumerate().for_each(|(i, &j)| {
+        if j != i {
+            donor_starts[j] += 1;
+        }
+    });
+    (1..=num).for_each(|i| donor_starts[i] += donor_starts[i - 1]);
+
+    // fill the donors from the ends, leaving the starts of the ranges
+    donors.clear();
+    donors.resize(donor_starts[num], 0);
+    receivers.iter().enumerate().rev().for_each(|(i, &j)| {
+        if j != i {
+            donor_starts[j] -= 1;
+            donors[donor_starts[j]] = i;
+        }
+    });
+
+    // traverse from the roots in the breadth-first order
+    order.clear();
+    order.extend((0..num).filter(|&i| receivers[i] == i));
+    let mut k = 0;
+    while k < order.len() {
+        let i = order[k];
+        order.extend_from_slice(&donors[donor_starts[i]..donor_starts[i + 1]]);
+        k += 1;
+    }
+}
@@ -1,6 +1,11 @@
 use naturalneighbor::Lerpable;
+use std::collections::BTreeMap;
+use thiserror::Error;
 
-use super::units::{Elevation, Erodibility, Slope, UpliftRate};
+use super::{
+    traits::Site,
+    units::{Elevation, Erodibility, Slope, UpliftRate},
+};
 
 /// The topographical parameters of sites.
 /// The shape of the terrain will be determined by these parameters.
@@ -11,6 +16,8 @@ use super::units::{Elevation, Erodibility, Slope, UpliftRate};
 ///
 ///  - `erodibility` is the erodibility.
 ///     This is the main parameter to determine the shape of the terrain.
+///     The value 0.0 makes the site non-erodible: it is not lowered by the flow and acts as a local base level
+///     for the sites upstream of it, rising only by the uplift in the transient mode. Negative values are invalid.
 ///
 ///  - `uplift_rate` is the uplift rate (unit: L/T).
 ///     The default value is 1.0. Configuring this value is still not recommended.
@@ -20,6 +27,43 @@ use super::units::{Elevation, Erodibility, Slope, UpliftRate};
 ///
 ///  - `max_slope` is the maximum slope (unit: rad). This value must be in the range of [0, π/2).
 ///     You can set `None` if you don't want to set the maximum slope.
+///
+///  - `solubility` is the fraction of the inflow from upstream which sinks into the subsurface (karst).
+///    This value must be in the range of [0, 1]. The default value is 0.0 (insoluble).
+///    The water lost underground emerges as a spring at the first insoluble site downstream.
+///    Only the flow remaining on the surface drives the erosion, so the channels over the soluble sites are less incised.
+///    The sites whose solubility is 1.0 swallow all the inflow and form sinkholes,
+///    which drain the surrounding surface as local sinks and are dissolved into closed depressions.
+///
+///  - `infiltration` and `evaporation` are the fractions of the inflow from upstream lost into the ground and into the air.
+///    They must be in the range of [0, 1], and the default values are 0.0 (no loss).
+///    The effective discharge, which drives the erosion, decreases downstream where the losses exceed the local runoff,
+///    so the rivers shrink or disappear in arid basins. The drainage areas are not changed.
+///    The infiltrated water returns to the surface as baseflow if the groundwater is enabled by `TerrainGenerator::set_groundwater_transmissivity`.
+///
+///  - `grain_direction` and `anisotropy_ratio` make the erodibility depend on the direction of the flow (unit: rad for the direction).
+///    The erodibility along an edge at the angle `θ` from the grain is `erodibility * (cos²θ + anisotropy_ratio * sin²θ)`,
+///    so with the ratio less than 1.0, the valleys preferentially align with the structural grain.
+///    The default ratio is 1.0 (isotropic). The direction is measured in the same way as `Site::direction`.
+///
+///  - `min_elevation` is the floor below which the erosion cannot cut (unit: L), such as a resistant basement surface.
+///    The elevation is clamped to it whenever the site is updated by the fluvial erosion, so the floor holds even where
+///    it is steeper than `max_slope`. The outlets are not clamped. You can set `None` if you don't want to set the floor.
+///
+///  - `max_elevation` is the ceiling above which the site cannot rise (unit: L), such as the limit of a skybox.
+///    After the fluvial erosion in each iteration, the volume above the ceiling is spread laterally to the surrounding sites
+///    below their ceilings instead of being simply clipped, so the capped mountains widen rather than have flat tops.
+///    You can set `None` if you don't want to set the ceiling. It must not be lower than `min_elevation`.
+///
+///  - `is_frozen` is whether the elevation of the site is frozen at `base_elevation`, such as a hand-authored set piece.
+///    The frozen sites are never changed by the erosion or the processes, and still pass the flow from upstream,
+///    so they act as the internal boundary conditions around which the surrounding terrain erodes.
+///    The default value is `false`.
+///
+///  - `blend_weight` and `authored_elevation` blend the simulated elevation with the authored one (unit: L for the elevation).
+///    Whenever the site is updated by the fluvial erosion, its elevation is set to
+///    `blend_weight * simulated + (1 - blend_weight) * authored_elevation`, so the drainage upstream is consistent
+///    with the blended surface. The weight is clamped to [0, 1], and the default value is 1.0 (no blending).
 #[derive(Debug, Clone)]
 pub struct TopographicalParameters {
     pub(crate) base_elevation: Elevation,
@@ -27,6 +71,16 @@ pub struct TopographicalParameters {
     pub(crate) uplift_rate: UpliftRate,
     pub(crate) is_outlet: bool,
     pub(crate) max_slope: Option<Slope>,
+    pub(crate) solubility: f64,
+    pub(crate) infiltration: f64,
+    pub(crate) evaporation: f64,
+    pub(crate) grain_direction: f64,
+    pub(crate) anisotropy_ratio: f64,
+    pub(crate) min_elevation: Option<Elevation>,
+    pub(crate) max_elevation: Option<Elevation>,
+    pub(crate) is_frozen: bool,
+    pub(crate) blend_weight: f64,
+    pub(crate) authored_elevation: Elevation,
 }
 
 impl Default for TopographicalParameters {
@@ -37,6 +91,16 @@ impl Default for TopographicalParameters {
             uplift_rate: 1.0,
             is_outlet: false,
             max_slope: None,
+            solubility: 0.0,
+            infiltration: 0.0,
+            evaporation: 0.0,
+            grain_direction: 0.0,
+            anisotropy_ratio: 1.0,
+            min_elevation: None,
+            max_elevation: None,
+            is_frozen: false,
+            blend_weight: 1.0,
+            authored_elevation: 0.0,
         }
     }
 }
@@ -66,6 +130,436 @@ impl TopographicalParameters {
         self.max_slope = max_slope;
         self
     }
+
+    pub fn set_solubility(mut self, solubility: f64) -> Self {
+        self.solubility = solubility.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn set_infiltration(mut self, infiltration: f64) -> Self {
+        self.infiltration = infiltration.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn set_evaporation(mut self, evaporation: f64) -> Self {
+        self.evaporation = evaporation.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Whether a part of the inflow is lost by infiltration or evaporation.
+    pub(crate) fn has_losses(&self) -> bool {
+        self.infiltration > 0.0 || self.evaporation > 0.0
+    }
+
+    pub fn set_anisotropy(mut self, grain_direction: f64, anisotropy_ratio: f64) -> Self {
+        self.grain_direction = grain_direction;
+        self.anisotropy_ratio = anisotropy_ratio.max(f64::EPSILON);
+        self
+    }
+
+    pub fn set_min_elevation(mut self, min_elevation: Option<Elevation>) -> Self {
+        self.min_elevation = min_elevation;
+        self
+    }
+
+    pub fn set_max_elevation(mut self, max_elevation: Option<Elevation>) -> Self {
+        self.max_elevation = max_elevation;
+        self
+    }
+
+    pub fn set_is_frozen(mut self, is_frozen: bool) -> Self {
+        self.is_frozen = is_frozen;
+        self
+    }
+
+    pub fn set_blend(mut self, blend_weight: f64, authored_elevation: Elevation) -> Self {
+        self.blend_weight = blend_weight.clamp(0.0, 1.0);
+        self.authored_elevation = authored_elevation;
+        self
+    }
+
+    /// Whether the simulated elevation is blended with the authored one.
+    pub(crate) fn is_blended(&self) -> bool {
+        self.blend_weight < 1.0
+    }
+
+    /// Whether the erodibility depends on the direction of the flow.
+    pub(crate) fn is_anisotropic(&self) -> bool {
+        self.anisotropy_ratio != 1.0
+    }
+
+    /// The erodibility along an edge in `direction` (unit: rad).
+    pub(crate) fn directional_erodibility(&self, direction: f64) -> Erodibility {
+        let (sin, cos) = (direction - self.grain_direction).sin_cos();
+        self.erodibility * (cos * cos + self.anisotropy_ratio * sin * sin)
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ParameterError {
+    #[error("The site {0} is out of the range of the parameters")]
+    SiteOutOfRange(usize),
+    #[error("The parameters of the site {0} have a non-finite value")]
+    NonFiniteValue(usize),
+    #[error("The erodibility of the site {0} must not be negative or NaN")]
+    InvalidErodibility(usize),
+    #[error("The maximum slope of the site {0} must be in the range of [0, π/2)")]
+    InvalidMaxSlope(usize),
+    #[error("The minimum elevation of the site {0} must not be higher than the maximum elevation")]
+    InvalidElevationBounds(usize),
+}
+
+/// A collection of the topographical parameters of the sites, indexed in the same order as the sites of the model.
+///
+/// This provides the bulk operations on the parameters, such as marking the sites on the boundary as outlets,
+/// and the validation of the parameters before the generation.
+/// It is converted into `Vec<TopographicalParameters>` to be passed to `TerrainGenerator::set_parameters`.
+#[derive(Debug, Clone, Default)]
+pub struct ParameterSet {
+    parameters: Vec<TopographicalParameters>,
+}
+
+impl ParameterSet {
+    /// Create the default parameters of `num` sites.
+    pub fn new(num: usize) -> Self {
+        Self {
+            parameters: vec![TopographicalParameters::default(); num],
+        }
+    }
+
+    /// Create the collection of the parameters of the sites.
+    pub fn from_parameters(parameters: Vec<TopographicalParameters>) -> Self {
+        Self { parameters }
+    }
+
+    /// The number of the sites.
+    pub fn len(&self) -> usize {
+        self.parameters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+
+    /// The parameters of the site.
+    pub fn get(&self, i: usize) -> Option<&TopographicalParameters> {
+        self.parameters.get(i)
+    }
+
+    pub fn parameters(&self) -> &[TopographicalParameters] {
+        &self.parameters
+    }
+
+    /// Take the parameters out of the collection.
+    pub fn into_parameters(self) -> Vec<TopographicalParameters> {
+        self.parameters
+    }
+
+    /// Replace the parameters of the site.
+    pub fn set(
+        mut self,
+        i: usize,
+        parameters: TopographicalParameters,
+    ) -> Result<Self, ParameterError> {
+        *self
+            .parameters
+            .get_mut(i)
+            .ok_or(ParameterError::SiteOutOfRange(i))? = parameters;
+        Ok(self)
+    }
+
+    /// Modify the parameters of all the sites by `f`, which takes the index of the site and its parameters.
+    pub fn map(
+        mut self,
+        f: impl Fn(usize, TopographicalParameters) -> TopographicalParameters,
+    ) -> Self {
+        self.parameters = std::mem::take(&mut self.parameters)
+            .into_iter()
+            .enumerate()
+            .map(|(i, parameters)| f(i, parameters))
+            .collect();
+        self
+    }
+
+    /// Mark the sites as outlets with the base elevation.
+    ///
+    /// The other parameters of the sites are kept. If any of the sites is out of the range, no site is modified.
+    pub fn mark_outlets(
+        mut self,
+        indices: &[usize],
+        base_elevation: Elevation,
+    ) -> Result<Self, ParameterError> {
+        if let Some(&i) = indices.iter().find(|&&i| i >= self.parameters.len()) {
+            return Err(ParameterError::SiteOutOfRange(i));
+        }
+        indices.iter().for_each(|&i| {
+            self.parameters[i].is_outlet = true;
+            self.parameters[i].base_elevation = base_elevation;
+        });
+        Ok(self)
+    }
+
+    /// Unmark the sites as outlets, keeping their base elevations.
+    pub fn unmark_outlets(mut self, indices: &[usize]) -> Result<Self, ParameterError> {
+        if let Some(&i) = indices.iter().find(|&&i| i >= self.parameters.len()) {
+            return Err(ParameterError::SiteOutOfRange(i));
+        }
+        indices
+            .iter()
+            .for_each(|&i| self.parameters[i].is_outlet = false);
+        Ok(self)
+    }
+
+    /// Freeze the sites at their elevations in `elevations`, which is indexed by the sites (e.g. the elevations of a terrain).
+    ///
+    /// The other parameters of the sites are kept. If any of the sites is out of the range of the parameters or
+    /// `elevations`, no site is modified.
+    pub fn freeze(
+        mut self,
+        indices: &[usize],
+        elevations: &[Elevation],
+    ) -> Result<Self, ParameterError> {
+        let len = self.parameters.len().min(elevations.len());
+        if let Some(&i) = indices.iter().find(|&&i| i >= len) {
+            return Err(ParameterError::SiteOutOfRange(i));
+        }
+        indices.iter().for_each(|&i| {
+            self.parameters[i].is_frozen = true;
+            self.parameters[i].base_elevation = elevations[i];
+        });
+        Ok(self)
+    }
+
+    /// The indices of the frozen sites in the ascending order.
+    pub fn frozen(&self) -> Vec<usize> {
+        self.parameters
+            .iter()
+            .enumerate()
+            .filter(|(_, parameters)| parameters.is_frozen)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The indices of the sites marked as outlets in the ascending order.
+    pub fn outlets(&self) -> Vec<usize> {
+        self.parameters
+            .iter()
+            .enumerate()
+            .filter(|(_, parameters)| parameters.is_outlet)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Check the consistency of the parameters, returning the first invalid site.
+    ///
+    /// The values must be finite, the erodibilities must not be negative and the maximum slopes must be in the range of [0, π/2).
+    pub fn validate(&self) -> Result<(), ParameterError> {
+        self.parameters
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, parameters)| {
+                let values = [
+                    parameters.base_elevation,
+                    parameters.erodibility,
+                    parameters.uplift_rate,
+                    parameters.grain_direction,
+                    parameters.anisotropy_ratio,
+                    parameters.blend_weight,
+                    parameters.authored_elevation,
+                ];
+                if values.iter().any(|value| !value.is_finite())
+                    || [parameters.min_elevation, parameters.max_elevation]
+                        .iter()
+                        .flatten()
+                        .any(|value| !value.is_finite())
+                {
+                    return Err(ParameterError::NonFiniteValue(i));
+                }
+                if let (Some(min_elevation), Some(max_elevation)) =
+                    (parameters.min_elevation, parameters.max_elevation)
+                {
+                    if min_elevation > max_elevation {
+                        return Err(ParameterError::InvalidElevationBounds(i));
+                    }
+                }
+                if parameters.erodibility.is_nan() || parameters.erodibility < 0.0 {
+                    return Err(ParameterError::InvalidErodibility(i));
+                }
+                if let Some(max_slope) = parameters.max_slope {
+                    if !(0.0..std::f64::consts::FRAC_PI_2).contains(&max_slope) {
+                        return Err(ParameterError::InvalidMaxSlope(i));
+                    }
+                }
+                Ok(())
+            })
+    }
+}
+
+/// A field of the topographical parameters, evaluated at each site on demand.
+///
+/// Instead of a materialized `Vec<TopographicalParameters>`, the generator can take a field (see `TerrainGenerator::set_parameter_field`),
+/// which computes the parameters of the site from its index and position only when the generation starts.
+/// This keeps the generator and its variations light for the huge models, and the parameters are never held twice.
+/// Any closure `Fn(usize, &S) -> TopographicalParameters` is a field.
+pub trait ParameterField<S: Site>: Send + Sync {
+    /// The parameters of the site of `index` at `site`.
+    fn at(&self, index: usize, site: &S) -> TopographicalParameters;
+}
+
+impl<S, F> ParameterField<S> for F
+where
+    S: Site,
+    F: Fn(usize, &S) -> TopographicalParameters + Send + Sync,
+{
+    fn at(&self, index: usize, site: &S) -> TopographicalParameters {
+        self(index, site)
+    }
+}
+
+impl<S: Site> ParameterField<S> for ParameterSet {
+    /// The parameters of the site of `index`, or the default parameters if it is out of the range.
+    fn at(&self, index: usize, _site: &S) -> TopographicalParameters {
+        self.parameters.get(index).cloned().unwrap_or_default()
+    }
+}
+
+/// The parameters shared by all the sites except a sparse set of overridden sites.
+///
+/// This avoids the allocation of the parameters of every site when most of the domain is homogeneous.
+/// It is a [ParameterField] which can be passed to `TerrainGenerator::set_parameter_field`.
+///
+/// ### Properties
+///  - `default` is the parameters of the sites without the overrides. The default value is [TopographicalParameters::default].
+///  - `overrides` is the parameters of the overridden sites keyed by their indices.
+#[derive(Debug, Clone, Default)]
+pub struct SparseParameters {
+    default: TopographicalParameters,
+    overrides: BTreeMap<usize, TopographicalParameters>,
+}
+
+impl SparseParameters {
+    /// Create the parameters shared by all the sites.
+    pub fn new(default: TopographicalParameters) -> Self {
+        Self {
+            default,
+            overrides: BTreeMap::new(),
+        }
+    }
+
+    pub fn set_default(mut self, default: TopographicalParameters) -> Self {
+        self.default = default;
+        self
+    }
+
+    /// Override the parameters of the site, replacing the existing override.
+    pub fn set_override(mut self, i: usize, parameters: TopographicalParameters) -> Self {
+        self.overrides.insert(i, parameters);
+        self
+    }
+
+    /// Override the parameters of the sites, replacing the existing overrides.
+    pub fn set_overrides(
+        mut self,
+        overrides: impl IntoIterator<Item = (usize, TopographicalParameters)>,
+    ) -> Self {
+        self.overrides.extend(overrides);
+        self
+    }
+
+    /// Remove the override of the site, so that it takes the default parameters again.
+    pub fn remove_override(mut self, i: usize) -> Self {
+        self.overrides.remove(&i);
+        self
+    }
+
+    /// Mark the sites as outlets with the base elevation, overriding the default parameters of the sites.
+    ///
+    /// The other parameters of the overridden sites are kept.
+    pub fn mark_outlets(mut self, indices: &[usize], base_elevation: Elevation) -> Self {
+        indices.iter().for_each(|&i| {
+            let parameters = self
+                .overrides
+                .entry(i)
+                .or_insert_with(|| self.default.clone());
+            parameters.is_outlet = true;
+            parameters.base_elevation = base_elevation;
+        });
+        self
+    }
+
+    pub fn default_parameters(&self) -> &TopographicalParameters {
+        &self.default
+    }
+
+    /// The number of the overridden sites.
+    pub fn num_overrides(&self) -> usize {
+        self.overrides.len()
+    }
+
+    /// The parameters of the site.
+    pub fn get(&self, i: usize) -> &TopographicalParameters {
+        self.overrides.get(&i).unwrap_or(&self.default)
+    }
+
+    /// Materialize the parameters of `num` sites, ignoring the overrides out of the range.
+    pub fn to_parameter_set(&self, num: usize) -> ParameterSet {
+        ParameterSet::from_parameters((0..num).map(|i| self.get(i).clone()).collect())
+    }
+}
+
+impl<S: Site> ParameterField<S> for SparseParameters {
+    fn at(&self, index: usize, _site: &S) -> TopographicalParameters {
+        self.get(index).clone()
+    }
+}
+
+impl From<ParameterSet> for Vec<TopographicalParameters> {
+    fn from(parameters: ParameterSet) -> Self {
+        parameters.into_parameters()
+    }
+}
+
+impl From<Vec<TopographicalParameters>> for ParameterSet {
+    fn from(parameters: Vec<TopographicalParameters>) -> Self {
+        Self::from_parameters(parameters)
+    }
+}
+
+/// The parameters of an edge between two sites, modifying the flow along the edge.
+///
+/// This represents the linear geological features which per-site parameters cannot represent,
+/// such as a fault gouge with a high erodibility or a resistant dike crossed by the flow.
+/// The parameters apply to the flow in both directions along the edge.
+///
+/// ### Properties
+///  - `erodibility_factor` is the factor multiplied to the erodibility of the flow along the edge. The default value is 1.0.
+///  - `distance_factor` is the factor multiplied to the distance along the edge when calculating the response time,
+///    so a larger factor makes the flow along the edge slower. The default value is 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeParameters {
+    pub(crate) erodibility_factor: f64,
+    pub(crate) distance_factor: f64,
+}
+
+impl Default for EdgeParameters {
+    fn default() -> Self {
+        Self {
+            erodibility_factor: 1.0,
+            distance_factor: 1.0,
+        }
+    }
+}
+
+impl EdgeParameters {
+    pub fn set_erodibility_factor(mut self, erodibility_factor: f64) -> Self {
+        self.erodibility_factor = erodibility_factor.max(f64::EPSILON);
+        self
+    }
+
+    pub fn set_distance_factor(mut self, distance_factor: f64) -> Self {
+        self.distance_factor = distance_factor.max(f64::EPSILON);
+        self
+    }
 }
 
 impl Lerpable for TopographicalParameters {
@@ -74,6 +568,10 @@ impl Lerpable for TopographicalParameters {
         let uplift_rate = self.uplift_rate * (1.0 - prop) + other.uplift_rate * prop;
         let erodibility = self.erodibility * (1.0 - prop) + other.erodibility * prop;
         let is_outlet = self.is_outlet || other.is_outlet;
+        let is_frozen = self.is_frozen || other.is_frozen;
+        let blend_weight = self.blend_weight * (1.0 - prop) + other.blend_weight * prop;
+        let authored_elevation =
+            self.authored_elevation * (1.0 - prop) + other.authored_elevation * prop;
         let max_slope = if let (Some(self_max_slope), Some(other_max_slope)) =
             (self.max_slope, other.max_slope)
         {
@@ -83,12 +581,50 @@ impl Lerpable for TopographicalParameters {
         } else {
             other.max_slope
         };
+        let solubility = self.solubility * (1.0 - prop) + other.solubility * prop;
+        let infiltration = self.infiltration * (1.0 - prop) + other.infiltration * prop;
+        let evaporation = self.evaporation * (1.0 - prop) + other.evaporation * prop;
+        let anisotropy_ratio = self.anisotropy_ratio * (1.0 - prop) + other.anisotropy_ratio * prop;
+        // the direction is axial, so it is taken from the nearer one instead of being interpolated
+        let grain_direction = if prop < 0.5 {
+            self.grain_direction
+        } else {
+            other.grain_direction
+        };
+        let min_elevation = if let (Some(self_min_elevation), Some(other_min_elevation)) =
+            (self.min_elevation, other.min_elevation)
+        {
+            Some(self_min_elevation * (1.0 - prop) + other_min_elevation * prop)
+        } else if prop < 0.5 {
+            self.min_elevation
+        } else {
+            other.min_elevation
+        };
+        let max_elevation = if let (Some(self_max_elevation), Some(other_max_elevation)) =
+            (self.max_elevation, other.max_elevation)
+        {
+            Some(self_max_elevation * (1.0 - prop) + other_max_elevation * prop)
+        } else if prop < 0.5 {
+            self.max_elevation
+        } else {
+            other.max_elevation
+        };
         TopographicalParameters {
             base_elevation,
             uplift_rate,
             erodibility,
             is_outlet,
             max_slope,
+            solubility,
+            infiltration,
+            evaporation,
+            grain_direction,
+            anisotropy_ratio,
+            min_elevation,
+            max_elevation,
+            is_frozen,
+            blend_weight,
+            authored_elevation,
         }
     }
 }
@@ -0,0 +1,68 @@
+use super::units::Elevation;
+
+/// The unit of the exported elevations.
+///
+/// ### Variants
+///  - `Arbitrary` keeps the elevations in the unit of the model (L).
+///  - `Meters` converts the elevations into meters, with the number of meters per unit length of the model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerticalUnit {
+    Arbitrary,
+    Meters(f64),
+}
+
+/// The conversion of the elevations from the model into the exported meshes and rasters.
+///
+/// The exported elevation is `elevation * meters per unit * exaggeration`. The horizontal coordinates are not converted,
+/// so `Meters` should be used with the sites placed in meters, or with the exaggeration compensating for the horizontal unit.
+///
+/// ### Properties
+///  - `unit` is the unit of the exported elevations (see [VerticalUnit]). The default value is `VerticalUnit::Arbitrary`.
+///  - `exaggeration` is the factor multiplied to the exported elevations. The default value is 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VerticalScale {
+    unit: VerticalUnit,
+    exaggeration: f64,
+}
+
+impl Default for VerticalScale {
+    fn default() -> Self {
+        Self {
+            unit: VerticalUnit::Arbitrary,
+            exaggeration: 1.0,
+        }
+    }
+}
+
+impl VerticalScale {
+    pub fn set_unit(mut self, unit: VerticalUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn set_exaggeration(mut self, exaggeration: f64) -> Self {
+        self.exaggeration = exaggeration;
+        self
+    }
+
+    pub fn unit(&self) -> VerticalUnit {
+        self.unit
+    }
+
+    pub fn exaggeration(&self) -> f64 {
+        self.exaggeration
+    }
+
+    /// The factor converting the elevations of the model into the exported elevations.
+    pub fn factor(&self) -> f64 {
+        match self.unit {
+            VerticalUnit::Arbitrary => self.exaggeration,
+            VerticalUnit::Meters(meters_per_unit) => meters_per_unit * self.exaggeration,
+        }
+    }
+
+    /// Convert an elevation of the model into the exported elevation.
+    pub fn apply(&self, elevation: Elevation) -> f64 {
+        elevation * self.factor()
+    }
+}
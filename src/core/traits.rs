@@ -1,6 +1,10 @@
 use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
 
-use super::units::{Area, Elevation, Length};
+use super::{
+    fields::SiteFields,
+    network::DrainageNetwork,
+    units::{Area, Elevation, Length},
+};
 
 pub trait Site: Copy + Clone + Default {
     /// Calculate the distance between two sites.
@@ -8,6 +12,12 @@ pub trait Site: Copy + Clone + Default {
 
     /// Calculate the squared distance between two sites.
     fn squared_distance(&self, other: &Self) -> Length;
+
+    /// Calculate the direction from the site to another site (unit: rad).
+    /// If the sites have no notion of direction, this returns `None` and the anisotropy of the erodibility is ignored.
+    fn direction(&self, _other: &Self) -> Option<f64> {
+        None
+    }
 }
 
 pub trait Model<S: Site, T> {
@@ -17,4 +27,15 @@ pub trait Model<S: Site, T> {
     fn default_outlets(&self) -> &[usize];
     fn graph(&self) -> &EdgeAttributedUndirectedGraph<Length>;
     fn create_terrain_from_result(&self, elevation: &[Elevation]) -> T;
+
+    /// Create the terrain with the additional fields and the drainage network produced by the simulation.
+    /// By default, they are discarded.
+    fn create_terrain_from_output(
+        &self,
+        elevation: &[Elevation],
+        _fields: &SiteFields,
+        _network: &DrainageNetwork,
+    ) -> T {
+        self.create_terrain_from_result(elevation)
+    }
 }
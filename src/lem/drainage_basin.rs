@@ -1,11 +1,16 @@
-use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
+use std::ops::Range;
 
-use crate::{core::units::Length, lem::stream_tree};
+use crate::{core::adjacency::Adjacency, lem::stream_tree};
 
 /// Represents the drainage basin.
 /// This enables to iterate over the sites in the drainage basin with no duplication.
+///
+/// The sites are indexed locally in the order of the traversal, so that the basin can be processed in isolation.
+/// The traversal is breadth-first, so the donors of each site are contiguous, and so are the sites at the same depth.
 pub struct DrainageBasin {
     traversal: Vec<usize>,
+    receivers: Vec<usize>,
+    donor_starts: Vec<usize>,
 }
 
 impl DrainageBasin {
@@ -13,17 +18,21 @@ impl DrainageBasin {
     pub fn construct(
         outlet: usize,
         stream_tree: &stream_tree::StreamTree,
-        graph: &EdgeAttributedUndirectedGraph<Length>,
+        adjacency: &Adjacency,
     ) -> Self {
         let mut traversal: Vec<usize> = Vec::new();
+        let mut receivers: Vec<usize> = Vec::new();
+        let mut donor_starts: Vec<usize> = Vec::new();
         traversal.push(outlet);
+        receivers.push(0);
         let mut i = 0;
         loop {
             let it = traversal[i];
-            graph.neighbors_of(it).iter().for_each(|ja| {
-                let jt = ja.0;
+            donor_starts.push(traversal.len());
+            adjacency.neighbors_of(it).iter().for_each(|&jt| {
                 if stream_tree.next[jt] == it {
                     traversal.push(jt);
+                    receivers.push(i);
                 }
             });
             i += 1;
@@ -31,17 +40,87 @@ impl DrainageBasin {
                 break;
             }
         }
+        donor_starts.push(traversal.len());
 
-        Self { traversal }
+        Self {
+            traversal,
+            receivers,
+            donor_starts,
+        }
+    }
+
+    /// The number of the sites in the drainage basin.
+    pub fn len(&self) -> usize {
+        self.traversal.len()
+    }
+
+    /// The site of the local index.
+    pub fn site(&self, k: usize) -> usize {
+        self.traversal[k]
+    }
+
+    /// The local index of the receiver of the local index. The outlet is its own receiver.
+    pub fn receiver(&self, k: usize) -> usize {
+        self.receivers[k]
+    }
+
+    /// Iterates over the local indices and the sites in the drainage basin from the outlet to the upstream.
+    pub fn for_each_upstream(&self, mut f: impl FnMut(usize, usize)) {
+        self.traversal
+            .iter()
+            .enumerate()
+            .for_each(|(k, i)| f(k, *i));
     }
 
-    /// Iterates over the sites in the drainage basin from the outlet to the upstream.
-    pub fn for_each_upstream(&self, mut f: impl FnMut(usize)) {
-        self.traversal.iter().for_each(|i| f(*i));
+    /// Iterates over the local indices and the sites in the drainage basin from the top of the stream to the downstream.
+    pub fn for_each_downstream(&self, mut f: impl FnMut(usize, usize)) {
+        self.traversal
+            .iter()
+            .enumerate()
+            .rev()
+            .for_each(|(k, i)| f(k, *i));
     }
 
-    /// Iterates over the sites in the drainage basin from the top of the stream to the downstream.
-    pub fn for_each_downstream(&self, mut f: impl FnMut(usize)) {
-        self.traversal.iter().rev().for_each(|i| f(*i));
+    /// Accumulate the values of the sites from the top of the stream to the downstream, indexed locally.
+    ///
+    /// Each site receives the sum of the accumulated values of its donors, added in the same order as
+    /// [DrainageBasin::for_each_downstream] would do, so the result does not depend on `num_threads`.
+    /// The sites at the same depth are independent, so the depths with at least `min_parallel_sites` sites
+    /// are processed by `num_threads` threads.
+    pub fn accumulate(&self, values: &mut [f64], num_threads: usize, min_parallel_sites: usize) {
+        // the ranges of the local indices at each depth
+        let mut depths: Vec<Range<usize>> = Vec::new();
+        depths.push(0..1);
+        while let Some(last) = depths.last().filter(|last| !last.is_empty()).cloned() {
+            depths.push(self.donor_starts[last.start]..self.donor_starts[last.end]);
+        }
+
+        depths.iter().rev().for_each(|depth| {
+            let (head, donors) = values.split_at_mut(depth.end);
+            let offset = depth.end;
+            let accumulate = |start: usize, chunk: &mut [f64]| {
+                chunk.iter_mut().enumerate().for_each(|(o, value)| {
+                    let k = start + o;
+                    (self.donor_starts[k]..self.donor_starts[k + 1])
+                        .rev()
+                        .for_each(|d| *value += donors[d - offset]);
+                });
+            };
+            let sites = &mut head[depth.start..];
+            if num_threads > 1 && sites.len() >= min_parallel_sites.max(1) {
+                let chunk_size = sites.len().div_ceil(num_threads);
+                std::thread::scope(|scope| {
+                    sites
+                        .chunks_mut(chunk_size)
+                        .enumerate()
+                        .for_each(|(c, chunk)| {
+                            let accumulate = &accumulate;
+                            scope.spawn(move || accumulate(depth.start + c * chunk_size, chunk));
+                        });
+                });
+            } else {
+                accumulate(depth.start, sites);
+            }
+        });
     }
 }
@@ -0,0 +1,35 @@
+use crate::{
+    core::units::{Elevation, Step},
+    lem::progress::GenerationProgress,
+};
+
+/// An event emitted while the simulation is running.
+///
+/// Events are emitted in the order below for each iteration:
+/// `StreamCaptured` (for each captured site), `StepCompleted`, `ConvergenceMetric` and `SnapshotReady` (if configured).
+/// `Finished` is emitted once at the end of the simulation.
+#[derive(Debug, Clone)]
+pub enum SimulationEvent {
+    /// An iteration is completed.
+    StepCompleted(GenerationProgress),
+    /// The maximum absolute change of elevations in the iteration.
+    /// The simulation converges as this approaches 0.
+    ConvergenceMetric {
+        step: Step,
+        max_elevation_change: Elevation,
+    },
+    /// A copy of the elevations, emitted at the interval set by `set_snapshot_interval`.
+    SnapshotReady {
+        step: Step,
+        elevations: Vec<Elevation>,
+    },
+    /// The flow from `site` was diverted into the drainage basin of `new_outlet` (stream capture).
+    StreamCaptured {
+        step: Step,
+        site: usize,
+        previous_outlet: usize,
+        new_outlet: usize,
+    },
+    /// The simulation is finished after `step` iterations.
+    Finished { step: Step },
+}
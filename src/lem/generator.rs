@@ -1,19 +1,40 @@
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use std::marker::PhantomData;
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    sync::{mpsc, Arc},
+};
+use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
 use thiserror::Error;
 
 use crate::{
     core::{
-        parameters::TopographicalParameters,
+        adjacency::mean_edge_length,
+        parameters::{EdgeParameters, ParameterField, TopographicalParameters},
         traits::{Model, Site},
-        units::{Length, Step},
+        units::{Elevation, Length, Step},
     },
-    lem::drainage_basin::DrainageBasin,
-    lem::stream_tree,
+    lem::events::SimulationEvent,
+    lem::manifest::{GenerationManifest, ManifestError},
+    lem::process::{Process, SimulationState},
+    lem::processes::diffusion::HillslopeDiffusionProcess,
+    lem::progress::{GenerationProgress, GenerationTask},
+    lem::record::{digest_model, digest_parameters, SimulationRecord},
+    lem::simulation::{simulate, EdgeParameterMap, SimulationConfig},
+    lem::sweep::MorphometricSummary,
 };
 
-/// The default value of the exponent `m` for calculating stream power.
-const DEFAULT_M_EXP: f64 = 0.5;
+#[cfg(feature = "mmap")]
+use std::path::PathBuf;
+
+/// The number of iterations of the aging per unit intensity.
+const AGING_STEPS_PER_INTENSITY: f64 = 20.0;
+
+/// The ratio of the erodibility during the aging to the original one, so that the diffusion dominates.
+const AGING_ERODIBILITY_RATIO: f64 = 0.1;
+
+/// The diffusivity during the aging relative to the typical area of the sites (unit: 1/T).
+const AGING_DIFFUSIVITY_RATE: f64 = 0.1;
 
 #[derive(Error, Debug)]
 pub enum GenerationError {
@@ -23,6 +44,197 @@ pub enum GenerationError {
     ParametersNotSet,
     #[error("You must set `TerrainModel` before generating terrain")]
     ModelNotSet,
+    #[error("The generation task was aborted before producing a result")]
+    TaskAborted,
+    #[error("Invariant violated at site {site} in iteration {step}: {reason}")]
+    InvariantViolated {
+        step: Step,
+        site: usize,
+        reason: &'static str,
+    },
+    #[error("The number of elevations must be equal to the number of sites")]
+    InvalidNumberOfElevations,
+    #[error("The number of receivers must be equal to the number of sites")]
+    InvalidNumberOfReceivers,
+    #[error("The number of areas must be equal to the number of sites")]
+    InvalidNumberOfAreas,
+    #[error("The edge between sites {0} and {1} does not exist in the model")]
+    InvalidEdge(usize, usize),
+    #[error("The site {0} is not connected to its receiver {1} by an edge")]
+    MissingEdge(usize, usize),
+    #[error("The erodibility of the site {0} must not be negative or NaN")]
+    InvalidErodibility(usize),
+    #[cfg(feature = "mmap")]
+    #[error("Failed to access the storage of the elevations: {0}")]
+    Storage(#[from] std::io::Error),
+}
+
+/// The distance between a site and its receiver used when they are not connected by an edge of the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FallbackDistance {
+    /// The mean length of the edges of the graph, so that the fallback follows the scale of the domain.
+    #[default]
+    MeanEdgeLength,
+    /// A fixed distance (unit: L).
+    Fixed(Length),
+    /// A receiver not connected by an edge is an error ([GenerationError::MissingEdge]).
+    Strict,
+}
+
+impl FallbackDistance {
+    /// The fallback distance in the graph. The mean length of the edges is used in the strict mode,
+    /// where the missing edges of the receivers are rejected before the distance is needed.
+    pub(crate) fn resolve(&self, graph: &EdgeAttributedUndirectedGraph<Length>) -> Length {
+        self.resolve_with(|| mean_edge_length(graph))
+    }
+
+    /// Resolve the distance with the mean length of the edges, which is computed only if needed.
+    pub(crate) fn resolve_with(&self, mean_edge_length: impl FnOnce() -> Length) -> Length {
+        match self {
+            Self::Fixed(distance) => *distance,
+            Self::MeanEdgeLength | Self::Strict => mean_edge_length(),
+        }
+    }
+}
+
+/// The distribution of the random perturbation of the initial elevations (see [Jitter]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JitterDistribution {
+    /// The uniform distribution from 0.0 to the amplitude.
+    #[default]
+    Uniform,
+    /// The normal distribution with the mean 0.0 and the standard deviation of the amplitude.
+    Normal,
+}
+
+/// The random perturbation added to the initial elevations of the sites which are not frozen.
+///
+/// The perturbation breaks the ties of the base elevations, so that the flow over the flat regions has definite directions.
+/// The default amplitude `f64::EPSILON` leaves the elevations practically unchanged, but it is lost by the rounding
+/// when the base elevations are far from 0.0 (e.g. a plateau at 1000.0), leaving artificial flat regions with degenerate flow.
+/// A larger amplitude keeps the ties broken on such regions.
+///
+/// If `correlation_length` is set, the perturbation is smoothed over the graph by averaging each site with its neighbors
+/// `(correlation_length / mean edge length)^2` times (at most 4096 times), and then rescaled to the mean and the standard deviation
+/// of the unsmoothed perturbation. The smooth undulation gathers the flow over the flat regions into longer channels instead of scattering it.
+///
+/// The perturbation is drawn from the seed of the generator, so the same seed always produces the same perturbation.
+///
+/// ### Properties
+///  - `amplitude` is the scale of the perturbation (unit: L). The default value is `f64::EPSILON`.
+///  - `distribution` is the distribution of the perturbation (see [JitterDistribution]). The default value is `JitterDistribution::Uniform`.
+///  - `correlation_length` is the distance over which the perturbation is correlated (unit: L). If not set, the perturbation of each site is independent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Jitter {
+    amplitude: Elevation,
+    distribution: JitterDistribution,
+    correlation_length: Option<Length>,
+}
+
+impl Default for Jitter {
+    fn default() -> Self {
+        Self {
+            amplitude: f64::EPSILON,
+            distribution: JitterDistribution::default(),
+            correlation_length: None,
+        }
+    }
+}
+
+impl Jitter {
+    pub fn set_amplitude(mut self, amplitude: Elevation) -> Self {
+        self.amplitude = amplitude.max(0.0);
+        self
+    }
+
+    pub fn set_distribution(mut self, distribution: JitterDistribution) -> Self {
+        self.distribution = distribution;
+        self
+    }
+
+    pub fn set_correlation_length(mut self, correlation_length: Option<Length>) -> Self {
+        self.correlation_length = correlation_length.filter(|&length| length > 0.0);
+        self
+    }
+
+    pub fn amplitude(&self) -> Elevation {
+        self.amplitude
+    }
+
+    pub fn distribution(&self) -> JitterDistribution {
+        self.distribution
+    }
+
+    pub fn correlation_length(&self) -> Option<Length> {
+        self.correlation_length
+    }
+
+    /// The perturbation of each site of the graph drawn from the seed.
+    pub(crate) fn sample(
+        &self,
+        graph: &EdgeAttributedUndirectedGraph<Length>,
+        seed: u64,
+    ) -> Vec<Elevation> {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+        let noise = (0..graph.order())
+            .map(|_| match self.distribution {
+                JitterDistribution::Uniform => rng.gen::<f64>() * self.amplitude,
+                JitterDistribution::Normal => {
+                    // the Box-Muller transform, avoiding the logarithm of 0.0
+                    let u = 1.0 - rng.gen::<f64>();
+                    let v = rng.gen::<f64>();
+                    (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos() * self.amplitude
+                }
+            })
+            .collect::<Vec<_>>();
+        match self.correlation_length {
+            Some(correlation_length) => correlate(graph, noise, correlation_length),
+            None => noise,
+        }
+    }
+}
+
+/// The maximum number of the rounds of the smoothing of the correlated perturbation.
+const MAX_JITTER_SMOOTHING_ROUNDS: usize = 4096;
+
+/// Smooth the perturbation over the correlation length, keeping its mean and standard deviation.
+fn correlate(
+    graph: &EdgeAttributedUndirectedGraph<Length>,
+    noise: Vec<Elevation>,
+    correlation_length: Length,
+) -> Vec<Elevation> {
+    let statistics = |values: &[Elevation]| {
+        let num = values.len().max(1) as f64;
+        let mean = values.iter().sum::<f64>() / num;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / num;
+        (mean, variance.sqrt())
+    };
+    let (mean, deviation) = statistics(&noise);
+    let rounds = ((correlation_length / mean_edge_length(graph))
+        .powi(2)
+        .ceil() as usize)
+        .min(MAX_JITTER_SMOOTHING_ROUNDS);
+    let smoothed = (0..rounds).fold(noise, |values, _| {
+        (0..values.len())
+            .map(|i| {
+                let neighbors = graph.neighbors_of(i);
+                let sum = neighbors.iter().map(|ja| values[ja.0]).sum::<f64>() + values[i];
+                sum / (neighbors.len() + 1) as f64
+            })
+            .collect()
+    });
+    let (smoothed_mean, smoothed_deviation) = statistics(&smoothed);
+    if smoothed_deviation > 0.0 {
+        smoothed
+            .iter()
+            .map(|v| (v - smoothed_mean) / smoothed_deviation * deviation + mean)
+            .collect()
+    } else {
+        smoothed
+    }
 }
 
 /// Provides methods for generating terrain.
@@ -30,8 +242,25 @@ pub enum GenerationError {
 /// ### Required properties
 ///  - `model` is the vector representation of the terrain network.
 ///  - `parameters` is the topographical parameters of sites. Each parameter contains the uplift rates, erodibilities, base elevations and maximum slopes (see [TopographicalParameters] for details).
+///     Instead, the parameters can be given as a field evaluated at each site (see [ParameterField]).
 /// ### Optional properties
 ///  - `max_iteration` is the maximum number of iterations. If not set, the iterations will be repeated until the elevations of all sites are stable.
+///  - `convergence_tolerance` is the maximum change of the elevations in an iteration relative to the relief to regard the terrain as stable. If not set, the terrain is stable only if no elevation changes.
+///  - `snapshot_interval` is the interval of iterations to take snapshots of the elevations. If not set, no snapshots will be taken.
+///  - `seed` is the seed of the random numbers used in the simulation. The default value is 0.
+///  - `debug_checks` is whether to validate the invariants of the simulation at each iteration. The default value is `false`.
+///  - `time_step` is the duration of an iteration (unit: T). If not set, the steady state of the terrain is computed.
+///  - `groundwater_transmissivity` is the capacity of the aquifer per unit slope (unit: L^2). If not set, the infiltrated water is lost.
+///  - `junction_tolerance` is the tolerance of the sites lower than their receivers (unit: L). If not set, the consistency is not enforced.
+///  - `num_threads` is the number of threads to calculate the drainage basins in parallel. The default value is 1.
+///  - `min_parallel_sites` is the minimum number of the sites at a depth of a drainage basin to accumulate the drainage areas in parallel. The default value is 4096.
+///  - `fast_powf` is whether to approximate the powers of the flows in the stream power law. The default value is `false`.
+///  - `fallback_distance` is the distance between a site and its receiver not connected by an edge (see [FallbackDistance]). The default value is the mean length of the edges.
+///  - `jitter` is the random perturbation of the initial elevations breaking the ties of the flow (see [Jitter]). The default amplitude is `f64::EPSILON`.
+///  - `processes` is the list of additional processes applied in each iteration (see [Process]).
+///  - `edge_parameters` is the parameters of the edges overriding the flow along them (see [EdgeParameters]).
+///  - `elevation_storage` is the file to store the elevations during the simulation (requires the feature `mmap`). If not set, the elevations are stored in the memory.
+///  - `snapshot_directory` is the directory to write the snapshots to (requires the feature `mmap`). If not set, the snapshots are not written.
 ///
 #[derive(Clone)]
 pub struct TerrainGenerator<S, M, T>
@@ -41,7 +270,9 @@ where
 {
     model: Option<M>,
     parameters: Option<Vec<TopographicalParameters>>,
-    max_iteration: Option<Step>,
+    parameter_field: Option<Arc<dyn ParameterField<S>>>,
+    edge_parameters: EdgeParameterMap,
+    config: SimulationConfig,
     _phantom: PhantomData<(S, T)>,
 }
 
@@ -54,7 +285,9 @@ where
         Self {
             model: None,
             parameters: None,
-            max_iteration: None,
+            parameter_field: None,
+            edge_parameters: EdgeParameterMap::new(),
+            config: SimulationConfig::default(),
             _phantom: PhantomData,
         }
     }
@@ -74,6 +307,25 @@ where
     /// Set the topographical parameters of sites. See [TopographicalParameters] about the parameters.
     pub fn set_parameters(mut self, parameters: Vec<TopographicalParameters>) -> Self {
         self.parameters = Some(parameters);
+        self.parameter_field = None;
+        self
+    }
+
+    /// Set the topographical parameters of sites as a field evaluated at each site, replacing the parameters set by `set_parameters`.
+    ///
+    /// The field is evaluated once when the generation starts. See [ParameterField] for details.
+    pub fn set_parameter_field(mut self, field: impl ParameterField<S> + 'static) -> Self {
+        self.parameter_field = Some(Arc::new(field));
+        self.parameters = None;
+        self
+    }
+
+    /// Set the parameters of the edge between the sites `i` and `j`, replacing the existing ones.
+    ///
+    /// The edge must exist in the graph of the model. See [EdgeParameters] about the parameters.
+    pub fn set_edge_parameters(mut self, i: usize, j: usize, parameters: EdgeParameters) -> Self {
+        self.edge_parameters
+            .insert((i.min(j), i.max(j)), parameters);
         self
     }
 
@@ -82,133 +334,568 @@ where
     /// The iteration(loop) for calculating elevations will be stopped when the number of iterations reaches `max_iteration`.
     /// If not set, the iterations will be repeated until the elevations of all sites are stable.
     pub fn set_max_iteration(mut self, max_iteration: Step) -> Self {
-        self.max_iteration = Some(max_iteration);
+        self.config.max_iteration = Some(max_iteration);
+        self
+    }
+
+    /// Set the tolerance of the convergence relative to the relief of the terrain.
+    ///
+    /// If set, the iterations are stopped when the maximum absolute change of the elevations in an iteration
+    /// is at most `convergence_tolerance` times the relief (the difference between the maximum and the minimum elevation),
+    /// such as `1e-6`. Since the tolerance is relative, it behaves the same regardless of the scale and the unit of the domain.
+    /// If not set, the iterations are stopped only when no elevation changes.
+    pub fn set_convergence_tolerance(mut self, convergence_tolerance: Option<f64>) -> Self {
+        self.config.convergence_tolerance =
+            convergence_tolerance.map(|tolerance| tolerance.max(0.0));
         self
     }
 
     /// Generate terrain.
     pub fn generate(self) -> Result<T, GenerationError> {
-        let model = {
-            if let Some(model) = &self.model {
-                model
-            } else {
-                return Err(GenerationError::ModelNotSet);
+        self.generate_with_events(|_| {})
+    }
+
+    /// Set the interval of iterations to emit [SimulationEvent::SnapshotReady] with a copy of the elevations.
+    ///
+    /// If not set, no snapshots will be taken.
+    pub fn set_snapshot_interval(mut self, snapshot_interval: Option<Step>) -> Self {
+        self.config.snapshot_interval = snapshot_interval;
+        self
+    }
+
+    /// Set the seed of the random numbers used in the simulation.
+    ///
+    /// The same seed with the same model and parameters always produces the same terrain.
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.config.seed = seed;
+        self
+    }
+
+    /// Set whether to validate the invariants of the simulation at each iteration.
+    ///
+    /// The invariants are: the stream tree is acyclic and drains into outlets, the drainage area of each site
+    /// is not smaller than its cell area, the response times are finite and non-negative,
+    /// and the elevations after the processes and the coupled models are finite.
+    /// If any of them is violated, the generation fails with [GenerationError::InvariantViolated]
+    /// holding the index of the offending site instead of producing corrupted terrain.
+    pub fn set_debug_checks(mut self, debug_checks: bool) -> Self {
+        self.config.debug_checks = debug_checks;
+        self
+    }
+
+    /// Set the duration of an iteration (unit: T) to simulate the transient evolution of the terrain.
+    ///
+    /// If not set, the elevations are computed as the steady state, where uplift and erosion are balanced,
+    /// from the response times of the drainage network.
+    /// If set, each iteration advances the terrain by `time_step` with the implicit scheme of the stream power law,
+    /// so that changes of the elevations by processes persist and evolve over time.
+    /// In this case the terrain rarely becomes exactly stable, so `max_iteration` should be set as the number of steps.
+    pub fn set_time_step(mut self, time_step: Option<f64>) -> Self {
+        self.config.time_step = time_step;
+        self
+    }
+
+    /// Set the transmissivity of the aquifer to return the infiltrated water to the surface as baseflow.
+    ///
+    /// The water infiltrated at each site (see [TopographicalParameters]) flows downstream through the aquifer,
+    /// whose capacity at each site is `groundwater_transmissivity * slope` as drainage area.
+    /// Where the capacity decreases, such as at the foot of the slopes, the water table intersects the surface
+    /// and the excess groundwater emerges as baseflow at the springs, adding to the discharge of the gaining streams.
+    /// The remaining groundwater emerges at the outlets. If not set, the infiltrated water is lost.
+    pub fn set_groundwater_transmissivity(
+        mut self,
+        groundwater_transmissivity: Option<f64>,
+    ) -> Self {
+        self.config.groundwater_transmissivity = groundwater_transmissivity;
+        self
+    }
+
+    /// Set the tolerance to enforce that no site is lower than its receiver, such as a tributary below the stream at the junction.
+    ///
+    /// The fluvial erosion never makes a site lower than its receiver, but the processes and the coupled models
+    /// may do so, leaving the flow of the exported network running uphill.
+    /// If set, after each iteration, the sites lower than their receivers by more than `junction_tolerance`
+    /// are raised to the level of their receivers. If not set, the consistency is not enforced.
+    pub fn set_junction_tolerance(mut self, junction_tolerance: Option<Elevation>) -> Self {
+        self.config.junction_tolerance = junction_tolerance;
+        self
+    }
+
+    /// Set the number of threads to calculate the drainage basins in parallel.
+    ///
+    /// The drainage basins are solved in isolation and merged in a fixed order,
+    /// so the result is bit-identical regardless of the number of threads. The default value is 1.
+    pub fn set_num_threads(mut self, num_threads: usize) -> Self {
+        self.config.num_threads = num_threads;
+        self
+    }
+
+    /// Set the file to store the elevations during the simulation.
+    ///
+    /// The elevations are memory-mapped from the file (see [MappedElevations](crate::lem::storage::MappedElevations)) instead of allocated in the memory,
+    /// and the file keeps the latest elevations after the simulation. If not set, the elevations are stored in the memory.
+    #[cfg(feature = "mmap")]
+    pub fn set_elevation_storage(mut self, path: Option<impl Into<PathBuf>>) -> Self {
+        self.config.elevation_storage = path.map(Into::into);
+        self
+    }
+
+    /// Set the directory to write the snapshots taken at `snapshot_interval` to.
+    ///
+    /// Each snapshot is written as a file of [MappedElevations](crate::lem::storage::MappedElevations) named `snapshot_{step}.flem` with the zero-padded step.
+    /// If not set, the snapshots are only emitted as [SimulationEvent::SnapshotReady].
+    #[cfg(feature = "mmap")]
+    pub fn set_snapshot_directory(mut self, path: Option<impl Into<PathBuf>>) -> Self {
+        self.config.snapshot_directory = path.map(Into::into);
+        self
+    }
+
+    /// Set the minimum number of the sites at a depth of a drainage basin to accumulate the drainage areas in parallel.
+    ///
+    /// If there are fewer drainage basins than `num_threads`, the spare threads accumulate the drainage areas
+    /// of the sites at the same depth of a basin in parallel, which pays off only at the wide depths of the large basins.
+    /// The result is bit-identical regardless of this value. The default value is 4096.
+    pub fn set_min_parallel_sites(mut self, min_parallel_sites: usize) -> Self {
+        self.config.min_parallel_sites = Some(min_parallel_sites);
+        self
+    }
+
+    /// Set whether to approximate the powers of the flows in the stream power law.
+    ///
+    /// The powers of the drainage areas dominate the time of the iterations of the large models. If enabled, they are
+    /// computed by [fast_powf](crate::lem::kernels::fast_powf) with the relative error below
+    /// [FAST_POWF_MAX_RELATIVE_ERROR](crate::lem::kernels::FAST_POWF_MAX_RELATIVE_ERROR), instead of `powf`.
+    /// The resulting terrains differ slightly from the exact ones. The default value is `false`.
+    pub fn set_fast_powf(mut self, fast_powf: bool) -> Self {
+        self.config.fast_powf = fast_powf;
+        self
+    }
+
+    /// Set the distance between a site and its receiver when they are not connected by an edge of the graph.
+    ///
+    /// Such receivers are not created by the stream tree of a valid model, so the fallback only guards against inconsistent graphs.
+    /// With [FallbackDistance::Strict], they are reported as [GenerationError::MissingEdge] instead.
+    pub fn set_fallback_distance(mut self, fallback_distance: FallbackDistance) -> Self {
+        self.config.fallback_distance = fallback_distance;
+        self
+    }
+
+    /// Set the random perturbation added to the initial elevations to break the ties of the flow (see [Jitter]).
+    ///
+    /// The perturbation is drawn from the seed (see [TerrainGenerator::set_seed]).
+    pub fn set_jitter(mut self, jitter: Jitter) -> Self {
+        self.config.jitter = jitter;
+        self
+    }
+
+    /// Add a process applied after the fluvial erosion in each iteration. See [Process] for details.
+    pub fn add_process(mut self, process: impl Process + 'static) -> Self {
+        self.config.processes.push(Arc::new(process));
+        self
+    }
+
+    /// Generate terrain in a background job, returning a [GenerationTask] to observe it.
+    ///
+    /// `spawn` receives the job and is responsible for running it off the caller's thread,
+    /// so any executor can be used: `std::thread::spawn`, `tokio::task::spawn_blocking`, a thread pool, etc.
+    /// The progress of each iteration, the simulation events and the final result are delivered through channels of the returned task.
+    pub fn generate_async<F>(self, spawn: F) -> GenerationTask<T>
+    where
+        Self: Send + 'static,
+        T: Send + 'static,
+        F: FnOnce(Box<dyn FnOnce() + Send>),
+    {
+        let (progress_sender, progress_receiver) = mpsc::channel();
+        let (event_sender, event_receiver) = mpsc::channel();
+        let (result_sender, result_receiver) = mpsc::channel();
+        spawn(Box::new(move || {
+            let result = self.generate_with_events(|event| {
+                // the receivers may already be dropped if the caller does not watch them
+                if let SimulationEvent::StepCompleted(progress) = &event {
+                    let _ = progress_sender.send(progress.clone());
+                }
+                let _ = event_sender.send(event);
+            });
+            let _ = result_sender.send(result);
+        }));
+        GenerationTask::new(progress_receiver, event_receiver, result_receiver)
+    }
+
+    /// Generate terrain on a newly spawned thread. See [TerrainGenerator::generate_async] for details.
+    pub fn generate_in_thread(self) -> GenerationTask<T>
+    where
+        Self: Send + 'static,
+        T: Send + 'static,
+    {
+        self.generate_async(|job| {
+            std::thread::spawn(job);
+        })
+    }
+
+    /// Generate terrain, calling `on_progress` each time an iteration is completed.
+    pub fn generate_with_progress(
+        self,
+        mut on_progress: impl FnMut(&GenerationProgress),
+    ) -> Result<T, GenerationError> {
+        self.generate_with_events(|event| {
+            if let SimulationEvent::StepCompleted(progress) = &event {
+                on_progress(progress);
             }
+        })
+    }
+
+    /// Generate terrain while recording all the inputs and the digests of elevations at each iteration.
+    ///
+    /// The returned [SimulationRecord] can be saved to a file and replayed later to verify that the simulation is reproduced bit by bit.
+    pub fn generate_with_record(self) -> Result<(T, SimulationRecord), GenerationError> {
+        let (model, parameters) = self.validate()?;
+        let edge_directions = edge_directions(model, &parameters);
+        let mut record = SimulationRecord::new(
+            model.areas(),
+            model.graph(),
+            edge_directions.as_ref(),
+            &self.edge_parameters,
+            model.default_outlets(),
+            &parameters,
+            &self.config,
+        );
+        let (elevations, fields, network) = simulate(
+            &self.config,
+            model.areas(),
+            model.graph(),
+            edge_directions.as_ref(),
+            &self.edge_parameters,
+            model.default_outlets(),
+            &parameters,
+            &mut |_| {},
+            &mut |_, elevations| record.push_digest(elevations),
+            None,
+        )?;
+        Ok((
+            model.create_terrain_from_output(&elevations, &fields, &network),
+            record,
+        ))
+    }
+
+    /// Generate terrain, calling `on_event` for each [SimulationEvent] emitted during the simulation.
+    pub fn generate_with_events(
+        self,
+        mut on_event: impl FnMut(SimulationEvent),
+    ) -> Result<T, GenerationError> {
+        let (model, parameters) = self.validate()?;
+        let edge_directions = edge_directions(model, &parameters);
+        let (elevations, fields, network) = simulate(
+            &self.config,
+            model.areas(),
+            model.graph(),
+            edge_directions.as_ref(),
+            &self.edge_parameters,
+            model.default_outlets(),
+            &parameters,
+            &mut on_event,
+            &mut |_, _| {},
+            None,
+        )?;
+
+        Ok(model.create_terrain_from_output(&elevations, &fields, &network))
+    }
+
+    /// Generate terrain, calling `on_state` with the [SimulationState] in each iteration after the processes.
+    ///
+    /// This couples external models (ecology, climate, land use, etc.) with the simulation in both ways:
+    /// `on_state` can read the drainage areas and modify the elevations, the parameters and the fields as a [Process] does,
+    /// but unlike processes, it can keep its own mutable state outside the generator.
+    pub fn generate_with_coupling(
+        self,
+        mut on_state: impl FnMut(&mut SimulationState),
+    ) -> Result<T, GenerationError> {
+        let (model, parameters) = self.validate()?;
+        let edge_directions = edge_directions(model, &parameters);
+        let (elevations, fields, network) = simulate(
+            &self.config,
+            model.areas(),
+            model.graph(),
+            edge_directions.as_ref(),
+            &self.edge_parameters,
+            model.default_outlets(),
+            &parameters,
+            &mut |_| {},
+            &mut |_, _| {},
+            Some(&mut on_state),
+        )?;
+
+        Ok(model.create_terrain_from_output(&elevations, &fields, &network))
+    }
+
+    /// Create the [GenerationManifest] capturing all the settings of the generator and the digests of the model and the parameters.
+    ///
+    /// The model and the parameters must be set, since their digests are computed here.
+    pub fn to_manifest(&self) -> Result<GenerationManifest, GenerationError> {
+        let (model, parameters) = self.validate()?;
+        let mut edge_parameters = self
+            .edge_parameters
+            .iter()
+            .map(|(&(i, j), parameters)| {
+                (
+                    i,
+                    j,
+                    parameters.erodibility_factor,
+                    parameters.distance_factor,
+                )
+            })
+            .collect::<Vec<_>>();
+        edge_parameters.sort_by_key(|&(i, j, _, _)| (i, j));
+        Ok(GenerationManifest {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            model_digest: digest_model(model.areas(), model.graph(), model.default_outlets()),
+            parameters_digest: digest_parameters(&parameters),
+            seed: self.config.seed,
+            max_iteration: self.config.max_iteration,
+            convergence_tolerance: self.config.convergence_tolerance,
+            snapshot_interval: self.config.snapshot_interval,
+            debug_checks: self.config.debug_checks,
+            time_step: self.config.time_step,
+            groundwater_transmissivity: self.config.groundwater_transmissivity,
+            junction_tolerance: self.config.junction_tolerance,
+            num_threads: self.config.num_threads,
+            min_parallel_sites: self.config.min_parallel_sites,
+            fast_powf: self.config.fast_powf,
+            fallback_distance: self.config.fallback_distance,
+            jitter: self.config.jitter,
+            edge_parameters,
+            processes: self
+                .config
+                .processes
+                .iter()
+                .map(|process| process.name().to_string())
+                .collect(),
+        })
+    }
+
+    /// Create the generator with the settings in the [GenerationManifest].
+    ///
+    /// The model, the parameters and the processes are not included in the manifest, so they must be set again.
+    /// Use [TerrainGenerator::check_manifest] to verify them against the manifest before the generation.
+    pub fn from_manifest(manifest: &GenerationManifest) -> Self {
+        let generator = Self::default()
+            .set_seed(manifest.seed)
+            .set_snapshot_interval(manifest.snapshot_interval)
+            .set_convergence_tolerance(manifest.convergence_tolerance)
+            .set_debug_checks(manifest.debug_checks)
+            .set_time_step(manifest.time_step)
+            .set_groundwater_transmissivity(manifest.groundwater_transmissivity)
+            .set_junction_tolerance(manifest.junction_tolerance)
+            .set_num_threads(manifest.num_threads)
+            .set_fast_powf(manifest.fast_powf)
+            .set_fallback_distance(manifest.fallback_distance)
+            .set_jitter(manifest.jitter);
+        let mut generator = manifest.edge_parameters.iter().fold(
+            generator,
+            |generator, &(i, j, erodibility_factor, distance_factor)| {
+                generator.set_edge_parameters(
+                    i,
+                    j,
+                    EdgeParameters::default()
+                        .set_erodibility_factor(erodibility_factor)
+                        .set_distance_factor(distance_factor),
+                )
+            },
+        );
+        generator.config.max_iteration = manifest.max_iteration;
+        generator.config.min_parallel_sites = manifest.min_parallel_sites;
+        generator
+    }
+
+    /// Check that the model, the parameters and the processes of the generator match the [GenerationManifest].
+    pub fn check_manifest(&self, manifest: &GenerationManifest) -> Result<(), ManifestError> {
+        let (model, parameters) = self.validate()?;
+        if digest_model(model.areas(), model.graph(), model.default_outlets())
+            != manifest.model_digest
+        {
+            return Err(ManifestError::ModelMismatch);
+        }
+        if digest_parameters(&parameters) != manifest.parameters_digest {
+            return Err(ManifestError::ParametersMismatch);
+        }
+        if !self
+            .config
+            .processes
+            .iter()
+            .map(|process| process.name())
+            .eq(manifest.processes.iter().map(String::as_str))
+        {
+            return Err(ManifestError::ProcessesMismatch);
+        }
+        Ok(())
+    }
+
+    /// Age the existing terrain: soften the peaks and widen the valleys by the diffusion-dominant evolution without uplift.
+    ///
+    /// Starting from `elevations` (e.g. `terrain.elevations()`), the terrain evolves for `ceil(intensity * 20)` iterations
+    /// of the unit time step, with no uplift, the erodibilities reduced to a tenth and [HillslopeDiffusionProcess]
+    /// whose diffusivity is a tenth of the typical area of the sites. The outlets stay at their elevations.
+    /// The other properties of the generator, such as the processes, are kept.
+    ///
+    /// The parameters are optional here; if not set, the default [TopographicalParameters] are used.
+    /// If `intensity` is 0.0 or less, the terrain is returned unchanged.
+    pub fn age(self, elevations: &[Elevation], intensity: f64) -> Result<T, GenerationError> {
+        let model = self.model.as_ref().ok_or(GenerationError::ModelNotSet)?;
+        if elevations.len() != model.num() {
+            return Err(GenerationError::InvalidNumberOfElevations);
+        }
+        let num_steps = (intensity.max(0.0) * AGING_STEPS_PER_INTENSITY).ceil() as Step;
+        if num_steps == 0 {
+            return Ok(model.create_terrain_from_result(elevations));
+        }
+        // the median is used since the areas of the sites on the hull may be unbounded
+        let typical_area = {
+            let mut areas = model.areas().to_vec();
+            areas.sort_by(|a, b| a.total_cmp(b));
+            areas.get(areas.len() / 2).copied().unwrap_or(0.0)
         };
 
-        let (num, sites, areas, graph, default_outlets) = (
-            model.num(),
-            model.sites(),
+        let generator = if self.parameters.is_none() && self.parameter_field.is_none() {
+            let num = model.num();
+            self.set_parameters(vec![TopographicalParameters::default(); num])
+        } else {
+            self
+        };
+        let parameters = generator
+            .validate()?
+            .1
+            .iter()
+            .zip(elevations)
+            .map(|(parameters, &elevation)| {
+                let erodibility = parameters.erodibility * AGING_ERODIBILITY_RATIO;
+                parameters
+                    .clone()
+                    .set_base_elevation(elevation)
+                    .set_uplift_rate(0.0)
+                    .set_erodibility(erodibility)
+            })
+            .collect();
+        generator
+            .set_parameters(parameters)
+            .set_time_step(Some(1.0))
+            .set_max_iteration(num_steps)
+            .add_process(
+                HillslopeDiffusionProcess::default()
+                    .set_diffusivity(typical_area * AGING_DIFFUSIVITY_RATE),
+            )
+            .generate()
+    }
+
+    /// Replace the parameters of all sites by `f`, used to derive the variations of the generator.
+    pub(crate) fn map_parameters(
+        mut self,
+        f: impl Fn(TopographicalParameters) -> TopographicalParameters,
+    ) -> Result<Self, GenerationError> {
+        let parameters = self.validate()?.1.into_owned();
+        self.parameters = Some(parameters.into_iter().map(f).collect());
+        self.parameter_field = None;
+        Ok(self)
+    }
+
+    /// Generate terrain with its [MorphometricSummary].
+    pub(crate) fn generate_with_summary(self) -> Result<(T, MorphometricSummary), GenerationError> {
+        let (model, parameters) = self.validate()?;
+        let edge_directions = edge_directions(model, &parameters);
+        let (elevations, fields, network) = simulate(
+            &self.config,
             model.areas(),
             model.graph(),
+            edge_directions.as_ref(),
+            &self.edge_parameters,
             model.default_outlets(),
+            &parameters,
+            &mut |_| {},
+            &mut |_, _| {},
+            None,
+        )?;
+        let summary = MorphometricSummary::new(
+            &elevations,
+            model.graph(),
+            &network,
+            self.config.fallback_distance.resolve(model.graph()),
         );
+        Ok((
+            model.create_terrain_from_output(&elevations, &fields, &network),
+            summary,
+        ))
+    }
+
+    /// Check that the model and parameters required for generation are set properly.
+    ///
+    /// The parameter field is evaluated here, so the parameters are borrowed only if they are set as a vector.
+    fn validate(&self) -> Result<(&M, Cow<'_, [TopographicalParameters]>), GenerationError> {
+        let model = {
+            if let Some(model) = &self.model {
+                model
+            } else {
+                return Err(GenerationError::ModelNotSet);
+            }
+        };
 
         let parameters = {
             if let Some(parameters) = &self.parameters {
-                if parameters.len() != num {
+                if parameters.len() != model.num() {
                     return Err(GenerationError::InvalidNumberOfParameters);
                 }
-                parameters
+                Cow::Borrowed(parameters.as_slice())
+            } else if let Some(field) = &self.parameter_field {
+                Cow::Owned(
+                    model
+                        .sites()
+                        .iter()
+                        .enumerate()
+                        .map(|(i, site)| field.at(i, site))
+                        .collect(),
+                )
             } else {
                 return Err(GenerationError::ParametersNotSet);
             }
         };
 
-        let m_exp = DEFAULT_M_EXP;
-
-        let outlets = {
-            let outlets = parameters
-                .iter()
-                .enumerate()
-                .filter(|(_, param)| param.is_outlet)
-                .map(|(i, _)| i)
-                .collect::<Vec<_>>();
-            if outlets.is_empty() {
-                default_outlets.to_vec()
-            } else {
-                outlets
-            }
-        };
+        if let Some(&(i, j)) = self
+            .edge_parameters
+            .keys()
+            .find(|&&(i, j)| j >= model.num() || !model.graph().has_edge(i, j).0)
+        {
+            return Err(GenerationError::InvalidEdge(i, j));
+        }
 
-        let mut rng: StdRng = SeedableRng::seed_from_u64(0);
-        let mut elevations = parameters
+        // NaN is rejected as well, since it is not caught by the comparison
+        if let Some(i) = parameters
             .iter()
-            .map(|a| a.base_elevation + rng.gen::<f64>() * f64::EPSILON)
-            .collect::<Vec<_>>();
+            .position(|param| param.erodibility.is_nan() || param.erodibility < 0.0)
+        {
+            return Err(GenerationError::InvalidErodibility(i));
+        }
 
-        for _ in 0..self.max_iteration.unwrap_or(u32::MAX) {
-            let stream_tree =
-                stream_tree::StreamTree::construct(sites, &elevations, graph, &outlets);
-
-            let mut drainage_areas: Vec<f64> = areas.to_vec();
-            let mut response_times = vec![0.0; num];
-            let mut changed = false;
-
-            // calculate elevations for each drainage basin
-            outlets.iter().for_each(|&outlet| {
-                // construct drainage basin
-                let drainage_basin = DrainageBasin::construct(outlet, &stream_tree, graph);
-
-                // calculate drainage areas
-                drainage_basin.for_each_downstream(|i| {
-                    let j = stream_tree.next[i];
-                    if j != i {
-                        drainage_areas[j] += drainage_areas[i];
-                    }
-                });
-
-                // calculate response times
-                drainage_basin.for_each_upstream(|i| {
-                    let j = stream_tree.next[i];
-                    let distance: Length = {
-                        let (ok, edge) = graph.has_edge(i, j);
-                        if ok {
-                            edge
-                        } else {
-                            1.0
-                        }
-                    };
-                    let celerity = parameters[i].erodibility * drainage_areas[i].powf(m_exp);
-                    response_times[i] += response_times[j] + 1.0 / celerity * distance;
-                });
-
-                // calculate elevations
-                drainage_basin.for_each_upstream(|i| {
-                    let mut new_elevation = elevations[outlet]
-                        + parameters[i].uplift_rate
-                            * (response_times[i] - response_times[outlet]).max(0.0);
-
-                    // check if the slope is too steep
-                    // if max_slope_func is not set, the slope is not checked
-                    if let Some(max_slope) = parameters[i].max_slope {
-                        let j = stream_tree.next[i];
-                        let distance: Length = {
-                            let (ok, edge) = graph.has_edge(i, j);
-                            if ok {
-                                edge
-                            } else {
-                                1.0
-                            }
-                        };
-                        let max_slope = max_slope.tan();
-                        let slope = (new_elevation - elevations[j]) / distance;
-                        if slope > max_slope {
-                            new_elevation = elevations[j] + max_slope * distance;
-                        }
-                    }
-
-                    changed |= new_elevation != elevations[i];
-                    elevations[i] = new_elevation;
-                });
-            });
+        Ok((model, parameters))
+    }
+}
 
-            // if the elevations of all sites are stable, break
-            if !changed {
-                break;
+/// The direction of each edge of the model, computed only if any site has the anisotropic erodibility.
+fn edge_directions<S, M, T>(
+    model: &M,
+    parameters: &[TopographicalParameters],
+) -> Option<EdgeAttributedUndirectedGraph<f64>>
+where
+    S: Site,
+    M: Model<S, T>,
+{
+    if !parameters.iter().any(|param| param.is_anisotropic()) {
+        return None;
+    }
+    let (sites, graph) = (model.sites(), model.graph());
+    let mut edge_directions = EdgeAttributedUndirectedGraph::new(graph.order());
+    for i in 0..graph.order() {
+        for ja in graph.neighbors_of(i) {
+            if i < ja.0 {
+                edge_directions.add_edge(i, ja.0, sites[i].direction(&sites[ja.0])?);
             }
         }
-
-        Ok(model.create_terrain_from_result(&elevations))
     }
+    Some(edge_directions)
 }
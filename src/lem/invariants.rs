@@ -0,0 +1,65 @@
+//! Checks of the invariants of the simulation, enabled by `TerrainGenerator::set_debug_checks`.
+//!
+//! Each check returns the index of the first offending site with the description of the violated invariant.
+
+use crate::core::units::{Area, Elevation};
+
+/// A violated invariant: the index of the offending site and the description of the invariant.
+pub(crate) type Violation = (usize, &'static str);
+
+/// Check that the stream tree is acyclic and every site flows into an outlet.
+pub(crate) fn check_stream_tree(next: &[usize], is_outlet: &[bool]) -> Result<(), Violation> {
+    // 0: not visited, 1: on the current path, 2: known to reach an outlet
+    let mut state = vec![0u8; next.len()];
+    for start in 0..next.len() {
+        let mut path = Vec::new();
+        let mut i = start;
+        while state[i] == 0 {
+            if is_outlet[i] {
+                break;
+            }
+            if next[i] == i {
+                return Err((i, "the stream tree has a root which is not an outlet"));
+            }
+            state[i] = 1;
+            path.push(i);
+            i = next[i];
+        }
+        if state[i] == 1 {
+            return Err((i, "the stream tree has a cycle"));
+        }
+        path.into_iter().for_each(|j| state[j] = 2);
+        state[i] = 2;
+    }
+    Ok(())
+}
+
+/// Check that the drainage area of every site is at least its own cell area.
+pub(crate) fn check_drainage_areas(
+    drainage_areas: &[Area],
+    areas: &[Area],
+) -> Result<(), Violation> {
+    match (0..areas.len()).find(|&i| drainage_areas[i].is_nan() || drainage_areas[i] < areas[i]) {
+        Some(i) => Err((i, "the drainage area is smaller than the cell area")),
+        None => Ok(()),
+    }
+}
+
+/// Check that the response time of every site is finite and non-negative.
+pub(crate) fn check_response_times(response_times: &[f64]) -> Result<(), Violation> {
+    match response_times
+        .iter()
+        .position(|&t| !t.is_finite() || t < 0.0)
+    {
+        Some(i) => Err((i, "the response time is not finite or negative")),
+        None => Ok(()),
+    }
+}
+
+/// Check that the elevation of every site is finite, as the processes and the coupled models may write any value.
+pub(crate) fn check_elevations(elevations: &[Elevation]) -> Result<(), Violation> {
+    match elevations.iter().position(|e| !e.is_finite()) {
+        Some(i) => Err((i, "the elevation is not finite")),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,167 @@
+//! The arithmetic kernels of the inner loops of the simulation, exposed for benchmarking.
+//!
+//! The kernels work on contiguous arrays. With the feature `simd`, they process the arrays in chunks of [LANES] values,
+//! which the compiler turns into vector instructions. In particular, the stream powers with the default exponent 0.5
+//! are computed by the vectorized square root, which may differ from `powf` in the last bit.
+//! Without the feature, the kernels are plain loops producing the same results as the scalar operations.
+
+use crate::core::units::{Area, Elevation, Length};
+
+/// The number of the values processed at once by the kernels with the feature `simd`.
+pub const LANES: usize = 4;
+
+/// Compute `flow^m_exp` of each flow, the factor of the celerity of the stream power law.
+pub fn stream_powers(flows: &[Area], m_exp: f64) -> Vec<f64> {
+    let mut powers = vec![0.0; flows.len()];
+    #[cfg(feature = "simd")]
+    {
+        if m_exp == 0.5 {
+            map_lanes(flows, &mut powers, |lane| lane.map(f64::sqrt));
+        } else {
+            map_lanes(flows, &mut powers, |lane| lane.map(|flow| flow.powf(m_exp)));
+        }
+    }
+    #[cfg(not(feature = "simd"))]
+    powers
+        .iter_mut()
+        .zip(flows)
+        .for_each(|(power, flow)| *power = flow.powf(m_exp));
+    powers
+}
+
+/// The bound of the relative error of [fast_powf] for the exponents `|y| <= 1` and the normal results.
+pub const FAST_POWF_MAX_RELATIVE_ERROR: f64 = 1e-6;
+
+/// Compute `x^y` approximately as `exp2(y * log2(x))` with polynomial approximations of the logarithm and the exponential.
+///
+/// For the normal positive `x` and the exponents `|y| <= 1`, which cover the exponents of the stream power law,
+/// the relative error is below [FAST_POWF_MAX_RELATIVE_ERROR]. The error of the logarithm grows in proportion to `|y|`
+/// for the larger exponents. Zero, the subnormal, negative and non-finite bases and the results out of the normal range
+/// fall back to `powf`.
+pub fn fast_powf(x: f64, y: f64) -> f64 {
+    let power = approximate_powf(x, y);
+    if is_approximable(x, power) {
+        power
+    } else {
+        x.powf(y)
+    }
+}
+
+/// Compute the stream powers by [fast_powf]. See [stream_powers].
+///
+/// The approximation has no branches, so the loop is vectorized by the compiler.
+/// The flows out of the range of the approximation are computed again by `powf` afterwards.
+pub fn fast_stream_powers(flows: &[Area], m_exp: f64) -> Vec<f64> {
+    let mut powers = flows
+        .iter()
+        .map(|&flow| approximate_powf(flow, m_exp))
+        .collect::<Vec<_>>();
+    powers
+        .iter_mut()
+        .zip(flows)
+        .filter(|(power, &flow)| !is_approximable(flow, **power))
+        .for_each(|(power, &flow)| *power = flow.powf(m_exp));
+    powers
+}
+
+/// The magic number rounding the values below 2^51 to the integers in the low bits of the mantissa.
+const ROUNDING_MAGIC: f64 = 6755399441055744.0;
+
+/// Whether the approximation `power` of the power of `x` is valid.
+fn is_approximable(x: f64, power: f64) -> bool {
+    x.is_normal() && x > 0.0 && power.is_normal()
+}
+
+/// `x^y` without the checks of the range, valid only if [is_approximable].
+fn approximate_powf(x: f64, y: f64) -> f64 {
+    // the exponent is clamped so that the scale below stays finite, and the results out of the range fail the check
+    let exponent = (y * approximate_log2(x)).clamp(-1100.0, 1100.0);
+
+    // split the exponent into the nearest integer `n` and the fraction `f` with |f| <= 0.5
+    let rounded = exponent + ROUNDING_MAGIC;
+    let f = exponent - (rounded - ROUNDING_MAGIC);
+    // 2^f interpolated at the Chebyshev nodes (relative error below 8e-8)
+    let polynomial = 1.000_000_075_454_897_2
+        + f * (0.693_147_188_026_228_5
+            + f * (0.240_221_074_853_082_1
+                + f * (0.055_503_571_142_194_61
+                    + f * (0.009_676_031_918_326_564 + f * 0.001_339_086_336_453_350_4))));
+    // 2^n is constructed in two halves, each of which is in the range of the exponent bits
+    let n = rounded.to_bits().wrapping_sub(ROUNDING_MAGIC.to_bits()) as i64;
+    let half = n >> 1;
+    let scale = |n: i64| f64::from_bits(((n + 1023) as u64) << 52);
+    polynomial * scale(half) * scale(n - half)
+}
+
+/// `log2(x)` of the normal positive `x`.
+fn approximate_log2(x: f64) -> f64 {
+    let bits = x.to_bits();
+    let exponent =
+        f64::from_bits(ROUNDING_MAGIC.to_bits() | ((bits >> 52) & 0x7ff)) - ROUNDING_MAGIC - 1023.0;
+    let mantissa = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+    // move the mantissa into [sqrt(1/2), sqrt(2)) to keep the polynomial short
+    let high = mantissa > std::f64::consts::SQRT_2;
+    let (mantissa, exponent) = if high {
+        (mantissa * 0.5, exponent + 1.0)
+    } else {
+        (mantissa, exponent)
+    };
+    // log2(1 + u) / u interpolated at the Chebyshev nodes (absolute error below 3.3e-7)
+    let u = mantissa - 1.0;
+    let polynomial = 1.442_694_994_893_046_5
+        + u * (-0.721_352_931_362_977
+            + u * (0.480_916_708_000_165_1
+                + u * (-0.360_225_182_460_790_8
+                    + u * (0.287_288_882_375_908_6
+                        + u * (-0.249_271_822_079_607_3
+                            + u * (0.232_652_578_806_726 + u * -0.142_759_734_326_603_97))))));
+    exponent + u * polynomial
+}
+
+/// Compute the slope `(elevation - lower) / distance` of each site towards the lower site, clamped to be non-negative.
+///
+/// The arrays must have the same length.
+pub fn downhill_slopes(
+    elevations: &[Elevation],
+    lower_elevations: &[Elevation],
+    distances: &[Length],
+) -> Vec<f64> {
+    let len = elevations.len();
+    assert!(lower_elevations.len() == len && distances.len() == len);
+    let mut slopes = vec![0.0; len];
+    #[cfg(feature = "simd")]
+    {
+        let chunks = len / LANES * LANES;
+        (0..chunks).step_by(LANES).for_each(|start| {
+            let lane: [f64; LANES] = std::array::from_fn(|l| {
+                let k = start + l;
+                ((elevations[k] - lower_elevations[k]) / distances[k]).max(0.0)
+            });
+            slopes[start..start + LANES].copy_from_slice(&lane);
+        });
+        (chunks..len).for_each(|k| {
+            slopes[k] = ((elevations[k] - lower_elevations[k]) / distances[k]).max(0.0);
+        });
+    }
+    #[cfg(not(feature = "simd"))]
+    (0..len).for_each(|k| {
+        slopes[k] = ((elevations[k] - lower_elevations[k]) / distances[k]).max(0.0);
+    });
+    slopes
+}
+
+/// Apply `f` to the chunks of [LANES] values, padding the last chunk with 1.0.
+#[cfg(feature = "simd")]
+fn map_lanes(input: &[f64], output: &mut [f64], f: impl Fn([f64; LANES]) -> [f64; LANES]) {
+    let mut inputs = input.chunks_exact(LANES);
+    let mut outputs = output.chunks_exact_mut(LANES);
+    (&mut inputs)
+        .zip(&mut outputs)
+        .for_each(|(input, output)| output.copy_from_slice(&f(input.try_into().unwrap())));
+    let rest = inputs.remainder();
+    let mut lane = [1.0; LANES];
+    lane[..rest.len()].copy_from_slice(rest);
+    outputs
+        .into_remainder()
+        .copy_from_slice(&f(lane)[..rest.len()]);
+}
@@ -0,0 +1,60 @@
+use thiserror::Error;
+
+use crate::{
+    core::units::Step,
+    lem::generator::{FallbackDistance, GenerationError, Jitter},
+};
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("The model does not match the digest in the manifest")]
+    ModelMismatch,
+    #[error("The topographical parameters do not match the digest in the manifest")]
+    ParametersMismatch,
+    #[error("The processes do not match the ones in the manifest")]
+    ProcessesMismatch,
+    #[error("Failed to validate the generator: {0}")]
+    Generation(#[from] GenerationError),
+}
+
+/// A reproducible recipe of a generation, created by `TerrainGenerator::to_manifest`.
+///
+/// The manifest captures all the settings of the generator and the seed, the parameters of the edges,
+/// and the digests (64-bit FNV-1a) of the model and the topographical parameters instead of their values.
+/// With the feature `serde`, it can be serialized into any format supported by serde (JSON, TOML, etc.)
+/// to be checked into version control.
+///
+/// The generator is rebuilt by `TerrainGenerator::from_manifest` with the same model and parameters,
+/// and `TerrainGenerator::check_manifest` verifies them against the digests.
+/// The processes are arbitrary code, so only their names are recorded and they must be added again in the same order.
+/// The storage of the elevations and the directory of the snapshots are not recorded since they do not affect the results.
+///
+/// ### Properties
+///  - `version` is the version of the crate which created the manifest.
+///  - `model_digest` is the digest of the areas, the graph and the default outlets of the model.
+///  - `parameters_digest` is the digest of the topographical parameters of the sites.
+///  - `edge_parameters` is the erodibility factor and the distance factor of each edge `(i, j)` with `i < j`, sorted by the edges.
+///  - `processes` is the names of the processes in the order they were added.
+///  - The other properties are the settings of `TerrainGenerator` with the same names.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenerationManifest {
+    pub version: String,
+    pub model_digest: u64,
+    pub parameters_digest: u64,
+    pub seed: u64,
+    pub max_iteration: Option<Step>,
+    pub convergence_tolerance: Option<f64>,
+    pub snapshot_interval: Option<Step>,
+    pub debug_checks: bool,
+    pub time_step: Option<f64>,
+    pub groundwater_transmissivity: Option<f64>,
+    pub junction_tolerance: Option<f64>,
+    pub num_threads: usize,
+    pub min_parallel_sites: Option<usize>,
+    pub fast_powf: bool,
+    pub fallback_distance: FallbackDistance,
+    pub jitter: Jitter,
+    pub edge_parameters: Vec<(usize, usize, f64, f64)>,
+    pub processes: Vec<String>,
+}
@@ -1,5 +1,18 @@
 //! Module `lem` provides calculation for simulating the erosion process based on a simplified Landscape Evolution Model.
+pub mod events;
 pub mod generator;
+pub mod kernels;
+pub mod manifest;
+pub mod phases;
+pub mod process;
+pub mod processes;
+pub mod progress;
+pub mod record;
+pub mod snapshot;
+pub mod storage;
+pub mod sweep;
 
 mod drainage_basin;
+mod invariants;
+mod simulation;
 mod stream_tree;
@@ -0,0 +1,150 @@
+//! The individual phases of an iteration of the simulation, exposed for benchmarking and experiments.
+//!
+//! An iteration of [crate::lem::generator::TerrainGenerator] constructs the stream tree from the elevations,
+//! accumulates the drainage areas along it, and updates the elevations of each drainage basin.
+//! The sweep of the multiple flow directions is provided as an alternative to the accumulation along the stream tree.
+//! The functions here run each phase alone on plain inputs, so that they can be measured and tuned individually.
+//! They do not consider the processes, the anisotropy or the parameters of the edges.
+//!
+//! The phases take the [Adjacency] of the graph of the model instead of the graph itself,
+//! so that it is built once with [Adjacency::from_graph] and reused across the calls like in the iterations.
+
+use crate::{
+    core::{
+        adjacency::Adjacency,
+        parameters::TopographicalParameters,
+        units::{Area, Elevation},
+    },
+    lem::{
+        drainage_basin::DrainageBasin,
+        generator::GenerationError,
+        simulation::{solve_basins, SimulationConfig},
+        stream_tree::StreamTree,
+    },
+};
+
+/// Construct the stream tree, the receiver of each site, from the elevations.
+///
+/// Each site flows to its steepest downhill neighbor, and the depressions are connected to the outlets across their lowest passes.
+/// The flat regions are crossed toward their lower edges and away from their higher edges.
+/// The outlets are their own receivers.
+pub fn construct_stream_tree(
+    elevations: &[Elevation],
+    adjacency: &Adjacency,
+    outlets: &[usize],
+) -> Vec<usize> {
+    StreamTree::construct(elevations, adjacency, outlets).next
+}
+
+/// Accumulate the areas of the sites along the receivers, returning the drainage area of each site.
+///
+/// The receivers must form a forest of the edges of `adjacency` whose roots are their own receivers,
+/// such as the result of [construct_stream_tree]. The drainage basin of each root is accumulated as in the iterations.
+/// The sites not draining to any root, such as the sites on a cycle of the receivers, keep their own areas.
+pub fn accumulate_drainage_areas(
+    receivers: &[usize],
+    areas: &[Area],
+    adjacency: &Adjacency,
+) -> Vec<Area> {
+    let stream_tree = StreamTree::from_next(receivers.to_vec());
+    let mut drainage_areas = areas.to_vec();
+    (0..receivers.len())
+        .filter(|&i| receivers[i] == i)
+        .for_each(|outlet| {
+            let basin = DrainageBasin::construct(outlet, &stream_tree, adjacency);
+            let mut values = (0..basin.len())
+                .map(|k| areas[basin.site(k)])
+                .collect::<Vec<_>>();
+            basin.accumulate(&mut values, 1, usize::MAX);
+            basin.for_each_upstream(|k, i| drainage_areas[i] = values[k]);
+        });
+    drainage_areas
+}
+
+/// Sweep the areas of the sites down all the lower neighbors with the multiple flow directions (MFD),
+/// returning the drainage area of each site.
+///
+/// The sites are visited from the highest to the lowest, and each site passes its drainage area to its lower neighbors
+/// in proportion to `slope^exponent`, where the slope is the drop to the neighbor per the length of the edge.
+/// A larger `exponent` concentrates the flow to the steepest neighbor, approaching the single flow direction of [construct_stream_tree].
+/// The outlets and the sites without lower neighbors keep the areas they receive.
+pub fn sweep_multiple_flow_directions(
+    elevations: &[Elevation],
+    adjacency: &Adjacency,
+    outlets: &[usize],
+    areas: &[Area],
+    exponent: f64,
+) -> Vec<Area> {
+    let num = elevations.len();
+    let mut is_outlet = vec![false; num];
+    outlets.iter().for_each(|&i| is_outlet[i] = true);
+    let mut order = (0..num).collect::<Vec<_>>();
+    order.sort_by(|&i, &j| elevations[j].total_cmp(&elevations[i]));
+
+    let mut drainage_areas = areas.to_vec();
+    let mut slopes = Vec::new();
+    order.into_iter().for_each(|i| {
+        if is_outlet[i] {
+            return;
+        }
+        slopes.clear();
+        slopes.extend(
+            adjacency
+                .iter_neighbors(i)
+                .filter(|&(j, length)| elevations[j] < elevations[i] && length > 0.0)
+                .map(|(j, length)| (j, (elevations[i] - elevations[j]) / length)),
+        );
+        let max_slope = slopes.iter().fold(0.0, |a: f64, &(_, slope)| a.max(slope));
+        if max_slope <= 0.0 {
+            return;
+        }
+        // the slopes are normalized by the steepest one so that the weights do not overflow
+        slopes
+            .iter_mut()
+            .for_each(|(_, slope)| *slope = (*slope / max_slope).powf(exponent));
+        let total_weight = slopes.iter().map(|(_, weight)| weight).sum::<f64>();
+        let area = drainage_areas[i];
+        slopes.iter().for_each(|&(j, weight)| {
+            drainage_areas[j] += area * weight / total_weight;
+        });
+    });
+    drainage_areas
+}
+
+/// Update the elevations of the drainage basins along the receivers for an iteration, returning the new elevations.
+///
+/// The receivers must be the neighbors in `adjacency`, such as the result of [construct_stream_tree].
+/// If `time_step` is `None`, the elevations are the steady state for the stream tree. Otherwise, the elevations
+/// are eroded for the time step (see [crate::lem::generator::TerrainGenerator::set_time_step]).
+pub fn update_elevations(
+    elevations: &[Elevation],
+    receivers: &[usize],
+    areas: &[Area],
+    adjacency: &Adjacency,
+    parameters: &[TopographicalParameters],
+    time_step: Option<f64>,
+) -> Result<Vec<Elevation>, GenerationError> {
+    if parameters.len() != elevations.len() {
+        return Err(GenerationError::InvalidNumberOfParameters);
+    }
+    if receivers.len() != elevations.len() {
+        return Err(GenerationError::InvalidNumberOfReceivers);
+    }
+    if areas.len() != elevations.len() {
+        return Err(GenerationError::InvalidNumberOfAreas);
+    }
+    let config = SimulationConfig {
+        time_step,
+        ..Default::default()
+    };
+    let stream_tree = StreamTree::from_next(receivers.to_vec());
+    let (_, elevations) = solve_basins(
+        &config,
+        areas,
+        adjacency,
+        parameters,
+        elevations,
+        &stream_tree,
+    );
+    Ok(elevations)
+}
@@ -0,0 +1,233 @@
+use std::{
+    fmt,
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
+
+use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
+
+use crate::core::{
+    distance::DistanceField,
+    fields::SiteFields,
+    parameters::TopographicalParameters,
+    units::{Area, Elevation, Length, Step},
+};
+
+/// The state of the simulation passed to the processes at each iteration.
+///
+/// ### Properties
+///  - `step` is the number of the current iteration (starting from 1).
+///  - `time_step` is the duration of an iteration (unit: T). This is 1.0 if the time step is not set.
+///  - `areas` is the areas of each site.
+///  - `graph` is the graph representing the connections between sites.
+///  - `receivers` is the next site of each site in the flow (the stream tree). Outlets are their own receivers.
+///  - `fallback_distance` is the distance between the sites not connected by an edge of the graph (unit: L).
+///  - `drainage_areas` is the drainage area of each site (unit: L^2).
+///  - `elevations` is the elevation of each site (unit: L).
+///  - `parameters` is the topographical parameters of each site. Changes are applied from the next iteration.
+///  - `fields` is the set of additional fields, which are finally attached to the generated terrain.
+pub struct SimulationState<'a> {
+    pub step: Step,
+    pub time_step: f64,
+    pub areas: &'a [Area],
+    pub graph: &'a EdgeAttributedUndirectedGraph<Length>,
+    pub receivers: &'a [usize],
+    pub fallback_distance: Length,
+    pub drainage_areas: &'a [Area],
+    pub elevations: &'a mut [Elevation],
+    pub parameters: &'a mut [TopographicalParameters],
+    pub fields: &'a mut SiteFields,
+}
+
+impl SimulationState<'_> {
+    /// The number of sites.
+    pub fn num(&self) -> usize {
+        self.areas.len()
+    }
+
+    /// The sites within `max_distance` from `source` along the edges of the graph, with their distances (unit: L).
+    ///
+    /// The sites are returned in the order of the distance, starting from `source` itself.
+    pub fn sites_within(&self, source: usize, max_distance: Length) -> Vec<(usize, Length)> {
+        DistanceField::new(self.graph, &[source], max_distance).into_reached()
+    }
+}
+
+/// A geomorphic process applied after the fluvial erosion in each iteration of the simulation.
+///
+/// Processes are added to the generator with `TerrainGenerator::add_process` and applied in the order they were added.
+/// A process keeps its own state in `SimulationState::fields` so that the same process can be shared among generators.
+///
+/// Note that without `TerrainGenerator::set_time_step`, the elevations are recomputed from scratch in each iteration,
+/// so the changes of the elevations made by processes only affect the flow routing of the next iteration.
+pub trait Process: Send + Sync {
+    /// The name of the process.
+    fn name(&self) -> &str;
+
+    /// Apply the process to the state.
+    fn apply(&self, state: &mut SimulationState);
+
+    /// The maximum time step with which the process is stable (unit: T), such as the limit of an explicit diffusion scheme.
+    ///
+    /// If the time step of the simulation exceeds it, the process is applied in the equal sub-steps not exceeding it
+    /// within the iteration (at most [MAX_SUB_STEPS] sub-steps). If `None`, the process is applied once per iteration.
+    fn max_time_step(&self, _state: &SimulationState) -> Option<f64> {
+        None
+    }
+}
+
+/// The maximum number of the sub-steps of a process in an iteration.
+pub const MAX_SUB_STEPS: usize = 1000;
+
+/// Apply the process to the state in the sub-steps required by its [Process::max_time_step].
+pub(crate) fn apply_in_sub_steps(process: &dyn Process, state: &mut SimulationState) {
+    let time_step = state.time_step;
+    let num_sub_steps = match process.max_time_step(state) {
+        Some(max_time_step) if max_time_step > 0.0 => {
+            ((time_step / max_time_step).ceil() as usize).clamp(1, MAX_SUB_STEPS)
+        }
+        _ => 1,
+    };
+    state.time_step = time_step / num_sub_steps as f64;
+    (0..num_sub_steps).for_each(|_| process.apply(state));
+    state.time_step = time_step;
+}
+
+/// A process applied in sub-steps, configured independently from the other processes.
+///
+/// The time step of each sub-step is the smallest of the time step of the iteration divided by `num_sub_steps`,
+/// `max_time_step` and the stability limit of the wrapped process (see [Process::max_time_step]).
+/// Only the processes whose changes are proportional to the time step should be sub-stepped;
+/// the events like craters or eruptions would be repeated in each sub-step.
+///
+/// ### Properties
+///  - `num_sub_steps` is the minimum number of the sub-steps in an iteration. The default value is 1.
+///  - `max_time_step` is the maximum time step of a sub-step (unit: T). If `None`, only the limit of the wrapped process is used.
+#[derive(Debug, Clone)]
+pub struct SubSteppedProcess<P: Process> {
+    process: P,
+    num_sub_steps: usize,
+    max_time_step: Option<f64>,
+}
+
+impl<P: Process> SubSteppedProcess<P> {
+    pub fn new(process: P) -> Self {
+        Self {
+            process,
+            num_sub_steps: 1,
+            max_time_step: None,
+        }
+    }
+
+    pub fn set_num_sub_steps(mut self, num_sub_steps: usize) -> Self {
+        self.num_sub_steps = num_sub_steps.max(1);
+        self
+    }
+
+    pub fn set_max_time_step(mut self, max_time_step: Option<f64>) -> Self {
+        self.max_time_step = max_time_step;
+        self
+    }
+}
+
+impl<P: Process> Process for SubSteppedProcess<P> {
+    fn name(&self) -> &str {
+        self.process.name()
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        self.process.apply(state);
+    }
+
+    fn max_time_step(&self, state: &SimulationState) -> Option<f64> {
+        [
+            Some(state.time_step / self.num_sub_steps as f64),
+            self.max_time_step,
+            self.process.max_time_step(state),
+        ]
+        .into_iter()
+        .flatten()
+        .reduce(f64::min)
+    }
+}
+
+type ActivationFn = dyn Fn(Step) -> bool + Send + Sync;
+
+/// A process applied only in the iterations where it is active, to schedule the processes over time declaratively,
+/// such as a glaciation between the iterations 200 and 400.
+///
+/// The process is applied in an iteration if the iteration is in `active_steps` and `activation` holds for it.
+/// The fluvial erosion is not a process, so it cannot be scheduled; change the erodibilities instead.
+///
+/// ### Properties
+///  - `active_steps` is the range of the iterations (starting from 1) in which the process is applied. The default value is all the iterations.
+///  - `activation` is the predicate of the iteration to apply the process. If `None`, only `active_steps` is used.
+#[derive(Clone)]
+pub struct ScheduledProcess<P: Process> {
+    process: P,
+    active_steps: (Bound<Step>, Bound<Step>),
+    activation: Option<Arc<ActivationFn>>,
+}
+
+impl<P: Process> ScheduledProcess<P> {
+    pub fn new(process: P) -> Self {
+        Self {
+            process,
+            active_steps: (Bound::Unbounded, Bound::Unbounded),
+            activation: None,
+        }
+    }
+
+    pub fn set_active_steps(mut self, active_steps: impl RangeBounds<Step>) -> Self {
+        self.active_steps = (
+            active_steps.start_bound().cloned(),
+            active_steps.end_bound().cloned(),
+        );
+        self
+    }
+
+    pub fn set_activation(
+        mut self,
+        activation: impl Fn(Step) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.activation = Some(Arc::new(activation));
+        self
+    }
+
+    /// Whether the process is applied in the iteration.
+    pub fn is_active(&self, step: Step) -> bool {
+        self.active_steps.contains(&step)
+            && self
+                .activation
+                .as_ref()
+                .map(|activation| activation(step))
+                .unwrap_or(true)
+    }
+}
+
+impl<P: Process> Process for ScheduledProcess<P> {
+    fn name(&self) -> &str {
+        self.process.name()
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        if self.is_active(state.step) {
+            self.process.apply(state);
+        }
+    }
+
+    fn max_time_step(&self, state: &SimulationState) -> Option<f64> {
+        // the inactive process is not sub-stepped
+        if self.is_active(state.step) {
+            self.process.max_time_step(state)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Debug for dyn Process {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Process({})", self.name())
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    core::units::{Elevation, Step},
+    lem::process::{Process, SimulationState},
+};
+
+/// A history of the base level, which raises or lowers the outlets over time.
+///
+/// The schedule is a list of keyframes `(step, offset)`, where `offset` is the base level at the iteration `step`
+/// relative to the initial elevations of the outlets, which are regarded as being at the offset of the iteration 0.
+/// The offset is linearly interpolated between the keyframes, and held constant before the first keyframe and after the last one.
+/// Falling base levels propagate incision upstream as knickpoints, and rising ones cause aggradation.
+///
+/// This process is intended to be used with `TerrainGenerator::set_time_step`, and should be added before the processes
+/// which depend on the base level.
+///
+/// ### Properties
+///  - `schedule` is the list of keyframes of the offset of the base level (unit: L).
+#[derive(Debug, Clone, Default)]
+pub struct BaseLevelProcess {
+    schedule: Vec<(Step, Elevation)>,
+}
+
+impl BaseLevelProcess {
+    pub fn set_schedule(mut self, mut schedule: Vec<(Step, Elevation)>) -> Self {
+        schedule.sort_by_key(|&(step, _)| step);
+        self.schedule = schedule;
+        self
+    }
+
+    /// The offset of the base level at the iteration `step`.
+    pub fn offset(&self, step: Step) -> Elevation {
+        let next = self.schedule.iter().position(|&(s, _)| s > step);
+        match next {
+            Some(0) => self.schedule[0].1,
+            Some(k) => {
+                let (s0, offset0) = self.schedule[k - 1];
+                let (s1, offset1) = self.schedule[k];
+                let t = (step - s0) as f64 / (s1 - s0) as f64;
+                offset0 + (offset1 - offset0) * t
+            }
+            None => self
+                .schedule
+                .last()
+                .map(|&(_, offset)| offset)
+                .unwrap_or(0.0),
+        }
+    }
+}
+
+impl Process for BaseLevelProcess {
+    fn name(&self) -> &str {
+        "base_level"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        // the outlets are at the initial offset until the first iteration
+        let prev_offset = self.offset(state.step.saturating_sub(1));
+        let change = self.offset(state.step) - prev_offset;
+        if change == 0.0 {
+            return;
+        }
+        (0..state.num()).for_each(|i| {
+            if state.receivers[i] == i {
+                state.elevations[i] += change;
+            }
+        });
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    core::units::{Length, Step},
+    lem::process::{Process, SimulationState},
+};
+
+/// An impact crater stamped on the terrain.
+///
+/// The profile of the crater is a parabolic bowl from the floor at `-depth` to the rim at `rim_height`,
+/// surrounded by the ejecta blanket whose thickness falls off as `rim_height * (r / radius)^-3`
+/// up to `ejecta_range` times the radius.
+///
+/// ### Properties
+///  - `site` is the index of the center of the crater.
+///  - `step` is the iteration in which the impact occurs.
+///  - `diameter` is the diameter of the crater measured at the rim (unit: L).
+///  - `depth` is the depth of the floor below the original surface (unit: L). The default value is 0.2 times the diameter.
+///  - `rim_height` is the height of the rim above the original surface (unit: L). The default value is 0.04 times the diameter.
+///  - `ejecta_range` is the extent of the ejecta blanket relative to the radius. The default value is 3.0.
+#[derive(Debug, Clone)]
+pub struct Crater {
+    site: usize,
+    step: Step,
+    diameter: Length,
+    depth: Length,
+    rim_height: Length,
+    ejecta_range: f64,
+}
+
+impl Crater {
+    pub fn new(site: usize, step: Step, diameter: Length) -> Self {
+        Self {
+            site,
+            step,
+            diameter,
+            depth: diameter * 0.2,
+            rim_height: diameter * 0.04,
+            ejecta_range: 3.0,
+        }
+    }
+
+    pub fn set_depth(mut self, depth: Length) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    pub fn set_rim_height(mut self, rim_height: Length) -> Self {
+        self.rim_height = rim_height;
+        self
+    }
+
+    pub fn set_ejecta_range(mut self, ejecta_range: f64) -> Self {
+        self.ejecta_range = ejecta_range.max(1.0);
+        self
+    }
+
+    /// The change of the elevation at `distance` from the center.
+    fn profile(&self, distance: Length) -> Length {
+        let radius = self.diameter * 0.5;
+        if radius <= 0.0 {
+            return 0.0;
+        }
+        let r = distance / radius;
+        if r < 1.0 {
+            -self.depth + (self.depth + self.rim_height) * r * r
+        } else if r <= self.ejecta_range {
+            self.rim_height / (r * r * r)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Impact craters stamped at the given iterations, which are degraded by the simulation afterwards.
+///
+/// The distance from the center is measured along the edges of the graph (see `SimulationState::sites_within`),
+/// since the process does not know the positions of the sites. The path along the edges is slightly longer than
+/// the straight distance, so the crater is slightly narrower than the ideal one and its outline follows the graph.
+/// The outlets are not changed.
+///
+/// This process is intended to be used with `TerrainGenerator::set_time_step`.
+/// Without the time step, the craters only affect the flow routing since the elevations are recomputed in each iteration.
+///
+/// ### Properties
+///  - `craters` is the list of craters (see [Crater]).
+#[derive(Debug, Clone, Default)]
+pub struct CraterProcess {
+    craters: Vec<Crater>,
+}
+
+impl CraterProcess {
+    pub fn add_crater(mut self, crater: Crater) -> Self {
+        self.craters.push(crater);
+        self
+    }
+}
+
+impl Process for CraterProcess {
+    fn name(&self) -> &str {
+        "crater"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let (num, step) = (state.num(), state.step);
+        self.craters
+            .iter()
+            .filter(|crater| crater.step == step && crater.site < num)
+            .for_each(|crater| {
+                let extent = crater.diameter * 0.5 * crater.ejecta_range;
+                state
+                    .sites_within(crater.site, extent)
+                    .into_iter()
+                    .for_each(|(i, distance)| {
+                        // the outlets are fixed as the base level
+                        if state.receivers[i] != i {
+                            state.elevations[i] += crater.profile(distance);
+                        }
+                    });
+            });
+    }
+}
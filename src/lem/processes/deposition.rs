@@ -0,0 +1,306 @@
+use crate::{
+    core::{
+        fields::{DEPOSIT_THICKNESS, SEDIMENT_FLUX},
+        units::{Elevation, Length},
+    },
+    lem::{
+        process::{Process, SimulationState},
+        simulation::DEFAULT_M_EXP,
+    },
+};
+
+/// Deposition of sediment where the channels abruptly lose their transport capacity, forming alluvial fans and deltas.
+///
+/// In each iteration, the sediment eroded by the stream power `K * A^m * S` is carried down the stream tree.
+/// The transport capacity of each site is `capacity_coefficient * K * A^m * S * A`, the sediment the site could carry
+/// if its whole drainage area were eroded at its own rate. So the capacity drops below the supply from upstream
+/// where the slope decreases at the foot of mountain ranges, and vanishes where the channels enter the standing water
+/// at or below `sea_level`.
+/// The sediment exceeding the capacity is deposited, spreading radially over the sites within `spreading_distance`
+/// which are not higher than the site of deposition, except the outlets. This builds fans at range fronts.
+/// In the standing water, the deposits spread over the sites below the water level and fill them up to the level, building deltas.
+/// The deposits do not rise above the site of deposition on land or above the water level,
+/// and the sediment which cannot be deposited continues downstream, leaving the domain at the outlets.
+/// The accumulated thickness of the deposits is attached to the terrain as the field [DEPOSIT_THICKNESS],
+/// and the sediment carried out of each site in the last iteration as the field [SEDIMENT_FLUX].
+///
+/// If `source_regions` is set, the sediment is tagged by the region of the site where it was eroded,
+/// and the sediment is assumed to be well mixed in the flow. The thickness of the deposits from each region is attached
+/// as the field named by [DepositionProcess::provenance_field], the sediment from each region carried out of each site
+/// in the last iteration as the field named by [DepositionProcess::sediment_flux_field], and the accumulated volume of the sediment from each region
+/// which left the domain at each outlet as the field named by [DepositionProcess::exported_provenance_field] (unit: L^3, 0.0 except at the outlets).
+///
+/// This process is intended to be used with `TerrainGenerator::set_time_step`.
+///
+/// ### Properties
+///  - `capacity_coefficient` is the coefficient of the transport capacity. The default value is 1.0.
+///  - `spreading_distance` is the distance over which the deposits spread (unit: L). The default value is 5.0.
+///  - `sea_level` is the level of the standing water (unit: L). If `None`, only the outlets are regarded as the standing water.
+///  - `source_regions` is the index of the source region of each site, from 0 to the number of the regions - 1.
+///     If `None` or if its length differs from the number of the sites, the provenance is not tracked.
+#[derive(Debug, Clone)]
+pub struct DepositionProcess {
+    capacity_coefficient: f64,
+    spreading_distance: Length,
+    sea_level: Option<Elevation>,
+    source_regions: Option<Vec<usize>>,
+}
+
+impl Default for DepositionProcess {
+    fn default() -> Self {
+        Self {
+            capacity_coefficient: 1.0,
+            spreading_distance: 5.0,
+            sea_level: None,
+            source_regions: None,
+        }
+    }
+}
+
+impl DepositionProcess {
+    pub fn set_capacity_coefficient(mut self, capacity_coefficient: f64) -> Self {
+        self.capacity_coefficient = capacity_coefficient;
+        self
+    }
+
+    pub fn set_spreading_distance(mut self, spreading_distance: Length) -> Self {
+        self.spreading_distance = spreading_distance;
+        self
+    }
+
+    pub fn set_sea_level(mut self, sea_level: Option<Elevation>) -> Self {
+        self.sea_level = sea_level;
+        self
+    }
+
+    pub fn set_source_regions(mut self, source_regions: Option<Vec<usize>>) -> Self {
+        self.source_regions = source_regions;
+        self
+    }
+
+    /// The name of the field of the accumulated thickness of the deposits from the source region (unit: L).
+    pub fn provenance_field(region: usize) -> String {
+        format!("provenance_{}", region)
+    }
+
+    /// The name of the field of the volume of the sediment from the source region carried out of each site in the last iteration (unit: L^3).
+    pub fn sediment_flux_field(region: usize) -> String {
+        format!("sediment_flux_{}", region)
+    }
+
+    /// The name of the field of the accumulated volume of the sediment from the source region which left the domain at each outlet (unit: L^3).
+    pub fn exported_provenance_field(region: usize) -> String {
+        format!("exported_provenance_{}", region)
+    }
+
+    /// Spread the deposit of `volume` from the site `i` radially over the lower sites except the outlets, and return the volume which could not be deposited.
+    ///
+    /// The deposits fill the sites up to the level of the site `i` on land, or up to the water level in the standing water.
+    /// The thickness of the deposit on each site is pushed to `deposits`.
+    fn spread(
+        &self,
+        state: &mut SimulationState,
+        thickness: &mut [f64],
+        deposits: &mut Vec<(usize, f64)>,
+        i: usize,
+        volume: f64,
+        in_water: bool,
+    ) -> f64 {
+        let level = if in_water {
+            self.sea_level.unwrap_or(state.elevations[i])
+        } else {
+            state.elevations[i]
+        };
+        let targets = state
+            .sites_within(i, self.spreading_distance)
+            .into_iter()
+            // the outlets are the boundary of the domain, so the sediment reaching them leaves it instead of being deposited
+            .filter(|&(j, _)| state.receivers[j] != j && state.elevations[j] < level)
+            .map(|(j, distance)| {
+                let weight = 1.0 - distance / self.spreading_distance.max(f64::EPSILON);
+                (j, weight.max(f64::EPSILON) * state.areas[j])
+            })
+            .collect::<Vec<_>>();
+        let total_weight = targets.iter().map(|(_, weight)| weight).sum::<f64>();
+        let mut remaining = volume;
+        targets.into_iter().for_each(|(j, weight)| {
+            let deposit =
+                (volume * weight / total_weight / state.areas[j]).min(level - state.elevations[j]);
+            state.elevations[j] += deposit;
+            thickness[j] += deposit;
+            deposits.push((j, deposit));
+            remaining -= deposit * state.areas[j];
+        });
+        remaining.max(0.0)
+    }
+}
+
+impl Process for DepositionProcess {
+    fn name(&self) -> &str {
+        "deposition"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let num = state.num();
+        let time_step = state.time_step;
+
+        // sort the sites from upstream to downstream
+        let order = {
+            let mut num_donors = vec![0; num];
+            (0..num).for_each(|i| {
+                let j = state.receivers[i];
+                if j != i {
+                    num_donors[j] += 1;
+                }
+            });
+            let mut order = (0..num).filter(|&i| num_donors[i] == 0).collect::<Vec<_>>();
+            let mut k = 0;
+            while k < order.len() {
+                let i = order[k];
+                let j = state.receivers[i];
+                if j != i {
+                    num_donors[j] -= 1;
+                    if num_donors[j] == 0 {
+                        order.push(j);
+                    }
+                }
+                k += 1;
+            }
+            order
+        };
+
+        // carry the sediment downstream and deposit where it exceeds the capacity
+        let mut thickness = std::mem::take(state.fields.get_or_insert(DEPOSIT_THICKNESS, num));
+        let mut fluxes = vec![0.0; num];
+        let mut deposits = Vec::new();
+
+        // the sediment flux from each source region
+        // the regions are ignored unless they are given for all the sites
+        let source_regions = self
+            .source_regions
+            .as_ref()
+            .filter(|regions| regions.len() == num);
+        let num_regions = source_regions
+            .map(|regions| regions.iter().map(|&r| r + 1).max().unwrap_or(0))
+            .unwrap_or(0);
+        let mut region_fluxes = vec![vec![0.0; num_regions]; num];
+        let mut provenances = (0..num_regions)
+            .map(|r| std::mem::take(state.fields.get_or_insert(&Self::provenance_field(r), num)))
+            .collect::<Vec<_>>();
+        let mut exported_provenances = (0..num_regions)
+            .map(|r| {
+                std::mem::take(
+                    state
+                        .fields
+                        .get_or_insert(&Self::exported_provenance_field(r), num),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for i in order {
+            let j = state.receivers[i];
+            let in_water = j == i
+                || self
+                    .sea_level
+                    .map(|sea_level| state.elevations[i] <= sea_level)
+                    .unwrap_or(false);
+            // the stream power per unit area
+            let stream_power = if in_water {
+                0.0
+            } else {
+                let distance = state
+                    .graph
+                    .neighbors_of(i)
+                    .iter()
+                    .find(|ja| ja.0 == j)
+                    .map(|ja| ja.1)
+                    .unwrap_or(state.fallback_distance);
+                let slope = ((state.elevations[i] - state.elevations[j]) / distance).max(0.0);
+                state.parameters[i].erodibility
+                    * state.drainage_areas[i].powf(DEFAULT_M_EXP)
+                    * slope
+            };
+            let eroded = stream_power * state.areas[i] * time_step;
+            let flux = fluxes[i] + eroded;
+            let capacity =
+                self.capacity_coefficient * stream_power * state.drainage_areas[i] * time_step;
+            fluxes[i] = flux.min(capacity);
+            deposits.clear();
+            if flux > capacity {
+                let remaining = self.spread(
+                    state,
+                    &mut thickness,
+                    &mut deposits,
+                    i,
+                    flux - capacity,
+                    in_water,
+                );
+                fluxes[i] += remaining;
+            }
+
+            if let Some(source_regions) = source_regions {
+                region_fluxes[i][source_regions[i]] += eroded;
+                // the deposits and the remaining flux have the mixture of the flux
+                let fractions = region_fluxes[i]
+                    .iter()
+                    .map(|&region_flux| if flux > 0.0 { region_flux / flux } else { 0.0 })
+                    .collect::<Vec<_>>();
+                deposits.iter().for_each(|&(k, deposit)| {
+                    fractions.iter().enumerate().for_each(|(r, &fraction)| {
+                        provenances[r][k] += deposit * fraction;
+                    });
+                });
+                region_fluxes[i] = fractions.iter().map(|&f| f * fluxes[i]).collect();
+                if j == i {
+                    region_fluxes[i]
+                        .iter()
+                        .enumerate()
+                        .for_each(|(r, &region_flux)| {
+                            exported_provenances[r][i] += region_flux;
+                        });
+                } else {
+                    let (upstream, downstream) = if i < j {
+                        let (a, b) = region_fluxes.split_at_mut(j);
+                        (&a[i], &mut b[0])
+                    } else {
+                        let (a, b) = region_fluxes.split_at_mut(i);
+                        (&b[0], &mut a[j])
+                    };
+                    downstream
+                        .iter_mut()
+                        .zip(upstream.iter())
+                        .for_each(|(d, u)| *d += u);
+                }
+            }
+
+            // the sediment reaching the outlets leaves the domain
+            if j != i {
+                fluxes[j] += fluxes[i];
+            }
+        }
+        *state.fields.get_or_insert(DEPOSIT_THICKNESS, num) = thickness;
+        *state.fields.get_or_insert(SEDIMENT_FLUX, num) = fluxes;
+        (0..num_regions).for_each(|r| {
+            *state
+                .fields
+                .get_or_insert(&Self::sediment_flux_field(r), num) = region_fluxes
+                .iter()
+                .map(|region_flux| region_flux[r])
+                .collect();
+        });
+        provenances
+            .into_iter()
+            .enumerate()
+            .for_each(|(r, provenance)| {
+                *state.fields.get_or_insert(&Self::provenance_field(r), num) = provenance;
+            });
+        exported_provenances
+            .into_iter()
+            .enumerate()
+            .for_each(|(r, exported)| {
+                *state
+                    .fields
+                    .get_or_insert(&Self::exported_provenance_field(r), num) = exported;
+            });
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{
+    core::fields::DIFFUSIVITY_FACTOR,
+    lem::{
+        process::{Process, SimulationState},
+        processes::regolith::FACE_LENGTH_RATIO,
+    },
+};
+
+/// Linear diffusion of the surface by hillslope processes (creep, rain splash, etc.), rounding the peaks and the ridges.
+///
+/// Unlike [crate::lem::processes::regolith::RegolithProcess], the whole surface is transported regardless of the soil.
+/// The volume moved across each edge is proportional to the slope, and the outlets are fixed as the base level.
+/// The diffusivity of each site is multiplied by the field [DIFFUSIVITY_FACTOR] if it exists.
+/// The diffusion is explicit, so the process is applied in sub-steps if the time step exceeds its stability limit.
+/// This process is intended to be used with `TerrainGenerator::set_time_step`.
+///
+/// ### Properties
+///  - `diffusivity` is the diffusivity of the surface (unit: L^2/T). The default value is 0.01.
+#[derive(Debug, Clone)]
+pub struct HillslopeDiffusionProcess {
+    diffusivity: f64,
+}
+
+impl Default for HillslopeDiffusionProcess {
+    fn default() -> Self {
+        Self { diffusivity: 0.01 }
+    }
+}
+
+impl HillslopeDiffusionProcess {
+    pub fn set_diffusivity(mut self, diffusivity: f64) -> Self {
+        self.diffusivity = diffusivity;
+        self
+    }
+
+    /// The diffusivity of each site multiplied by [DIFFUSIVITY_FACTOR].
+    fn diffusivities(&self, state: &SimulationState) -> Vec<f64> {
+        match state.fields.get(DIFFUSIVITY_FACTOR) {
+            Some(factor) => factor.iter().map(|f| self.diffusivity * f).collect(),
+            None => vec![self.diffusivity; state.num()],
+        }
+    }
+}
+
+impl Process for HillslopeDiffusionProcess {
+    fn name(&self) -> &str {
+        "hillslope_diffusion"
+    }
+
+    fn max_time_step(&self, state: &SimulationState) -> Option<f64> {
+        // the explicit diffusion is stable if no site loses more than half of its excess height in a step
+        let diffusivities = self.diffusivities(state);
+        (0..state.num())
+            .map(|i| {
+                let conductance =
+                    diffusivities[i] * FACE_LENGTH_RATIO * state.graph.neighbors_of(i).len() as f64;
+                0.5 * state.areas[i] / conductance
+            })
+            .filter(|limit| limit.is_finite() && *limit > 0.0)
+            .reduce(f64::min)
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let num = state.num();
+        let diffusivities = self.diffusivities(state);
+        let mut change = vec![0.0; num];
+        (0..num).for_each(|i| {
+            state.graph.neighbors_of(i).iter().for_each(|ja| {
+                let (j, distance) = (ja.0, ja.1);
+                let slope = (state.elevations[i] - state.elevations[j]) / distance;
+                if slope > 0.0 {
+                    let volume =
+                        diffusivities[i] * slope * distance * FACE_LENGTH_RATIO * state.time_step;
+                    change[i] -= volume / state.areas[i];
+                    change[j] += volume / state.areas[j];
+                }
+            });
+        });
+        (0..num).for_each(|i| {
+            // the sediment delivered to outlets leaves the domain
+            if state.receivers[i] != i {
+                state.elevations[i] += change[i];
+            }
+        });
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{
+    core::{fields::FAULT_THROW, units::UpliftRate},
+    lem::process::{Process, SimulationState},
+};
+
+/// A fault with progressive slip, which raises the hanging wall relative to the footwall.
+///
+/// The hanging wall is given as a mask of the sites, such as the side of the trace of the fault
+/// (see `FaultTrace2D::hanging_wall` for the 2D surface model). It is uplifted by `slip_rate` in each unit of time
+/// in addition to the uplift rate, creating a growing scarp along the trace and offsetting the drainages crossing it.
+/// The outlets are not uplifted. The accumulated throw of each site is attached to the terrain as the field [FAULT_THROW].
+///
+/// This process is intended to be used with `TerrainGenerator::set_time_step`.
+///
+/// ### Properties
+///  - `hanging_wall` is whether each site is on the hanging wall. The sites beyond its length are on the footwall.
+///  - `slip_rate` is the vertical slip rate of the hanging wall (unit: L/T).
+#[derive(Debug, Clone)]
+pub struct FaultProcess {
+    hanging_wall: Vec<bool>,
+    slip_rate: UpliftRate,
+}
+
+impl FaultProcess {
+    pub fn new(hanging_wall: Vec<bool>, slip_rate: UpliftRate) -> Self {
+        Self {
+            hanging_wall,
+            slip_rate,
+        }
+    }
+
+    /// Whether each site is on the hanging wall.
+    pub fn hanging_wall(&self) -> &[bool] {
+        &self.hanging_wall
+    }
+}
+
+impl Process for FaultProcess {
+    fn name(&self) -> &str {
+        "fault"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let num = state.num();
+        let slip = self.slip_rate * state.time_step;
+        let mut throw = std::mem::take(state.fields.get_or_insert(FAULT_THROW, num));
+
+        (0..num).for_each(|i| {
+            // the outlets are fixed as the base level
+            if self.hanging_wall.get(i).copied().unwrap_or(false) && state.receivers[i] != i {
+                state.elevations[i] += slip;
+                throw[i] += slip;
+            }
+        });
+
+        *state.fields.get_or_insert(FAULT_THROW, num) = throw;
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{
+    core::{fields::ICE, units::Elevation},
+    lem::process::{Process, SimulationState},
+};
+
+/// Permanent snow and ice above the snow line.
+///
+/// The temperature of each site is `sea_level_temperature - lapse_rate * (elevation - sea_level)`,
+/// and the sites below the freezing point are covered by ice, which is attached to the terrain as the field [ICE].
+/// The snow line is at the elevation `sea_level + sea_level_temperature / lapse_rate`.
+///
+/// The erodibility of the sites covered by ice is multiplied by `erodibility_factor`, so that the fluvial erosion
+/// is suppressed (or enhanced if it is greater than 1.0) above the snow line. With the default factor 1.0, the process only outputs the mask.
+///
+/// ### Properties
+///  - `sea_level` is the sea level (unit: L). The default value is 0.0.
+///  - `sea_level_temperature` is the temperature at the sea level (unit: °C). The default value is 15.0.
+///  - `lapse_rate` is the decrease of the temperature per unit elevation (unit: °C/L). The default value is 0.0065.
+///  - `erodibility_factor` is the factor multiplied to the erodibility of the sites covered by ice. The default value is 1.0.
+#[derive(Debug, Clone)]
+pub struct IceProcess {
+    sea_level: Elevation,
+    sea_level_temperature: f64,
+    lapse_rate: f64,
+    erodibility_factor: f64,
+}
+
+impl Default for IceProcess {
+    fn default() -> Self {
+        Self {
+            sea_level: 0.0,
+            sea_level_temperature: 15.0,
+            lapse_rate: 0.0065,
+            erodibility_factor: 1.0,
+        }
+    }
+}
+
+impl IceProcess {
+    pub fn set_sea_level(mut self, sea_level: Elevation) -> Self {
+        self.sea_level = sea_level;
+        self
+    }
+
+    pub fn set_sea_level_temperature(mut self, sea_level_temperature: f64) -> Self {
+        self.sea_level_temperature = sea_level_temperature;
+        self
+    }
+
+    pub fn set_lapse_rate(mut self, lapse_rate: f64) -> Self {
+        self.lapse_rate = lapse_rate;
+        self
+    }
+
+    pub fn set_erodibility_factor(mut self, erodibility_factor: f64) -> Self {
+        self.erodibility_factor = erodibility_factor.max(f64::EPSILON);
+        self
+    }
+
+    /// The elevation of the snow line (unit: L). If the lapse rate is not positive, this is `None`.
+    pub fn snow_line(&self) -> Option<Elevation> {
+        if self.lapse_rate > 0.0 {
+            Some(self.sea_level + self.sea_level_temperature / self.lapse_rate)
+        } else {
+            None
+        }
+    }
+}
+
+impl Process for IceProcess {
+    fn name(&self) -> &str {
+        "ice"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let num = state.num();
+        let mut ice = std::mem::take(state.fields.get_or_insert(ICE, num));
+        let snow_line = self.snow_line();
+
+        (0..num).for_each(|i| {
+            let covered = snow_line
+                .map(|snow_line| state.elevations[i] > snow_line)
+                .unwrap_or(self.sea_level_temperature < 0.0);
+            let prev_covered = ice[i] > 0.0;
+            if covered != prev_covered {
+                // the erodibility is rescaled when the cover changes so that the base erodibility does not need to be kept
+                if covered {
+                    state.parameters[i].erodibility *= self.erodibility_factor;
+                } else {
+                    state.parameters[i].erodibility /= self.erodibility_factor;
+                }
+            }
+            ice[i] = if covered { 1.0 } else { 0.0 };
+        });
+
+        *state.fields.get_or_insert(ICE, num) = ice;
+    }
+}
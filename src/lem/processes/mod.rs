@@ -0,0 +1,14 @@
+//! Module `processes` provides the built-in geomorphic processes which can be added to `TerrainGenerator` (see [crate::lem::process::Process]).
+pub mod base_level;
+pub mod crater;
+pub mod deposition;
+pub mod diffusion;
+pub mod fault;
+pub mod ice;
+pub mod plate;
+pub mod regolith;
+pub mod surface_age;
+pub mod terrace;
+pub mod uplift_noise;
+pub mod vegetation;
+pub mod volcano;
@@ -0,0 +1,243 @@
+use crate::{
+    core::{
+        fields::{PLATE, TECTONIC_UPLIFT},
+        traits::Site,
+        units::{Length, UpliftRate},
+    },
+    lem::process::{Process, SimulationState},
+    models::surface::sites::Site2D,
+};
+
+/// A rigid plate moving on the plane.
+///
+/// The velocity of the plate at the position `p` is `velocity + angular_velocity * k × (p - pole)`,
+/// i.e. the translation plus the rotation around the pole (the 2D counterpart of an Euler pole).
+///
+/// ### Properties
+///  - `seed` is the initial position of the reference point of the plate. Each site belongs to the plate with the nearest reference point.
+///  - `velocity` is the translation velocity of the plate (unit: L/T). The default value is (0.0, 0.0).
+///  - `pole` is the center of the rotation. The default value is the seed.
+///  - `angular_velocity` is the counterclockwise angular velocity around the pole (unit: rad/T). The default value is 0.0.
+#[derive(Debug, Clone)]
+pub struct Plate {
+    seed: Site2D,
+    velocity: (f64, f64),
+    pole: Site2D,
+    angular_velocity: f64,
+}
+
+impl Plate {
+    pub fn new(seed: Site2D) -> Self {
+        Self {
+            seed,
+            velocity: (0.0, 0.0),
+            pole: seed,
+            angular_velocity: 0.0,
+        }
+    }
+
+    pub fn set_velocity(mut self, vx: f64, vy: f64) -> Self {
+        self.velocity = (vx, vy);
+        self
+    }
+
+    pub fn set_rotation(mut self, pole: Site2D, angular_velocity: f64) -> Self {
+        self.pole = pole;
+        self.angular_velocity = angular_velocity;
+        self
+    }
+
+    /// The velocity of the plate at the position (unit: L/T).
+    pub fn velocity_at(&self, site: &Site2D) -> (f64, f64) {
+        (
+            self.velocity.0 - self.angular_velocity * (site.y - self.pole.y),
+            self.velocity.1 + self.angular_velocity * (site.x - self.pole.x),
+        )
+    }
+
+    /// The position of the reference point of the plate at the time.
+    pub fn seed_at(&self, time: f64) -> Site2D {
+        let omega = self.angular_velocity;
+        if omega == 0.0 {
+            return Site2D {
+                x: self.seed.x + self.velocity.0 * time,
+                y: self.seed.y + self.velocity.1 * time,
+            };
+        }
+        // the rigid motion with the rotation is the pure rotation around the shifted center
+        let center = Site2D {
+            x: self.pole.x - self.velocity.1 / omega,
+            y: self.pole.y + self.velocity.0 / omega,
+        };
+        let (sin, cos) = (omega * time).sin_cos();
+        let (dx, dy) = (self.seed.x - center.x, self.seed.y - center.y);
+        Site2D {
+            x: center.x + cos * dx - sin * dy,
+            y: center.y + sin * dx + cos * dy,
+        }
+    }
+}
+
+/// The uplift derived from the kinematics of rigid plates, instead of the hand-painted uplift rates.
+///
+/// In each iteration, each site is assigned to the plate whose reference point is the nearest at the time
+/// (the plates move with their velocities as the time elapses), and takes the velocity of the plate.
+/// The divergence of the velocities is estimated from the neighbors of each site, which is 0.0 in the interior of
+/// the plates and concentrated at their boundaries. The sites on the convergent boundaries are uplifted by
+/// `-divergence * thickening_length`, and the divergent boundaries are subsided by the same rate, as long as
+/// the total uplift rate is not negative.
+///
+/// The uplift rate added by the plates is attached to the terrain as the field [TECTONIC_UPLIFT] (unit: L/T),
+/// and is removed before the next iteration so that the uplift rates do not accumulate.
+/// The plate of each site is attached to the terrain as the field [PLATE].
+///
+/// This process is intended to be used with `TerrainGenerator::set_time_step`, and only supports the 2D model.
+///
+/// ### Properties
+///  - `thickening_length` is the uplift rate per the rate of the convergence (unit: L). The default value is 10.0.
+///  - `boundary_width` is the width over which the uplift of the boundaries is spread (unit: L).
+///     If `None`, the uplift is concentrated on the sites along the boundaries. The default value is `None`.
+#[derive(Debug, Clone)]
+pub struct PlateProcess {
+    sites: Vec<Site2D>,
+    plates: Vec<Plate>,
+    thickening_length: Length,
+    boundary_width: Option<Length>,
+}
+
+impl PlateProcess {
+    /// Create the process from the sites of the model and the plates.
+    pub fn new(sites: &[Site2D], plates: Vec<Plate>) -> Self {
+        Self {
+            sites: sites.to_vec(),
+            plates,
+            thickening_length: 10.0,
+            boundary_width: None,
+        }
+    }
+
+    pub fn set_thickening_length(mut self, thickening_length: Length) -> Self {
+        self.thickening_length = thickening_length;
+        self
+    }
+
+    pub fn set_boundary_width(mut self, boundary_width: Option<Length>) -> Self {
+        self.boundary_width = boundary_width;
+        self
+    }
+
+    /// The plate which each site belongs to at the time.
+    pub fn plates_at(&self, time: f64) -> Vec<usize> {
+        let seeds = self
+            .plates
+            .iter()
+            .map(|plate| plate.seed_at(time))
+            .collect::<Vec<_>>();
+        self.sites
+            .iter()
+            .map(|site| {
+                seeds
+                    .iter()
+                    .enumerate()
+                    .map(|(p, seed)| (p, site.squared_distance(seed)))
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(p, _)| p)
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    /// The divergence of the velocities of the plates at each site (unit: 1/T).
+    fn divergences(&self, state: &SimulationState, plates: &[usize]) -> Vec<f64> {
+        let velocities = self
+            .sites
+            .iter()
+            .zip(plates)
+            .map(|(site, &p)| self.plates[p].velocity_at(site))
+            .collect::<Vec<_>>();
+        (0..state.num())
+            .map(|i| {
+                let neighbors = state.graph.neighbors_of(i);
+                if neighbors.is_empty() {
+                    return 0.0;
+                }
+                // the mean of the directional derivatives along the edges is the half of the divergence
+                let sum = neighbors
+                    .iter()
+                    .filter(|ja| ja.1 > 0.0)
+                    .map(|&(j, length)| {
+                        let (ex, ey) = (
+                            (self.sites[j].x - self.sites[i].x) / length,
+                            (self.sites[j].y - self.sites[i].y) / length,
+                        );
+                        ((velocities[j].0 - velocities[i].0) * ex
+                            + (velocities[j].1 - velocities[i].1) * ey)
+                            / length
+                    })
+                    .sum::<f64>();
+                2.0 * sum / neighbors.len() as f64
+            })
+            .collect()
+    }
+
+    /// Spread the values over `boundary_width` with the Gaussian kernel, keeping their sum.
+    fn spread(&self, state: &SimulationState, values: Vec<f64>) -> Vec<f64> {
+        let width = match self.boundary_width {
+            Some(width) if width > 0.0 => width,
+            _ => return values,
+        };
+        let mut spread = vec![0.0; values.len()];
+        values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value != 0.0)
+            .for_each(|(i, value)| {
+                let weights = state
+                    .sites_within(i, width * 2.0)
+                    .into_iter()
+                    .map(|(j, distance)| {
+                        let r = distance / width;
+                        (j, (-0.5 * r * r).exp())
+                    })
+                    .collect::<Vec<_>>();
+                let total = weights.iter().map(|(_, w)| w).sum::<f64>();
+                weights.into_iter().for_each(|(j, w)| {
+                    spread[j] += value * w / total;
+                });
+            });
+        spread
+    }
+}
+
+impl Process for PlateProcess {
+    fn name(&self) -> &str {
+        "plate"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let num = state.num();
+        if self.plates.is_empty() || self.sites.len() != num {
+            return;
+        }
+        let time = state.step as f64 * state.time_step;
+        let plates = self.plates_at(time);
+        let rates: Vec<UpliftRate> = self.spread(
+            state,
+            self.divergences(state, &plates)
+                .into_iter()
+                .map(|divergence| -divergence * self.thickening_length)
+                .collect(),
+        );
+
+        let mut tectonic_uplift = std::mem::take(state.fields.get_or_insert(TECTONIC_UPLIFT, num));
+        (0..num).for_each(|i| {
+            let uplift_rate = state.parameters[i].uplift_rate - tectonic_uplift[i];
+            tectonic_uplift[i] = rates[i].max(-uplift_rate);
+            state.parameters[i].uplift_rate = uplift_rate + tectonic_uplift[i];
+        });
+        state.fields.insert(TECTONIC_UPLIFT, tectonic_uplift);
+        state
+            .fields
+            .insert(PLATE, plates.into_iter().map(|p| p as f64).collect());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{
+    core::{
+        fields::{DIFFUSIVITY_FACTOR, SOIL_THICKNESS},
+        units::Area,
+    },
+    lem::process::{Process, SimulationState},
+};
+
+/// The ratio of the length of the shared face of two adjacent Voronoi cells to the distance between their sites,
+/// assuming the cells are approximately regular hexagons.
+pub(crate) const FACE_LENGTH_RATIO: f64 = 0.577_350_269_189_625_8;
+
+/// A two-layer model tracking the thickness of the soil (regolith) on top of the bedrock.
+///
+/// In each iteration:
+///  1. Soil is produced from the bedrock at the rate `production_rate * exp(-h / production_depth)` where `h` is the soil thickness.
+///     The surface does not move since the bedrock is converted into soil.
+///  2. Soil creeps downslope by linear diffusion with `diffusivity`. Only the existing soil can be transported.
+///     The diffusivity of each site is multiplied by the field [DIFFUSIVITY_FACTOR] if it exists.
+///  3. Soil slides down to the lower neighbor wherever the slope exceeds `critical_slope` (landslides).
+///  4. Soil is stripped from the channels, the sites whose drainage area exceeds `channel_area`, by fluvial incision.
+///
+/// As a result, gentle hillslopes are mantled by soil while steep peaks and channels expose the bare bedrock.
+/// The soil thickness is attached to the terrain as the field [SOIL_THICKNESS].
+/// The creep is explicit, so the process is applied in sub-steps if the time step exceeds its stability limit.
+/// This process is intended to be used with `TerrainGenerator::set_time_step`.
+///
+/// ### Properties
+///  - `production_rate` is the maximum rate of soil production on bare bedrock (unit: L/T). The default value is 0.01.
+///  - `production_depth` is the characteristic depth of the exponential decline of the production (unit: L). The default value is 0.5.
+///  - `diffusivity` is the diffusivity of soil creep (unit: L^2/T). The default value is 0.01.
+///  - `critical_slope` is the slope above which soil slides down (unit: rad). The default value is π/6.
+///  - `channel_area` is the drainage area above which soil is stripped (unit: L^2). If `None`, soil is not stripped.
+#[derive(Debug, Clone)]
+pub struct RegolithProcess {
+    production_rate: f64,
+    production_depth: f64,
+    diffusivity: f64,
+    critical_slope: f64,
+    channel_area: Option<Area>,
+}
+
+impl Default for RegolithProcess {
+    fn default() -> Self {
+        Self {
+            production_rate: 0.01,
+            production_depth: 0.5,
+            diffusivity: 0.01,
+            critical_slope: std::f64::consts::FRAC_PI_6,
+            channel_area: None,
+        }
+    }
+}
+
+impl RegolithProcess {
+    pub fn set_production_rate(mut self, production_rate: f64) -> Self {
+        self.production_rate = production_rate;
+        self
+    }
+
+    pub fn set_production_depth(mut self, production_depth: f64) -> Self {
+        self.production_depth = production_depth;
+        self
+    }
+
+    pub fn set_diffusivity(mut self, diffusivity: f64) -> Self {
+        self.diffusivity = diffusivity;
+        self
+    }
+
+    pub fn set_critical_slope(mut self, critical_slope: f64) -> Self {
+        self.critical_slope = critical_slope;
+        self
+    }
+
+    pub fn set_channel_area(mut self, channel_area: Option<Area>) -> Self {
+        self.channel_area = channel_area;
+        self
+    }
+}
+
+impl Process for RegolithProcess {
+    fn name(&self) -> &str {
+        "regolith"
+    }
+
+    fn max_time_step(&self, state: &SimulationState) -> Option<f64> {
+        // the explicit diffusion is stable if no site loses more than half of its excess height in a step
+        let diffusivity_factor = state.fields.get(DIFFUSIVITY_FACTOR);
+        (0..state.num())
+            .map(|i| {
+                let diffusivity =
+                    self.diffusivity * diffusivity_factor.map(|f| f[i]).unwrap_or(1.0);
+                let conductance =
+                    diffusivity * FACE_LENGTH_RATIO * state.graph.neighbors_of(i).len() as f64;
+                0.5 * state.areas[i] / conductance
+            })
+            .filter(|limit| limit.is_finite() && *limit > 0.0)
+            .reduce(f64::min)
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let num = state.num();
+        let time_step = state.time_step;
+        let mut soil = std::mem::take(state.fields.get_or_insert(SOIL_THICKNESS, num));
+
+        // produce soil from the bedrock
+        soil.iter_mut().for_each(|h| {
+            *h += self.production_rate * (-*h / self.production_depth).exp() * time_step;
+        });
+
+        // transport soil by creep: the volume moved across each edge in the time step
+        let diffusivity_factor = state.fields.get(DIFFUSIVITY_FACTOR);
+        let mut outflow = vec![0.0; num];
+        let mut fluxes = Vec::new();
+        (0..num).for_each(|i| {
+            state.graph.neighbors_of(i).iter().for_each(|ja| {
+                let (j, distance) = (ja.0, ja.1);
+                let slope = (state.elevations[i] - state.elevations[j]) / distance;
+                if slope > 0.0 {
+                    let diffusivity =
+                        self.diffusivity * diffusivity_factor.map(|f| f[i]).unwrap_or(1.0);
+                    let volume = diffusivity * slope * distance * FACE_LENGTH_RATIO * time_step;
+                    outflow[i] += volume;
+                    fluxes.push((i, j, volume));
+                }
+            });
+        });
+        let mut change = vec![0.0; num];
+        fluxes.into_iter().for_each(|(i, j, volume)| {
+            // only the existing soil can be transported
+            let available = soil[i] * state.areas[i];
+            let volume = if outflow[i] > available {
+                volume * available / outflow[i]
+            } else {
+                volume
+            };
+            change[i] -= volume / state.areas[i];
+            change[j] += volume / state.areas[j];
+        });
+        (0..num).for_each(|i| {
+            // soil delivered to outlets leaves the domain
+            if state.receivers[i] == i {
+                soil[i] = 0.0;
+                return;
+            }
+            soil[i] = (soil[i] + change[i]).max(0.0);
+            state.elevations[i] += change[i];
+        });
+
+        // slide soil down where the slope is over the critical slope
+        let max_gradient = self.critical_slope.tan();
+        (0..num).for_each(|i| {
+            let j = state.receivers[i];
+            if j == i || soil[i] <= 0.0 {
+                return;
+            }
+            let distance = state
+                .graph
+                .neighbors_of(i)
+                .iter()
+                .find(|ja| ja.0 == j)
+                .map(|ja| ja.1)
+                .unwrap_or(state.fallback_distance);
+            let excess = state.elevations[i] - state.elevations[j] - max_gradient * distance;
+            if excess <= 0.0 {
+                return;
+            }
+            // the volume to bring the slope back to critical, limited by the existing soil
+            let (area_i, area_j) = (state.areas[i], state.areas[j]);
+            let volume = (excess * area_i * area_j / (area_i + area_j)).min(soil[i] * area_i);
+            soil[i] = (soil[i] - volume / area_i).max(0.0);
+            state.elevations[i] -= volume / area_i;
+            if state.receivers[j] != j {
+                soil[j] += volume / area_j;
+                state.elevations[j] += volume / area_j;
+            }
+        });
+
+        // strip soil from the channels
+        if let Some(channel_area) = self.channel_area {
+            (0..num).for_each(|i| {
+                if state.drainage_areas[i] > channel_area {
+                    state.elevations[i] -= soil[i];
+                    soil[i] = 0.0;
+                }
+            });
+        }
+
+        *state.fields.get_or_insert(SOIL_THICKNESS, num) = soil;
+    }
+}
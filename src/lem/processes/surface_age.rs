@@ -0,0 +1,73 @@
+use crate::{
+    core::{
+        fields::{SURFACE_AGE, SURFACE_LEVEL},
+        units::Elevation,
+    },
+    lem::process::{Process, SimulationState},
+};
+
+/// Tracking of the surface age, the time elapsed since each site last experienced significant erosion or deposition.
+///
+/// The surface level of each site is the elevation when its surface was last renewed, raised by the uplift since then.
+/// So the difference between the elevation and the surface level is the net erosion or deposition since the renewal.
+/// When the difference exceeds `min_change`, the surface is renewed: the level is reset to the elevation and the age to 0.0.
+/// Otherwise, the age increases by the time step.
+///
+/// The results are attached to the terrain as the fields:
+///  - [SURFACE_AGE] is the time elapsed since the surface was last renewed (unit: T).
+///  - [SURFACE_LEVEL] is the surface level of each site (unit: L).
+///
+/// This process should be added after the other processes changing the elevations,
+/// and is intended to be used with `TerrainGenerator::set_time_step`.
+///
+/// ### Properties
+///  - `min_change` is the net erosion or deposition renewing the surface (unit: L). The default value is 0.01.
+#[derive(Debug, Clone)]
+pub struct SurfaceAgeProcess {
+    min_change: Elevation,
+}
+
+impl Default for SurfaceAgeProcess {
+    fn default() -> Self {
+        Self { min_change: 0.01 }
+    }
+}
+
+impl SurfaceAgeProcess {
+    pub fn set_min_change(mut self, min_change: Elevation) -> Self {
+        self.min_change = min_change;
+        self
+    }
+}
+
+impl Process for SurfaceAgeProcess {
+    fn name(&self) -> &str {
+        "surface_age"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let num = state.num();
+        // the surface is regarded as renewed at the start of the simulation
+        let initialized = state.fields.get(SURFACE_LEVEL).is_some();
+        let mut surface_level = std::mem::take(state.fields.get_or_insert(SURFACE_LEVEL, num));
+        let mut surface_age = std::mem::take(state.fields.get_or_insert(SURFACE_AGE, num));
+
+        (0..num).for_each(|i| {
+            let elevation = state.elevations[i];
+            if !initialized {
+                surface_level[i] = elevation;
+                return;
+            }
+            surface_level[i] += state.parameters[i].uplift_rate * state.time_step;
+            if (elevation - surface_level[i]).abs() > self.min_change {
+                surface_level[i] = elevation;
+                surface_age[i] = 0.0;
+            } else {
+                surface_age[i] += state.time_step;
+            }
+        });
+
+        state.fields.insert(SURFACE_LEVEL, surface_level);
+        state.fields.insert(SURFACE_AGE, surface_age);
+    }
+}
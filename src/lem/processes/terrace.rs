@@ -0,0 +1,100 @@
+use crate::{
+    core::{
+        fields::{FLOODPLAIN, FLOODPLAIN_LEVEL, TERRACE_AGE, TERRACE_LEVEL},
+        units::{Area, Elevation},
+    },
+    lem::process::{Process, SimulationState},
+};
+
+/// Tracking of river terraces, the floodplain surfaces abandoned by the incision of the valleys.
+///
+/// In each iteration, the active floodplain consists of the channels, the sites whose drainage area exceeds `channel_area`,
+/// and their neighbors standing no higher than `floodplain_height` above them.
+/// When a site of the floodplain is left above the floodplain (mostly by the incision after a fall of the base level,
+/// see [crate::lem::processes::base_level::BaseLevelProcess]), it becomes a terrace, and the elevation of the floodplain
+/// at that time is kept as the terrace level. The terrace is reset if it becomes a part of the floodplain again.
+///
+/// The results are attached to the terrain as the fields:
+///  - [FLOODPLAIN] marks the active floodplain with 1.0 (0.0 elsewhere).
+///  - [FLOODPLAIN_LEVEL] is the elevation of each site when it was last a part of the floodplain.
+///  - [TERRACE_LEVEL] is the elevation of the abandoned floodplain on terraces (0.0 elsewhere).
+///  - [TERRACE_AGE] is the time elapsed since the abandonment on terraces (0.0 elsewhere, unit: T).
+///
+/// This process is intended to be used with `TerrainGenerator::set_time_step`.
+///
+/// ### Properties
+///  - `channel_area` is the drainage area above which the sites are regarded as channels (unit: L^2). The default value is 100.0.
+///  - `floodplain_height` is the maximum height of the floodplain above the channel (unit: L). The default value is 0.1.
+#[derive(Debug, Clone)]
+pub struct TerraceProcess {
+    channel_area: Area,
+    floodplain_height: Elevation,
+}
+
+impl Default for TerraceProcess {
+    fn default() -> Self {
+        Self {
+            channel_area: 100.0,
+            floodplain_height: 0.1,
+        }
+    }
+}
+
+impl TerraceProcess {
+    pub fn set_channel_area(mut self, channel_area: Area) -> Self {
+        self.channel_area = channel_area;
+        self
+    }
+
+    pub fn set_floodplain_height(mut self, floodplain_height: Elevation) -> Self {
+        self.floodplain_height = floodplain_height;
+        self
+    }
+}
+
+impl Process for TerraceProcess {
+    fn name(&self) -> &str {
+        "terrace"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let num = state.num();
+        let prev_floodplain = std::mem::take(state.fields.get_or_insert(FLOODPLAIN, num));
+        let mut floodplain_level =
+            std::mem::take(state.fields.get_or_insert(FLOODPLAIN_LEVEL, num));
+        let mut terrace_level = std::mem::take(state.fields.get_or_insert(TERRACE_LEVEL, num));
+        let mut terrace_age = std::mem::take(state.fields.get_or_insert(TERRACE_AGE, num));
+
+        let mut floodplain = vec![0.0; num];
+        (0..num).for_each(|i| {
+            if state.drainage_areas[i] < self.channel_area {
+                return;
+            }
+            floodplain[i] = 1.0;
+            state.graph.neighbors_of(i).iter().for_each(|ja| {
+                if state.elevations[ja.0] - state.elevations[i] <= self.floodplain_height {
+                    floodplain[ja.0] = 1.0;
+                }
+            });
+        });
+
+        (0..num).for_each(|i| {
+            if floodplain[i] > 0.0 {
+                floodplain_level[i] = state.elevations[i];
+                terrace_level[i] = 0.0;
+                terrace_age[i] = 0.0;
+            } else if prev_floodplain[i] > 0.0 {
+                // abandoned in this iteration
+                terrace_level[i] = floodplain_level[i];
+                terrace_age[i] = state.time_step;
+            } else if terrace_age[i] > 0.0 {
+                terrace_age[i] += state.time_step;
+            }
+        });
+
+        state.fields.insert(FLOODPLAIN, floodplain);
+        state.fields.insert(FLOODPLAIN_LEVEL, floodplain_level);
+        state.fields.insert(TERRACE_LEVEL, terrace_level);
+        state.fields.insert(TERRACE_AGE, terrace_age);
+    }
+}
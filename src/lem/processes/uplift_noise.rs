@@ -0,0 +1,119 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    core::{fields::UPLIFT_PERTURBATION, units::Length},
+    lem::process::{Process, SimulationState},
+};
+
+/// The number of the impulses of the noise per the area of the square of the correlation length.
+const IMPULSE_DENSITY: f64 = 4.0;
+
+/// Stochastic forcing of the uplift, perturbing the uplift rate of each site in each iteration with spatially correlated noise.
+///
+/// In each iteration, the noise is made by scattering random impulses over the sites and spreading each of them with the
+/// Gaussian kernel of `correlation_length` along the edges of the graph, and normalized to zero mean and unit variance.
+/// The uplift rate of each site is multiplied by `1 + amplitude * noise` (clamped to 0.0 or more) for the next iteration.
+/// The noise of each iteration is derived from `seed` and the step, so the results are reproducible.
+///
+/// The perturbation added to the uplift rate is attached to the terrain as the field [UPLIFT_PERTURBATION] (unit: L/T),
+/// and is removed before the next perturbation so that the perturbations do not accumulate.
+///
+/// ### Properties
+///  - `amplitude` is the standard deviation of the perturbation relative to the uplift rate. The default value is 0.1.
+///  - `correlation_length` is the length over which the noise is correlated (unit: L). The default value is 10.0.
+///  - `seed` is the seed of the noise. The default value is 0.
+#[derive(Debug, Clone)]
+pub struct UpliftNoiseProcess {
+    amplitude: f64,
+    correlation_length: Length,
+    seed: u64,
+}
+
+impl Default for UpliftNoiseProcess {
+    fn default() -> Self {
+        Self {
+            amplitude: 0.1,
+            correlation_length: 10.0,
+            seed: 0,
+        }
+    }
+}
+
+impl UpliftNoiseProcess {
+    pub fn set_amplitude(mut self, amplitude: f64) -> Self {
+        self.amplitude = amplitude;
+        self
+    }
+
+    pub fn set_correlation_length(mut self, correlation_length: Length) -> Self {
+        self.correlation_length = correlation_length;
+        self
+    }
+
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The noise of the step with zero mean and unit variance.
+    fn noise(&self, state: &SimulationState) -> Vec<f64> {
+        let num = state.num();
+        let mut noise = vec![0.0; num];
+        if num == 0 || self.correlation_length <= 0.0 {
+            return noise;
+        }
+        let mut rng: StdRng = SeedableRng::seed_from_u64(self.seed ^ ((state.step as u64) << 32));
+        // the median is used since the areas of the sites on the hull may be unbounded
+        let total_area = {
+            let mut areas = state.areas.to_vec();
+            areas.sort_by(|a, b| a.total_cmp(b));
+            areas[num / 2] * num as f64
+        };
+        let num_impulses = ((IMPULSE_DENSITY * total_area
+            / (self.correlation_length * self.correlation_length))
+            .ceil() as usize)
+            .max(1);
+        (0..num_impulses).for_each(|_| {
+            let center = rng.gen_range(0..num);
+            let weight = rng.gen::<f64>() * 2.0 - 1.0;
+            state
+                .sites_within(center, self.correlation_length * 3.0)
+                .into_iter()
+                .for_each(|(i, distance)| {
+                    let r = distance / self.correlation_length;
+                    noise[i] += weight * (-0.5 * r * r).exp();
+                });
+        });
+
+        let mean = noise.iter().sum::<f64>() / num as f64;
+        let variance = noise.iter().map(|n| (n - mean) * (n - mean)).sum::<f64>() / num as f64;
+        let deviation = variance.sqrt();
+        noise.iter_mut().for_each(|n| {
+            *n = if deviation > 0.0 {
+                (*n - mean) / deviation
+            } else {
+                0.0
+            };
+        });
+        noise
+    }
+}
+
+impl Process for UpliftNoiseProcess {
+    fn name(&self) -> &str {
+        "uplift_noise"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let num = state.num();
+        let noise = self.noise(state);
+        let mut perturbation = std::mem::take(state.fields.get_or_insert(UPLIFT_PERTURBATION, num));
+        (0..num).for_each(|i| {
+            let uplift_rate = state.parameters[i].uplift_rate - perturbation[i];
+            let factor = (1.0 + self.amplitude * noise[i]).max(0.0);
+            perturbation[i] = uplift_rate * (factor - 1.0);
+            state.parameters[i].uplift_rate = uplift_rate + perturbation[i];
+        });
+        state.fields.insert(UPLIFT_PERTURBATION, perturbation);
+    }
+}
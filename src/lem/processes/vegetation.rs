@@ -0,0 +1,136 @@
+use crate::{
+    core::{
+        fields::{DIFFUSIVITY_FACTOR, VEGETATION_COVER},
+        units::Elevation,
+    },
+    lem::process::{Process, SimulationState},
+};
+
+/// The maximum reduction, which keeps the effective erodibility positive.
+const MAX_REDUCTION: f64 = 0.99;
+
+/// A vegetation cover which protects the surface from erosion.
+///
+/// In each iteration, the vegetation cover of each site grows (or declines) towards its equilibrium by `growth_rate`.
+/// The equilibrium is determined by the precipitation `P` as `P / (P + half_saturation)`,
+/// and declines linearly to zero from `treeline - treeline_width` to `treeline`.
+///
+/// Dense vegetation reduces the erodibility by up to `erodibility_reduction`, and the diffusivity of hillslope processes
+/// (see [DIFFUSIVITY_FACTOR]) by up to `diffusivity_reduction`.
+/// This produces the contrast between arid badlands and vegetated hills.
+/// The vegetation cover is attached to the terrain as the field [VEGETATION_COVER].
+///
+/// ### Properties
+///  - `precipitation` is the precipitation of each site (unit: L/T). If `None`, 1.0 is used for all sites.
+///  - `half_saturation` is the precipitation at which the equilibrium cover is 0.5 (unit: L/T). The default value is 1.0.
+///  - `treeline` is the elevation above which no vegetation grows (unit: L). If `None`, there is no treeline.
+///  - `treeline_width` is the range of elevations below the treeline where the vegetation declines (unit: L). The default value is 1.0.
+///  - `growth_rate` is the fraction of the difference to the equilibrium recovered in an iteration (0.0 to 1.0). The default value is 0.5.
+///  - `erodibility_reduction` is the reduction of the erodibility under full cover (0.0 to 1.0). The default value is 0.5.
+///  - `diffusivity_reduction` is the reduction of the diffusivity under full cover (0.0 to 1.0). The default value is 0.5.
+#[derive(Debug, Clone)]
+pub struct VegetationProcess {
+    precipitation: Option<Vec<f64>>,
+    half_saturation: f64,
+    treeline: Option<Elevation>,
+    treeline_width: Elevation,
+    growth_rate: f64,
+    erodibility_reduction: f64,
+    diffusivity_reduction: f64,
+}
+
+impl Default for VegetationProcess {
+    fn default() -> Self {
+        Self {
+            precipitation: None,
+            half_saturation: 1.0,
+            treeline: None,
+            treeline_width: 1.0,
+            growth_rate: 0.5,
+            erodibility_reduction: 0.5,
+            diffusivity_reduction: 0.5,
+        }
+    }
+}
+
+impl VegetationProcess {
+    pub fn set_precipitation(mut self, precipitation: Option<Vec<f64>>) -> Self {
+        self.precipitation = precipitation;
+        self
+    }
+
+    pub fn set_half_saturation(mut self, half_saturation: f64) -> Self {
+        self.half_saturation = half_saturation;
+        self
+    }
+
+    pub fn set_treeline(mut self, treeline: Option<Elevation>) -> Self {
+        self.treeline = treeline;
+        self
+    }
+
+    pub fn set_treeline_width(mut self, treeline_width: Elevation) -> Self {
+        self.treeline_width = treeline_width;
+        self
+    }
+
+    pub fn set_growth_rate(mut self, growth_rate: f64) -> Self {
+        self.growth_rate = growth_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn set_erodibility_reduction(mut self, erodibility_reduction: f64) -> Self {
+        self.erodibility_reduction = erodibility_reduction.clamp(0.0, MAX_REDUCTION);
+        self
+    }
+
+    pub fn set_diffusivity_reduction(mut self, diffusivity_reduction: f64) -> Self {
+        self.diffusivity_reduction = diffusivity_reduction.clamp(0.0, MAX_REDUCTION);
+        self
+    }
+
+    fn equilibrium_cover(&self, i: usize, elevation: Elevation) -> f64 {
+        let precipitation = self
+            .precipitation
+            .as_ref()
+            .and_then(|p| p.get(i).copied())
+            .unwrap_or(1.0)
+            .max(0.0);
+        let cover = precipitation / (precipitation + self.half_saturation);
+        let altitude_factor = if let Some(treeline) = self.treeline {
+            ((treeline - elevation) / self.treeline_width.max(f64::EPSILON)).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        cover * altitude_factor
+    }
+}
+
+impl Process for VegetationProcess {
+    fn name(&self) -> &str {
+        "vegetation"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let num = state.num();
+        let mut cover = std::mem::take(state.fields.get_or_insert(VEGETATION_COVER, num));
+
+        (0..num).for_each(|i| {
+            let prev_cover = cover[i];
+            let equilibrium = self.equilibrium_cover(i, state.elevations[i]);
+            cover[i] = prev_cover + self.growth_rate * (equilibrium - prev_cover);
+
+            // the erodibility is rescaled from the previous cover so that the base erodibility does not need to be kept
+            let prev_factor = 1.0 - self.erodibility_reduction * prev_cover;
+            let factor = 1.0 - self.erodibility_reduction * cover[i];
+            state.parameters[i].erodibility *= factor / prev_factor;
+        });
+
+        let diffusivity_factor = cover
+            .iter()
+            .map(|c| 1.0 - self.diffusivity_reduction * c)
+            .collect::<Vec<_>>();
+        state.fields.insert(DIFFUSIVITY_FACTOR, diffusivity_factor);
+        *state.fields.get_or_insert(VEGETATION_COVER, num) = cover;
+    }
+}
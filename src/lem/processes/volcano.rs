@@ -0,0 +1,241 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    core::{
+        fields::LAVA_FLOW,
+        units::{Length, Step},
+    },
+    lem::process::{Process, SimulationState},
+};
+
+/// A volcanic eruption building a cone around the vent.
+///
+/// ### Properties
+///  - `site` is the index of the vent. If `None`, a random site is chosen from the seed of the process.
+///  - `start_step` is the iteration in which the eruption starts.
+///  - `duration` is the number of iterations over which the cone is built. The default value is 1.
+///  - `volume` is the total volume of the cone (unit: L^3).
+///  - `flank_slope` is the slope of the flanks of the cone (unit: rad). The default value is π/12.
+///  - `lava_length` is the length of the lava flow running down from the vent (unit: L). The default value is 0.0 (no lava flow).
+#[derive(Debug, Clone)]
+pub struct Eruption {
+    site: Option<usize>,
+    start_step: Step,
+    duration: Step,
+    volume: f64,
+    flank_slope: f64,
+    lava_length: Length,
+}
+
+impl Eruption {
+    pub fn new(start_step: Step, volume: f64) -> Self {
+        Self {
+            site: None,
+            start_step,
+            duration: 1,
+            volume,
+            flank_slope: std::f64::consts::PI / 12.0,
+            lava_length: 0.0,
+        }
+    }
+
+    pub fn set_site(mut self, site: Option<usize>) -> Self {
+        self.site = site;
+        self
+    }
+
+    pub fn set_duration(mut self, duration: Step) -> Self {
+        self.duration = duration.max(1);
+        self
+    }
+
+    pub fn set_flank_slope(mut self, flank_slope: f64) -> Self {
+        self.flank_slope = flank_slope;
+        self
+    }
+
+    pub fn set_lava_length(mut self, lava_length: Length) -> Self {
+        self.lava_length = lava_length;
+        self
+    }
+
+    /// The height of the cone after `volume` is erupted.
+    fn cone_height(&self, volume: f64) -> f64 {
+        // V = π r^2 H / 3 where r = H / tan(slope)
+        let gradient = self.flank_slope.tan();
+        (3.0 * volume * gradient * gradient / std::f64::consts::PI).cbrt()
+    }
+}
+
+/// Volcanic edifices built by scheduled or stochastic eruptions.
+///
+/// During an eruption, a cone with the flank slope of the eruption grows around the vent in each iteration
+/// until the volume of the eruption is reached, and is eroded by the simulation afterwards.
+/// The distance from the vent is measured along the edges of the graph (see `SimulationState::sites_within`),
+/// since the process does not know the positions of the sites. The path along the edges is slightly longer than
+/// the straight distance, so the cone is slightly narrower than the ideal one and its outline follows the graph. The outlets are not raised.
+/// At the start of an eruption, lava flows down from the vent along the stream tree for the length of the lava flow,
+/// and the erodibility of the covered sites is multiplied by `lava_erodibility_factor` (hardened).
+/// The covered sites are attached to the terrain as the field [LAVA_FLOW].
+///
+/// In addition to the scheduled eruptions, a new eruption with the template `random_eruption` starts at a random site
+/// with the probability `eruption_probability` in each iteration. The same `seed` always produces the same eruptions.
+/// This process is intended to be used with `TerrainGenerator::set_time_step`.
+///
+/// ### Properties
+///  - `eruptions` is the list of scheduled eruptions (see [Eruption]).
+///  - `eruption_probability` is the probability that a random eruption starts in an iteration. The default value is 0.0.
+///  - `random_eruption` is the template of random eruptions. The site and the start step of the template are ignored.
+///  - `lava_erodibility_factor` is the factor multiplied to the erodibility of the sites covered by lava. The default value is 0.5.
+///  - `seed` is the seed of the random numbers. The default value is 0.
+#[derive(Debug, Clone)]
+pub struct VolcanoProcess {
+    eruptions: Vec<Eruption>,
+    eruption_probability: f64,
+    random_eruption: Eruption,
+    lava_erodibility_factor: f64,
+    seed: u64,
+}
+
+impl Default for VolcanoProcess {
+    fn default() -> Self {
+        Self {
+            eruptions: Vec::new(),
+            eruption_probability: 0.0,
+            random_eruption: Eruption::new(0, 1000.0),
+            lava_erodibility_factor: 0.5,
+            seed: 0,
+        }
+    }
+}
+
+impl VolcanoProcess {
+    pub fn add_eruption(mut self, eruption: Eruption) -> Self {
+        self.eruptions.push(eruption);
+        self
+    }
+
+    pub fn set_eruption_probability(mut self, eruption_probability: f64) -> Self {
+        self.eruption_probability = eruption_probability.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn set_random_eruption(mut self, random_eruption: Eruption) -> Self {
+        self.random_eruption = random_eruption;
+        self
+    }
+
+    pub fn set_lava_erodibility_factor(mut self, lava_erodibility_factor: f64) -> Self {
+        self.lava_erodibility_factor = lava_erodibility_factor;
+        self
+    }
+
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The eruptions active in `step` with their vents.
+    ///
+    /// The random numbers are derived from the seed and the step of each eruption
+    /// so that the process does not need to keep any state between iterations.
+    fn active_eruptions(&self, step: Step, num: usize) -> Vec<(Eruption, usize)> {
+        let scheduled = self.eruptions.iter().enumerate().map(|(k, eruption)| {
+            let site = eruption.site.unwrap_or_else(|| {
+                let mut rng: StdRng = SeedableRng::seed_from_u64(self.seed ^ ((k as u64) << 32));
+                rng.gen_range(0..num)
+            });
+            (eruption.clone(), site)
+        });
+
+        let random = (step.saturating_sub(self.random_eruption.duration - 1)..=step).filter_map(
+            |start_step| {
+                let mut rng: StdRng = SeedableRng::seed_from_u64(self.seed ^ start_step as u64);
+                if rng.gen::<f64>() >= self.eruption_probability {
+                    return None;
+                }
+                let eruption = Eruption {
+                    site: None,
+                    start_step,
+                    ..self.random_eruption.clone()
+                };
+                Some((eruption, rng.gen_range(0..num)))
+            },
+        );
+
+        scheduled
+            .chain(random)
+            .filter(|(eruption, site)| {
+                *site < num
+                    && step >= eruption.start_step
+                    && step < eruption.start_step + eruption.duration
+            })
+            .collect()
+    }
+}
+
+impl Process for VolcanoProcess {
+    fn name(&self) -> &str {
+        "volcano"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        let num = state.num();
+        if num == 0 {
+            return;
+        }
+        let mut lava = std::mem::take(state.fields.get_or_insert(LAVA_FLOW, num));
+
+        self.active_eruptions(state.step, num)
+            .into_iter()
+            .for_each(|(eruption, vent)| {
+                // build the increment of the cone erupted in this iteration
+                let elapsed = (state.step - eruption.start_step) as f64;
+                let duration = eruption.duration as f64;
+                let prev_height = eruption.cone_height(eruption.volume * elapsed / duration);
+                let height = eruption.cone_height(eruption.volume * (elapsed + 1.0) / duration);
+                let gradient = eruption.flank_slope.tan();
+                state
+                    .sites_within(vent, height / gradient)
+                    .into_iter()
+                    .for_each(|(i, distance)| {
+                        // the outlets are fixed as the base level
+                        if state.receivers[i] == i {
+                            return;
+                        }
+                        let increment = (height - gradient * distance).max(0.0)
+                            - (prev_height - gradient * distance).max(0.0);
+                        state.elevations[i] += increment;
+                    });
+
+                // run the lava down the stream tree and harden the covered sites
+                if elapsed == 0.0 && eruption.lava_length > 0.0 {
+                    let mut i = vent;
+                    let mut length = 0.0;
+                    loop {
+                        if lava[i] == 0.0 {
+                            lava[i] = 1.0;
+                            state.parameters[i].erodibility *= self.lava_erodibility_factor;
+                        }
+                        let j = state.receivers[i];
+                        if j == i {
+                            break;
+                        }
+                        length += state
+                            .graph
+                            .neighbors_of(i)
+                            .iter()
+                            .find(|ja| ja.0 == j)
+                            .map(|ja| ja.1)
+                            .unwrap_or(state.fallback_distance);
+                        if length > eruption.lava_length {
+                            break;
+                        }
+                        i = j;
+                    }
+                }
+            });
+
+        *state.fields.get_or_insert(LAVA_FLOW, num) = lava;
+    }
+}
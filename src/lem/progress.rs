@@ -0,0 +1,154 @@
+use std::{
+    sync::mpsc::{Receiver, TryRecvError},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    core::units::Step,
+    lem::{events::SimulationEvent, generator::GenerationError},
+};
+
+/// The progress of terrain generation, reported after each iteration.
+///
+/// ### Properties
+///  - `step` is the number of iterations completed so far.
+///  - `max_iteration` is the maximum number of iterations if it was set.
+///  - `num_changed` is the number of sites whose elevation changed in the last iteration.
+///    The generation is finished when this reaches 0.
+///  - `elapsed` is the time elapsed since the start of the iterations.
+///  - `remaining_steps` is the estimated number of the remaining iterations, the smaller of the ones until `max_iteration`
+///    and until `num_changed` reaches 0 at its recent rate of decrease. `None` if neither can be estimated yet.
+///  - `remaining_time` is the estimated time of the remaining iterations at the average time per iteration so far.
+#[derive(Debug, Clone)]
+pub struct GenerationProgress {
+    pub step: Step,
+    pub max_iteration: Option<Step>,
+    pub num_changed: usize,
+    pub elapsed: Duration,
+    pub remaining_steps: Option<Step>,
+    pub remaining_time: Option<Duration>,
+}
+
+/// The weight of the latest iteration in the moving average of the rate of decrease of the changed sites.
+const RATE_SMOOTHING: f64 = 0.3;
+
+/// Estimates the remaining iterations and time from the history of the iterations.
+///
+/// The number of the changed sites is assumed to decrease geometrically as the simulation converges,
+/// with the ratio between the iterations averaged exponentially over the recent iterations.
+pub(crate) struct ProgressEstimator {
+    start: Instant,
+    prev_num_changed: Option<usize>,
+    log_ratio: Option<f64>,
+}
+
+impl ProgressEstimator {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            prev_num_changed: None,
+            log_ratio: None,
+        }
+    }
+
+    /// The progress after the iteration `step`, updating the history.
+    pub(crate) fn progress(
+        &mut self,
+        step: Step,
+        max_iteration: Option<Step>,
+        num_changed: usize,
+    ) -> GenerationProgress {
+        if let Some(prev_num_changed) = self.prev_num_changed {
+            if prev_num_changed > 0 && num_changed > 0 {
+                let log_ratio = (num_changed as f64 / prev_num_changed as f64).ln();
+                self.log_ratio = Some(match self.log_ratio {
+                    Some(average) => average + RATE_SMOOTHING * (log_ratio - average),
+                    None => log_ratio,
+                });
+            }
+        }
+        self.prev_num_changed = Some(num_changed);
+
+        let until_converged = if num_changed == 0 {
+            Some(0)
+        } else {
+            self.log_ratio
+                .filter(|log_ratio| *log_ratio < 0.0)
+                .map(|log_ratio| ((num_changed as f64).ln() / -log_ratio).ceil() as Step)
+        };
+        let until_max_iteration =
+            max_iteration.map(|max_iteration| max_iteration.saturating_sub(step));
+        let remaining_steps = match (until_converged, until_max_iteration) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        let elapsed = self.start.elapsed();
+        let remaining_time = remaining_steps
+            .filter(|_| step > 0)
+            .map(|remaining_steps| elapsed.div_f64(step as f64).mul_f64(remaining_steps as f64));
+        GenerationProgress {
+            step,
+            max_iteration,
+            num_changed,
+            elapsed,
+            remaining_steps,
+            remaining_time,
+        }
+    }
+}
+
+/// A handle of terrain generation running in a background job.
+///
+/// This is created by [crate::lem::generator::TerrainGenerator::generate_async].
+/// Receiving from the channels never blocks the job itself, so the task can be polled from an async executor.
+pub struct GenerationTask<T> {
+    progress: Receiver<GenerationProgress>,
+    events: Receiver<SimulationEvent>,
+    result: Receiver<Result<T, GenerationError>>,
+}
+
+impl<T> GenerationTask<T> {
+    pub(crate) fn new(
+        progress: Receiver<GenerationProgress>,
+        events: Receiver<SimulationEvent>,
+        result: Receiver<Result<T, GenerationError>>,
+    ) -> Self {
+        Self {
+            progress,
+            events,
+            result,
+        }
+    }
+
+    /// The receiver of the progress reported after each iteration.
+    pub fn progress(&self) -> &Receiver<GenerationProgress> {
+        &self.progress
+    }
+
+    /// The receiver of all the [SimulationEvent]s, which can be consumed on another thread for live previews.
+    ///
+    /// Note that events are buffered until they are received.
+    pub fn events(&self) -> &Receiver<SimulationEvent> {
+        &self.events
+    }
+
+    /// Get the result without blocking.
+    ///
+    /// Returns `None` if the generation is still running.
+    /// The result can be taken only once; after that `GenerationError::TaskAborted` will be returned.
+    pub fn try_result(&self) -> Option<Result<T, GenerationError>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(GenerationError::TaskAborted)),
+        }
+    }
+
+    /// Block the current thread until the generation is finished and get the result.
+    pub fn wait(self) -> Result<T, GenerationError> {
+        self.result
+            .recv()
+            .unwrap_or(Err(GenerationError::TaskAborted))
+    }
+}
@@ -0,0 +1,608 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Read, Write},
+};
+
+use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
+
+use crate::{
+    core::{
+        parameters::{EdgeParameters, TopographicalParameters},
+        units::{Area, Elevation, Length, Step},
+    },
+    lem::generator::{FallbackDistance, GenerationError, Jitter, JitterDistribution},
+    lem::simulation::{simulate, EdgeParameterMap, SimulationConfig},
+};
+
+/// The magic bytes at the beginning of a record file.
+const RECORD_MAGIC: &[u8; 8] = b"FLEMREC1";
+
+/// A record of a simulation, which is created by `TerrainGenerator::generate_with_record`.
+///
+/// The record captures all the inputs of the simulation (the areas and the graph computed from the sites,
+/// the topographical parameters, the seed and the configuration) and a digest of the elevations at each iteration.
+/// It can be written to a compact binary file and replayed on another machine to verify that the simulation is
+/// reproduced bit by bit.
+#[derive(Debug, Clone)]
+pub struct SimulationRecord {
+    areas: Vec<Area>,
+    edges: Vec<(usize, usize, Length)>,
+    edge_directions: Option<Vec<f64>>,
+    edge_parameters: Vec<(usize, usize, EdgeParameters)>,
+    default_outlets: Vec<usize>,
+    parameters: Vec<TopographicalParameters>,
+    config: SimulationConfig,
+    digests: Vec<u64>,
+}
+
+/// The result of replaying a [SimulationRecord].
+///
+/// ### Properties
+///  - `recorded_steps` is the number of iterations in the record.
+///  - `replayed_steps` is the number of iterations in the replay.
+///  - `first_divergent_step` is the first iteration whose elevations differ from the record, if any.
+#[derive(Debug, Clone)]
+pub struct ReplayReport {
+    pub recorded_steps: usize,
+    pub replayed_steps: usize,
+    pub first_divergent_step: Option<Step>,
+}
+
+impl ReplayReport {
+    /// Whether the replay reproduced the record bit by bit.
+    pub fn is_identical(&self) -> bool {
+        self.first_divergent_step.is_none() && self.recorded_steps == self.replayed_steps
+    }
+}
+
+impl SimulationRecord {
+    pub(crate) fn new(
+        areas: &[Area],
+        graph: &EdgeAttributedUndirectedGraph<Length>,
+        edge_directions: Option<&EdgeAttributedUndirectedGraph<f64>>,
+        edge_parameters: &EdgeParameterMap,
+        default_outlets: &[usize],
+        parameters: &[TopographicalParameters],
+        config: &SimulationConfig,
+    ) -> Self {
+        let edges = edges_in_insertion_order(graph);
+        let edge_directions = edge_directions.map(|edge_directions| {
+            edges
+                .iter()
+                .map(|&(i, j, _)| edge_directions.has_edge(i, j).1)
+                .collect()
+        });
+        // sorted to make the written record deterministic
+        let mut edge_parameters = edge_parameters
+            .iter()
+            .map(|(&(i, j), &parameters)| (i, j, parameters))
+            .collect::<Vec<_>>();
+        edge_parameters.sort_by_key(|&(i, j, _)| (i, j));
+        Self {
+            areas: areas.to_vec(),
+            edges,
+            edge_directions,
+            edge_parameters,
+            default_outlets: default_outlets.to_vec(),
+            parameters: parameters.to_vec(),
+            config: config.clone(),
+            digests: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push_digest(&mut self, elevations: &[Elevation]) {
+        self.digests.push(digest_elevations(elevations));
+    }
+
+    /// The digests of the elevations at each iteration.
+    pub fn digests(&self) -> &[u64] {
+        &self.digests
+    }
+
+    /// Run the simulation again from the recorded inputs and compare the digests with the record.
+    pub fn replay(&self) -> Result<ReplayReport, GenerationError> {
+        let mut graph = EdgeAttributedUndirectedGraph::new(self.areas.len());
+        self.edges.iter().for_each(|&(i, j, distance)| {
+            graph.add_edge(i, j, distance);
+        });
+
+        let edge_directions = self.edge_directions.as_ref().map(|directions| {
+            let mut edge_directions = EdgeAttributedUndirectedGraph::new(self.areas.len());
+            self.edges
+                .iter()
+                .zip(directions.iter())
+                .for_each(|(&(i, j, _), &direction)| {
+                    edge_directions.add_edge(i, j, direction);
+                });
+            edge_directions
+        });
+
+        let edge_parameters = self
+            .edge_parameters
+            .iter()
+            .map(|&(i, j, parameters)| ((i, j), parameters))
+            .collect::<EdgeParameterMap>();
+
+        let mut digests = Vec::with_capacity(self.digests.len());
+        simulate(
+            &self.config,
+            &self.areas,
+            &graph,
+            edge_directions.as_ref(),
+            &edge_parameters,
+            &self.default_outlets,
+            &self.parameters,
+            &mut |_| {},
+            &mut |_, elevations| digests.push(digest_elevations(elevations)),
+            None,
+        )?;
+
+        let first_divergent_step = self
+            .digests
+            .iter()
+            .zip(digests.iter())
+            .position(|(recorded, replayed)| recorded != replayed)
+            .map(|i| i as Step + 1);
+
+        Ok(ReplayReport {
+            recorded_steps: self.digests.len(),
+            replayed_steps: digests.len(),
+            first_divergent_step,
+        })
+    }
+
+    /// Write the record in a compact binary format.
+    ///
+    /// Processes cannot be written since they are arbitrary code.
+    /// The record of a simulation with processes can only be replayed in memory.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        if !self.config.processes.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The record of a simulation with processes cannot be written",
+            ));
+        }
+
+        writer.write_all(RECORD_MAGIC)?;
+
+        write_u64(&mut writer, self.config.seed)?;
+        write_option_u64(&mut writer, self.config.max_iteration.map(|s| s as u64))?;
+        write_option_u64(&mut writer, self.config.snapshot_interval.map(|s| s as u64))?;
+        writer.write_all(&[self.config.debug_checks as u8])?;
+        write_option_f64(&mut writer, self.config.time_step)?;
+        write_option_f64(&mut writer, self.config.groundwater_transmissivity)?;
+        write_option_f64(&mut writer, self.config.junction_tolerance)?;
+        write_option_f64(&mut writer, self.config.convergence_tolerance)?;
+        writer.write_all(&[self.config.fast_powf as u8])?;
+        write_fallback_distance(&mut writer, self.config.fallback_distance)?;
+        write_jitter(&mut writer, self.config.jitter)?;
+
+        write_u64(&mut writer, self.areas.len() as u64)?;
+        for &area in &self.areas {
+            write_f64(&mut writer, area)?;
+        }
+
+        write_u64(&mut writer, self.edges.len() as u64)?;
+        for &(i, j, distance) in &self.edges {
+            write_u64(&mut writer, i as u64)?;
+            write_u64(&mut writer, j as u64)?;
+            write_f64(&mut writer, distance)?;
+        }
+        writer.write_all(&[self.edge_directions.is_some() as u8])?;
+        if let Some(edge_directions) = &self.edge_directions {
+            for &direction in edge_directions {
+                write_f64(&mut writer, direction)?;
+            }
+        }
+
+        write_u64(&mut writer, self.edge_parameters.len() as u64)?;
+        for &(i, j, parameters) in &self.edge_parameters {
+            write_u64(&mut writer, i as u64)?;
+            write_u64(&mut writer, j as u64)?;
+            write_f64(&mut writer, parameters.erodibility_factor)?;
+            write_f64(&mut writer, parameters.distance_factor)?;
+        }
+
+        write_u64(&mut writer, self.default_outlets.len() as u64)?;
+        for &outlet in &self.default_outlets {
+            write_u64(&mut writer, outlet as u64)?;
+        }
+
+        write_u64(&mut writer, self.parameters.len() as u64)?;
+        for param in &self.parameters {
+            write_parameters(&mut writer, param)?;
+        }
+
+        write_u64(&mut writer, self.digests.len() as u64)?;
+        for &digest in &self.digests {
+            write_u64(&mut writer, digest)?;
+        }
+
+        Ok(())
+    }
+
+    /// Read a record written by [SimulationRecord::write_to].
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != RECORD_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The data is not a simulation record",
+            ));
+        }
+
+        let config = SimulationConfig {
+            seed: read_u64(&mut reader)?,
+            max_iteration: read_option_u64(&mut reader)?.map(|s| s as Step),
+            snapshot_interval: read_option_u64(&mut reader)?.map(|s| s as Step),
+            debug_checks: read_u8(&mut reader)? != 0,
+            time_step: read_option_f64(&mut reader)?,
+            groundwater_transmissivity: read_option_f64(&mut reader)?,
+            junction_tolerance: read_option_f64(&mut reader)?,
+            convergence_tolerance: read_option_f64(&mut reader)?,
+            fast_powf: read_u8(&mut reader)? != 0,
+            fallback_distance: read_fallback_distance(&mut reader)?,
+            jitter: read_jitter(&mut reader)?,
+            // the number of threads does not affect the result, so it is not recorded
+            num_threads: 1,
+            min_parallel_sites: None,
+            processes: Vec::new(),
+            // the storage does not affect the result, so it is not recorded
+            #[cfg(feature = "mmap")]
+            elevation_storage: None,
+            #[cfg(feature = "mmap")]
+            snapshot_directory: None,
+        };
+
+        let num = read_u64(&mut reader)? as usize;
+        let areas = (0..num)
+            .map(|_| read_f64(&mut reader))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let num_edges = read_u64(&mut reader)? as usize;
+        let edges = (0..num_edges)
+            .map(|_| {
+                let i = read_u64(&mut reader)? as usize;
+                let j = read_u64(&mut reader)? as usize;
+                let distance = read_f64(&mut reader)?;
+                if i >= num || j >= num {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "An edge refers to a site out of range",
+                    ));
+                }
+                Ok((i, j, distance))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let edge_directions = if read_u8(&mut reader)? != 0 {
+            Some(
+                (0..num_edges)
+                    .map(|_| read_f64(&mut reader))
+                    .collect::<io::Result<Vec<_>>>()?,
+            )
+        } else {
+            None
+        };
+
+        let num_edge_parameters = read_u64(&mut reader)? as usize;
+        let edge_parameters = (0..num_edge_parameters)
+            .map(|_| {
+                let i = read_u64(&mut reader)? as usize;
+                let j = read_u64(&mut reader)? as usize;
+                let parameters = EdgeParameters::default()
+                    .set_erodibility_factor(read_f64(&mut reader)?)
+                    .set_distance_factor(read_f64(&mut reader)?);
+                if i >= num || j >= num {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "An edge refers to a site out of range",
+                    ));
+                }
+                Ok((i, j, parameters))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let num_outlets = read_u64(&mut reader)? as usize;
+        let default_outlets = (0..num_outlets)
+            .map(|_| read_u64(&mut reader).map(|i| i as usize))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let num_parameters = read_u64(&mut reader)? as usize;
+        let parameters = (0..num_parameters)
+            .map(|_| {
+                let base_elevation = read_f64(&mut reader)?;
+                let erodibility = read_f64(&mut reader)?;
+                let uplift_rate = read_f64(&mut reader)?;
+                let is_outlet = read_u8(&mut reader)? != 0;
+                let max_slope = read_option_f64(&mut reader)?;
+                let solubility = read_f64(&mut reader)?;
+                let infiltration = read_f64(&mut reader)?;
+                let evaporation = read_f64(&mut reader)?;
+                let grain_direction = read_f64(&mut reader)?;
+                let anisotropy_ratio = read_f64(&mut reader)?;
+                let min_elevation = read_option_f64(&mut reader)?;
+                let max_elevation = read_option_f64(&mut reader)?;
+                let is_frozen = read_u8(&mut reader)? != 0;
+                let blend_weight = read_f64(&mut reader)?;
+                let authored_elevation = read_f64(&mut reader)?;
+                Ok(TopographicalParameters::default()
+                    .set_base_elevation(base_elevation)
+                    .set_erodibility(erodibility)
+                    .set_uplift_rate(uplift_rate)
+                    .set_is_outlet(is_outlet)
+                    .set_max_slope(max_slope)
+                    .set_solubility(solubility)
+                    .set_infiltration(infiltration)
+                    .set_evaporation(evaporation)
+                    .set_anisotropy(grain_direction, anisotropy_ratio)
+                    .set_min_elevation(min_elevation)
+                    .set_max_elevation(max_elevation)
+                    .set_is_frozen(is_frozen)
+                    .set_blend(blend_weight, authored_elevation))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        if parameters.len() != num || default_outlets.iter().any(|&i| i >= num) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The parameters or outlets do not match the number of sites",
+            ));
+        }
+
+        let num_digests = read_u64(&mut reader)? as usize;
+        let digests = (0..num_digests)
+            .map(|_| read_u64(&mut reader))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            areas,
+            edges,
+            edge_directions,
+            edge_parameters,
+            default_outlets,
+            parameters,
+            config,
+            digests,
+        })
+    }
+}
+
+/// List the edges of the graph in an order which reproduces the same order of neighbors for every site
+/// when the edges are added to a new graph one by one.
+///
+/// The order of neighbors affects the order of floating point operations in the simulation,
+/// so it must be restored exactly to reproduce the result bit by bit.
+fn edges_in_insertion_order(
+    graph: &EdgeAttributedUndirectedGraph<Length>,
+) -> Vec<(usize, usize, Length)> {
+    let mut edges = Vec::with_capacity(graph.size());
+    let mut edge_ids: HashMap<(usize, usize), usize> = HashMap::with_capacity(graph.size());
+    (0..graph.order()).for_each(|i| {
+        graph.neighbors_of(i).iter().for_each(|ja| {
+            if i < ja.0 {
+                edge_ids.insert((i, ja.0), edges.len());
+                edges.push((i, ja.0, ja.1));
+            }
+        });
+    });
+
+    // an edge must be added after the edges preceding it in the neighbor lists of both of its ends
+    let mut successors: Vec<Vec<usize>> = vec![Vec::new(); edges.len()];
+    let mut num_predecessors = vec![0; edges.len()];
+    (0..graph.order()).for_each(|i| {
+        let ids = graph
+            .neighbors_of(i)
+            .iter()
+            .map(|ja| edge_ids[&(i.min(ja.0), i.max(ja.0))])
+            .collect::<Vec<_>>();
+        ids.windows(2).for_each(|w| {
+            successors[w[0]].push(w[1]);
+            num_predecessors[w[1]] += 1;
+        });
+    });
+
+    let mut queue = (0..edges.len())
+        .filter(|&e| num_predecessors[e] == 0)
+        .collect::<VecDeque<_>>();
+    let mut ordered = Vec::with_capacity(edges.len());
+    while let Some(e) = queue.pop_front() {
+        ordered.push(edges[e]);
+        successors[e].iter().for_each(|&f| {
+            num_predecessors[f] -= 1;
+            if num_predecessors[f] == 0 {
+                queue.push_back(f);
+            }
+        });
+    }
+    ordered
+}
+
+/// Calculate the digest (64-bit FNV-1a) of the bit patterns of the elevations.
+pub fn digest_elevations(elevations: &[Elevation]) -> u64 {
+    elevations
+        .iter()
+        .flat_map(|e| e.to_bits().to_le_bytes())
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// The 64-bit FNV-1a hash of the bytes written to it.
+struct DigestWriter(u64);
+
+impl Default for DigestWriter {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Write for DigestWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 = buf.iter().fold(self.0, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Calculate the digest (64-bit FNV-1a) of the topographical parameters in the layout of the record.
+pub(crate) fn digest_parameters(parameters: &[TopographicalParameters]) -> u64 {
+    let mut writer = DigestWriter::default();
+    // writing to the digest never fails
+    parameters.iter().for_each(|param| {
+        let _ = write_parameters(&mut writer, param);
+    });
+    writer.0
+}
+
+/// Calculate the digest (64-bit FNV-1a) of the areas, the graph and the default outlets of a model in the layout of the record.
+pub(crate) fn digest_model(
+    areas: &[Area],
+    graph: &EdgeAttributedUndirectedGraph<Length>,
+    default_outlets: &[usize],
+) -> u64 {
+    let mut writer = DigestWriter::default();
+    areas.iter().for_each(|&area| {
+        let _ = write_f64(&mut writer, area);
+    });
+    edges_in_insertion_order(graph)
+        .into_iter()
+        .for_each(|(i, j, distance)| {
+            let _ = write_u64(&mut writer, i as u64);
+            let _ = write_u64(&mut writer, j as u64);
+            let _ = write_f64(&mut writer, distance);
+        });
+    default_outlets.iter().for_each(|&outlet| {
+        let _ = write_u64(&mut writer, outlet as u64);
+    });
+    writer.0
+}
+
+/// Write the fallback distance as a tag (0: the mean length of the edges, 1: fixed, 2: strict) and the fixed distance.
+fn write_fallback_distance(
+    writer: &mut impl Write,
+    fallback_distance: FallbackDistance,
+) -> io::Result<()> {
+    let (tag, distance) = match fallback_distance {
+        FallbackDistance::MeanEdgeLength => (0, 0.0),
+        FallbackDistance::Fixed(distance) => (1, distance),
+        FallbackDistance::Strict => (2, 0.0),
+    };
+    writer.write_all(&[tag])?;
+    write_f64(writer, distance)
+}
+
+fn read_fallback_distance(reader: &mut impl Read) -> io::Result<FallbackDistance> {
+    let tag = read_u8(reader)?;
+    let distance = read_f64(reader)?;
+    match tag {
+        0 => Ok(FallbackDistance::MeanEdgeLength),
+        1 => Ok(FallbackDistance::Fixed(distance)),
+        2 => Ok(FallbackDistance::Strict),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "The fallback distance is invalid",
+        )),
+    }
+}
+
+/// Write the jitter as the amplitude, a tag of the distribution (0: uniform, 1: normal) and the correlation length.
+fn write_jitter(writer: &mut impl Write, jitter: Jitter) -> io::Result<()> {
+    write_f64(writer, jitter.amplitude())?;
+    let tag = match jitter.distribution() {
+        JitterDistribution::Uniform => 0,
+        JitterDistribution::Normal => 1,
+    };
+    writer.write_all(&[tag])?;
+    write_option_f64(writer, jitter.correlation_length())
+}
+
+fn read_jitter(reader: &mut impl Read) -> io::Result<Jitter> {
+    let amplitude = read_f64(reader)?;
+    let distribution = match read_u8(reader)? {
+        0 => JitterDistribution::Uniform,
+        1 => JitterDistribution::Normal,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The distribution of the jitter is invalid",
+            ))
+        }
+    };
+    Ok(Jitter::default()
+        .set_amplitude(amplitude)
+        .set_distribution(distribution)
+        .set_correlation_length(read_option_f64(reader)?))
+}
+
+fn write_parameters(writer: &mut impl Write, param: &TopographicalParameters) -> io::Result<()> {
+    write_f64(writer, param.base_elevation)?;
+    write_f64(writer, param.erodibility)?;
+    write_f64(writer, param.uplift_rate)?;
+    writer.write_all(&[param.is_outlet as u8])?;
+    write_option_f64(writer, param.max_slope)?;
+    write_f64(writer, param.solubility)?;
+    write_f64(writer, param.infiltration)?;
+    write_f64(writer, param.evaporation)?;
+    write_f64(writer, param.grain_direction)?;
+    write_f64(writer, param.anisotropy_ratio)?;
+    write_option_f64(writer, param.min_elevation)?;
+    write_option_f64(writer, param.max_elevation)?;
+    writer.write_all(&[param.is_frozen as u8])?;
+    write_f64(writer, param.blend_weight)?;
+    write_f64(writer, param.authored_elevation)?;
+    Ok(())
+}
+
+fn write_u64(writer: &mut impl Write, value: u64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_f64(writer: &mut impl Write, value: f64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_option_u64(writer: &mut impl Write, value: Option<u64>) -> io::Result<()> {
+    writer.write_all(&[value.is_some() as u8])?;
+    write_u64(writer, value.unwrap_or(0))
+}
+
+fn write_option_f64(writer: &mut impl Write, value: Option<f64>) -> io::Result<()> {
+    writer.write_all(&[value.is_some() as u8])?;
+    write_f64(writer, value.unwrap_or(0.0))
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_f64(reader: &mut impl Read) -> io::Result<f64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(f64::from_le_bytes(buf))
+}
+
+fn read_option_u64(reader: &mut impl Read) -> io::Result<Option<u64>> {
+    let flag = read_u8(reader)?;
+    let value = read_u64(reader)?;
+    Ok(if flag != 0 { Some(value) } else { None })
+}
+
+fn read_option_f64(reader: &mut impl Read) -> io::Result<Option<f64>> {
+    let flag = read_u8(reader)?;
+    let value = read_f64(reader)?;
+    Ok(if flag != 0 { Some(value) } else { None })
+}
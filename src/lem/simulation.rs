@@ -0,0 +1,1030 @@
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
+
+use crate::{
+    core::{
+        adjacency::Adjacency,
+        distance::DistanceField,
+        fields::{
+            SiteFields, BASEFLOW, CELERITY, CHANNEL_STEEPNESS, COAST_DISTANCE, CONTINENTALITY,
+            DISCHARGE, GROUNDWATER_FLOW, INFILTRATION, RESPONSE_TIME, SINKHOLE, SPRING_DISCHARGE,
+            SURFACE_DRAINAGE_AREA, UNDERGROUND_FLOW,
+        },
+        network::DrainageNetwork,
+        parameters::{EdgeParameters, TopographicalParameters},
+        units::{Area, Elevation, Erodibility, Length, Step},
+    },
+    lem::drainage_basin::DrainageBasin,
+    lem::events::SimulationEvent,
+    lem::generator::{FallbackDistance, GenerationError, Jitter},
+    lem::invariants,
+    lem::kernels,
+    lem::process::{apply_in_sub_steps, Process, SimulationState},
+    lem::progress::ProgressEstimator,
+    lem::storage::ElevationBuffer,
+    lem::stream_tree,
+};
+
+#[cfg(feature = "mmap")]
+use {
+    crate::lem::storage::{self, MappedElevations},
+    std::path::PathBuf,
+};
+
+/// The default value of the exponent `m` for calculating stream power.
+pub(crate) const DEFAULT_M_EXP: f64 = 0.5;
+
+/// The default minimum number of the sites at a depth of a drainage basin to accumulate the drainage areas in parallel.
+pub(crate) const DEFAULT_MIN_PARALLEL_SITES: usize = 4096;
+
+/// The depth of a sinkhole below its lowest neighbor relative to the height of the site above it.
+const SINKHOLE_DEPTH_RATIO: f64 = 0.5;
+
+/// The fraction of the room below the ceiling which a site takes from the excess volume spread over it.
+/// Taking only a part of the room makes the surroundings approach the ceiling gradually instead of forming a plateau.
+const SPREAD_FILL_RATIO: f64 = 0.5;
+
+/// The maximum number of the sites over which the excess volume of a site is spread. The remaining volume is clipped.
+const MAX_SPREAD_SITES: usize = 4096;
+
+/// The parameters of the edges keyed by the pair of the sites in the ascending order.
+pub(crate) type EdgeParameterMap = HashMap<(usize, usize), EdgeParameters>;
+
+/// The settings of the simulation which are independent from the model.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SimulationConfig {
+    pub max_iteration: Option<Step>,
+    pub convergence_tolerance: Option<f64>,
+    pub snapshot_interval: Option<Step>,
+    pub seed: u64,
+    pub debug_checks: bool,
+    pub time_step: Option<f64>,
+    pub groundwater_transmissivity: Option<f64>,
+    pub junction_tolerance: Option<Elevation>,
+    pub num_threads: usize,
+    pub min_parallel_sites: Option<usize>,
+    pub fast_powf: bool,
+    pub fallback_distance: FallbackDistance,
+    pub jitter: Jitter,
+    pub processes: Vec<Arc<dyn Process>>,
+    #[cfg(feature = "mmap")]
+    pub elevation_storage: Option<PathBuf>,
+    #[cfg(feature = "mmap")]
+    pub snapshot_directory: Option<PathBuf>,
+}
+
+/// The inputs of the fluvial erosion shared by all drainage basins in an iteration.
+struct BasinContext<'a> {
+    config: &'a SimulationConfig,
+    areas: &'a [Area],
+    adjacency: &'a Adjacency,
+    fallback_distance: Length,
+    accumulation_threads: usize,
+    edge_directions: Option<&'a EdgeAttributedUndirectedGraph<f64>>,
+    edge_parameters: &'a EdgeParameterMap,
+    parameters: &'a [TopographicalParameters],
+    elevations: &'a [Elevation],
+    has_karst: bool,
+    has_losses: bool,
+    m_exp: f64,
+}
+
+/// The result of the fluvial erosion in a drainage basin, indexed locally in the basin.
+struct BasinSolution {
+    basin: DrainageBasin,
+    drainage_areas: Vec<Area>,
+    underground_flows: Vec<f64>,
+    spring_discharges: Vec<f64>,
+    discharges: Vec<f64>,
+    infiltrations: Vec<f64>,
+    groundwater_flows: Vec<f64>,
+    baseflows: Vec<f64>,
+    response_times: Vec<f64>,
+    celerities: Vec<f64>,
+    elevations: Vec<Elevation>,
+    num_changed: usize,
+    max_elevation_change: Elevation,
+}
+
+impl BasinContext<'_> {
+    /// The distance from the site to its receiver.
+    fn distance(&self, i: usize, j: usize) -> Length {
+        self.adjacency
+            .length_between(i, j)
+            .unwrap_or(self.fallback_distance)
+    }
+
+    /// The stream powers of the flows, approximated if `fast_powf` is enabled.
+    fn stream_powers(&self, flows: &[Area]) -> Vec<f64> {
+        if self.config.fast_powf {
+            kernels::fast_stream_powers(flows, self.m_exp)
+        } else {
+            kernels::stream_powers(flows, self.m_exp)
+        }
+    }
+
+    /// The parameters of the edge, if overridden.
+    fn edge_parameters(&self, i: usize, j: usize) -> Option<&EdgeParameters> {
+        if self.edge_parameters.is_empty() {
+            return None;
+        }
+        self.edge_parameters.get(&(i.min(j), i.max(j)))
+    }
+
+    /// The distance from the site to its receiver traveled by the flow, modified by the parameters of the edge.
+    fn travel_distance(&self, i: usize, j: usize) -> Length {
+        let distance = self.distance(i, j);
+        match self.edge_parameters(i, j) {
+            Some(edge) => distance * edge.distance_factor,
+            None => distance,
+        }
+    }
+
+    /// The erodibility of the site along the edge to its receiver.
+    fn erodibility(&self, i: usize, j: usize) -> Erodibility {
+        let parameters = &self.parameters[i];
+        let mut erodibility = parameters.erodibility;
+        if let Some(edge_directions) = self.edge_directions {
+            if parameters.is_anisotropic() {
+                // the anisotropy is axial, so the direction of either end of the edge can be used
+                let (ok, direction) = edge_directions.has_edge(i, j);
+                if ok {
+                    erodibility = parameters.directional_erodibility(direction);
+                }
+            }
+        }
+        match self.edge_parameters(i, j) {
+            Some(edge) => erodibility * edge.erodibility_factor,
+            None => erodibility,
+        }
+    }
+
+    /// Calculate the drainage areas, the response times and the elevations of the drainage basin.
+    fn solve(&self, basin: DrainageBasin) -> BasinSolution {
+        let parameters = self.parameters;
+        let len = basin.len();
+        let mut drainage_areas = (0..len)
+            .map(|k| self.areas[basin.site(k)])
+            .collect::<Vec<_>>();
+        let mut underground_flows = vec![0.0; len];
+        let mut spring_discharges = vec![0.0; len];
+        let mut response_times = vec![0.0; len];
+        let mut celerities = vec![0.0; len];
+        let mut elevations = (0..len)
+            .map(|k| self.elevations[basin.site(k)])
+            .collect::<Vec<_>>();
+        let mut num_changed = 0;
+        let mut max_elevation_change: Elevation = 0.0;
+
+        // calculate drainage areas
+        basin.accumulate(
+            &mut drainage_areas,
+            self.accumulation_threads,
+            self.config
+                .min_parallel_sites
+                .unwrap_or(DEFAULT_MIN_PARALLEL_SITES),
+        );
+
+        // trace the flow through karst
+        // `drainage_areas` is kept as the topographic drainage area, and the water sinking underground is subtracted from the erosive flow
+        if self.has_karst {
+            basin.for_each_downstream(|k, i| {
+                let solubility = parameters[i].solubility;
+                if solubility > 0.0 {
+                    // a part of the surface inflow sinks into the subsurface
+                    let surface_inflow = drainage_areas[k] - self.areas[i] - underground_flows[k];
+                    underground_flows[k] += surface_inflow.max(0.0) * solubility;
+                } else if underground_flows[k] > 0.0 {
+                    // the underground flow emerges as a spring at the lithology boundary
+                    spring_discharges[k] = underground_flows[k];
+                    underground_flows[k] = 0.0;
+                }
+
+                let l = basin.receiver(k);
+                if l != k {
+                    underground_flows[l] += underground_flows[k];
+                }
+            });
+        }
+
+        // apply the losses of the inflow by infiltration and evaporation
+        // the local runoff of each site is never lost, so the discharge is always positive
+        let mut discharges = Vec::new();
+        let mut infiltrations = Vec::new();
+        let mut groundwater_flows = Vec::new();
+        let mut baseflows = Vec::new();
+        if self.has_losses {
+            discharges = (0..len).map(|k| self.areas[basin.site(k)]).collect();
+            infiltrations = vec![0.0; len];
+            let transmissivity = self.config.groundwater_transmissivity;
+            if transmissivity.is_some() {
+                groundwater_flows = vec![0.0; len];
+                baseflows = vec![0.0; len];
+            }
+            basin.for_each_downstream(|k, i| {
+                let inflow = (discharges[k] - self.areas[i]).max(0.0);
+                let infiltration = parameters[i].infiltration;
+                let evaporation = parameters[i].evaporation.min(1.0 - infiltration);
+                infiltrations[k] = inflow * infiltration;
+                discharges[k] -= inflow * (infiltration + evaporation);
+
+                let l = basin.receiver(k);
+                if let Some(transmissivity) = transmissivity {
+                    // the groundwater flows downstream up to the capacity of the aquifer, which is proportional to the slope,
+                    // and the excess emerges as baseflow where the water table intersects the surface
+                    groundwater_flows[k] += infiltrations[k];
+                    let capacity = if l != k {
+                        let j = basin.site(l);
+                        let slope = (self.elevations[i] - self.elevations[j]) / self.distance(i, j);
+                        transmissivity * slope.max(0.0)
+                    } else {
+                        0.0
+                    };
+                    if groundwater_flows[k] > capacity {
+                        baseflows[k] = groundwater_flows[k] - capacity;
+                        discharges[k] += baseflows[k];
+                        groundwater_flows[k] = capacity;
+                    }
+                    if l != k {
+                        groundwater_flows[l] += groundwater_flows[k];
+                    }
+                }
+
+                if l != k {
+                    discharges[l] += discharges[k];
+                }
+            });
+        }
+        // the flow driving the erosion
+        let flows = if self.has_losses {
+            &discharges
+        } else {
+            &drainage_areas
+        };
+        // the water sinking underground no longer erodes the channels, while the local runoff always remains on the surface
+        let surface_flows;
+        let flows = if self.has_karst {
+            surface_flows = (0..len)
+                .map(|k| (flows[k] - underground_flows[k]).max(self.areas[basin.site(k)]))
+                .collect::<Vec<_>>();
+            &surface_flows
+        } else {
+            flows
+        };
+
+        let powers = self.stream_powers(flows);
+
+        // calculate response times
+        basin.for_each_upstream(|k, i| {
+            let l = basin.receiver(k);
+            let j = basin.site(l);
+            let distance = self.travel_distance(i, j);
+            let celerity = self.erodibility(i, j) * powers[k];
+            // the response time across a non-erodible site is infinite, so it is not propagated across the site
+            // and the sites upstream respond to it as their local base level
+            let delay = if celerity > 0.0 {
+                1.0 / celerity * distance
+            } else {
+                0.0
+            };
+            response_times[k] += response_times[l] + delay;
+            if l != k {
+                celerities[k] = celerity;
+            }
+        });
+
+        // calculate elevations
+        // the elevations in the steady state are measured from the nearest frozen, blended or non-erodible site downstream
+        // (or the outlet), so that the frozen sites act as the internal boundary conditions and the blended surface drains consistently
+        let mut anchors = vec![0; len];
+        basin.for_each_upstream(|k, i| {
+            let l = basin.receiver(k);
+            let j = basin.site(l);
+            if parameters[i].is_frozen {
+                anchors[k] = k;
+                return;
+            }
+            anchors[k] = anchors[l];
+            let mut new_elevation = if let Some(time_step) = self.config.time_step {
+                // transient: erode the elevation for a time step with the implicit scheme
+                // the receiver is always updated before the site in the upstream order
+                if l == k {
+                    elevations[k]
+                } else {
+                    let distance = self.travel_distance(i, j);
+                    let factor = self.erodibility(i, j) * powers[k] * time_step / distance;
+                    (elevations[k] + parameters[i].uplift_rate * time_step + factor * elevations[l])
+                        / (1.0 + factor)
+                }
+            } else if l != k && self.erodibility(i, j) == 0.0 {
+                // steady state: the non-erodible site is not lowered and anchors the sites upstream
+                anchors[k] = k;
+                elevations[k]
+            } else {
+                // steady state: the elevation is determined by the response time
+                let anchor = anchors[k];
+                elevations[anchor]
+                    + parameters[i].uplift_rate
+                        * (response_times[k] - response_times[anchor]).max(0.0)
+            };
+
+            // check if the slope is too steep
+            // if max_slope_func is not set, the slope is not checked
+            if let Some(max_slope) = parameters[i].max_slope {
+                let distance = self.distance(i, j);
+                let max_slope = max_slope.tan();
+                let slope = (new_elevation - elevations[l]) / distance;
+                if slope > max_slope {
+                    new_elevation = elevations[l] + max_slope * distance;
+                }
+            }
+
+            // the erosion cannot cut below the floor
+            // the raised sites are the anchors of the donors in the steady state, so that the donors stay above them
+            if let Some(min_elevation) = parameters[i].min_elevation {
+                if l != k && new_elevation < min_elevation {
+                    new_elevation = min_elevation;
+                    anchors[k] = k;
+                }
+            }
+
+            // blend with the authored elevation before the donors refer to it
+            // the blended sites are also the anchors of the donors in the steady state
+            if parameters[i].is_blended() && l != k {
+                let weight = parameters[i].blend_weight;
+                new_elevation =
+                    weight * new_elevation + (1.0 - weight) * parameters[i].authored_elevation;
+                anchors[k] = k;
+            }
+
+            if new_elevation != elevations[k] {
+                num_changed += 1;
+                max_elevation_change =
+                    max_elevation_change.max((new_elevation - elevations[k]).abs());
+            }
+            elevations[k] = new_elevation;
+        });
+
+        BasinSolution {
+            basin,
+            drainage_areas,
+            underground_flows,
+            spring_discharges,
+            discharges,
+            infiltrations,
+            groundwater_flows,
+            baseflows,
+            response_times,
+            celerities,
+            elevations,
+            num_changed,
+            max_elevation_change,
+        }
+    }
+}
+
+/// Solve the fluvial erosion of the drainage basins of the outlets of the stream tree in isolation,
+/// returning the drainage areas and the elevations after an iteration.
+///
+/// The anisotropy and the parameters of the edges are not considered.
+pub(crate) fn solve_basins(
+    config: &SimulationConfig,
+    areas: &[Area],
+    adjacency: &Adjacency,
+    parameters: &[TopographicalParameters],
+    elevations: &[Elevation],
+    stream_tree: &stream_tree::StreamTree,
+) -> (Vec<Area>, Vec<Elevation>) {
+    let edge_parameters = EdgeParameterMap::new();
+    let context = BasinContext {
+        config,
+        areas,
+        adjacency,
+        fallback_distance: config
+            .fallback_distance
+            .resolve_with(|| adjacency.mean_length()),
+        accumulation_threads: 1,
+        edge_directions: None,
+        edge_parameters: &edge_parameters,
+        parameters,
+        elevations,
+        has_karst: parameters.iter().any(|param| param.solubility > 0.0),
+        has_losses: parameters.iter().any(|param| param.has_losses()),
+        m_exp: DEFAULT_M_EXP,
+    };
+    let mut drainage_areas = areas.to_vec();
+    let mut new_elevations = elevations.to_vec();
+    (0..stream_tree.next.len())
+        .filter(|&i| stream_tree.next[i] == i)
+        .for_each(|outlet| {
+            let solution = context.solve(DrainageBasin::construct(outlet, stream_tree, adjacency));
+            solution.basin.for_each_upstream(|k, i| {
+                drainage_areas[i] = solution.drainage_areas[k];
+                new_elevations[i] = solution.elevations[k];
+            });
+        });
+    (drainage_areas, new_elevations)
+}
+
+/// Raise the sites lower than their receivers by more than `tolerance` to the level of the receivers.
+///
+/// The sites are visited from the outlets to upstream, so that the raised sites also raise their donors if needed.
+fn enforce_junction_consistency(
+    receivers: &[usize],
+    elevations: &mut [Elevation],
+    tolerance: Elevation,
+) {
+    let num = receivers.len();
+    let mut donors = vec![Vec::new(); num];
+    (0..num).for_each(|i| {
+        if receivers[i] != i {
+            donors[receivers[i]].push(i);
+        }
+    });
+    let mut queue = (0..num).filter(|&i| receivers[i] == i).collect::<Vec<_>>();
+    let mut k = 0;
+    while k < queue.len() {
+        let j = queue[k];
+        donors[j].iter().for_each(|&i| {
+            if elevations[i] < elevations[j] - tolerance {
+                elevations[i] = elevations[j];
+            }
+            queue.push(i);
+        });
+        k += 1;
+    }
+}
+
+/// Cut the sites above their ceilings (see [TopographicalParameters]) and spread the excess volume laterally.
+///
+/// The excess volume of each capped site is spread over the surrounding sites in the breadth-first order from the site,
+/// each of which takes [SPREAD_FILL_RATIO] of its room below its ceiling (or the ceiling of the capped site if it has none)
+/// until the volume is exhausted.
+/// The outlets do not take the volume and the volume is not spread beyond them.
+/// The sites are visited in the order of the indices, so the result is deterministic.
+fn spread_excess(
+    graph: &EdgeAttributedUndirectedGraph<Length>,
+    areas: &[Area],
+    parameters: &[TopographicalParameters],
+    receivers: &[usize],
+    elevations: &mut [Elevation],
+) {
+    let num = elevations.len();
+    let mut visited = vec![usize::MAX; num];
+    let mut queue = Vec::new();
+    (0..num).for_each(|i| {
+        let max_elevation = match parameters[i].max_elevation {
+            Some(max_elevation) if elevations[i] > max_elevation && receivers[i] != i => {
+                max_elevation
+            }
+            _ => return,
+        };
+        let mut excess = (elevations[i] - max_elevation) * areas[i];
+        elevations[i] = max_elevation;
+        if !excess.is_finite() {
+            return;
+        }
+
+        queue.clear();
+        queue.push(i);
+        visited[i] = i;
+        let mut k = 0;
+        while k < queue.len() && queue.len() <= MAX_SPREAD_SITES && excess > 0.0 {
+            let j = queue[k];
+            k += 1;
+            if j != i {
+                // the sites without the ceilings are not raised above the ceiling of the source
+                let ceiling = parameters[j].max_elevation.unwrap_or(max_elevation);
+                let room = (ceiling - elevations[j]).max(0.0) * areas[j];
+                let volume = (room * SPREAD_FILL_RATIO).min(excess);
+                if volume.is_finite() && volume > 0.0 {
+                    elevations[j] += volume / areas[j];
+                    excess -= volume;
+                }
+            }
+            graph.neighbors_of(j).iter().for_each(|ja| {
+                if visited[ja.0] != i && receivers[ja.0] != ja.0 {
+                    visited[ja.0] = i;
+                    queue.push(ja.0);
+                }
+            });
+        }
+    });
+}
+
+/// Run the simulation and return the resulting elevations with the additional fields and the drainage network.
+///
+/// `on_event` receives the [SimulationEvent]s and `on_step` receives the elevations after each iteration.
+/// `on_state` receives the state after the processes in each iteration, used to couple external models.
+/// `edge_directions` is the direction of each edge, required only if any site has the anisotropic erodibility.
+/// `edge_parameters` is the parameters of the edges overriding the flow along them.
+/// The inputs are assumed to be validated by the caller.
+/// If `debug_checks` is enabled, the invariants are checked at each iteration and the first violation is returned as an error.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn simulate(
+    config: &SimulationConfig,
+    areas: &[Area],
+    graph: &EdgeAttributedUndirectedGraph<Length>,
+    edge_directions: Option<&EdgeAttributedUndirectedGraph<f64>>,
+    edge_parameters: &EdgeParameterMap,
+    default_outlets: &[usize],
+    parameters: &[TopographicalParameters],
+    on_event: &mut dyn FnMut(SimulationEvent),
+    on_step: &mut dyn FnMut(Step, &[Elevation]),
+    mut on_state: Option<&mut dyn FnMut(&mut SimulationState)>,
+) -> Result<(Vec<Elevation>, SiteFields, DrainageNetwork), GenerationError> {
+    let num = areas.len();
+
+    // processes may modify the parameters during the simulation, so they are copied only when the processes are applied
+    let mut parameters = Cow::Borrowed(parameters);
+    let mut fields = SiteFields::default();
+
+    let m_exp = DEFAULT_M_EXP;
+
+    let outlets = {
+        let outlets = parameters
+            .iter()
+            .enumerate()
+            .filter(|(_, param)| param.is_outlet)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        if outlets.is_empty() {
+            default_outlets.to_vec()
+        } else {
+            outlets
+        }
+    };
+
+    let is_outlet = {
+        let mut is_outlet = vec![false; num];
+        outlets.iter().for_each(|&i| is_outlet[i] = true);
+        is_outlet
+    };
+
+    let has_karst = parameters.iter().any(|param| param.solubility > 0.0);
+    let has_ceiling = parameters.iter().any(|param| param.max_elevation.is_some());
+    let has_losses = parameters.iter().any(|param| param.has_losses());
+
+    let jitter = config.jitter.sample(graph, config.seed);
+    let initial_elevations = parameters
+        .iter()
+        .zip(jitter)
+        .map(|(a, noise)| {
+            if a.is_frozen {
+                a.base_elevation
+            } else {
+                a.base_elevation + noise
+            }
+        })
+        .collect::<Vec<_>>();
+    #[cfg(feature = "mmap")]
+    let mut elevations = match &config.elevation_storage {
+        Some(path) => {
+            ElevationBuffer::Mapped(MappedElevations::create_from(path, &initial_elevations)?)
+        }
+        None => ElevationBuffer::Memory(initial_elevations),
+    };
+    #[cfg(not(feature = "mmap"))]
+    let mut elevations = ElevationBuffer::Memory(initial_elevations);
+
+    // the neighbors are iterated in the hot loops of each iteration, so they are laid out contiguously
+    let adjacency = Adjacency::from_graph(graph);
+
+    let fallback_distance = config.fallback_distance.resolve(graph);
+
+    // the outlet of the drainage basin to which each site belonged in the previous iteration
+    let mut prev_basin_outlets: Option<Vec<usize>> = None;
+
+    // the buffers reused across the iterations, so that the large vectors are not allocated in each iteration
+    let mut drainage_areas: Vec<Area> = areas.to_vec();
+    let mut stream_tree = stream_tree::StreamTree::default();
+    let mut prev_elevations_buffer: Vec<Elevation> = Vec::new();
+
+    // the roots of the stream tree: the outlets and the sinkholes of the previous iteration as the local sinks
+    let mut roots = outlets.clone();
+    let mut is_root = is_outlet.clone();
+
+    let mut last_step = 0;
+    let mut estimator = ProgressEstimator::new();
+    for step in 0..config.max_iteration.unwrap_or(u32::MAX) {
+        stream_tree.reconstruct(&elevations, &adjacency, &roots);
+        let step = step + 1;
+
+        // `violation` converts a violated invariant into an error
+        let violation = |(site, reason)| GenerationError::InvariantViolated { step, site, reason };
+
+        if config.debug_checks {
+            invariants::check_stream_tree(&stream_tree.next, &is_root).map_err(violation)?;
+        }
+
+        if config.fallback_distance == FallbackDistance::Strict {
+            if let Some(i) = (0..num).find(|&i| {
+                let j = stream_tree.next[i];
+                j != i && adjacency.find(i, j).is_none()
+            }) {
+                return Err(GenerationError::MissingEdge(i, stream_tree.next[i]));
+            }
+        }
+
+        // the elevations before the iteration, required only to count the changes after the fluvial erosion
+        let prev_elevations = if config.processes.is_empty()
+            && on_state.is_none()
+            && config.junction_tolerance.is_none()
+            && !has_ceiling
+        {
+            None
+        } else {
+            prev_elevations_buffer.clear();
+            prev_elevations_buffer.extend_from_slice(&elevations);
+            Some(&prev_elevations_buffer)
+        };
+
+        drainage_areas.copy_from_slice(areas);
+        let mut underground_flows: Vec<f64> = Vec::new();
+        let mut spring_discharges: Vec<f64> = Vec::new();
+        let mut discharges: Vec<f64> = Vec::new();
+        let mut infiltrations: Vec<f64> = Vec::new();
+        let mut groundwater_flows: Vec<f64> = Vec::new();
+        let mut baseflows: Vec<f64> = Vec::new();
+        let has_groundwater = has_losses && config.groundwater_transmissivity.is_some();
+        if has_karst {
+            underground_flows = vec![0.0; num];
+            spring_discharges = vec![0.0; num];
+        }
+        if has_losses {
+            discharges = vec![0.0; num];
+            infiltrations = vec![0.0; num];
+        }
+        if has_groundwater {
+            groundwater_flows = vec![0.0; num];
+            baseflows = vec![0.0; num];
+        }
+        let mut response_times = vec![0.0; num];
+        let mut celerities = vec![0.0; num];
+        let mut basin_outlets = vec![0; num];
+        let mut num_changed = 0;
+        let mut max_elevation_change: Elevation = 0.0;
+
+        // calculate elevations for each drainage basin
+        // the basins are disjoint and solved in isolation, so the results do not depend on the number of threads
+        // the threads not used to solve the basins in parallel accumulate the drainage areas within the basins
+        let basin_threads = if config.num_threads > 1 && roots.len() > 1 {
+            roots.len().min(config.num_threads)
+        } else {
+            1
+        };
+        let context = BasinContext {
+            config,
+            areas,
+            adjacency: &adjacency,
+            fallback_distance,
+            accumulation_threads: (config.num_threads / basin_threads).max(1),
+            edge_directions,
+            edge_parameters,
+            parameters: &parameters,
+            elevations: &elevations,
+            has_karst,
+            has_losses,
+            m_exp,
+        };
+        let solve = |outlet: usize| {
+            context.solve(DrainageBasin::construct(outlet, &stream_tree, &adjacency))
+        };
+        let solutions = if config.num_threads > 1 && roots.len() > 1 {
+            let chunk_size = roots.len().div_ceil(config.num_threads);
+            std::thread::scope(|scope| {
+                let handles = roots
+                    .chunks(chunk_size)
+                    .map(|chunk| {
+                        scope.spawn(|| {
+                            chunk
+                                .iter()
+                                .map(|&outlet| solve(outlet))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().unwrap())
+                    .collect::<Vec<_>>()
+            })
+        } else {
+            roots.iter().map(|&outlet| solve(outlet)).collect()
+        };
+
+        // merge the solutions in the order of the roots
+        solutions.into_iter().for_each(|solution| {
+            let outlet = solution.basin.site(0);
+            solution.basin.for_each_upstream(|k, i| {
+                basin_outlets[i] = outlet;
+                drainage_areas[i] = solution.drainage_areas[k];
+                if has_karst {
+                    underground_flows[i] = solution.underground_flows[k];
+                    spring_discharges[i] = solution.spring_discharges[k];
+                }
+                if has_losses {
+                    discharges[i] = solution.discharges[k];
+                    infiltrations[i] = solution.infiltrations[k];
+                }
+                if has_groundwater {
+                    groundwater_flows[i] = solution.groundwater_flows[k];
+                    baseflows[i] = solution.baseflows[k];
+                }
+                // relative to the outlet of the basin
+                response_times[i] = solution.response_times[k] - solution.response_times[0];
+                celerities[i] = solution.celerities[k];
+                elevations[i] = solution.elevations[k];
+            });
+            num_changed += solution.num_changed;
+            max_elevation_change = max_elevation_change.max(solution.max_elevation_change);
+        });
+
+        if has_karst {
+            // sinkholes swallow all the inflow
+            let sinkholes = (0..num)
+                .map(|i| {
+                    let is_sinkhole = parameters[i].solubility >= 1.0
+                        && underground_flows[i] > 0.0
+                        && !is_outlet[i];
+                    if is_sinkhole {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                })
+                .collect::<Vec<_>>();
+            let surface_drainage_areas = (0..num)
+                .map(|i| drainage_areas[i] - underground_flows[i])
+                .collect::<Vec<_>>();
+            fields.insert(SURFACE_DRAINAGE_AREA, surface_drainage_areas);
+            fields.insert(UNDERGROUND_FLOW, underground_flows);
+            fields.insert(SPRING_DISCHARGE, spring_discharges);
+
+            // the sinkholes drain the surface around them in the next iteration
+            // the water swallowed by them leaves the surface network, as they are the roots of their own basins
+            roots.truncate(outlets.len());
+            roots.extend((0..num).filter(|&i| sinkholes[i] > 0.0));
+            is_root.copy_from_slice(&is_outlet);
+            roots.iter().for_each(|&i| is_root[i] = true);
+            fields.insert(SINKHOLE, sinkholes);
+        }
+
+        if has_losses {
+            fields.insert(DISCHARGE, discharges);
+            fields.insert(INFILTRATION, infiltrations);
+        }
+        if has_groundwater {
+            fields.insert(GROUNDWATER_FLOW, groundwater_flows);
+            fields.insert(BASEFLOW, baseflows);
+        }
+
+        if config.debug_checks {
+            invariants::check_drainage_areas(&drainage_areas, areas).map_err(violation)?;
+            invariants::check_response_times(&response_times).map_err(violation)?;
+        }
+        fields.insert(RESPONSE_TIME, response_times);
+        fields.insert(CELERITY, celerities);
+
+        if let Some(prev_elevations) = prev_elevations {
+            if has_ceiling {
+                spread_excess(
+                    graph,
+                    areas,
+                    &parameters,
+                    &stream_tree.next,
+                    &mut elevations,
+                );
+            }
+
+            {
+                let mut state = SimulationState {
+                    step,
+                    time_step: config.time_step.unwrap_or(1.0),
+                    areas,
+                    graph,
+                    receivers: &stream_tree.next,
+                    fallback_distance,
+                    drainage_areas: &drainage_areas,
+                    elevations: &mut elevations,
+                    parameters: parameters.to_mut(),
+                    fields: &mut fields,
+                };
+                config
+                    .processes
+                    .iter()
+                    .for_each(|process| apply_in_sub_steps(process.as_ref(), &mut state));
+                if let Some(on_state) = on_state.as_mut() {
+                    on_state(&mut state);
+                }
+            }
+
+            if let Some(tolerance) = config.junction_tolerance {
+                enforce_junction_consistency(&stream_tree.next, &mut elevations, tolerance);
+            }
+
+            // the frozen sites are kept even if the processes changed them
+            (0..num).for_each(|i| {
+                if parameters[i].is_frozen {
+                    elevations[i] = prev_elevations[i];
+                }
+            });
+
+            // count the changes again including the ones by the ceilings, processes, the coupled models and the consistency pass
+            num_changed = 0;
+            max_elevation_change = 0.0;
+            (0..num).for_each(|i| {
+                let change = (elevations[i] - prev_elevations[i]).abs();
+                if change > 0.0 {
+                    max_elevation_change = max_elevation_change.max(change);
+                    num_changed += 1;
+                }
+            });
+        }
+
+        if config.debug_checks {
+            invariants::check_elevations(&elevations).map_err(violation)?;
+        }
+
+        last_step = step;
+
+        // a stream capture occurs where a site is rerouted into a basin which its new receiver already belonged to
+        if let Some(prev_basin_outlets) = &prev_basin_outlets {
+            (0..num).for_each(|i| {
+                let j = stream_tree.next[i];
+                if basin_outlets[i] != prev_basin_outlets[i]
+                    && prev_basin_outlets[j] == basin_outlets[i]
+                {
+                    on_event(SimulationEvent::StreamCaptured {
+                        step,
+                        site: i,
+                        previous_outlet: prev_basin_outlets[i],
+                        new_outlet: basin_outlets[i],
+                    });
+                }
+            });
+        }
+        prev_basin_outlets = Some(basin_outlets);
+
+        on_step(step, &elevations);
+
+        on_event(SimulationEvent::StepCompleted(estimator.progress(
+            step,
+            config.max_iteration,
+            num_changed,
+        )));
+        on_event(SimulationEvent::ConvergenceMetric {
+            step,
+            max_elevation_change,
+        });
+        if let Some(snapshot_interval) = config.snapshot_interval {
+            if snapshot_interval > 0 && step % snapshot_interval == 0 {
+                #[cfg(feature = "mmap")]
+                if let Some(directory) = &config.snapshot_directory {
+                    storage::write_snapshot(directory, step, &elevations)?;
+                }
+                on_event(SimulationEvent::SnapshotReady {
+                    step,
+                    elevations: elevations.to_vec(),
+                });
+            }
+        }
+
+        // if the elevations of all sites are stable, break
+        if num_changed == 0 {
+            break;
+        }
+        // if the changes are negligible compared to the relief, break
+        if let Some(tolerance) = config.convergence_tolerance {
+            let (min_elevation, max_elevation) = elevations
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &e| {
+                    (min.min(e), max.max(e))
+                });
+            if max_elevation_change <= tolerance * (max_elevation - min_elevation) {
+                break;
+            }
+        }
+    }
+
+    // the drainage network of the last iteration
+    let network = if last_step > 0 {
+        DrainageNetwork::from_parts(stream_tree.next, drainage_areas, stream_tree.order)
+    } else {
+        DrainageNetwork::default()
+    };
+
+    // sinkholes are dissolved below their lowest neighbor as closed depressions
+    // this is done after the iterations since the depressions would otherwise reroute the flow
+    if let Some(sinkholes) = fields.get(SINKHOLE) {
+        let sinkholes = sinkholes
+            .iter()
+            .enumerate()
+            .filter(|(_, &sinkhole)| sinkhole > 0.0)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        let original_elevations = elevations.to_vec();
+        sinkholes.iter().for_each(|&i| {
+            let lowest = graph
+                .neighbors_of(i)
+                .iter()
+                .map(|ja| original_elevations[ja.0])
+                .fold(original_elevations[i], f64::min);
+            let depth = (original_elevations[i] - lowest) * SINKHOLE_DEPTH_RATIO;
+            elevations[i] = lowest - depth;
+        });
+    }
+
+    // the distance of each site from the coast, the sites adjacent to the outlets (ocean)
+    let coast_distances = {
+        let coast = (0..num)
+            .filter(|&i| !is_outlet[i] && graph.neighbors_of(i).iter().any(|ja| is_outlet[ja.0]))
+            .collect::<Vec<_>>();
+        let field = DistanceField::new(graph, &coast, f64::INFINITY);
+        let distances = field.distances();
+        (0..num)
+            .map(|i| {
+                if is_outlet[i] || !distances[i].is_finite() {
+                    0.0
+                } else {
+                    distances[i]
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let max_coast_distance = coast_distances.iter().fold(0.0, |a: f64, &b| a.max(b));
+    let continentalities = coast_distances
+        .iter()
+        .map(|&d| {
+            if max_coast_distance > 0.0 {
+                d / max_coast_distance
+            } else {
+                0.0
+            }
+        })
+        .collect::<Vec<_>>();
+    fields.insert(COAST_DISTANCE, coast_distances);
+    fields.insert(CONTINENTALITY, continentalities);
+
+    // the channel steepness index `S * A^m` relative to its maximum
+    let channel_steepnesses = {
+        let receivers = network.receivers();
+        // the sites without the edges to their receivers have no steepness
+        let distances = (0..num)
+            .map(|i| {
+                let j = receivers.get(i).copied().unwrap_or(i);
+                match adjacency.length_between(i, j) {
+                    Some(distance) if j != i && distance > 0.0 => Some(distance),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        let lower_elevations = (0..num)
+            .map(|i| elevations[receivers.get(i).copied().unwrap_or(i)])
+            .collect::<Vec<_>>();
+        let slopes = kernels::downhill_slopes(
+            &elevations,
+            &lower_elevations,
+            &distances
+                .iter()
+                .map(|distance| distance.unwrap_or(1.0))
+                .collect::<Vec<_>>(),
+        );
+        let powers = if config.fast_powf {
+            kernels::fast_stream_powers(network.drainage_areas(), m_exp)
+        } else {
+            kernels::stream_powers(network.drainage_areas(), m_exp)
+        };
+        let steepnesses = (0..num)
+            .map(|i| {
+                if distances[i].is_none() {
+                    return 0.0;
+                }
+                let steepness = slopes[i] * powers[i];
+                if steepness.is_finite() {
+                    steepness
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<_>>();
+        let max_steepness = steepnesses.iter().fold(0.0, |a: f64, &b| a.max(b));
+        steepnesses
+            .iter()
+            .map(|&k| {
+                if max_steepness > 0.0 {
+                    k / max_steepness
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    fields.insert(CHANNEL_STEEPNESS, channel_steepnesses);
+
+    on_event(SimulationEvent::Finished { step: last_step });
+
+    #[cfg(feature = "mmap")]
+    elevations.flush()?;
+    Ok((elevations.into_vec(), fields, network))
+}
@@ -0,0 +1,222 @@
+use std::io::{self, Read, Write};
+
+use crate::core::units::{Elevation, Step};
+
+/// The magic bytes at the beginning of a snapshot file.
+const SNAPSHOT_MAGIC: &[u8; 8] = b"FLEMSNP1";
+
+/// A snapshot encoded as the deltas from the previous one.
+#[derive(Debug, Clone)]
+struct Frame {
+    step: Step,
+    num: usize,
+    data: Vec<u8>,
+}
+
+/// A compressed sequence of snapshots of the elevations, for animations of long transient runs.
+///
+/// The elevations are quantized by `quantum`, so each decoded elevation differs from the original one by at most `quantum / 2`.
+/// Each snapshot is stored as the differences of the quantized values from the previous snapshot, encoded as variable-length integers,
+/// so the slowly changing terrain takes only a few bytes per site. If the number of sites changes, the snapshot is stored as is.
+///
+/// The snapshots are typically pushed from [crate::lem::events::SimulationEvent::SnapshotReady] in `TerrainGenerator::generate_with_events`.
+#[derive(Debug, Clone)]
+pub struct SnapshotSequence {
+    quantum: Elevation,
+    frames: Vec<Frame>,
+    last: Vec<i64>,
+}
+
+impl SnapshotSequence {
+    /// Create an empty sequence with the quantization step `quantum` (unit: L).
+    pub fn new(quantum: Elevation) -> Self {
+        Self {
+            quantum: quantum.max(f64::MIN_POSITIVE),
+            frames: Vec::new(),
+            last: Vec::new(),
+        }
+    }
+
+    pub fn quantum(&self) -> Elevation {
+        self.quantum
+    }
+
+    /// The number of the snapshots.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The iteration of each snapshot.
+    pub fn steps(&self) -> Vec<Step> {
+        self.frames.iter().map(|frame| frame.step).collect()
+    }
+
+    /// The total size of the encoded snapshots in bytes.
+    pub fn encoded_size(&self) -> usize {
+        self.frames.iter().map(|frame| frame.data.len()).sum()
+    }
+
+    /// Append a snapshot of the elevations at the iteration `step`.
+    pub fn push(&mut self, step: Step, elevations: &[Elevation]) {
+        if self.last.len() != elevations.len() {
+            self.last = vec![0; elevations.len()];
+        }
+        let mut data = Vec::new();
+        elevations.iter().enumerate().for_each(|(i, &elevation)| {
+            let value = quantize(elevation, self.quantum);
+            write_varint(&mut data, zigzag(value.wrapping_sub(self.last[i])));
+            self.last[i] = value;
+        });
+        self.frames.push(Frame {
+            step,
+            num: elevations.len(),
+            data,
+        });
+    }
+
+    /// Iterate over the decoded snapshots with their iterations.
+    pub fn iter(&self) -> SnapshotIter<'_> {
+        SnapshotIter {
+            sequence: self,
+            index: 0,
+            values: Vec::new(),
+        }
+    }
+
+    /// Decode the `index`-th snapshot.
+    ///
+    /// Since the snapshots are stored as deltas, this decodes all the preceding snapshots.
+    /// Use [SnapshotSequence::iter] to decode the snapshots in order.
+    pub fn decode(&self, index: usize) -> Option<Vec<Elevation>> {
+        self.iter().nth(index).map(|(_, elevations)| elevations)
+    }
+
+    /// Write the sequence to `writer` in a compact binary format.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(SNAPSHOT_MAGIC)?;
+        writer.write_all(&self.quantum.to_le_bytes())?;
+        writer.write_all(&(self.frames.len() as u64).to_le_bytes())?;
+        self.frames.iter().try_for_each(|frame| {
+            writer.write_all(&(frame.step as u64).to_le_bytes())?;
+            writer.write_all(&(frame.num as u64).to_le_bytes())?;
+            writer.write_all(&(frame.data.len() as u64).to_le_bytes())?;
+            writer.write_all(&frame.data)
+        })
+    }
+
+    /// Read a sequence written by [SnapshotSequence::write_to].
+    ///
+    /// The sequence can be continued by [SnapshotSequence::push] after reading.
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != SNAPSHOT_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The data is not a snapshot sequence",
+            ));
+        }
+        let quantum = f64::from_le_bytes(read_bytes(&mut reader)?);
+        let num_frames = u64::from_le_bytes(read_bytes(&mut reader)?) as usize;
+        let frames = (0..num_frames)
+            .map(|_| {
+                let step = u64::from_le_bytes(read_bytes(&mut reader)?) as Step;
+                let num = u64::from_le_bytes(read_bytes(&mut reader)?) as usize;
+                let len = u64::from_le_bytes(read_bytes(&mut reader)?) as usize;
+                let mut data = vec![0u8; len];
+                reader.read_exact(&mut data)?;
+                Ok(Frame { step, num, data })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut sequence = Self {
+            quantum,
+            frames,
+            last: Vec::new(),
+        };
+        // restore the quantized values of the last snapshot to continue the deltas
+        let mut iter = sequence.iter();
+        while iter.next().is_some() {}
+        sequence.last = iter.values;
+        Ok(sequence)
+    }
+}
+
+/// An iterator over the decoded snapshots of a [SnapshotSequence].
+pub struct SnapshotIter<'a> {
+    sequence: &'a SnapshotSequence,
+    index: usize,
+    values: Vec<i64>,
+}
+
+impl Iterator for SnapshotIter<'_> {
+    type Item = (Step, Vec<Elevation>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame = self.sequence.frames.get(self.index)?;
+        self.index += 1;
+        if self.values.len() != frame.num {
+            self.values = vec![0; frame.num];
+        }
+        let mut cursor = 0;
+        self.values.iter_mut().for_each(|value| {
+            *value = value.wrapping_add(unzigzag(read_varint(&frame.data, &mut cursor)));
+        });
+        let quantum = self.sequence.quantum;
+        Some((
+            frame.step,
+            self.values.iter().map(|&v| v as f64 * quantum).collect(),
+        ))
+    }
+}
+
+fn quantize(elevation: Elevation, quantum: Elevation) -> i64 {
+    if elevation.is_finite() {
+        (elevation / quantum).round() as i64
+    } else {
+        0
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn write_varint(data: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        data.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+}
+
+fn read_varint(data: &[u8], cursor: &mut usize) -> u64 {
+    let mut value = 0;
+    let mut shift = 0;
+    while let Some(&byte) = data.get(*cursor) {
+        *cursor += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+        if shift >= 64 {
+            break;
+        }
+    }
+    value
+}
+
+fn read_bytes(reader: &mut impl Read) -> io::Result<[u8; 8]> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
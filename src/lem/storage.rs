@@ -0,0 +1,180 @@
+//! The storage of the elevations during the simulation.
+//!
+//! With the feature `mmap`, the elevations can be backed by memory-mapped files (see [MappedElevations]),
+//! so that the simulation of huge models does not exhaust the memory and the snapshots persist on the disk.
+
+use std::ops::{Deref, DerefMut};
+
+use crate::core::units::Elevation;
+
+#[cfg(feature = "mmap")]
+pub use mapped::MappedElevations;
+
+/// The buffer of the elevations updated by the simulation.
+pub(crate) enum ElevationBuffer {
+    Memory(Vec<Elevation>),
+    #[cfg(feature = "mmap")]
+    Mapped(MappedElevations),
+}
+
+impl ElevationBuffer {
+    /// Take the elevations out of the buffer.
+    pub(crate) fn into_vec(self) -> Vec<Elevation> {
+        match self {
+            Self::Memory(elevations) => elevations,
+            #[cfg(feature = "mmap")]
+            Self::Mapped(elevations) => elevations.to_vec(),
+        }
+    }
+
+    /// Write the changes to the mapped file, if any.
+    #[cfg(feature = "mmap")]
+    pub(crate) fn flush(&self) -> std::io::Result<()> {
+        match self {
+            Self::Memory(_) => Ok(()),
+            Self::Mapped(elevations) => elevations.flush(),
+        }
+    }
+}
+
+impl Deref for ElevationBuffer {
+    type Target = [Elevation];
+
+    fn deref(&self) -> &[Elevation] {
+        match self {
+            Self::Memory(elevations) => elevations,
+            #[cfg(feature = "mmap")]
+            Self::Mapped(elevations) => elevations,
+        }
+    }
+}
+
+impl DerefMut for ElevationBuffer {
+    fn deref_mut(&mut self) -> &mut [Elevation] {
+        match self {
+            Self::Memory(elevations) => elevations,
+            #[cfg(feature = "mmap")]
+            Self::Mapped(elevations) => elevations,
+        }
+    }
+}
+
+#[cfg(feature = "mmap")]
+mod mapped {
+    use std::{
+        fs::{self, File, OpenOptions},
+        io,
+        ops::{Deref, DerefMut},
+        path::{Path, PathBuf},
+    };
+
+    use memmap2::MmapMut;
+
+    use crate::core::units::{Elevation, Step};
+
+    /// The magic bytes at the beginning of a file of mapped elevations.
+    const MAPPED_MAGIC: &[u8; 8] = b"FLEMMAP1";
+
+    /// The elevations stored in a memory-mapped file.
+    ///
+    /// The file consists of the magic bytes `FLEMMAP1` followed by the elevations as 64-bit floating point numbers
+    /// in the native byte order, so the files can be read only on machines of the same byte order.
+    /// The changes are written to the file by the operating system, or explicitly by [MappedElevations::flush].
+    pub struct MappedElevations {
+        mmap: MmapMut,
+        len: usize,
+    }
+
+    impl MappedElevations {
+        /// Create the file of `len` elevations initialized to 0.0, overwriting the existing file.
+        pub fn create(path: impl AsRef<Path>, len: usize) -> io::Result<Self> {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)?;
+            file.set_len((MAPPED_MAGIC.len() + len * std::mem::size_of::<Elevation>()) as u64)?;
+            let mut mapped = Self::map(&file, len)?;
+            mapped.mmap[..MAPPED_MAGIC.len()].copy_from_slice(MAPPED_MAGIC);
+            Ok(mapped)
+        }
+
+        /// Create the file with a copy of the elevations, overwriting the existing file.
+        pub fn create_from(path: impl AsRef<Path>, elevations: &[Elevation]) -> io::Result<Self> {
+            let mut mapped = Self::create(path, elevations.len())?;
+            mapped.copy_from_slice(elevations);
+            Ok(mapped)
+        }
+
+        /// Open the file created by [MappedElevations::create].
+        pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+            let file = OpenOptions::new().read(true).write(true).open(path)?;
+            let size = file.metadata()?.len() as usize;
+            let element = std::mem::size_of::<Elevation>();
+            if size < MAPPED_MAGIC.len() || !(size - MAPPED_MAGIC.len()).is_multiple_of(element) {
+                return Err(invalid_data());
+            }
+            let mapped = Self::map(&file, (size - MAPPED_MAGIC.len()) / element)?;
+            if &mapped.mmap[..MAPPED_MAGIC.len()] != MAPPED_MAGIC {
+                return Err(invalid_data());
+            }
+            Ok(mapped)
+        }
+
+        /// Write the changes to the file.
+        pub fn flush(&self) -> io::Result<()> {
+            self.mmap.flush()
+        }
+
+        fn map(file: &File, len: usize) -> io::Result<Self> {
+            // SAFETY: the file is owned by the simulation while it is mapped, and the elevations follow
+            // the 8-byte magic in the page-aligned mapping, so they are aligned for `f64`.
+            let mmap = unsafe { MmapMut::map_mut(file)? };
+            Ok(Self { mmap, len })
+        }
+    }
+
+    impl Deref for MappedElevations {
+        type Target = [Elevation];
+
+        fn deref(&self) -> &[Elevation] {
+            let data = &self.mmap[MAPPED_MAGIC.len()..];
+            // SAFETY: the mapping has `len` aligned elevations after the magic (see `map`).
+            unsafe { std::slice::from_raw_parts(data.as_ptr() as *const Elevation, self.len) }
+        }
+    }
+
+    impl DerefMut for MappedElevations {
+        fn deref_mut(&mut self) -> &mut [Elevation] {
+            let data = &mut self.mmap[MAPPED_MAGIC.len()..];
+            // SAFETY: the mapping has `len` aligned elevations after the magic (see `map`).
+            unsafe { std::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut Elevation, self.len) }
+        }
+    }
+
+    /// The path of the file of the snapshot at the iteration `step` in the directory.
+    pub(crate) fn snapshot_path(directory: &Path, step: Step) -> PathBuf {
+        directory.join(format!("snapshot_{:08}.flem", step))
+    }
+
+    /// Write the snapshot of the elevations at the iteration `step` to the directory.
+    pub(crate) fn write_snapshot(
+        directory: &Path,
+        step: Step,
+        elevations: &[Elevation],
+    ) -> io::Result<()> {
+        fs::create_dir_all(directory)?;
+        MappedElevations::create_from(snapshot_path(directory, step), elevations)?.flush()
+    }
+
+    fn invalid_data() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "The file does not contain mapped elevations",
+        )
+    }
+}
+
+#[cfg(feature = "mmap")]
+pub(crate) use mapped::write_snapshot;
@@ -1,15 +1,37 @@
 use std::collections::BinaryHeap;
-use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
 
 use crate::core::{
-    traits::Site,
+    adjacency::Adjacency,
+    distance::QueueItem,
+    network::upstream_order,
     units::{Elevation, Length},
 };
 
 /// Tree structure for representing the flow of water.
 ///  - `next` is the next site of each site in the flow.
+///  - `order` is the sites in the order from the outlets upstream, where each site comes after its next site.
+///
+/// The tree is reconstructed in place in each iteration (see [StreamTree::reconstruct]),
+/// so that the buffers of the tree and of its construction are allocated only once.
+///
+/// The flow over the flat regions, where the sites have no lower neighbors but neighbors of the same elevation,
+/// is routed along a gradient imposed over each region (Garbrecht and Martz, 1997) instead of depending on the jitter of the elevations.
+#[derive(Default)]
 pub struct StreamTree {
     pub next: Vec<usize>,
+    pub order: Vec<usize>,
+    is_outlet: Vec<bool>,
+    subroot: Vec<Option<usize>>,
+    root: Vec<Option<usize>>,
+    visited: Vec<bool>,
+    ridgestack: BinaryHeap<RidgeElement>,
+    donor_starts: Vec<usize>,
+    donors: Vec<usize>,
+    flat_labels: Vec<Option<usize>>,
+    flat_sites: Vec<usize>,
+    to_lower: Vec<Length>,
+    from_higher: Vec<Length>,
+    flat_queue: BinaryHeap<QueueItem>,
 }
 
 struct RidgeElement {
@@ -45,61 +67,78 @@ impl PartialOrd for RidgeElement {
 
 impl StreamTree {
     /// Constructs a stream tree from a given terrain data.
-    pub fn construct<S: Site>(
-        sites: &[S],
+    pub fn construct(elevations: &[Elevation], adjacency: &Adjacency, outlets: &[usize]) -> Self {
+        let mut stream_tree = Self::default();
+        stream_tree.reconstruct(elevations, adjacency, outlets);
+        stream_tree
+    }
+
+    /// Constructs a stream tree from the given next sites.
+    pub fn from_next(next: Vec<usize>) -> Self {
+        let mut stream_tree = Self {
+            next,
+            ..Default::default()
+        };
+        stream_tree.update_order();
+        stream_tree
+    }
+
+    /// Reconstructs the stream tree in place from a given terrain data, reusing the buffers.
+    pub fn reconstruct(
+        &mut self,
         elevations: &[Elevation],
-        graph: &EdgeAttributedUndirectedGraph<Length>,
+        adjacency: &Adjacency,
         outlets: &[usize],
-    ) -> Self {
-        let num = sites.len();
+    ) {
+        let num = elevations.len();
 
         // `is_outlet` is a table that indicates whether a site is an outlet or not.
-        let is_outlet = Self::create_outlet_table(sites, outlets);
+        self.is_outlet.clear();
+        self.is_outlet.resize(num, false);
+        outlets.iter().for_each(|&i| {
+            self.is_outlet[i] = true;
+        });
 
         // `next` is the next site of each site in the flow.
         // at this point, the stream tree can create lakes: a root of a stream tree not connected to an outlet.
-        let next = Self::construct_initial_stream_tree(num, elevations, graph, &is_outlet);
-
-        // `subroot` is the root of each site in the flow. lakes are not removed yet.
-        let (subroot, has_lake) = Self::find_roots_with_lakes(num, &is_outlet, &next);
+        let has_flat = self.construct_initial_stream_tree(num, elevations, adjacency);
 
-        // if there are no lakes, stream tree is already complete
-        if !has_lake {
-            return StreamTree { next };
+        // the flat regions draining to lower sites are routed across instead of being left as lakes
+        if has_flat {
+            self.resolve_flats(num, elevations, adjacency);
         }
 
-        // remove lakes from the stream tree
-        let next = Self::remove_lakes_from_stream_tree(&next, num, graph, outlets, &subroot);
+        // `subroot` is the root of each site in the flow. lakes are not removed yet.
+        let has_lake = self.find_roots_with_lakes(num);
 
-        StreamTree { next }
-    }
+        // if there are lakes, remove them from the stream tree
+        if has_lake {
+            self.remove_lakes_from_stream_tree(num, adjacency, outlets);
+        }
 
-    fn create_outlet_table<S: Site>(sites: &[S], outlets: &[usize]) -> Vec<bool> {
-        let mut is_outlet = vec![false; sites.len()];
-        outlets.iter().for_each(|&i| {
-            is_outlet[i] = true;
-        });
-        is_outlet
+        self.update_order();
     }
 
+    /// Direct each site to its steepest lower neighbor, returning whether any site is left on a flat region.
     fn construct_initial_stream_tree(
+        &mut self,
         num: usize,
         elevations: &[Elevation],
-        graph: &EdgeAttributedUndirectedGraph<Length>,
-        is_outlet: &[bool],
-    ) -> Vec<usize> {
-        let mut next: Vec<usize> = (0..num).collect();
+        adjacency: &Adjacency,
+    ) -> bool {
+        let next = &mut self.next;
+        next.clear();
+        next.extend(0..num);
 
+        let mut has_flat = false;
         (0..num).for_each(|i| {
-            if is_outlet[i] {
+            if self.is_outlet[i] {
                 return;
             }
 
             let mut steepest_slope = 0.0;
-            graph.neighbors_of(i).iter().for_each(|ja| {
-                let j = ja.0;
+            adjacency.iter_neighbors(i).for_each(|(j, distance)| {
                 if elevations[i] > elevations[j] {
-                    let distance = ja.1;
                     let down_hill_slope = (elevations[i] - elevations[j]) / distance;
                     if down_hill_slope > steepest_slope {
                         steepest_slope = down_hill_slope;
@@ -107,15 +146,123 @@ impl StreamTree {
                     }
                 }
             });
+            if next[i] == i {
+                has_flat |= adjacency
+                    .iter_neighbors(i)
+                    .any(|(j, _)| elevations[j] == elevations[i]);
+            }
         });
+        has_flat
+    }
+
+    /// Route the flow across the flat regions which drain to lower sites or outlets.
+    ///
+    /// A flat region is a connected set of the sites of the same elevation containing sites without lower neighbors.
+    /// Its drains are the sites of the region with lower neighbors and the outlets in it. The gradient of each site is
+    /// the distance to the nearest drain counted twice (toward the lower terrain), plus the distance from the sites bordering
+    /// higher terrain subtracted from its maximum in the region (away from the higher terrain), with the distances along the edges
+    /// within the region. The gradient strictly decreases toward the drains, so the flow converges to the middle of the region
+    /// and leaves it through the drains without cycles. The closed regions without drains are left to the removal of the lakes.
+    fn resolve_flats(&mut self, num: usize, elevations: &[Elevation], adjacency: &Adjacency) {
+        let (next, is_outlet) = (&mut self.next, &self.is_outlet);
+        let (labels, sites) = (&mut self.flat_labels, &mut self.flat_sites);
+        let (to_lower, from_higher) = (&mut self.to_lower, &mut self.from_higher);
+        let queue = &mut self.flat_queue;
+        labels.clear();
+        labels.resize(num, None);
+        to_lower.clear();
+        to_lower.resize(num, f64::INFINITY);
+        from_higher.clear();
+        from_higher.resize(num, f64::INFINITY);
+
+        (0..num).for_each(|start| {
+            if labels[start].is_some() || next[start] != start || is_outlet[start] {
+                return;
+            }
 
-        next
+            // collect the region of the same elevation
+            sites.clear();
+            sites.push(start);
+            labels[start] = Some(start);
+            let mut k = 0;
+            while k < sites.len() {
+                let i = sites[k];
+                adjacency.iter_neighbors(i).for_each(|(j, _)| {
+                    if labels[j].is_none() && elevations[j] == elevations[i] {
+                        labels[j] = Some(start);
+                        sites.push(j);
+                    }
+                });
+                k += 1;
+            }
+            if sites.len() == 1 {
+                return;
+            }
+
+            let is_drain = |i: usize| next[i] != i || is_outlet[i];
+            if !sites.iter().any(|&i| is_drain(i)) {
+                return;
+            }
+            let borders_higher = |i: usize| {
+                adjacency
+                    .iter_neighbors(i)
+                    .any(|(j, _)| elevations[j] > elevations[i])
+            };
+
+            let in_region = |j: usize| labels[j] == Some(start);
+            distances_within(
+                adjacency,
+                sites.iter().copied().filter(|&i| is_drain(i)),
+                in_region,
+                to_lower,
+                queue,
+            );
+            distances_within(
+                adjacency,
+                sites.iter().copied().filter(|&i| borders_higher(i)),
+                in_region,
+                from_higher,
+                queue,
+            );
+            let max_from_higher = sites
+                .iter()
+                .map(|&i| from_higher[i])
+                .filter(|d| d.is_finite())
+                .fold(0.0, f64::max);
+            let gradient = |i: usize| {
+                let away_from_higher = if from_higher[i].is_finite() {
+                    max_from_higher - from_higher[i]
+                } else {
+                    0.0
+                };
+                2.0 * to_lower[i] + away_from_higher
+            };
+
+            sites.iter().for_each(|&i| {
+                if is_outlet[i] || next[i] != i {
+                    return;
+                }
+                let mut steepest_slope = 0.0;
+                let mut receiver = i;
+                adjacency.iter_neighbors(i).for_each(|(j, distance)| {
+                    if !in_region(j) {
+                        return;
+                    }
+                    let slope = (gradient(i) - gradient(j)) / distance;
+                    if slope > steepest_slope {
+                        steepest_slope = slope;
+                        receiver = j;
+                    }
+                });
+                next[i] = receiver;
+            });
+        });
     }
 
-    fn find_roots_with_lakes(num: usize, is_outlet: &[bool], next: &[usize]) -> (Vec<usize>, bool) {
-        let mut subroot: Vec<Option<usize>> = (0..num)
-            .map(|i| if is_outlet[i] { Some(i) } else { None })
-            .collect();
+    fn find_roots_with_lakes(&mut self, num: usize) -> bool {
+        let (next, is_outlet, subroot) = (&self.next, &self.is_outlet, &mut self.subroot);
+        subroot.clear();
+        subroot.extend((0..num).map(|i| if is_outlet[i] { Some(i) } else { None }));
 
         let mut has_lake = false;
 
@@ -145,19 +292,24 @@ impl StreamTree {
             subroot[iv] = ir;
         });
 
-        (subroot.iter().map(|&r| r.unwrap()).collect(), has_lake)
+        has_lake
     }
 
     fn remove_lakes_from_stream_tree(
-        next: &[usize],
+        &mut self,
         num: usize,
-        graph: &EdgeAttributedUndirectedGraph<Length>,
+        adjacency: &Adjacency,
         outlets: &[usize],
-        subroot: &[usize],
-    ) -> Vec<usize> {
+    ) {
+        let subroot = &self.subroot;
+        let next = &mut self.next;
+
         // final roots of the stream tree
-        let mut root: Vec<Option<usize>> = vec![None; num];
-        let mut ridgestack: BinaryHeap<RidgeElement> = BinaryHeap::with_capacity(num);
+        let root = &mut self.root;
+        root.clear();
+        root.resize(num, None);
+        let ridgestack = &mut self.ridgestack;
+        ridgestack.clear();
         outlets.iter().for_each(|&outlet| {
             root[outlet] = Some(outlet);
             ridgestack.push(RidgeElement {
@@ -167,8 +319,9 @@ impl StreamTree {
         });
 
         // remove lakes
-        let mut visited: Vec<bool> = vec![false; num];
-        let mut next = next.to_owned();
+        let visited = &mut self.visited;
+        visited.clear();
+        visited.resize(num, false);
 
         while let Some(element) = ridgestack.pop() {
             let i = element.index;
@@ -177,44 +330,75 @@ impl StreamTree {
                 continue;
             }
 
-            graph
-                .neighbors_of(i)
-                .iter()
-                .enumerate()
-                .for_each(|(_, ja)| {
-                    let j = ja.0;
-                    if visited[j] {
-                        return;
-                    }
+            adjacency.iter_neighbors(i).for_each(|(j, distance)| {
+                if visited[j] {
+                    return;
+                }
 
-                    if root[subroot[j]].is_none() {
-                        let mut k = j;
-                        let mut nk = i;
-                        loop {
-                            if next[k] != k {
-                                // flip flow
-                                let tmp = next[k];
-                                next[k] = nk;
-                                nk = k;
-                                k = tmp;
-                            } else {
-                                break;
-                            }
+                let sj = subroot[j].unwrap();
+                let si = subroot[i].unwrap();
+                if root[sj].is_none() {
+                    let mut k = j;
+                    let mut nk = i;
+                    loop {
+                        if next[k] != k {
+                            // flip flow
+                            let tmp = next[k];
+                            next[k] = nk;
+                            nk = k;
+                            k = tmp;
+                        } else {
+                            break;
                         }
-                        next[k] = nk;
-                        root[subroot[j]] = root[subroot[i]];
                     }
+                    next[k] = nk;
+                    root[sj] = root[si];
+                }
 
-                    let distance = ja.1;
-                    ridgestack.push(RidgeElement {
-                        index: j,
-                        dist: distance,
-                    });
+                ridgestack.push(RidgeElement {
+                    index: j,
+                    dist: distance,
                 });
-            root[i] = root[subroot[i]];
+            });
+            root[i] = root[subroot[i].unwrap()];
             visited[i] = true;
         }
+    }
 
-        next
+    /// Update `order` from `next`.
+    fn update_order(&mut self) {
+        upstream_order(
+            &self.next,
+            &mut self.donor_starts,
+            &mut self.donors,
+            &mut self.order,
+        );
+    }
+}
+
+/// Calculate the distances from the seeds along the edges between the sites in the region (Dijkstra's algorithm).
+fn distances_within(
+    adjacency: &Adjacency,
+    seeds: impl Iterator<Item = usize>,
+    in_region: impl Fn(usize) -> bool,
+    distances: &mut [Length],
+    queue: &mut BinaryHeap<QueueItem>,
+) {
+    queue.clear();
+    seeds.for_each(|seed| {
+        distances[seed] = 0.0;
+        queue.push(QueueItem(seed, 0.0));
+    });
+    while let Some(QueueItem(i, distance)) = queue.pop() {
+        if distance > distances[i] {
+            continue;
+        }
+        adjacency.iter_neighbors(i).for_each(|(j, length)| {
+            let next_distance = distance + length;
+            if in_region(j) && next_distance < distances[j] {
+                distances[j] = next_distance;
+                queue.push(QueueItem(j, next_distance));
+            }
+        });
     }
 }
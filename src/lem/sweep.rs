@@ -0,0 +1,259 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
+
+use crate::{
+    core::{
+        network::DrainageNetwork,
+        parameters::TopographicalParameters,
+        traits::{Model, Site},
+        units::{Elevation, Length},
+    },
+    lem::generator::{GenerationError, TerrainGenerator},
+};
+
+type ApplyFn = dyn Fn(TopographicalParameters, f64) -> TopographicalParameters + Send + Sync;
+
+/// An axis of a parameter sweep: a named list of values applied to the topographical parameters of all sites.
+#[derive(Clone)]
+pub struct SweepAxis {
+    name: String,
+    values: Vec<f64>,
+    apply: Arc<ApplyFn>,
+}
+
+impl SweepAxis {
+    /// Create an axis where `apply` returns the parameters of a site modified by a value.
+    pub fn new(
+        name: &str,
+        values: Vec<f64>,
+        apply: impl Fn(TopographicalParameters, f64) -> TopographicalParameters + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            values,
+            apply: Arc::new(apply),
+        }
+    }
+
+    /// An axis multiplying the erodibility of all sites by each value.
+    pub fn erodibility_factor(values: Vec<f64>) -> Self {
+        Self::new("erodibility_factor", values, |mut parameters, value| {
+            parameters.erodibility *= value;
+            parameters
+        })
+    }
+
+    /// An axis multiplying the uplift rate of all sites by each value.
+    pub fn uplift_rate_factor(values: Vec<f64>) -> Self {
+        Self::new("uplift_rate_factor", values, |mut parameters, value| {
+            parameters.uplift_rate *= value;
+            parameters
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+/// The morphometric summary of a generated terrain.
+///
+/// ### Properties
+///  - `min_elevation`, `max_elevation` and `mean_elevation` are the statistics of the elevations (unit: L).
+///  - `relief` is the difference between the maximum and the minimum elevation (unit: L).
+///  - `hypsometric_integral` is `(mean - min) / (max - min)`, from 0.0 (deeply dissected) to 1.0 (plateau-like).
+///  - `mean_gradient` is the mean gradient from each site to its receiver, excluding the outlets.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MorphometricSummary {
+    pub min_elevation: Elevation,
+    pub max_elevation: Elevation,
+    pub mean_elevation: Elevation,
+    pub relief: Elevation,
+    pub hypsometric_integral: f64,
+    pub mean_gradient: f64,
+}
+
+impl MorphometricSummary {
+    pub(crate) fn new(
+        elevations: &[Elevation],
+        graph: &EdgeAttributedUndirectedGraph<Length>,
+        network: &DrainageNetwork,
+        fallback_distance: Length,
+    ) -> Self {
+        if elevations.is_empty() {
+            return Self::default();
+        }
+        let min_elevation = elevations.iter().copied().fold(f64::INFINITY, f64::min);
+        let max_elevation = elevations.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mean_elevation = elevations.iter().sum::<f64>() / elevations.len() as f64;
+        let relief = max_elevation - min_elevation;
+        let hypsometric_integral = if relief > 0.0 {
+            (mean_elevation - min_elevation) / relief
+        } else {
+            0.0
+        };
+
+        let gradients = (0..network.receivers().len())
+            .filter(|&i| !network.is_outlet(i))
+            .map(|i| {
+                let j = network.receivers()[i];
+                let (ok, distance) = graph.has_edge(i, j);
+                let distance = if ok { distance } else { fallback_distance };
+                (elevations[i] - elevations[j]) / distance.max(f64::EPSILON)
+            })
+            .collect::<Vec<_>>();
+        let mean_gradient = if gradients.is_empty() {
+            0.0
+        } else {
+            gradients.iter().sum::<f64>() / gradients.len() as f64
+        };
+
+        Self {
+            min_elevation,
+            max_elevation,
+            mean_elevation,
+            relief,
+            hypsometric_integral,
+            mean_gradient,
+        }
+    }
+}
+
+/// A run of a parameter sweep.
+///
+/// ### Properties
+///  - `values` is the value of each axis applied to the run, in the order of the axes.
+///  - `terrain` is the generated terrain.
+///  - `summary` is the morphometric summary of the terrain.
+#[derive(Debug, Clone)]
+pub struct SweepRun<T> {
+    pub values: Vec<f64>,
+    pub terrain: T,
+    pub summary: MorphometricSummary,
+}
+
+/// Provides a runner of parameter sweeps, generating terrains for all the combinations of the values of the axes.
+///
+/// Each run starts from the model, the parameters and the configuration of the base generator,
+/// and the axes are applied to the parameters of all sites in the order they were added.
+/// The runs are returned in the row-major order of the combinations (the last axis varies fastest), regardless of `num_threads`.
+///
+/// ### Properties
+///  - `generator` is the base generator, with the model and the parameters set.
+///  - `axes` is the list of the axes (see [SweepAxis]).
+///  - `num_threads` is the number of threads running the combinations in parallel. The default value is 1.
+pub struct ParameterSweep<S, M, T>
+where
+    S: Site,
+    M: Model<S, T>,
+{
+    generator: TerrainGenerator<S, M, T>,
+    axes: Vec<SweepAxis>,
+    num_threads: usize,
+}
+
+impl<S, M, T> ParameterSweep<S, M, T>
+where
+    S: Site,
+    M: Model<S, T>,
+    TerrainGenerator<S, M, T>: Clone,
+{
+    pub fn new(generator: TerrainGenerator<S, M, T>) -> Self {
+        Self {
+            generator,
+            axes: Vec::new(),
+            num_threads: 1,
+        }
+    }
+
+    pub fn add_axis(mut self, axis: SweepAxis) -> Self {
+        self.axes.push(axis);
+        self
+    }
+
+    pub fn set_num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads.max(1);
+        self
+    }
+
+    /// The values of the axes of each combination, in the order of the runs.
+    pub fn combinations(&self) -> Vec<Vec<f64>> {
+        self.axes.iter().fold(vec![vec![]], |combinations, axis| {
+            combinations
+                .iter()
+                .flat_map(|values| {
+                    axis.values.iter().map(move |&value| {
+                        let mut values = values.clone();
+                        values.push(value);
+                        values
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// Run all the combinations.
+    ///
+    /// If any run fails, the error of the first failed run is returned.
+    pub fn run(&self) -> Result<Vec<SweepRun<T>>, GenerationError>
+    where
+        TerrainGenerator<S, M, T>: Send + Sync,
+        T: Send,
+    {
+        let combinations = self.combinations();
+        let run_one = |values: &[f64]| -> Result<SweepRun<T>, GenerationError> {
+            let generator = self.generator.clone().map_parameters(|parameters| {
+                self.axes
+                    .iter()
+                    .zip(values.iter())
+                    .fold(parameters, |parameters, (axis, &value)| {
+                        (axis.apply)(parameters, value)
+                    })
+            })?;
+            let (terrain, summary) = generator.generate_with_summary()?;
+            Ok(SweepRun {
+                values: values.to_vec(),
+                terrain,
+                summary,
+            })
+        };
+
+        if self.num_threads <= 1 {
+            return combinations.iter().map(|values| run_one(values)).collect();
+        }
+
+        // the combinations are taken one by one by the threads, and the results are stored by index to keep the order
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(
+            (0..combinations.len())
+                .map(|_| None)
+                .collect::<Vec<Option<Result<SweepRun<T>, GenerationError>>>>(),
+        );
+        std::thread::scope(|scope| {
+            (0..self.num_threads.min(combinations.len())).for_each(|_| {
+                scope.spawn(|| loop {
+                    let k = next.fetch_add(1, Ordering::Relaxed);
+                    if k >= combinations.len() {
+                        break;
+                    }
+                    let result = run_one(&combinations[k]);
+                    results.lock().unwrap()[k] = Some(result);
+                });
+            });
+        });
+        results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|result| result.unwrap_or(Err(GenerationError::TaskAborted)))
+            .collect()
+    }
+}
@@ -3,3 +3,6 @@
 pub mod core;
 pub mod lem;
 pub mod models;
+
+#[cfg(feature = "test-util")]
+pub mod test_util;
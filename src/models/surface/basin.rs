@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+
+use crate::core::{traits::Model, units::Area};
+
+use super::{cells::outline_cells, model::TerrainModel2D, sites::Site2D, terrain::Terrain2D};
+
+/// A polygon of a drainage basin.
+///
+/// ### Properties
+///  - `label` is the label of the basin.
+///  - `exterior` is the outer boundary of the polygon, as a closed ring in counterclockwise order without repeating the first vertex.
+///  - `holes` is the inner boundaries of the polygon, as closed rings in clockwise order.
+#[derive(Debug, Clone)]
+pub struct BasinPolygon2D {
+    pub label: usize,
+    pub exterior: Vec<Site2D>,
+    pub holes: Vec<Vec<Site2D>>,
+}
+
+impl BasinPolygon2D {
+    /// The area of the polygon excluding the holes (unit: L^2).
+    pub fn area(&self) -> Area {
+        signed_area(&self.exterior).abs()
+            - self
+                .holes
+                .iter()
+                .map(|hole| signed_area(hole).abs())
+                .sum::<f64>()
+    }
+}
+
+/// Provides the polygons of drainage basins, unioning the Voronoi cells of the sites of each basin.
+///
+/// A basin may consist of several polygons if its sites are not connected, and the polygons have holes
+/// where other basins are enclosed. The cells on the convex hull of the sites are clipped at the hull.
+///
+/// ### Properties
+///  - `min_area` is the area below which the polygons are discarded (unit: L^2). The holes are kept regardless of the area. The default value is 0.0.
+#[derive(Debug, Clone)]
+pub struct BasinPolygonizer2D {
+    min_area: Area,
+}
+
+impl Default for BasinPolygonizer2D {
+    fn default() -> Self {
+        Self { min_area: 0.0 }
+    }
+}
+
+impl BasinPolygonizer2D {
+    pub fn set_min_area(mut self, min_area: Area) -> Self {
+        self.min_area = min_area;
+        self
+    }
+
+    /// Polygonize the basins given by the label of each site, sorted by the label.
+    ///
+    /// The number of the labels must be the same as the number of the sites of the model.
+    pub fn polygonize(&self, model: &TerrainModel2D, labels: &[usize]) -> Vec<BasinPolygon2D> {
+        if labels.len() != model.num() {
+            return Vec::new();
+        }
+        let mut basins: BTreeMap<usize, Vec<bool>> = BTreeMap::new();
+        labels.iter().enumerate().for_each(|(i, &label)| {
+            basins
+                .entry(label)
+                .or_insert_with(|| vec![false; labels.len()])[i] = true;
+        });
+        basins
+            .into_iter()
+            .flat_map(|(label, mask)| {
+                let rings = outline_cells(model.sites(), model.triangles(), model.hull(), &mask);
+                self.assemble(label, rings)
+            })
+            .collect()
+    }
+
+    /// Polygonize the drainage basins of the terrain, labeled by the index of the outlet of each site.
+    ///
+    /// This returns an empty list if the terrain has no drainage network.
+    pub fn polygonize_terrain(
+        &self,
+        model: &TerrainModel2D,
+        terrain: &Terrain2D,
+    ) -> Vec<BasinPolygon2D> {
+        let network = terrain.network();
+        if network.is_empty() {
+            return Vec::new();
+        }
+        let receivers = network.receivers();
+        let labels = (0..receivers.len())
+            .map(|i| {
+                let mut j = i;
+                while receivers[j] != j {
+                    j = receivers[j];
+                }
+                j
+            })
+            .collect::<Vec<_>>();
+        self.polygonize(model, &labels)
+    }
+
+    /// Classify the rings of a basin into the exteriors and the holes, and assign each hole to the smallest exterior containing it.
+    fn assemble(&self, label: usize, rings: Vec<Vec<Site2D>>) -> Vec<BasinPolygon2D> {
+        let rings = rings
+            .into_iter()
+            .filter(|ring| ring.len() >= 3)
+            .collect::<Vec<_>>();
+        let areas = rings
+            .iter()
+            .map(|ring| signed_area(ring).abs())
+            .collect::<Vec<_>>();
+        // the rings are disjoint, so a ring is a hole if it is enclosed by an odd number of the other rings
+        let enclosing = (0..rings.len())
+            .map(|k| {
+                let probe = ring_probe(&rings[k]);
+                (0..rings.len())
+                    .filter(|&l| l != k && contains(&rings[l], &probe))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let is_hole = enclosing
+            .iter()
+            .map(|enclosing| enclosing.len() % 2 == 1)
+            .collect::<Vec<_>>();
+
+        let mut polygons = BTreeMap::new();
+        (0..rings.len()).filter(|&k| !is_hole[k]).for_each(|k| {
+            polygons.insert(k, (oriented(&rings[k], true), Vec::new()));
+        });
+        (0..rings.len()).filter(|&k| is_hole[k]).for_each(|k| {
+            let exterior = enclosing[k]
+                .iter()
+                .filter(|&&l| !is_hole[l])
+                .min_by(|&&a, &&b| areas[a].total_cmp(&areas[b]));
+            if let Some(polygon) = exterior.and_then(|l| polygons.get_mut(l)) {
+                polygon.1.push(oriented(&rings[k], false));
+            }
+        });
+        polygons
+            .into_values()
+            .map(|(exterior, holes)| BasinPolygon2D {
+                label,
+                exterior,
+                holes,
+            })
+            .filter(|polygon| polygon.area() >= self.min_area)
+            .collect()
+    }
+}
+
+/// The signed area of the ring, positive if counterclockwise.
+fn signed_area(ring: &[Site2D]) -> f64 {
+    (0..ring.len())
+        .map(|k| {
+            let (a, b) = (&ring[k], &ring[(k + 1) % ring.len()]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+/// The ring in counterclockwise order if `counterclockwise`, otherwise in clockwise order.
+fn oriented(ring: &[Site2D], counterclockwise: bool) -> Vec<Site2D> {
+    let mut ring = ring.to_vec();
+    if (signed_area(&ring) > 0.0) != counterclockwise {
+        ring.reverse();
+    }
+    ring
+}
+
+/// A point on the ring used to test the containment, the midpoint of its first edge.
+fn ring_probe(ring: &[Site2D]) -> Site2D {
+    Site2D::new((ring[0].x + ring[1].x) * 0.5, (ring[0].y + ring[1].y) * 0.5)
+}
+
+/// Whether the point is inside the ring (the even-odd rule).
+fn contains(ring: &[Site2D], point: &Site2D) -> bool {
+    let mut inside = false;
+    (0..ring.len()).for_each(|k| {
+        let (a, b) = (&ring[k], &ring[(k + 1) % ring.len()]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+    });
+    inside
+}
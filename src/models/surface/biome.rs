@@ -0,0 +1,218 @@
+use crate::core::{
+    traits::Site,
+    units::{Elevation, Length},
+};
+
+use super::terrain::Terrain2D;
+
+/// A biome in the Whittaker classification by the annual mean temperature and precipitation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Biome {
+    Ocean,
+    Ice,
+    Tundra,
+    BorealForest,
+    Desert,
+    TemperateGrassland,
+    TemperateForest,
+    TemperateRainforest,
+    Savanna,
+    TropicalSeasonalForest,
+    TropicalRainforest,
+    Wetland,
+}
+
+impl Biome {
+    /// Classify the biome by the annual mean temperature (unit: °C) and the annual precipitation (unit: mm).
+    pub fn classify(temperature: f64, precipitation: f64) -> Self {
+        if temperature < -10.0 {
+            Biome::Ice
+        } else if temperature < -5.0 {
+            Biome::Tundra
+        } else if temperature < 5.0 {
+            if precipitation < 300.0 {
+                Biome::Tundra
+            } else {
+                Biome::BorealForest
+            }
+        } else if temperature < 20.0 {
+            if precipitation < 250.0 {
+                Biome::Desert
+            } else if precipitation < 750.0 {
+                Biome::TemperateGrassland
+            } else if precipitation < 2000.0 {
+                Biome::TemperateForest
+            } else {
+                Biome::TemperateRainforest
+            }
+        } else if precipitation < 500.0 {
+            Biome::Desert
+        } else if precipitation < 1500.0 {
+            Biome::Savanna
+        } else if precipitation < 2500.0 {
+            Biome::TropicalSeasonalForest
+        } else {
+            Biome::TropicalRainforest
+        }
+    }
+}
+
+/// Provides a classification of the biomes of the terrain from its relief, drainage and climate.
+///
+/// The temperature of each site is `sea_level_temperature - lapse_rate * (elevation - sea_level)`,
+/// lowered by `latitude_gradient` per unit distance from the line `y = equator`.
+/// The precipitation of each site is redistributed by the topographic wetness index (TWI) `ln(a / tan(β))`,
+/// where `a` is the drainage area per unit contour width and `β` is the slope:
+/// the moisture is `precipitation * exp(wetness_weight * (TWI - mean TWI))`, so valleys are wetter than ridges.
+/// The sites below `sea_level` are [Biome::Ocean], and the sites whose TWI exceeds `wetland_wetness` are [Biome::Wetland]
+/// unless they are frozen.
+///
+/// ### Properties
+///  - `sea_level` is the sea level (unit: L). The default value is 0.0.
+///  - `sea_level_temperature` is the temperature at the sea level on the equator (unit: °C). The default value is 25.0.
+///  - `lapse_rate` is the decrease of the temperature per unit elevation (unit: °C/L). The default value is 0.0065.
+///  - `equator` is the y coordinate of the equator (unit: L). The default value is 0.0.
+///  - `latitude_gradient` is the decrease of the temperature per unit distance from the equator (unit: °C/L). The default value is 0.0.
+///  - `precipitation` is the annual precipitation of each site (unit: mm). If `None`, 1000.0 is used for all sites.
+///  - `wetness_weight` is the weight of the TWI on the moisture. The default value is 0.2.
+///  - `wetland_wetness` is the TWI above which the sites are wetlands. If `None`, there are no wetlands.
+#[derive(Debug, Clone)]
+pub struct BiomeClassifier2D {
+    sea_level: Elevation,
+    sea_level_temperature: f64,
+    lapse_rate: f64,
+    equator: Length,
+    latitude_gradient: f64,
+    precipitation: Option<Vec<f64>>,
+    wetness_weight: f64,
+    wetland_wetness: Option<f64>,
+}
+
+impl Default for BiomeClassifier2D {
+    fn default() -> Self {
+        Self {
+            sea_level: 0.0,
+            sea_level_temperature: 25.0,
+            lapse_rate: 0.0065,
+            equator: 0.0,
+            latitude_gradient: 0.0,
+            precipitation: None,
+            wetness_weight: 0.2,
+            wetland_wetness: None,
+        }
+    }
+}
+
+impl BiomeClassifier2D {
+    pub fn set_sea_level(mut self, sea_level: Elevation) -> Self {
+        self.sea_level = sea_level;
+        self
+    }
+
+    pub fn set_sea_level_temperature(mut self, sea_level_temperature: f64) -> Self {
+        self.sea_level_temperature = sea_level_temperature;
+        self
+    }
+
+    pub fn set_lapse_rate(mut self, lapse_rate: f64) -> Self {
+        self.lapse_rate = lapse_rate;
+        self
+    }
+
+    pub fn set_equator(mut self, equator: Length) -> Self {
+        self.equator = equator;
+        self
+    }
+
+    pub fn set_latitude_gradient(mut self, latitude_gradient: f64) -> Self {
+        self.latitude_gradient = latitude_gradient;
+        self
+    }
+
+    pub fn set_precipitation(mut self, precipitation: Option<Vec<f64>>) -> Self {
+        self.precipitation = precipitation;
+        self
+    }
+
+    pub fn set_wetness_weight(mut self, wetness_weight: f64) -> Self {
+        self.wetness_weight = wetness_weight;
+        self
+    }
+
+    pub fn set_wetland_wetness(mut self, wetland_wetness: Option<f64>) -> Self {
+        self.wetland_wetness = wetland_wetness;
+        self
+    }
+
+    /// The annual mean temperature of each site (unit: °C).
+    pub fn temperatures(&self, terrain: &Terrain2D) -> Vec<f64> {
+        terrain
+            .sites()
+            .iter()
+            .zip(terrain.elevations().iter())
+            .map(|(site, &elevation)| {
+                self.sea_level_temperature
+                    - self.lapse_rate * (elevation - self.sea_level).max(0.0)
+                    - self.latitude_gradient * (site.y - self.equator).abs()
+            })
+            .collect()
+    }
+
+    /// Classify the biome of each site.
+    pub fn classify(&self, terrain: &Terrain2D) -> Vec<Biome> {
+        let temperatures = self.temperatures(terrain);
+        let wetness = wetness_indices(terrain);
+        let mean_wetness = if wetness.is_empty() {
+            0.0
+        } else {
+            wetness.iter().sum::<f64>() / wetness.len() as f64
+        };
+
+        terrain
+            .elevations()
+            .iter()
+            .enumerate()
+            .map(|(i, &elevation)| {
+                if elevation < self.sea_level {
+                    return Biome::Ocean;
+                }
+                let frozen = temperatures[i] < -5.0;
+                if let Some(wetland_wetness) = self.wetland_wetness {
+                    if !frozen && wetness[i] > wetland_wetness {
+                        return Biome::Wetland;
+                    }
+                }
+                let precipitation = self
+                    .precipitation
+                    .as_ref()
+                    .and_then(|p| p.get(i).copied())
+                    .unwrap_or(1000.0);
+                let moisture =
+                    precipitation * (self.wetness_weight * (wetness[i] - mean_wetness)).exp();
+                Biome::classify(temperatures[i], moisture)
+            })
+            .collect()
+    }
+}
+
+/// The topographic wetness index `ln(a / tan(β))` of each site.
+///
+/// The contour width of each site is approximated by the distance to its receiver.
+/// If the terrain has no drainage network, this is 0.0 for all sites.
+pub fn wetness_indices(terrain: &Terrain2D) -> Vec<f64> {
+    let network = terrain.network();
+    if network.is_empty() {
+        return vec![0.0; terrain.sites().len()];
+    }
+    let sites = terrain.sites();
+    let elevations = terrain.elevations();
+    (0..sites.len())
+        .map(|i| {
+            let j = network.receivers()[i];
+            let distance = sites[i].distance(&sites[j]).max(f64::EPSILON);
+            let gradient = ((elevations[i] - elevations[j]) / distance).max(1e-6);
+            let specific_area = network.drainage_areas()[i] / distance;
+            (specific_area / gradient).ln()
+        })
+        .collect()
+}
@@ -0,0 +1,80 @@
+/// The condition of a side of the domain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundaryCondition {
+    /// The sites on the side are the outlets fixed at their base elevations.
+    #[default]
+    BaseLevel,
+    /// The side is a wall through which nothing flows; the sites on it drain inward.
+    Wall,
+    /// The side is connected to the opposite side, which must also be periodic.
+    Periodic,
+}
+
+/// The conditions of the four sides of the rectangular domain.
+///
+/// ### Properties
+///  - `left` and `right` are the conditions of the sides at the minimum and maximum x. The default value is [BoundaryCondition::BaseLevel].
+///  - `bottom` and `top` are the conditions of the sides at the minimum and maximum y. The default value is [BoundaryCondition::BaseLevel].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BoundaryConditions2D {
+    left: BoundaryCondition,
+    right: BoundaryCondition,
+    bottom: BoundaryCondition,
+    top: BoundaryCondition,
+}
+
+impl BoundaryConditions2D {
+    /// Create the conditions with all the sides set to `condition`.
+    pub fn uniform(condition: BoundaryCondition) -> Self {
+        Self {
+            left: condition,
+            right: condition,
+            bottom: condition,
+            top: condition,
+        }
+    }
+
+    pub fn set_left(mut self, left: BoundaryCondition) -> Self {
+        self.left = left;
+        self
+    }
+
+    pub fn set_right(mut self, right: BoundaryCondition) -> Self {
+        self.right = right;
+        self
+    }
+
+    pub fn set_bottom(mut self, bottom: BoundaryCondition) -> Self {
+        self.bottom = bottom;
+        self
+    }
+
+    pub fn set_top(mut self, top: BoundaryCondition) -> Self {
+        self.top = top;
+        self
+    }
+
+    pub fn left(&self) -> BoundaryCondition {
+        self.left
+    }
+
+    pub fn right(&self) -> BoundaryCondition {
+        self.right
+    }
+
+    pub fn bottom(&self) -> BoundaryCondition {
+        self.bottom
+    }
+
+    pub fn top(&self) -> BoundaryCondition {
+        self.top
+    }
+
+    /// Whether the periodic sides are paired with the opposite sides.
+    pub(super) fn is_valid(&self) -> bool {
+        let paired = |a: BoundaryCondition, b: BoundaryCondition| {
+            (a == BoundaryCondition::Periodic) == (b == BoundaryCondition::Periodic)
+        };
+        paired(self.left, self.right) && paired(self.bottom, self.top)
+    }
+}
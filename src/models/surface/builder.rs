@@ -1,14 +1,110 @@
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
 use thiserror::Error;
-use voronoice::{BoundingBox, VoronoiBuilder};
+use voronoice::{BoundingBox, Voronoi, VoronoiBuilder};
 
 use crate::core::{
     traits::Site,
     units::{Area, Length},
 };
 
-use super::{model::TerrainModel2D, sites::Site2D};
+use super::{
+    boundary::{BoundaryCondition, BoundaryConditions2D},
+    index::SiteIndex2D,
+    model::TerrainModel2D,
+    sites::{Site2D, SiteGenerator2D},
+};
+
+/// The maximum number of the iterations of `refine_sites`.
+pub const MAX_REFINEMENT_ITERATIONS: usize = 32;
+
+/// The Voronoi diagram of the sites clipped by the bounding rectangle.
+fn voronoi(sites: &[Site2D], bound_min: &Site2D, bound_max: &Site2D) -> Option<Voronoi> {
+    VoronoiBuilder::default()
+        .set_sites(
+            sites
+                .iter()
+                .map(|s| voronoice::Point { x: s.x, y: s.y })
+                .collect(),
+        )
+        .set_bounding_box(BoundingBox::new(
+            voronoice::Point {
+                x: (bound_max.x + bound_min.x) / 2.0,
+                y: (bound_max.y + bound_min.y) / 2.0,
+            },
+            bound_max.x - bound_min.x,
+            bound_max.y - bound_min.y,
+        ))
+        .build()
+}
+
+/// The centroids of the Voronoi cells weighted by the density.
+///
+/// The density varies linearly over the triangles fanning out from each site to the edges of its cell.
+fn weighted_centroids(voronoi: &Voronoi, density: impl Fn(&Site2D) -> f64) -> Vec<Site2D> {
+    voronoi
+        .iter_cells()
+        .map(|cell| {
+            let site = Site2D {
+                x: cell.site_position().x,
+                y: cell.site_position().y,
+            };
+            let vertices = cell
+                .iter_vertices()
+                .map(|v| Site2D { x: v.x, y: v.y })
+                .collect::<Vec<_>>();
+            let (mut mass, mut mx, mut my) = (0.0, 0.0, 0.0);
+            (0..vertices.len()).for_each(|k| {
+                let (b, c) = (vertices[k], vertices[(k + 1) % vertices.len()]);
+                let area =
+                    ((b.x - site.x) * (c.y - site.y) - (c.x - site.x) * (b.y - site.y)).abs() / 2.0;
+                let (ra, rb, rc) = (density(&site), density(&b), density(&c));
+                // the moments of the triangle with the linear density
+                mass += area * (ra + rb + rc) / 3.0;
+                mx += area / 12.0
+                    * ((2.0 * ra + rb + rc) * site.x
+                        + (ra + 2.0 * rb + rc) * b.x
+                        + (ra + rb + 2.0 * rc) * c.x);
+                my += area / 12.0
+                    * ((2.0 * ra + rb + rc) * site.y
+                        + (ra + 2.0 * rb + rc) * b.y
+                        + (ra + rb + 2.0 * rc) * c.y);
+            });
+            let centroid = Site2D {
+                x: mx / mass,
+                y: my / mass,
+            };
+            if mass > 0.0 && centroid.x.is_finite() && centroid.y.is_finite() {
+                centroid
+            } else {
+                site
+            }
+        })
+        .collect()
+}
+
+/// A side of the bounding rectangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+    Bottom,
+    Top,
+}
+
+/// The side of the bounding rectangle nearest to the site.
+fn nearest_side(site: &Site2D, bound_min: &Site2D, bound_max: &Site2D) -> Side {
+    [
+        (Side::Left, site.x - bound_min.x),
+        (Side::Right, bound_max.x - site.x),
+        (Side::Bottom, site.y - bound_min.y),
+        (Side::Top, bound_max.y - site.y),
+    ]
+    .into_iter()
+    .reduce(|nearest, side| if side.1 < nearest.1 { side } else { nearest })
+    .map(|(side, _)| side)
+    .unwrap_or(Side::Left)
+}
 
 #[derive(Error, Debug)]
 pub enum ModelBuilderError {
@@ -18,6 +114,14 @@ pub enum ModelBuilderError {
     BoundsNotSet,
     #[error("Failed to calculate voronoi diagram")]
     VoronoiError,
+    #[error("The periodic sides must be paired with the opposite sides")]
+    InvalidBoundaryConditions,
+    #[error("At least one site on the convex hull must be on a side of the base level")]
+    NoOutlets,
+    #[error("The sites must be inside the bounding box")]
+    SiteOutOfBounds,
+    #[error("The weights must be positive and as many as the sites")]
+    InvalidWeights,
 }
 
 /// Provides methods to construct a `TerrainModel2D`, which is the vector representation of the terrain network.
@@ -27,11 +131,17 @@ pub enum ModelBuilderError {
 /// ### Optional parameters
 /// - `bound_min` and `bound_max` are the bounding rectangle of the sites. If not set, the bounding rectangle will be computed from the sites.
 ///    This parameter is used to calculate the area or to relocate the sites to apploximately evenly spaced positions using Lloyd's algorithm.
+/// - `boundary_conditions` is the conditions of the sides of the bounding rectangle (see [BoundaryConditions2D]).
+///    Each site on the convex hull belongs to the nearest side. By default, all the sides are the base level.
+/// - `weights` is the importance of each site, the relative density of the sites demanded around it.
+///    This is used by `relaxate_sites` and `refine_sites`. By default, all the sites have the weight 1.0.
 #[derive(Default, Clone)]
 pub struct TerrainModel2DBulider {
     sites: Option<Vec<Site2D>>,
     bound_min: Option<Site2D>,
     bound_max: Option<Site2D>,
+    boundary_conditions: BoundaryConditions2D,
+    weights: Option<Vec<f64>>,
 }
 
 impl TerrainModel2DBulider {
@@ -48,6 +158,19 @@ impl TerrainModel2DBulider {
             sites: Some(sites),
             bound_min: Some(bound_min),
             bound_max: Some(bound_max),
+            boundary_conditions: BoundaryConditions2D::default(),
+            weights: None,
+        }
+    }
+
+    /// Create the builder with the sites and the bounding rectangle of the generator (see [SiteGenerator2D]).
+    pub fn from_generator(generator: &SiteGenerator2D) -> Self {
+        let (bound_min, bound_max) = generator.bounding_box();
+        Self {
+            sites: Some(generator.generate()),
+            bound_min: Some(bound_min),
+            bound_max: Some(bound_max),
+            ..Default::default()
         }
     }
 
@@ -124,6 +247,10 @@ impl TerrainModel2DBulider {
             })
             .collect::<Vec<_>>();
 
+        // the edge sites have the default weight
+        if let Some(weights) = &mut self.weights {
+            weights.resize(sites.len() + edge_sites.len(), 1.0);
+        }
         let sites = sites.into_iter().chain(edge_sites).collect::<Vec<_>>();
 
         self.sites = Some(sites);
@@ -150,8 +277,32 @@ impl TerrainModel2DBulider {
         self
     }
 
+    /// Set the conditions of the sides of the bounding rectangle.
+    ///
+    /// The sites on the sides of [BoundaryCondition::BaseLevel] are the default outlets,
+    /// the sites on the sides of [BoundaryCondition::Wall] drain inward, and the sites on the sides of
+    /// [BoundaryCondition::Periodic] are connected to the nearest sites on the opposite side.
+    /// Adding the sites on the sides with `add_edge_sites` is recommended so that the sides are densely connected.
+    pub fn set_boundary_conditions(mut self, boundary_conditions: BoundaryConditions2D) -> Self {
+        self.boundary_conditions = boundary_conditions;
+        self
+    }
+
+    /// Set the weight of each site, the relative density of the sites demanded around it.
+    ///
+    /// The number of the weights must be the same as the number of the sites, and the weights must be positive.
+    /// If `None`, all the sites have the weight 1.0.
+    pub fn set_site_weights(mut self, weights: Option<Vec<f64>>) -> Self {
+        self.weights = weights;
+        self
+    }
+
     /// Relocate the sites to apploximately evenly spaced positions using Lloyd's algorithm.
     /// The number of times for Lloyd's algorithm is specified by `times`.
+    ///
+    /// If the weights of the sites are set, each site moves to the centroid of its Voronoi cell weighted by the density,
+    /// the weight of the nearest site before the relocation, so the sites gather around the sites of large weights.
+    /// The weights are then resampled at the relocated sites.
     pub fn relaxate_sites(mut self, times: usize) -> Result<Self, ModelBuilderError> {
         if times == 0 {
             return Ok(self);
@@ -167,6 +318,28 @@ impl TerrainModel2DBulider {
             }
         };
 
+        if self.weights.is_some() {
+            // the density is the weight of the nearest site before the relocation
+            let weights = self.query_weights(sites.len())?;
+            let index = SiteIndex2D::new(sites);
+            let density = |site: &Site2D| index.nearest(site).map(|i| weights[i]).unwrap_or(1.0);
+            let mut relocated = sites.clone();
+            for _ in 0..times {
+                let voronoi = voronoi(&relocated, &bound_min, &bound_max)
+                    .ok_or(ModelBuilderError::VoronoiError)?;
+                relocated = weighted_centroids(&voronoi, density)
+                    .into_iter()
+                    .map(|site| Site2D {
+                        x: site.x.clamp(bound_min.x, bound_max.x),
+                        y: site.y.clamp(bound_min.y, bound_max.y),
+                    })
+                    .collect();
+            }
+            self.weights = Some(relocated.iter().map(density).collect());
+            self.sites = Some(relocated);
+            return Ok(self);
+        }
+
         let voronoi_opt = VoronoiBuilder::default()
             .set_sites(
                 sites
@@ -199,7 +372,64 @@ impl TerrainModel2DBulider {
         }
     }
 
+    /// Insert the sites until the area of each Delaunay triangle multiplied by the mean weight of its vertices is not more than `max_area`.
+    ///
+    /// A site is inserted at the centroid of each triangle exceeding the limit, taking the mean weight of the vertices of the triangle.
+    /// This is repeated up to [MAX_REFINEMENT_ITERATIONS] times, so the sites are denser around the sites of large weights.
+    pub fn refine_sites(mut self, max_area: Area) -> Result<Self, ModelBuilderError> {
+        let (bound_min, bound_max) = (self.query_bound_min()?, self.query_bound_max()?);
+        let mut sites = match &self.sites {
+            Some(sites) => sites.clone(),
+            None => return Err(ModelBuilderError::SitesNotSet),
+        };
+        let mut weights = self.query_weights(sites.len())?;
+        if max_area <= 0.0 {
+            return Ok(self);
+        }
+
+        for _ in 0..MAX_REFINEMENT_ITERATIONS {
+            let voronoi =
+                voronoi(&sites, &bound_min, &bound_max).ok_or(ModelBuilderError::VoronoiError)?;
+            let inserted = voronoi
+                .triangulation()
+                .triangles
+                .chunks_exact(3)
+                .filter_map(|triangle| {
+                    let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| sites[i]);
+                    let area = ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2.0;
+                    let weight = triangle.iter().map(|&i| weights[i]).sum::<f64>() / 3.0;
+                    if area * weight > max_area {
+                        let centroid = Site2D {
+                            x: (a.x + b.x + c.x) / 3.0,
+                            y: (a.y + b.y + c.y) / 3.0,
+                        };
+                        Some((centroid, weight))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            if inserted.is_empty() {
+                break;
+            }
+            inserted.into_iter().for_each(|(site, weight)| {
+                sites.push(site);
+                weights.push(weight);
+            });
+        }
+
+        self.sites = Some(sites);
+        if self.weights.is_some() {
+            self.weights = Some(weights);
+        }
+        Ok(self)
+    }
+
     pub fn build(&self) -> Result<TerrainModel2D, ModelBuilderError> {
+        if !self.boundary_conditions.is_valid() {
+            return Err(ModelBuilderError::InvalidBoundaryConditions);
+        }
+
         let sites = {
             if let Some(sites) = &self.sites {
                 sites
@@ -233,10 +463,17 @@ impl TerrainModel2DBulider {
                 .iter()
                 .map(|s| Site2D { x: s.x, y: s.y })
                 .collect::<Vec<Site2D>>();
-            let areas: Vec<Area> = voronoi
+            let cells = voronoi
                 .iter_cells()
                 .map(|cell| {
-                    let vertices = cell.iter_vertices().collect::<Vec<_>>();
+                    cell.iter_vertices()
+                        .map(|v| Site2D { x: v.x, y: v.y })
+                        .collect::<Vec<Site2D>>()
+                })
+                .collect::<Vec<_>>();
+            let areas: Vec<Area> = cells
+                .iter()
+                .map(|vertices| {
                     let mut area = 0.0;
                     for i in 0..vertices.len() {
                         let j = (i + 1) % vertices.len();
@@ -248,7 +485,7 @@ impl TerrainModel2DBulider {
 
             let triangulation = voronoi.triangulation();
 
-            let graph: EdgeAttributedUndirectedGraph<Length> = {
+            let mut graph: EdgeAttributedUndirectedGraph<Length> = {
                 let mut graph: EdgeAttributedUndirectedGraph<f64> =
                     EdgeAttributedUndirectedGraph::new(sites.len());
                 for triangle in triangulation.triangles.chunks_exact(3) {
@@ -267,19 +504,112 @@ impl TerrainModel2DBulider {
                 graph
             };
 
-            let default_outlets = triangulation.hull.to_vec();
+            let hull = triangulation.hull.to_vec();
+            let hull_sides = hull
+                .iter()
+                .map(|&i| nearest_side(&sites[i], &bound_min, &bound_max))
+                .collect::<Vec<_>>();
+            let conditions = &self.boundary_conditions;
+            let condition_of = |side: Side| match side {
+                Side::Left => conditions.left(),
+                Side::Right => conditions.right(),
+                Side::Bottom => conditions.bottom(),
+                Side::Top => conditions.top(),
+            };
+
+            let default_outlets = hull
+                .iter()
+                .zip(hull_sides.iter())
+                .filter(|(_, &side)| condition_of(side) == BoundaryCondition::BaseLevel)
+                .map(|(&i, _)| i)
+                .collect::<Vec<_>>();
+            if default_outlets.is_empty() {
+                return Err(ModelBuilderError::NoOutlets);
+            }
+
+            // connect the periodic sides across the domain
+            let on_side = |side: Side| {
+                hull.iter()
+                    .zip(hull_sides.iter())
+                    .filter(|(_, &s)| s == side)
+                    .map(|(&i, _)| i)
+                    .collect::<Vec<_>>()
+            };
+            let width = bound_max.x - bound_min.x;
+            let height = bound_max.y - bound_min.y;
+            [
+                (Side::Left, Side::Right, Site2D { x: width, y: 0.0 }),
+                (Side::Bottom, Side::Top, Site2D { x: 0.0, y: height }),
+            ]
+            .into_iter()
+            .filter(|&(side, _, _)| condition_of(side) == BoundaryCondition::Periodic)
+            .for_each(|(side, opposite, period)| {
+                let (near, far) = (on_side(side), on_side(opposite));
+                // the site on the near side shifted by the period faces the far side
+                let shifted = |i: usize| Site2D {
+                    x: sites[i].x + period.x,
+                    y: sites[i].y + period.y,
+                };
+                let mut connect = |a: usize, b: usize| {
+                    if !graph.has_edge(a, b).0 {
+                        graph.add_edge(a, b, shifted(a).distance(&sites[b]));
+                    }
+                };
+                near.iter().for_each(|&a| {
+                    if let Some(&b) = far.iter().min_by(|&&p, &&q| {
+                        shifted(a)
+                            .distance(&sites[p])
+                            .total_cmp(&shifted(a).distance(&sites[q]))
+                    }) {
+                        connect(a, b);
+                    }
+                });
+                far.iter().for_each(|&b| {
+                    if let Some(&a) = near.iter().min_by(|&&p, &&q| {
+                        shifted(p)
+                            .distance(&sites[b])
+                            .total_cmp(&shifted(q).distance(&sites[b]))
+                    }) {
+                        connect(a, b);
+                    }
+                });
+            });
+
+            let triangles = triangulation
+                .triangles
+                .chunks_exact(3)
+                .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+                .collect::<Vec<_>>();
 
             Ok(TerrainModel2D::new(
                 sites.to_vec(),
                 areas,
                 graph,
                 default_outlets,
+                hull,
+                triangles,
+                cells,
+                self.boundary_conditions,
+                (bound_min, bound_max),
             ))
         } else {
             Err(ModelBuilderError::VoronoiError)
         }
     }
 
+    /// The weights of the sites, or 1.0 for all the sites if the weights are not set.
+    fn query_weights(&self, num: usize) -> Result<Vec<f64>, ModelBuilderError> {
+        match &self.weights {
+            Some(weights) => {
+                if weights.len() != num || !weights.iter().all(|&w| w > 0.0 && w.is_finite()) {
+                    return Err(ModelBuilderError::InvalidWeights);
+                }
+                Ok(weights.clone())
+            }
+            None => Ok(vec![1.0; num]),
+        }
+    }
+
     fn query_bound_min(&self) -> Result<Site2D, ModelBuilderError> {
         if let Some(bound_min) = self.bound_min {
             Ok(bound_min)
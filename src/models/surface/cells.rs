@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use super::sites::Site2D;
+
+/// A vertex of the outline of Voronoi cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Vertex {
+    /// The circumcenter of a triangle.
+    Circumcenter(usize),
+    /// The midpoint of an edge on the convex hull, where the cells are clipped.
+    HullMidpoint(usize, usize),
+    /// A site on the convex hull, where the cells are clipped.
+    HullSite(usize),
+}
+
+/// The circumcenter of the triangle.
+pub(crate) fn circumcenter(a: &Site2D, b: &Site2D, c: &Site2D) -> Site2D {
+    let d = 2.0 * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+    if d == 0.0 {
+        return Site2D::new((a.x + b.x + c.x) / 3.0, (a.y + b.y + c.y) / 3.0);
+    }
+    let (a2, b2, c2) = (
+        a.x * a.x + a.y * a.y,
+        b.x * b.x + b.y * b.y,
+        c.x * c.x + c.y * c.y,
+    );
+    Site2D::new(
+        (a2 * (b.y - c.y) + b2 * (c.y - a.y) + c2 * (a.y - b.y)) / d,
+        (a2 * (c.x - b.x) + b2 * (a.x - c.x) + c2 * (b.x - a.x)) / d,
+    )
+}
+
+/// The point itself if it is inside the convex hull, otherwise the nearest point on the hull.
+fn clip_to_hull(sites: &[Site2D], hull: &[usize], point: Site2D) -> Site2D {
+    if hull.len() < 3 {
+        return point;
+    }
+    let edges = (0..hull.len()).map(|k| (&sites[hull[k]], &sites[hull[(k + 1) % hull.len()]]));
+    let crosses = edges
+        .clone()
+        .map(|(a, b)| (b.x - a.x) * (point.y - a.y) - (b.y - a.y) * (point.x - a.x))
+        .collect::<Vec<_>>();
+    if crosses.iter().all(|&c| c >= 0.0) || crosses.iter().all(|&c| c <= 0.0) {
+        return point;
+    }
+    edges
+        .map(|(a, b)| {
+            let (dx, dy) = (b.x - a.x, b.y - a.y);
+            let length2 = dx * dx + dy * dy;
+            let t = if length2 > 0.0 {
+                (((point.x - a.x) * dx + (point.y - a.y) * dy) / length2).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            Site2D::new(a.x + dx * t, a.y + dy * t)
+        })
+        .min_by(|p, q| {
+            let dp = (p.x - point.x).powi(2) + (p.y - point.y).powi(2);
+            let dq = (q.x - point.x).powi(2) + (q.y - point.y).powi(2);
+            dp.total_cmp(&dq)
+        })
+        .unwrap_or(point)
+}
+
+/// The outlines of the union of the Voronoi cells of the sites where `mask` is true.
+///
+/// The cells on the convex hull are clipped at the hull, and the circumcenters outside the hull are moved to the nearest points on the hull.
+/// Each outline is a closed ring without repeating the first vertex.
+pub(crate) fn outline_cells(
+    sites: &[Site2D],
+    triangles: &[[usize; 3]],
+    hull: &[usize],
+    mask: &[bool],
+) -> Vec<Vec<Site2D>> {
+    let key = |a: usize, b: usize| if a < b { (a, b) } else { (b, a) };
+
+    // the triangles sharing each edge
+    let mut edge_triangles: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+    triangles.iter().enumerate().for_each(|(t, triangle)| {
+        (0..3).for_each(|k| {
+            edge_triangles
+                .entry(key(triangle[k], triangle[(k + 1) % 3]))
+                .or_default()
+                .push(t);
+        });
+    });
+
+    // the segments of the outline are the Voronoi edges between the masked and the unmasked sites
+    let mut segments = Vec::new();
+    edge_triangles.iter().for_each(|(&(a, b), ts)| {
+        if mask[a] == mask[b] {
+            return;
+        }
+        match ts.as_slice() {
+            [t0, t1] => segments.push((Vertex::Circumcenter(*t0), Vertex::Circumcenter(*t1))),
+            [t0] => segments.push((Vertex::Circumcenter(*t0), Vertex::HullMidpoint(a, b))),
+            _ => {}
+        }
+    });
+
+    // the masked cells on the hull are closed along the hull
+    (0..hull.len()).for_each(|k| {
+        let (a, b) = (hull[k], hull[(k + 1) % hull.len()]);
+        let (p, q) = key(a, b);
+        if mask[a] {
+            segments.push((Vertex::HullSite(a), Vertex::HullMidpoint(p, q)));
+        }
+        if mask[b] {
+            segments.push((Vertex::HullMidpoint(p, q), Vertex::HullSite(b)));
+        }
+    });
+
+    let position = |vertex: Vertex| match vertex {
+        Vertex::Circumcenter(t) => {
+            let [a, b, c] = triangles[t];
+            clip_to_hull(sites, hull, circumcenter(&sites[a], &sites[b], &sites[c]))
+        }
+        Vertex::HullMidpoint(a, b) => Site2D::new(
+            (sites[a].x + sites[b].x) * 0.5,
+            (sites[a].y + sites[b].y) * 0.5,
+        ),
+        Vertex::HullSite(a) => sites[a],
+    };
+
+    // chain the segments into rings
+    let mut adjacency: BTreeMap<Vertex, Vec<(Vertex, usize)>> = BTreeMap::new();
+    segments.iter().enumerate().for_each(|(k, &(u, v))| {
+        adjacency.entry(u).or_default().push((v, k));
+        adjacency.entry(v).or_default().push((u, k));
+    });
+    let mut used = vec![false; segments.len()];
+    let mut rings = Vec::new();
+    (0..segments.len()).for_each(|k| {
+        if used[k] {
+            return;
+        }
+        used[k] = true;
+        let (start, mut current) = segments[k];
+        let mut ring = vec![position(start)];
+        while current != start {
+            ring.push(position(current));
+            let next = adjacency[&current].iter().find(|(_, l)| !used[*l]).copied();
+            if let Some((next, l)) = next {
+                used[l] = true;
+                current = next;
+            } else {
+                break;
+            }
+        }
+        rings.push(ring);
+    });
+    rings
+}
@@ -0,0 +1,347 @@
+use crate::core::{
+    fields::DISCHARGE,
+    traits::Site,
+    units::{Area, Elevation, Length},
+};
+
+use super::{sites::Site2D, terrain::Terrain2D};
+
+/// The estimated cross section of the channel at a site.
+///
+/// ### Properties
+///  - `discharge` is the bankfull discharge (unit: L^3/T).
+///  - `width` is the bankfull width (unit: L).
+///  - `depth` is the bankfull depth (unit: L).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelGeometry {
+    pub discharge: f64,
+    pub width: Length,
+    pub depth: Length,
+}
+
+/// Provides the estimation of the width and the depth of the channels by the hydraulic geometry.
+///
+/// The discharge of each site is `precipitation * drainage area`, where the drainage area is replaced by the effective discharge
+/// (the field [DISCHARGE]) if the terrain has the losses by infiltration and evaporation.
+/// The width and the depth are `width_coefficient * discharge^width_exponent` and `depth_coefficient * discharge^depth_exponent`.
+///
+/// ### Properties
+///  - `precipitation` is the runoff per unit area (unit: L/T). The default value is 1.0.
+///  - `width_coefficient` is the coefficient of the width. The default value is 0.05.
+///  - `width_exponent` is the exponent of the width. The default value is 0.5.
+///  - `depth_coefficient` is the coefficient of the depth. The default value is 0.02.
+///  - `depth_exponent` is the exponent of the depth. The default value is 0.4.
+#[derive(Debug, Clone)]
+pub struct HydraulicGeometry2D {
+    precipitation: f64,
+    width_coefficient: f64,
+    width_exponent: f64,
+    depth_coefficient: f64,
+    depth_exponent: f64,
+}
+
+impl Default for HydraulicGeometry2D {
+    fn default() -> Self {
+        Self {
+            precipitation: 1.0,
+            width_coefficient: 0.05,
+            width_exponent: 0.5,
+            depth_coefficient: 0.02,
+            depth_exponent: 0.4,
+        }
+    }
+}
+
+impl HydraulicGeometry2D {
+    pub fn set_precipitation(mut self, precipitation: f64) -> Self {
+        self.precipitation = precipitation;
+        self
+    }
+
+    pub fn set_width_coefficient(mut self, width_coefficient: f64) -> Self {
+        self.width_coefficient = width_coefficient;
+        self
+    }
+
+    pub fn set_width_exponent(mut self, width_exponent: f64) -> Self {
+        self.width_exponent = width_exponent;
+        self
+    }
+
+    pub fn set_depth_coefficient(mut self, depth_coefficient: f64) -> Self {
+        self.depth_coefficient = depth_coefficient;
+        self
+    }
+
+    pub fn set_depth_exponent(mut self, depth_exponent: f64) -> Self {
+        self.depth_exponent = depth_exponent;
+        self
+    }
+
+    /// Estimate the channel from the discharge (unit: L^3/T).
+    pub fn channel(&self, discharge: f64) -> ChannelGeometry {
+        let discharge = discharge.max(0.0);
+        ChannelGeometry {
+            discharge,
+            width: self.width_coefficient * discharge.powf(self.width_exponent),
+            depth: self.depth_coefficient * discharge.powf(self.depth_exponent),
+        }
+    }
+
+    /// The steady discharge of each site, `precipitation * drainage area` (or the effective discharge) (unit: L^3/T).
+    ///
+    /// All the sites are 0.0 if the terrain has no drainage network.
+    pub fn discharges(&self, terrain: &Terrain2D) -> Vec<f64> {
+        let network = terrain.network();
+        if network.is_empty() {
+            return vec![0.0; terrain.sites().len()];
+        }
+        let effective_areas = terrain
+            .fields()
+            .get(DISCHARGE)
+            .unwrap_or(network.drainage_areas());
+        effective_areas
+            .iter()
+            .map(|&effective_area| (self.precipitation * effective_area).max(0.0))
+            .collect()
+    }
+
+    /// The approximate water depth of each site, the bankfull depth of the channel (see [HydraulicGeometry2D::estimate]).
+    /// The sites without channels are 0.0 (unit: L).
+    pub fn water_depths(&self, terrain: &Terrain2D, min_drainage_area: Area) -> Vec<Length> {
+        self.estimate(terrain, min_drainage_area)
+            .iter()
+            .map(|channel| channel.map(|channel| channel.depth).unwrap_or(0.0))
+            .collect()
+    }
+
+    /// Estimate the channel of each site whose drainage area is not less than `min_drainage_area`,
+    /// the same threshold as `Terrain2D::extract_rivers`. The other sites are `None`.
+    ///
+    /// All the sites are `None` if the terrain has no drainage network.
+    pub fn estimate(
+        &self,
+        terrain: &Terrain2D,
+        min_drainage_area: Area,
+    ) -> Vec<Option<ChannelGeometry>> {
+        let network = terrain.network();
+        if network.is_empty() {
+            return vec![None; terrain.sites().len()];
+        }
+        let drainage_areas = network.drainage_areas();
+        let effective_areas = terrain.fields().get(DISCHARGE).unwrap_or(drainage_areas);
+        drainage_areas
+            .iter()
+            .zip(effective_areas.iter())
+            .map(|(&drainage_area, &effective_area)| {
+                if drainage_area >= min_drainage_area {
+                    Some(self.channel(self.precipitation * effective_area))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// A segment of a channel between two adjacent sites of a river.
+#[derive(Debug, Clone)]
+struct ChannelSegment {
+    a: Site2D,
+    b: Site2D,
+    elevations: [Elevation; 2],
+    widths: [Length; 2],
+    depths: [Length; 2],
+}
+
+/// Provides the incision of the channels into the sampled elevations, used by the rasterizer and the tile exporter.
+///
+/// The channels are the reaches of the rivers extracted from the terrain (see `Terrain2D::extract_rivers`)
+/// with the width and the depth estimated by [HydraulicGeometry2D]. The cross section is parabolic:
+/// a sample at the distance `d` from the center line of the channel of the width `w` and the depth `h` is lowered to
+/// `elevation of the channel - h * (1 - (2d / w)^2)`, unless it is already lower.
+/// This restores the river beds which the triangulation of the sites smooths away.
+#[derive(Debug, Clone)]
+pub struct ChannelCarver2D {
+    segments: Vec<ChannelSegment>,
+    bound_min: Site2D,
+    cell_size: Length,
+    grid_size: (usize, usize),
+    grid: Vec<Vec<usize>>,
+}
+
+impl ChannelCarver2D {
+    /// Create the carver of the channels whose drainage area is not less than `min_drainage_area`.
+    pub fn new(
+        terrain: &Terrain2D,
+        geometry: &HydraulicGeometry2D,
+        min_drainage_area: Area,
+    ) -> Self {
+        let channels = geometry.estimate(terrain, min_drainage_area);
+        let sites = terrain.sites();
+        let elevations = terrain.elevations();
+        let segments = terrain
+            .extract_rivers(min_drainage_area)
+            .iter()
+            .flat_map(|river| {
+                river
+                    .sites()
+                    .windows(2)
+                    .filter_map(|pair| {
+                        let (i, j) = (pair[0], pair[1]);
+                        let (ci, cj) = (channels[i]?, channels[j]?);
+                        Some(ChannelSegment {
+                            a: sites[i],
+                            b: sites[j],
+                            elevations: [elevations[i], elevations[j]],
+                            widths: [ci.width, cj.width],
+                            depths: [ci.depth, cj.depth],
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|segment| {
+                segment.elevations.iter().all(|e| e.is_finite())
+                    && segment.widths.iter().all(|w| w.is_finite() && *w > 0.0)
+            })
+            .collect::<Vec<_>>();
+        Self::from_segments(segments)
+    }
+
+    fn from_segments(segments: Vec<ChannelSegment>) -> Self {
+        // the segments are indexed by a uniform grid covering them with their half widths
+        let reach = |segment: &ChannelSegment| segment.widths[0].max(segment.widths[1]) * 0.5;
+        let (bound_min, bound_max) = segments.iter().fold(
+            (
+                Site2D::new(f64::INFINITY, f64::INFINITY),
+                Site2D::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(min, max), segment| {
+                let r = reach(segment);
+                (
+                    Site2D::new(
+                        min.x.min(segment.a.x.min(segment.b.x) - r),
+                        min.y.min(segment.a.y.min(segment.b.y) - r),
+                    ),
+                    Site2D::new(
+                        max.x.max(segment.a.x.max(segment.b.x) + r),
+                        max.y.max(segment.a.y.max(segment.b.y) + r),
+                    ),
+                )
+            },
+        );
+        let cell_size = segments
+            .iter()
+            .map(|segment| segment.a.distance(&segment.b).max(reach(segment) * 2.0))
+            .fold(0.0, f64::max)
+            .max(f64::EPSILON);
+        if segments.is_empty() {
+            return Self {
+                segments,
+                bound_min: Site2D::default(),
+                cell_size,
+                grid_size: (0, 0),
+                grid: Vec::new(),
+            };
+        }
+        let grid_size = (
+            ((bound_max.x - bound_min.x) / cell_size).floor() as usize + 1,
+            ((bound_max.y - bound_min.y) / cell_size).floor() as usize + 1,
+        );
+        let mut grid = vec![Vec::new(); grid_size.0 * grid_size.1];
+        let cell =
+            |v: f64, min: f64, n: usize| (((v - min) / cell_size).floor() as usize).min(n - 1);
+        segments.iter().enumerate().for_each(|(s, segment)| {
+            let r = reach(segment);
+            let (x0, x1) = (
+                cell(segment.a.x.min(segment.b.x) - r, bound_min.x, grid_size.0),
+                cell(segment.a.x.max(segment.b.x) + r, bound_min.x, grid_size.0),
+            );
+            let (y0, y1) = (
+                cell(segment.a.y.min(segment.b.y) - r, bound_min.y, grid_size.1),
+                cell(segment.a.y.max(segment.b.y) + r, bound_min.y, grid_size.1),
+            );
+            (y0..=y1).for_each(|y| (x0..=x1).for_each(|x| grid[y * grid_size.0 + x].push(s)));
+        });
+        Self {
+            segments,
+            bound_min,
+            cell_size,
+            grid_size,
+            grid,
+        }
+    }
+
+    /// The number of the segments of the channels.
+    pub fn num_segments(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// The elevation at the position after carving the channels.
+    pub fn carve(&self, site: &Site2D, elevation: Elevation) -> Elevation {
+        self.segments_around(site)
+            .iter()
+            .filter_map(|&s| self.segments[s].bed(site))
+            .fold(elevation, f64::min)
+    }
+
+    /// The depth of the water at the position, from the bed of the channel to its bankfull level (unit: L).
+    /// This is 0.0 outside the channels.
+    ///
+    /// The water surface is at the elevation of the channel, so the water covers the carved bed (see [ChannelCarver2D::carve]).
+    pub fn water_depth(&self, site: &Site2D) -> Length {
+        self.segments_around(site)
+            .iter()
+            .filter_map(|&s| self.segments[s].water_depth(site))
+            .fold(0.0, f64::max)
+    }
+
+    /// The indices of the segments in the cell of the grid containing the position.
+    fn segments_around(&self, site: &Site2D) -> &[usize] {
+        let (gx, gy) = (
+            (site.x - self.bound_min.x) / self.cell_size,
+            (site.y - self.bound_min.y) / self.cell_size,
+        );
+        if !(gx >= 0.0 && gy >= 0.0) {
+            return &[];
+        }
+        let (gx, gy) = (gx.floor() as usize, gy.floor() as usize);
+        if gx >= self.grid_size.0 || gy >= self.grid_size.1 {
+            return &[];
+        }
+        &self.grid[gy * self.grid_size.0 + gx]
+    }
+}
+
+impl ChannelSegment {
+    /// The elevation of the bed of the channel at the position, or `None` if it is outside the channel.
+    fn bed(&self, site: &Site2D) -> Option<Elevation> {
+        let (t, depth) = self.cross_section(site)?;
+        Some(self.elevations[0] * (1.0 - t) + self.elevations[1] * t - depth)
+    }
+
+    /// The depth of the water at the position, or `None` if it is outside the channel.
+    fn water_depth(&self, site: &Site2D) -> Option<Length> {
+        self.cross_section(site).map(|(_, depth)| depth)
+    }
+
+    /// The position along the segment and the depth of the parabolic cross section at the position,
+    /// or `None` if it is outside the channel.
+    fn cross_section(&self, site: &Site2D) -> Option<(f64, Length)> {
+        let (dx, dy) = (self.b.x - self.a.x, self.b.y - self.a.y);
+        let length2 = dx * dx + dy * dy;
+        let t = if length2 > 0.0 {
+            (((site.x - self.a.x) * dx + (site.y - self.a.y) * dy) / length2).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let lerp = |v: [f64; 2]| v[0] * (1.0 - t) + v[1] * t;
+        let distance = site.distance(&Site2D::new(self.a.x + dx * t, self.a.y + dy * t));
+        let half_width = lerp(self.widths) * 0.5;
+        if distance >= half_width {
+            return None;
+        }
+        let ratio = distance / half_width;
+        Some((t, lerp(self.depths) * (1.0 - ratio * ratio)))
+    }
+}
@@ -0,0 +1,186 @@
+use crate::core::scale::VerticalScale;
+
+use super::{sites::Site2D, terrain::Terrain2D};
+
+/// The axis pointing up in the coordinate system of the exported mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpAxis {
+    Y,
+    #[default]
+    Z,
+}
+
+/// The handedness of the coordinate system of the exported mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Handedness {
+    #[default]
+    Right,
+    Left,
+}
+
+/// The order of the vertices of the triangles seen from above, which decides the front faces in most physics engines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Winding {
+    #[default]
+    CounterClockwise,
+    Clockwise,
+}
+
+/// An indexed triangle mesh of a terrain for the colliders of physics engines.
+///
+/// ### Properties
+///  - `vertices` is the position of each vertex in the coordinate system of the exporter.
+///  - `indices` is the indices of the vertices of the triangles, three per triangle in the winding of the exporter.
+///  - `sites` is the index of the site of each vertex in the terrain.
+#[derive(Debug, Clone)]
+pub struct ColliderMesh2D {
+    pub vertices: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+    pub sites: Vec<usize>,
+}
+
+/// Provides an export of the triangulation of a terrain as a collider mesh in the layout expected by physics engines.
+///
+/// The x axis of the model stays the x axis, and the elevation goes to the up axis. The y axis of the model (the north) goes to:
+///  - `+y` for z-up and right-handed (e.g. Blender).
+///  - `-y` for z-up and left-handed (e.g. Unreal Engine).
+///  - `-z` for y-up and right-handed (e.g. glTF, Godot, Rapier, Bevy).
+///  - `+z` for y-up and left-handed (e.g. Unity).
+///
+/// so that the terrain is not mirrored in any coordinate system.
+/// The degenerate triangles and the triangles with non-finite elevations are dropped, and only the vertices used by the triangles are exported.
+/// The vertices are single precision, so `origin` should be near the terrain to keep the precision of large terrains.
+///
+/// ### Properties
+///  - `up_axis` is the up axis (see [UpAxis]). The default value is `UpAxis::Z`.
+///  - `handedness` is the handedness (see [Handedness]). The default value is `Handedness::Right`.
+///  - `winding` is the order of the vertices of the triangles seen from above (see [Winding]). The default value is `Winding::CounterClockwise`.
+///  - `origin` is the position of the model placed at the origin. The default value is `(0.0, 0.0)`.
+///  - `horizontal_scale` is the factor multiplied to the horizontal coordinates. The default value is 1.0.
+///  - `vertical_scale` is the conversion of the elevations (see [VerticalScale]). The default value is the identity.
+#[derive(Debug, Clone)]
+pub struct ColliderExporter2D {
+    up_axis: UpAxis,
+    handedness: Handedness,
+    winding: Winding,
+    origin: Site2D,
+    horizontal_scale: f64,
+    vertical_scale: VerticalScale,
+}
+
+impl Default for ColliderExporter2D {
+    fn default() -> Self {
+        Self {
+            up_axis: UpAxis::default(),
+            handedness: Handedness::default(),
+            winding: Winding::default(),
+            origin: Site2D::new(0.0, 0.0),
+            horizontal_scale: 1.0,
+            vertical_scale: VerticalScale::default(),
+        }
+    }
+}
+
+impl ColliderExporter2D {
+    pub fn set_up_axis(mut self, up_axis: UpAxis) -> Self {
+        self.up_axis = up_axis;
+        self
+    }
+
+    pub fn set_handedness(mut self, handedness: Handedness) -> Self {
+        self.handedness = handedness;
+        self
+    }
+
+    pub fn set_winding(mut self, winding: Winding) -> Self {
+        self.winding = winding;
+        self
+    }
+
+    pub fn set_origin(mut self, origin: Site2D) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn set_horizontal_scale(mut self, horizontal_scale: f64) -> Self {
+        self.horizontal_scale = horizontal_scale;
+        self
+    }
+
+    pub fn set_vertical_scale(mut self, vertical_scale: VerticalScale) -> Self {
+        self.vertical_scale = vertical_scale;
+        self
+    }
+
+    /// The position of the site at the elevation in the coordinate system of the exporter.
+    pub fn convert(&self, site: &Site2D, elevation: f64) -> [f32; 3] {
+        let x = (site.x - self.origin.x) * self.horizontal_scale;
+        let y = (site.y - self.origin.y) * self.horizontal_scale;
+        let up = self.vertical_scale.apply(elevation);
+        let position = match (self.up_axis, self.handedness) {
+            (UpAxis::Z, Handedness::Right) => [x, y, up],
+            (UpAxis::Z, Handedness::Left) => [x, -y, up],
+            (UpAxis::Y, Handedness::Right) => [x, up, -y],
+            (UpAxis::Y, Handedness::Left) => [x, up, y],
+        };
+        position.map(|v| v as f32)
+    }
+
+    /// The horizontal position (east, north) of the exported position, inverting the axes of [ColliderExporter2D::convert].
+    fn horizontal(&self, position: &[f32; 3]) -> (f64, f64) {
+        let [x, y, z] = position.map(|v| v as f64);
+        match (self.up_axis, self.handedness) {
+            (UpAxis::Z, Handedness::Right) => (x, y),
+            (UpAxis::Z, Handedness::Left) => (x, -y),
+            (UpAxis::Y, Handedness::Right) => (x, -z),
+            (UpAxis::Y, Handedness::Left) => (x, z),
+        }
+    }
+
+    /// Export the triangulation of the terrain (see [Terrain2D::triangles]).
+    ///
+    /// The mesh is empty if the terrain has no triangles.
+    pub fn export(&self, terrain: &Terrain2D) -> ColliderMesh2D {
+        let sites = terrain.sites();
+        let elevations = terrain.elevations();
+        let mut vertex_of = vec![None; sites.len()];
+        let mut mesh = ColliderMesh2D {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            sites: Vec::new(),
+        };
+        terrain.triangles().iter().for_each(|&[a, b, c]| {
+            if [a, b, c].iter().any(|&i| !elevations[i].is_finite()) {
+                return;
+            }
+            // the orientation in single precision, since the slivers on the convex hull may collapse by the rounding
+            let [pa, pb, pc] = [a, b, c].map(|i| self.horizontal(&self.convert(&sites[i], 0.0)));
+            let orientation = (pb.0 - pa.0) * (pc.1 - pa.1) - (pb.1 - pa.1) * (pc.0 - pa.0);
+            if orientation == 0.0 {
+                return;
+            }
+            let counterclockwise = if orientation > 0.0 {
+                [a, b, c]
+            } else {
+                [a, c, b]
+            };
+            let triangle = match self.winding {
+                Winding::CounterClockwise => counterclockwise,
+                Winding::Clockwise => [
+                    counterclockwise[0],
+                    counterclockwise[2],
+                    counterclockwise[1],
+                ],
+            };
+            triangle.iter().for_each(|&i| {
+                let vertex = *vertex_of[i].get_or_insert_with(|| {
+                    mesh.vertices.push(self.convert(&sites[i], elevations[i]));
+                    mesh.sites.push(i);
+                    mesh.sites.len() - 1
+                });
+                mesh.indices.push(vertex as u32);
+            });
+        });
+        mesh
+    }
+}
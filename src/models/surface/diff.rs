@@ -0,0 +1,113 @@
+use std::io::{self, Write};
+use thiserror::Error;
+
+use crate::core::units::Elevation;
+
+use super::{
+    raster::{Raster2D, Rasterizer2D},
+    raster_writer::PngWriter,
+    sites::Site2D,
+    terrain::Terrain2D,
+};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum TerrainDiffError {
+    #[error("The number of elevations must be equal to the number of sites")]
+    InvalidNumberOfElevations,
+}
+
+/// The summary statistics of the changes of the elevations.
+///
+/// ### Properties
+///  - `mean_change` is the mean of the signed changes (unit: L).
+///  - `mean_absolute_change` is the mean of the absolute changes (unit: L).
+///  - `max_rise` is the largest rise, or 0.0 if no site rose (unit: L).
+///  - `max_fall` is the largest fall as a positive value, or 0.0 if no site fell (unit: L).
+///  - `num_changed` is the number of the sites whose elevations changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerrainDiffSummary {
+    pub mean_change: Elevation,
+    pub mean_absolute_change: Elevation,
+    pub max_rise: Elevation,
+    pub max_fall: Elevation,
+    pub num_changed: usize,
+}
+
+/// The signed change of the elevation of each site between two states of a terrain, such as two snapshots.
+///
+/// The change is `b - a`, so the sites rising from `a` to `b` are positive.
+/// The changes are interpolated in the same way as the elevations of the terrain, to render a heat-map of
+/// where the landscape is still evolving or how an event reshaped it.
+#[derive(Clone)]
+pub struct TerrainDiff2D {
+    terrain: Terrain2D,
+    changes: Vec<Elevation>,
+}
+
+impl TerrainDiff2D {
+    /// The changes from the terrain `a` to the terrain `b`, which must have the same sites.
+    ///
+    /// The sites of `b` are used to interpolate the changes.
+    pub fn between(a: &Terrain2D, b: &Terrain2D) -> Result<Self, TerrainDiffError> {
+        Self::between_elevations(b, a.elevations(), b.elevations())
+    }
+
+    /// The changes from the elevations `a` to `b` of the sites of the terrain,
+    /// such as the snapshots of `SimulationEvent::SnapshotReady` or `SnapshotSequence`.
+    pub fn between_elevations(
+        terrain: &Terrain2D,
+        a: &[Elevation],
+        b: &[Elevation],
+    ) -> Result<Self, TerrainDiffError> {
+        let num = terrain.sites().len();
+        if a.len() != num || b.len() != num {
+            return Err(TerrainDiffError::InvalidNumberOfElevations);
+        }
+        Ok(Self {
+            terrain: terrain.clone(),
+            changes: a.iter().zip(b).map(|(a, b)| b - a).collect(),
+        })
+    }
+
+    /// The signed change of each site (unit: L).
+    pub fn changes(&self) -> &[Elevation] {
+        &self.changes
+    }
+
+    /// Get the interpolated change at the site.
+    pub fn get_change(&self, site: &Site2D) -> Option<Elevation> {
+        self.terrain.interpolate(&self.changes, site)
+    }
+
+    /// The summary statistics of the changes.
+    pub fn summary(&self) -> TerrainDiffSummary {
+        let num = self.changes.len().max(1) as f64;
+        TerrainDiffSummary {
+            mean_change: self.changes.iter().sum::<f64>() / num,
+            mean_absolute_change: self.changes.iter().map(|c| c.abs()).sum::<f64>() / num,
+            max_rise: self.changes.iter().cloned().fold(0.0, f64::max),
+            max_fall: -self.changes.iter().cloned().fold(0.0, f64::min),
+            num_changed: self.changes.iter().filter(|c| **c != 0.0).count(),
+        }
+    }
+
+    /// Rasterize the interpolated changes.
+    pub fn rasterize(&self, rasterizer: &Rasterizer2D) -> Raster2D {
+        rasterizer.rasterize(|site| self.get_change(site))
+    }
+
+    /// Write the heat-map of the changes as a 16-bit grayscale PNG (see [PngWriter]).
+    ///
+    /// The range is symmetric around 0.0 so that the unchanged sites are the middle gray, the rises are brighter
+    /// and the falls are darker. The pixels outside the terrain are black.
+    pub fn write_image(&self, rasterizer: &Rasterizer2D, writer: impl Write) -> io::Result<()> {
+        let summary = self.summary();
+        let range = match summary.max_rise.max(summary.max_fall) {
+            range if range > 0.0 => range,
+            _ => 1.0,
+        };
+        let (width, height) = rasterizer.size();
+        let mut writer = PngWriter::new(writer, width, height, -range, range)?;
+        rasterizer.write_rows(|site| self.get_change(site), &mut writer)
+    }
+}
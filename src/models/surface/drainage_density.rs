@@ -0,0 +1,153 @@
+use crate::core::{
+    traits::Site,
+    units::{Area, Length},
+};
+
+use super::{sites::Site2D, terrain::Terrain2D};
+
+/// The drainage density for a channel-initiation threshold.
+///
+/// ### Properties
+///  - `threshold` is the drainage area above which the sites are regarded as channels (unit: L^2).
+///  - `channel_length` is the total length of the channels (unit: L).
+///  - `density` is the total length of the channels per unit area (unit: L^-1).
+#[derive(Debug, Clone, Copy)]
+pub struct DrainageDensity {
+    pub threshold: Area,
+    pub channel_length: Length,
+    pub density: f64,
+}
+
+/// Provides the curve of the drainage density of the terrain against the channel-initiation threshold.
+///
+/// The channels are the edges from the sites whose drainage area is not less than the threshold to their receivers.
+/// The density is the total length of the channels divided by the area of the convex hull of the sites.
+///
+/// ### Properties
+///  - `thresholds` is the list of the thresholds (unit: L^2). If `None`, `num_thresholds` thresholds are spaced logarithmically
+///    between the smallest and the largest drainage areas of the terrain. The default value is `None`.
+///  - `num_thresholds` is the number of the thresholds if `thresholds` is `None`. The default value is 20.
+#[derive(Debug, Clone)]
+pub struct DrainageDensityAnalysis2D {
+    thresholds: Option<Vec<Area>>,
+    num_thresholds: usize,
+}
+
+impl Default for DrainageDensityAnalysis2D {
+    fn default() -> Self {
+        Self {
+            thresholds: None,
+            num_thresholds: 20,
+        }
+    }
+}
+
+impl DrainageDensityAnalysis2D {
+    pub fn set_thresholds(mut self, thresholds: Option<Vec<Area>>) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    pub fn set_num_thresholds(mut self, num_thresholds: usize) -> Self {
+        self.num_thresholds = num_thresholds;
+        self
+    }
+
+    /// Compute the drainage density for each threshold, in the ascending order of the thresholds.
+    ///
+    /// This returns an empty list if the terrain has no drainage network.
+    pub fn analyze(&self, terrain: &Terrain2D) -> Vec<DrainageDensity> {
+        let network = terrain.network();
+        if network.is_empty() {
+            return Vec::new();
+        }
+        let sites = terrain.sites();
+        let receivers = network.receivers();
+        let drainage_areas = network.drainage_areas();
+
+        // the length of the edge from each site to its receiver, sorted by the drainage area
+        let mut edges = (0..receivers.len())
+            .filter(|&i| receivers[i] != i && drainage_areas[i].is_finite())
+            .map(|i| (drainage_areas[i], sites[i].distance(&sites[receivers[i]])))
+            .collect::<Vec<_>>();
+        edges.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut thresholds = match &self.thresholds {
+            Some(thresholds) => thresholds.clone(),
+            None => {
+                let (min, max) = match (edges.first(), edges.last()) {
+                    (Some(first), Some(last)) if first.0 > 0.0 => (first.0.ln(), last.0.ln()),
+                    _ => return Vec::new(),
+                };
+                let n = self.num_thresholds;
+                (0..n)
+                    .map(|k| {
+                        let t = if n > 1 {
+                            k as f64 / (n - 1) as f64
+                        } else {
+                            0.0
+                        };
+                        (min + (max - min) * t).exp()
+                    })
+                    .collect()
+            }
+        };
+        thresholds.sort_by(|a, b| a.total_cmp(b));
+
+        // the total length of the edges not less than each drainage area, accumulated from the largest
+        let mut suffix_lengths = vec![0.0; edges.len() + 1];
+        (0..edges.len()).rev().for_each(|k| {
+            suffix_lengths[k] = suffix_lengths[k + 1] + edges[k].1;
+        });
+        let area = hull_area(sites);
+
+        thresholds
+            .into_iter()
+            .map(|threshold| {
+                let k = edges.partition_point(|edge| edge.0 < threshold);
+                let channel_length = suffix_lengths[k];
+                DrainageDensity {
+                    threshold,
+                    channel_length,
+                    density: if area > 0.0 {
+                        channel_length / area
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+/// The area of the convex hull of the sites (Andrew's monotone chain).
+fn hull_area(sites: &[Site2D]) -> Area {
+    let mut points = sites.to_vec();
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    if points.len() < 3 {
+        return 0.0;
+    }
+    let cross =
+        |o: &Site2D, a: &Site2D, b: &Site2D| (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x);
+    let mut hull: Vec<Site2D> = Vec::with_capacity(points.len() * 2);
+    let chain = |hull: &mut Vec<Site2D>, lower: usize, p: &Site2D| {
+        while hull.len() >= lower + 2
+            && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], p) <= 0.0
+        {
+            hull.pop();
+        }
+        hull.push(*p);
+    };
+    points.iter().for_each(|p| chain(&mut hull, 0, p));
+    let lower = hull.len() - 1;
+    points.iter().rev().for_each(|p| chain(&mut hull, lower, p));
+    hull.pop();
+    (0..hull.len())
+        .map(|k| {
+            let (a, b) = (&hull[k], &hull[(k + 1) % hull.len()]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f64>()
+        .abs()
+        / 2.0
+}
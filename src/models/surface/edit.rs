@@ -0,0 +1,202 @@
+use crate::core::traits::Model;
+
+use super::{
+    builder::{ModelBuilderError, TerrainModel2DBulider},
+    model::TerrainModel2D,
+    sites::Site2D,
+};
+
+/// The correspondence of the sites of a model before and after an edit
+/// (see [TerrainModel2D::insert_sites], [TerrainModel2D::remove_sites] and [TerrainModel2D::merge]).
+///
+/// The sites kept by the edit correspond to themselves, and the inserted sites correspond to the nearest sites before the edit.
+#[derive(Debug, Clone)]
+pub struct SiteMapping2D {
+    previous_indices: Vec<Option<usize>>,
+    new_indices: Vec<Option<usize>>,
+    sources: Vec<usize>,
+}
+
+impl SiteMapping2D {
+    /// The index of the site before the edit, or `None` if the site `i` is inserted by the edit.
+    pub fn previous_index(&self, i: usize) -> Option<usize> {
+        self.previous_indices.get(i).copied().flatten()
+    }
+
+    /// The index of the site after the edit, or `None` if the site `i` is removed by the edit.
+    pub fn new_index(&self, i: usize) -> Option<usize> {
+        self.new_indices.get(i).copied().flatten()
+    }
+
+    /// Remap the values of the sites before the edit (such as the parameters) to the sites after the edit.
+    ///
+    /// The inserted sites take the values of the nearest sites before the edit.
+    pub fn remap<T: Clone>(&self, values: &[T]) -> Vec<T> {
+        self.sources.iter().map(|&j| values[j].clone()).collect()
+    }
+}
+
+impl TerrainModel2D {
+    /// Create the model with the sites inserted, keeping the bounding rectangle and the boundary conditions.
+    ///
+    /// The sites of the model keep their indices, and the inserted sites follow them in the given order.
+    /// The triangulation and the Voronoi cells are recomputed.
+    pub fn insert_sites(
+        &self,
+        sites: &[Site2D],
+    ) -> Result<(TerrainModel2D, SiteMapping2D), ModelBuilderError> {
+        let (bound_min, bound_max) = self.bounds();
+        let is_inside = |site: &Site2D| {
+            site.x >= bound_min.x
+                && site.x <= bound_max.x
+                && site.y >= bound_min.y
+                && site.y <= bound_max.y
+        };
+        if !sites.iter().all(is_inside) {
+            return Err(ModelBuilderError::SiteOutOfBounds);
+        }
+        let num = self.num();
+        let sources = (0..num)
+            .map(Ok)
+            .chain(sites.iter().map(|site| {
+                self.nearest_site(site)
+                    .ok_or(ModelBuilderError::VoronoiError)
+            }))
+            .collect::<Result<Vec<_>, _>>()?;
+        let model = self.rebuild([self.sites(), sites].concat(), self.bounds())?;
+        let mapping = SiteMapping2D {
+            previous_indices: (0..num + sites.len())
+                .map(|i| if i < num { Some(i) } else { None })
+                .collect(),
+            new_indices: (0..num).map(Some).collect(),
+            sources,
+        };
+        Ok((model, mapping))
+    }
+
+    /// Create the model with the sites of the given indices removed, keeping the bounding rectangle and the boundary conditions.
+    ///
+    /// The remaining sites keep their order. The indices out of range are ignored.
+    /// The triangulation and the Voronoi cells are recomputed.
+    pub fn remove_sites(
+        &self,
+        indices: &[usize],
+    ) -> Result<(TerrainModel2D, SiteMapping2D), ModelBuilderError> {
+        let num = self.num();
+        let mut removed = vec![false; num];
+        indices
+            .iter()
+            .filter(|&&i| i < num)
+            .for_each(|&i| removed[i] = true);
+        let sources = (0..num).filter(|&i| !removed[i]).collect::<Vec<_>>();
+        let mut new_indices = vec![None; num];
+        sources
+            .iter()
+            .enumerate()
+            .for_each(|(k, &i)| new_indices[i] = Some(k));
+        let model = self.rebuild(
+            sources.iter().map(|&i| self.sites()[i]).collect(),
+            self.bounds(),
+        )?;
+        let mapping = SiteMapping2D {
+            previous_indices: sources.iter().map(|&i| Some(i)).collect(),
+            new_indices,
+            sources,
+        };
+        Ok((model, mapping))
+    }
+
+    /// Merge the model with another model into one domain, such as a high-resolution region inset in a coarse continent.
+    ///
+    /// The sites of this model inside the bounding rectangle of `other` are replaced by the sites of `other`, and the
+    /// triangulation is recomputed over the sites so that the models are stitched. The bounding rectangle of the merged model
+    /// is the union of the bounding rectangles, and the boundary conditions are the ones of this model.
+    /// So the outlets of the merged model are the outlets of the models remaining on the convex hull of the merged sites.
+    ///
+    /// The kept sites of this model come first, followed by the sites of `other`.
+    /// The returned mappings are the correspondences of the sites of this model and `other` to the merged sites.
+    /// The values of the merged sites can be taken from the model for which [SiteMapping2D::previous_index] is `Some`.
+    pub fn merge(
+        &self,
+        other: &TerrainModel2D,
+    ) -> Result<(TerrainModel2D, SiteMapping2D, SiteMapping2D), ModelBuilderError> {
+        let (other_min, other_max) = other.bounds();
+        let is_covered = |site: &Site2D| {
+            site.x >= other_min.x
+                && site.x <= other_max.x
+                && site.y >= other_min.y
+                && site.y <= other_max.y
+        };
+        let num = self.num();
+        let kept = (0..num)
+            .filter(|&i| !is_covered(&self.sites()[i]))
+            .collect::<Vec<_>>();
+        let num_merged = kept.len() + other.num();
+
+        let mut new_indices = vec![None; num];
+        kept.iter()
+            .enumerate()
+            .for_each(|(k, &i)| new_indices[i] = Some(k));
+        let mut previous_indices = kept.iter().map(|&i| Some(i)).collect::<Vec<_>>();
+        previous_indices.resize(num_merged, None);
+        let sources = kept
+            .iter()
+            .copied()
+            .map(Ok)
+            .chain(other.sites().iter().map(|site| {
+                self.nearest_site(site)
+                    .ok_or(ModelBuilderError::VoronoiError)
+            }))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mapping = SiteMapping2D {
+            previous_indices,
+            new_indices,
+            sources,
+        };
+
+        let other_mapping = SiteMapping2D {
+            previous_indices: (0..num_merged).map(|i| i.checked_sub(kept.len())).collect(),
+            new_indices: (0..other.num()).map(|i| Some(kept.len() + i)).collect(),
+            sources: kept
+                .iter()
+                .map(|&i| {
+                    other
+                        .nearest_site(&self.sites()[i])
+                        .ok_or(ModelBuilderError::VoronoiError)
+                })
+                .chain((0..other.num()).map(Ok))
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+
+        let (self_min, self_max) = self.bounds();
+        let bounds = (
+            Site2D {
+                x: self_min.x.min(other_min.x),
+                y: self_min.y.min(other_min.y),
+            },
+            Site2D {
+                x: self_max.x.max(other_max.x),
+                y: self_max.y.max(other_max.y),
+            },
+        );
+        let sites = kept
+            .iter()
+            .map(|&i| self.sites()[i])
+            .chain(other.sites().iter().copied())
+            .collect();
+        let model = self.rebuild(sites, bounds)?;
+        Ok((model, mapping, other_mapping))
+    }
+
+    fn rebuild(
+        &self,
+        sites: Vec<Site2D>,
+        (bound_min, bound_max): (Site2D, Site2D),
+    ) -> Result<TerrainModel2D, ModelBuilderError> {
+        TerrainModel2DBulider::default()
+            .set_sites(sites)
+            .set_bounding_box(Some(bound_min), Some(bound_max))
+            .set_boundary_conditions(*self.boundary_conditions())
+            .build()
+    }
+}
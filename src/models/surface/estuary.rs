@@ -0,0 +1,152 @@
+use std::collections::VecDeque;
+
+use crate::core::{
+    traits::Model,
+    units::{Area, Elevation},
+};
+
+use super::{cells::outline_cells, model::TerrainModel2D, sites::Site2D, terrain::Terrain2D};
+
+/// A drowned river valley.
+///
+/// ### Properties
+///  - `sites` is the indices of the drowned sites of the estuary.
+///  - `outlines` is the outlines of the union of the Voronoi cells of the sites, as closed rings.
+#[derive(Debug, Clone)]
+pub struct Estuary2D {
+    pub sites: Vec<usize>,
+    pub outlines: Vec<Vec<Site2D>>,
+}
+
+/// Provides a detection of estuaries (rias), the river valleys drowned by the rise of the sea level.
+///
+/// The ocean is the set of the sites below `sea_level` connected to the outlets. The sites of the ocean which were above
+/// `previous_sea_level` were land before the rise, and their connected groups containing the valleys, the sites whose
+/// drainage area is not less than `min_drainage_area`, are the estuaries.
+/// Since the valleys are lower than the ridges between them, the drowned valleys appear as branching inlets of the ocean.
+///
+/// To combine with the history of the sea level, simulate the terrain with the base level of the lowstand
+/// (see [crate::lem::processes::base_level::BaseLevelProcess]) and set `sea_level` to the final highstand.
+///
+/// ### Properties
+///  - `sea_level` is the current sea level (unit: L). The default value is 0.0.
+///  - `previous_sea_level` is the sea level before the rise (unit: L). If `None`, the minimum elevation of the outlets is used.
+///  - `min_drainage_area` is the drainage area above which the sites are regarded as valleys (unit: L^2). The default value is 100.0.
+#[derive(Debug, Clone)]
+pub struct EstuaryDetector2D {
+    sea_level: Elevation,
+    previous_sea_level: Option<Elevation>,
+    min_drainage_area: Area,
+}
+
+impl Default for EstuaryDetector2D {
+    fn default() -> Self {
+        Self {
+            sea_level: 0.0,
+            previous_sea_level: None,
+            min_drainage_area: 100.0,
+        }
+    }
+}
+
+impl EstuaryDetector2D {
+    pub fn set_sea_level(mut self, sea_level: Elevation) -> Self {
+        self.sea_level = sea_level;
+        self
+    }
+
+    pub fn set_previous_sea_level(mut self, previous_sea_level: Option<Elevation>) -> Self {
+        self.previous_sea_level = previous_sea_level;
+        self
+    }
+
+    pub fn set_min_drainage_area(mut self, min_drainage_area: Area) -> Self {
+        self.min_drainage_area = min_drainage_area;
+        self
+    }
+
+    /// The mask of the ocean, the sites below the sea level connected to the outlets.
+    pub fn ocean_mask(&self, model: &TerrainModel2D, terrain: &Terrain2D) -> Vec<bool> {
+        let elevations = terrain.elevations();
+        let mut ocean = vec![false; model.num()];
+        let mut queue = self
+            .outlets(model, terrain)
+            .into_iter()
+            .filter(|&i| elevations[i] <= self.sea_level)
+            .collect::<VecDeque<_>>();
+        queue.iter().for_each(|&i| ocean[i] = true);
+        while let Some(i) = queue.pop_front() {
+            model.graph().neighbors_of(i).iter().for_each(|ja| {
+                let j = ja.0;
+                if !ocean[j] && elevations[j] < self.sea_level {
+                    ocean[j] = true;
+                    queue.push_back(j);
+                }
+            });
+        }
+        ocean
+    }
+
+    /// Detect the estuaries on the terrain.
+    pub fn detect(&self, model: &TerrainModel2D, terrain: &Terrain2D) -> Vec<Estuary2D> {
+        let elevations = terrain.elevations();
+        let drainage_areas = terrain.network().drainage_areas();
+        let previous_sea_level = self.previous_sea_level.unwrap_or_else(|| {
+            self.outlets(model, terrain)
+                .iter()
+                .map(|&i| elevations[i])
+                .fold(f64::INFINITY, f64::min)
+        });
+        let ocean = self.ocean_mask(model, terrain);
+        let drowned = (0..model.num())
+            .map(|i| ocean[i] && elevations[i] > previous_sea_level)
+            .collect::<Vec<_>>();
+
+        // group the drowned sites and keep the groups containing valleys
+        let mut visited = vec![false; model.num()];
+        (0..model.num())
+            .filter_map(|start| {
+                if !drowned[start] || visited[start] {
+                    return None;
+                }
+                visited[start] = true;
+                let mut sites = vec![start];
+                let mut k = 0;
+                while k < sites.len() {
+                    let i = sites[k];
+                    model.graph().neighbors_of(i).iter().for_each(|ja| {
+                        if drowned[ja.0] && !visited[ja.0] {
+                            visited[ja.0] = true;
+                            sites.push(ja.0);
+                        }
+                    });
+                    k += 1;
+                }
+                let has_valley = sites.iter().any(|&i| {
+                    drainage_areas
+                        .get(i)
+                        .map(|&area| area >= self.min_drainage_area)
+                        .unwrap_or(false)
+                });
+                if !has_valley {
+                    return None;
+                }
+                let mut mask = vec![false; model.num()];
+                sites.iter().for_each(|&i| mask[i] = true);
+                let outlines = outline_cells(model.sites(), model.triangles(), model.hull(), &mask);
+                sites.sort_unstable();
+                Some(Estuary2D { sites, outlines })
+            })
+            .collect()
+    }
+
+    /// The outlets of the terrain, or the default outlets of the model if the terrain has no drainage network.
+    fn outlets(&self, model: &TerrainModel2D, terrain: &Terrain2D) -> Vec<usize> {
+        let network = terrain.network();
+        if network.is_empty() {
+            model.default_outlets().to_vec()
+        } else {
+            (0..model.num()).filter(|&i| network.is_outlet(i)).collect()
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::core::units::Length;
+
+use super::sites::Site2D;
+
+/// The trace of a fault on the surface, a polyline deciding the hanging wall of a [crate::lem::processes::fault::FaultProcess].
+///
+/// The hanging wall is the side on the left of the direction of the polyline.
+/// The side of each site is decided by the nearest segment of the polyline.
+#[derive(Debug, Clone)]
+pub struct FaultTrace2D {
+    points: Vec<Site2D>,
+}
+
+impl FaultTrace2D {
+    pub fn new(points: Vec<Site2D>) -> Self {
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[Site2D] {
+        &self.points
+    }
+
+    /// Whether each site is on the hanging wall.
+    ///
+    /// `extent` is the distance from the trace within which the hanging wall is uplifted (unit: L).
+    /// If `None`, the whole side is the hanging wall. All the sites are on the footwall if the trace has less than two points.
+    pub fn hanging_wall(&self, sites: &[Site2D], extent: Option<Length>) -> Vec<bool> {
+        sites
+            .iter()
+            .map(|site| {
+                self.side_of(site).is_some_and(|(distance, side)| {
+                    side > 0.0 && extent.is_none_or(|extent| distance <= extent)
+                })
+            })
+            .collect()
+    }
+
+    /// The distance from the site to the nearest segment of the trace, and the side of the site:
+    /// positive on the left of the direction of the trace, and negative on the right.
+    fn side_of(&self, site: &Site2D) -> Option<(Length, f64)> {
+        self.points
+            .windows(2)
+            .map(|segment| {
+                let (a, b) = (segment[0], segment[1]);
+                let (dx, dy) = (b.x - a.x, b.y - a.y);
+                let squared_length = dx * dx + dy * dy;
+                let t = if squared_length > 0.0 {
+                    (((site.x - a.x) * dx + (site.y - a.y) * dy) / squared_length).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let (px, py) = (a.x + t * dx, a.y + t * dy);
+                let distance = ((site.x - px).powi(2) + (site.y - py).powi(2)).sqrt();
+                let side = dx * (site.y - a.y) - dy * (site.x - a.x);
+                (distance, side)
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+    }
+}
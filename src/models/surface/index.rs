@@ -0,0 +1,37 @@
+use rtree_rs::{RTree, Rect};
+
+use super::sites::Site2D;
+
+/// A spatial index of the sites for the nearest site queries.
+pub(crate) struct SiteIndex2D {
+    tree: RTree<2, f64, usize>,
+}
+
+impl SiteIndex2D {
+    pub(crate) fn new(sites: &[Site2D]) -> Self {
+        let mut tree = RTree::new();
+        sites.iter().enumerate().for_each(|(i, site)| {
+            tree.insert(Rect::new_point([site.x, site.y]), i);
+        });
+        Self { tree }
+    }
+
+    /// The index of the nearest site, that is, the site whose Voronoi cell contains `site`.
+    pub(crate) fn nearest(&self, site: &Site2D) -> Option<usize> {
+        let target = Rect::new_point([site.x, site.y]);
+        self.tree
+            .nearby(|rect, _| rect.box_dist(&target))
+            .next()
+            .map(|item| *item.data)
+    }
+
+    /// The indices of the `k` nearest sites, from the nearest.
+    pub(crate) fn k_nearest(&self, site: &Site2D, k: usize) -> Vec<usize> {
+        let target = Rect::new_point([site.x, site.y]);
+        self.tree
+            .nearby(|rect, _| rect.box_dist(&target))
+            .take(k)
+            .map(|item| *item.data)
+            .collect()
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::core::{
+    distance::QueueItem,
+    fields::DISCHARGE,
+    traits::{Model, Site},
+    units::{Area, Elevation, Length},
+};
+
+use super::{
+    basin::{BasinPolygon2D, BasinPolygonizer2D},
+    model::TerrainModel2D,
+    river::River2D,
+    sites::Site2D,
+    terrain::Terrain2D,
+};
+
+/// The measure to choose the main river.
+///
+/// ### Variants
+///  - `Length` chooses the longest path along the channels from a channel head to an outlet.
+///  - `Discharge` chooses the river of the largest discharge, following the tributary of the largest discharge upstream at each confluence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RiverMeasure {
+    #[default]
+    Length,
+    Discharge,
+}
+
+/// A site standing out in the terrain, such as a peak or the bottom of a valley.
+///
+/// ### Properties
+///  - `site` is the index of the site.
+///  - `position` is the position of the site.
+///  - `elevation` is the elevation of the site.
+///  - `depth` is the depth of the valley below the highest site within the radius (unit: L). This is 0.0 for peaks.
+#[derive(Debug, Clone)]
+pub struct Landmark2D {
+    pub site: usize,
+    pub position: Site2D,
+    pub elevation: Elevation,
+    pub depth: Length,
+}
+
+/// A drainage basin with its geometry.
+///
+/// ### Properties
+///  - `outlet` is the index of the outlet of the basin.
+///  - `sites` is the indices of the sites of the basin in the ascending order.
+///  - `area` is the area of the basin (unit: L^2).
+///  - `polygons` is the polygons of the basin (see [BasinPolygon2D]).
+#[derive(Debug, Clone)]
+pub struct LandmarkBasin2D {
+    pub outlet: usize,
+    pub sites: Vec<usize>,
+    pub area: Area,
+    pub polygons: Vec<BasinPolygon2D>,
+}
+
+/// The named features of a terrain for labeling, each of which is `None` if the terrain has no such feature.
+///
+/// ### Properties
+///  - `main_river` is the main river (see [LandmarkExtractor2D::main_river]).
+///  - `largest_basin` is the largest drainage basin (see [LandmarkExtractor2D::largest_basin]).
+///  - `highest_peak` is the highest site (see [LandmarkExtractor2D::highest_peak]).
+///  - `deepest_valley` is the deepest valley (see [LandmarkExtractor2D::deepest_valley]).
+#[derive(Debug, Clone)]
+pub struct Landmarks2D {
+    pub main_river: Option<River2D>,
+    pub largest_basin: Option<LandmarkBasin2D>,
+    pub highest_peak: Option<Landmark2D>,
+    pub deepest_valley: Option<Landmark2D>,
+}
+
+/// Provides an extraction of the named features of a terrain, so that the user interfaces can label them without deriving the hydrology again.
+///
+/// The channels are the sites whose drainage area is not less than `min_drainage_area`, as in `Terrain2D::extract_rivers`.
+/// The discharge is taken from the field [DISCHARGE], or the drainage area is used instead if the terrain does not have the field.
+///
+/// ### Properties
+///  - `min_drainage_area` is the drainage area above which the sites are channels (unit: L^2). The default value is 100.0.
+///  - `river_measure` is the measure to choose the main river (see [RiverMeasure]). The default value is `RiverMeasure::Length`.
+///  - `valley_radius` is the distance along the edges within which the depth of the valleys is measured (unit: L). The default value is 10.0.
+#[derive(Debug, Clone)]
+pub struct LandmarkExtractor2D {
+    min_drainage_area: Area,
+    river_measure: RiverMeasure,
+    valley_radius: Length,
+}
+
+impl Default for LandmarkExtractor2D {
+    fn default() -> Self {
+        Self {
+            min_drainage_area: 100.0,
+            river_measure: RiverMeasure::default(),
+            valley_radius: 10.0,
+        }
+    }
+}
+
+impl LandmarkExtractor2D {
+    pub fn set_min_drainage_area(mut self, min_drainage_area: Area) -> Self {
+        self.min_drainage_area = min_drainage_area;
+        self
+    }
+
+    pub fn set_river_measure(mut self, river_measure: RiverMeasure) -> Self {
+        self.river_measure = river_measure;
+        self
+    }
+
+    pub fn set_valley_radius(mut self, valley_radius: Length) -> Self {
+        self.valley_radius = valley_radius.max(0.0);
+        self
+    }
+
+    /// Extract all the features of the terrain generated from the model.
+    pub fn extract(&self, model: &TerrainModel2D, terrain: &Terrain2D) -> Landmarks2D {
+        Landmarks2D {
+            main_river: self.main_river(terrain),
+            largest_basin: self.largest_basin(model, terrain),
+            highest_peak: self.highest_peak(terrain),
+            deepest_valley: self.deepest_valley(model, terrain),
+        }
+    }
+
+    /// The main river from its source down to the outlet, chosen by the river measure.
+    ///
+    /// This returns `None` if the terrain has no drainage network or no channel reaching an outlet.
+    pub fn main_river(&self, terrain: &Terrain2D) -> Option<River2D> {
+        let network = terrain.network();
+        if network.is_empty() {
+            return None;
+        }
+        let sites = terrain.sites();
+        let receivers = network.receivers();
+        let drainage_areas = network.drainage_areas();
+        let is_channel = |i: usize| drainage_areas[i] >= self.min_drainage_area;
+
+        // the measure of the river upstream of each channel site and the channel donor continuing it
+        let mut measures = vec![0.0; receivers.len()];
+        let mut upstream = vec![None; receivers.len()];
+        match self.river_measure {
+            RiverMeasure::Length => {
+                // the donors come after their receivers, so the longest paths are accumulated in the reverse order
+                network.order().iter().rev().for_each(|&i| {
+                    let j = receivers[i];
+                    if j == i || !is_channel(i) || !is_channel(j) {
+                        return;
+                    }
+                    let length = measures[i] + sites[i].distance(&sites[j]);
+                    if upstream[j].is_none() || length > measures[j] {
+                        measures[j] = length;
+                        upstream[j] = Some(i);
+                    }
+                });
+            }
+            RiverMeasure::Discharge => {
+                let discharges = terrain.fields().get(DISCHARGE).unwrap_or(drainage_areas);
+                measures.copy_from_slice(discharges);
+                (0..receivers.len()).for_each(|i| {
+                    let j = receivers[i];
+                    if j == i || !is_channel(i) || !is_channel(j) {
+                        return;
+                    }
+                    if upstream[j].is_none_or(|k: usize| discharges[i] > discharges[k]) {
+                        upstream[j] = Some(i);
+                    }
+                });
+            }
+        }
+
+        let outlet = (0..receivers.len())
+            .filter(|&i| receivers[i] == i && upstream[i].is_some())
+            .max_by(|&a, &b| measures[a].total_cmp(&measures[b]).then(b.cmp(&a)))?;
+        let mut path = vec![outlet];
+        let mut i = outlet;
+        while let Some(k) = upstream[i] {
+            path.push(k);
+            i = k;
+        }
+        path.reverse();
+        Some(River2D::from_path(
+            sites,
+            terrain.elevations(),
+            network,
+            path,
+        ))
+    }
+
+    /// The drainage basin of the largest area.
+    ///
+    /// This returns `None` if the terrain has no drainage network or does not have the same sites as the model.
+    pub fn largest_basin(
+        &self,
+        model: &TerrainModel2D,
+        terrain: &Terrain2D,
+    ) -> Option<LandmarkBasin2D> {
+        let network = terrain.network();
+        if network.is_empty() || network.receivers().len() != model.num() {
+            return None;
+        }
+        let receivers = network.receivers();
+        let drainage_areas = network.drainage_areas();
+        let outlet = (0..receivers.len())
+            .filter(|&i| receivers[i] == i)
+            .max_by(|&a, &b| {
+                drainage_areas[a]
+                    .total_cmp(&drainage_areas[b])
+                    .then(b.cmp(&a))
+            })?;
+        let labels = (0..receivers.len())
+            .map(|i| {
+                let mut j = i;
+                while receivers[j] != j {
+                    j = receivers[j];
+                }
+                usize::from(j == outlet)
+            })
+            .collect::<Vec<_>>();
+        let sites = (0..labels.len())
+            .filter(|&i| labels[i] == 1)
+            .collect::<Vec<_>>();
+        let area = sites.iter().map(|&i| model.areas()[i]).sum();
+        let polygons = BasinPolygonizer2D::default()
+            .polygonize(model, &labels)
+            .into_iter()
+            .filter(|polygon| polygon.label == 1)
+            .collect();
+        Some(LandmarkBasin2D {
+            outlet,
+            sites,
+            area,
+            polygons,
+        })
+    }
+
+    /// The highest site of the terrain.
+    ///
+    /// This returns `None` if the terrain has no sites with finite elevations.
+    pub fn highest_peak(&self, terrain: &Terrain2D) -> Option<Landmark2D> {
+        let elevations = terrain.elevations();
+        let site = (0..elevations.len())
+            .filter(|&i| elevations[i].is_finite())
+            .max_by(|&a, &b| elevations[a].total_cmp(&elevations[b]).then(b.cmp(&a)))?;
+        Some(Landmark2D {
+            site,
+            position: terrain.sites()[site],
+            elevation: elevations[site],
+            depth: 0.0,
+        })
+    }
+
+    /// The channel site deepest below the highest site within `valley_radius` along the edges.
+    ///
+    /// This returns `None` if the terrain has no channels or does not have the same sites as the model.
+    pub fn deepest_valley(
+        &self,
+        model: &TerrainModel2D,
+        terrain: &Terrain2D,
+    ) -> Option<Landmark2D> {
+        let network = terrain.network();
+        if network.is_empty() || network.receivers().len() != model.num() {
+            return None;
+        }
+        let elevations = terrain.elevations();
+        let (site, depth) = (0..elevations.len())
+            .filter(|&i| {
+                network.drainage_areas()[i] >= self.min_drainage_area && elevations[i].is_finite()
+            })
+            .map(|i| (i, self.depth(model, elevations, i)))
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))?;
+        Some(Landmark2D {
+            site,
+            position: terrain.sites()[site],
+            elevation: elevations[site],
+            depth,
+        })
+    }
+
+    /// The depth of the site below the highest site within the radius, searching only the neighborhood of the site.
+    fn depth(&self, model: &TerrainModel2D, elevations: &[Elevation], source: usize) -> Length {
+        let mut distances = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        let mut highest = elevations[source];
+        distances.insert(source, 0.0);
+        queue.push(QueueItem(source, 0.0));
+        while let Some(QueueItem(i, distance)) = queue.pop() {
+            if distance > distances[&i] {
+                continue;
+            }
+            if elevations[i].is_finite() {
+                highest = highest.max(elevations[i]);
+            }
+            model.graph().neighbors_of(i).iter().for_each(|ja| {
+                let next_distance = distance + ja.1;
+                if next_distance <= self.valley_radius
+                    && distances.get(&ja.0).is_none_or(|&d| next_distance < d)
+                {
+                    distances.insert(ja.0, next_distance);
+                    queue.push(QueueItem(ja.0, next_distance));
+                }
+            });
+        }
+        highest - elevations[source]
+    }
+}
@@ -0,0 +1,279 @@
+use crate::core::{scale::VerticalScale, traits::Model, units::Elevation};
+
+use super::{model::TerrainModel2D, sites::Site2D, terrain::Terrain2D};
+
+/// A triangle mesh of a terrain for renderers.
+///
+/// ### Properties
+///  - `vertices` is the position (x, y, elevation) of each vertex, where the elevation is converted by the vertical scale.
+///  - `indices` is the indices of the vertices of the triangles, three per triangle in counterclockwise order.
+///  - `sites` is the index of the site of each vertex in the model.
+///  - `max_error` is the maximum vertical error of the mesh from the elevations of all sites, before the vertical scale is applied (unit: L).
+#[derive(Debug, Clone)]
+pub struct TerrainMesh2D {
+    pub vertices: Vec<[f64; 3]>,
+    pub indices: Vec<u32>,
+    pub sites: Vec<usize>,
+    pub max_error: Elevation,
+}
+
+/// Provides level-of-detail meshes of a terrain by decimating the triangulation of the sites.
+///
+/// The edges are collapsed greedily into one of their ends as long as the vertical error stays within the tolerance,
+/// where the error is the difference between the elevation of each removed site and the elevation of the decimated mesh at the site.
+/// The levels are decimated progressively in the order of the tolerances, so the vertices of each level are a subset of the previous one.
+/// The sites on the convex hull are always kept so that the outline of the terrain does not change.
+///
+/// ### Properties
+///  - `tolerances` is the maximum vertical error of each level (unit: L). The default value is `[0.0, 0.5, 2.0, 8.0]`.
+///  - `vertical_scale` is the conversion of the elevations of the vertices (see [VerticalScale]). The default value is the identity.
+#[derive(Debug, Clone)]
+pub struct LodGenerator2D {
+    tolerances: Vec<Elevation>,
+    vertical_scale: VerticalScale,
+}
+
+impl Default for LodGenerator2D {
+    fn default() -> Self {
+        Self {
+            tolerances: vec![0.0, 0.5, 2.0, 8.0],
+            vertical_scale: VerticalScale::default(),
+        }
+    }
+}
+
+impl LodGenerator2D {
+    pub fn set_tolerances(mut self, tolerances: Vec<Elevation>) -> Self {
+        self.tolerances = tolerances;
+        self
+    }
+
+    pub fn set_vertical_scale(mut self, vertical_scale: VerticalScale) -> Self {
+        self.vertical_scale = vertical_scale;
+        self
+    }
+
+    /// Generate the mesh of each level.
+    pub fn generate(&self, model: &TerrainModel2D, terrain: &Terrain2D) -> Vec<TerrainMesh2D> {
+        let mut decimator = Decimator::new(model.sites(), terrain.elevations(), model.triangles());
+        self.tolerances
+            .iter()
+            .map(|&tolerance| {
+                decimator.decimate(tolerance);
+                let mut mesh = decimator.mesh();
+                mesh.vertices
+                    .iter_mut()
+                    .for_each(|vertex| vertex[2] = self.vertical_scale.apply(vertex[2]));
+                mesh
+            })
+            .collect()
+    }
+}
+
+/// The twice signed area of the triangle, positive if counterclockwise.
+fn orientation(a: &Site2D, b: &Site2D, c: &Site2D) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+struct Decimator<'a> {
+    sites: &'a [Site2D],
+    elevations: &'a [Elevation],
+    triangles: Vec<[usize; 3]>,
+    alive: Vec<bool>,
+    /// the removed sites located in each triangle
+    contained: Vec<Vec<usize>>,
+    /// the triangles incident to each site, including the dead ones
+    incident: Vec<Vec<usize>>,
+    removed: Vec<bool>,
+    fixed: Vec<bool>,
+    max_error: Elevation,
+}
+
+impl<'a> Decimator<'a> {
+    fn new(sites: &'a [Site2D], elevations: &'a [Elevation], triangles: &[[usize; 3]]) -> Self {
+        let num = sites.len();
+        let triangles = triangles
+            .iter()
+            .map(|&[a, b, c]| {
+                if orientation(&sites[a], &sites[b], &sites[c]) < 0.0 {
+                    [a, c, b]
+                } else {
+                    [a, b, c]
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut incident = vec![Vec::new(); num];
+        let mut edge_counts = std::collections::HashMap::new();
+        triangles.iter().enumerate().for_each(|(t, triangle)| {
+            (0..3).for_each(|k| {
+                let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+                incident[a].push(t);
+                *edge_counts.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            });
+        });
+        // the sites on the boundary edges, which belong to only one triangle
+        let mut fixed = vec![false; num];
+        edge_counts
+            .iter()
+            .filter(|(_, &count)| count == 1)
+            .for_each(|(&(a, b), _)| {
+                fixed[a] = true;
+                fixed[b] = true;
+            });
+        // the sites not in any triangle cannot be decimated
+        (0..num)
+            .filter(|&i| incident[i].is_empty())
+            .for_each(|i| fixed[i] = true);
+
+        Self {
+            sites,
+            elevations,
+            alive: vec![true; triangles.len()],
+            contained: vec![Vec::new(); triangles.len()],
+            triangles,
+            incident,
+            removed: vec![false; num],
+            fixed,
+            max_error: 0.0,
+        }
+    }
+
+    /// Collapse the edges until no collapse keeps the error within `tolerance`.
+    fn decimate(&mut self, tolerance: Elevation) {
+        loop {
+            let mut collapsed = false;
+            (0..self.sites.len()).for_each(|u| {
+                if self.removed[u] || self.fixed[u] {
+                    return;
+                }
+                let best = self
+                    .neighbors(u)
+                    .into_iter()
+                    .filter_map(|v| self.evaluate(u, v).map(|(error, _)| (error, v)))
+                    .filter(|&(error, _)| error <= tolerance)
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+                if let Some((_, v)) = best {
+                    self.collapse(u, v);
+                    collapsed = true;
+                }
+            });
+            if !collapsed {
+                break;
+            }
+        }
+    }
+
+    fn star(&self, u: usize) -> Vec<usize> {
+        self.incident[u]
+            .iter()
+            .copied()
+            .filter(|&t| self.alive[t] && self.triangles[t].contains(&u))
+            .collect()
+    }
+
+    fn neighbors(&self, u: usize) -> Vec<usize> {
+        let mut neighbors = self
+            .star(u)
+            .iter()
+            .flat_map(|&t| self.triangles[t])
+            .filter(|&v| v != u)
+            .collect::<Vec<_>>();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+
+    /// The triangles after collapsing `u` into `v` and the location of the removed sites in them,
+    /// or `None` if the collapse folds the mesh.
+    #[allow(clippy::type_complexity)]
+    fn evaluate(&self, u: usize, v: usize) -> Option<(Elevation, Vec<([usize; 3], Vec<usize>)>)> {
+        let star = self.star(u);
+        let mut new_triangles = star
+            .iter()
+            .map(|&t| self.triangles[t])
+            .filter(|triangle| !triangle.contains(&v))
+            .map(|triangle| {
+                let triangle = triangle.map(|i| if i == u { v } else { i });
+                let [a, b, c] = triangle.map(|i| &self.sites[i]);
+                if orientation(a, b, c) > 0.0 {
+                    Some((triangle, Vec::new()))
+                } else {
+                    None
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut error: Elevation = 0.0;
+        let points = star
+            .iter()
+            .flat_map(|&t| self.contained[t].iter().copied())
+            .chain(std::iter::once(u));
+        for p in points {
+            let site = &self.sites[p];
+            let (k, elevation) = new_triangles
+                .iter()
+                .enumerate()
+                .find_map(|(k, (triangle, _))| self.interpolate(triangle, site).map(|e| (k, e)))?;
+            error = error.max((elevation - self.elevations[p]).abs());
+            new_triangles[k].1.push(p);
+        }
+        Some((error.max(self.max_error), new_triangles))
+    }
+
+    /// The linear interpolation of the elevation in the triangle, or `None` if the site is outside.
+    fn interpolate(&self, triangle: &[usize; 3], site: &Site2D) -> Option<Elevation> {
+        let [a, b, c] = triangle.map(|i| &self.sites[i]);
+        let area = orientation(a, b, c);
+        let wa = orientation(site, b, c) / area;
+        let wb = orientation(a, site, c) / area;
+        let wc = 1.0 - wa - wb;
+        let eps = -1e-9;
+        if wa < eps || wb < eps || wc < eps {
+            return None;
+        }
+        let [za, zb, zc] = triangle.map(|i| self.elevations[i]);
+        Some(wa * za + wb * zb + wc * zc)
+    }
+
+    fn collapse(&mut self, u: usize, v: usize) {
+        let Some((error, new_triangles)) = self.evaluate(u, v) else {
+            return;
+        };
+        self.star(u).iter().for_each(|&t| self.alive[t] = false);
+        new_triangles.into_iter().for_each(|(triangle, contained)| {
+            let t = self.triangles.len();
+            self.triangles.push(triangle);
+            self.alive.push(true);
+            self.contained.push(contained);
+            triangle.iter().for_each(|&i| self.incident[i].push(t));
+        });
+        self.removed[u] = true;
+        self.max_error = error;
+    }
+
+    fn mesh(&self) -> TerrainMesh2D {
+        let mut index_of = vec![None; self.sites.len()];
+        let mut sites = Vec::new();
+        let indices = (0..self.triangles.len())
+            .filter(|&t| self.alive[t])
+            .flat_map(|t| self.triangles[t])
+            .map(|i| {
+                *index_of[i].get_or_insert_with(|| {
+                    sites.push(i);
+                    sites.len() as u32 - 1
+                })
+            })
+            .collect();
+        let vertices = sites
+            .iter()
+            .map(|&i| [self.sites[i].x, self.sites[i].y, self.elevations[i]])
+            .collect();
+        TerrainMesh2D {
+            vertices,
+            indices,
+            sites,
+            max_error: self.max_error,
+        }
+    }
+}
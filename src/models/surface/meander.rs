@@ -0,0 +1,305 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::core::{
+    traits::Site,
+    units::{Elevation, Length},
+};
+
+use super::{river::River2D, sites::Site2D, terrain::Terrain2D};
+
+/// The number of points of the centerline per wavelength.
+const POINTS_PER_WAVELENGTH: usize = 16;
+
+/// A meandering channel generated from a reach of a river.
+///
+/// ### Properties
+///  - `river` is the index of the original reach in the list passed to [MeanderGenerator2D::generate].
+///  - `centerline` is the displaced centerline of the channel from upstream to downstream.
+///  - `oxbows` is the centerlines of the loops cut off from the channel (oxbow lakes).
+#[derive(Debug, Clone)]
+pub struct MeanderedRiver2D {
+    river: usize,
+    centerline: Vec<Site2D>,
+    oxbows: Vec<Vec<Site2D>>,
+}
+
+impl MeanderedRiver2D {
+    pub fn river(&self) -> usize {
+        self.river
+    }
+
+    pub fn centerline(&self) -> &[Site2D] {
+        &self.centerline
+    }
+
+    pub fn oxbows(&self) -> &[Vec<Site2D>] {
+        &self.oxbows
+    }
+
+    /// The ratio of the length of the centerline to the distance between its ends.
+    pub fn sinuosity(&self) -> f64 {
+        let length = polyline_length(&self.centerline);
+        match (self.centerline.first(), self.centerline.last()) {
+            (Some(first), Some(last)) if first.distance(last) > 0.0 => {
+                length / first.distance(last)
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+/// Provides a post-process generating meandering centerlines for the low-gradient reaches of rivers.
+///
+/// The centerline of each reach whose mean gradient is below `max_gradient` is displaced sideways by a sine wave
+/// whose wavelength varies randomly by `wavelength_variation`. The amplitude is tapered to zero at both ends of the reach
+/// so that the channel stays connected to the network. If `cutoff_distance` is set, the loops whose necks are narrower than it
+/// are cut off from the channel and returned as oxbows.
+///
+/// The meandering channels can also be carved into the elevations of the terrain with [MeanderGenerator2D::carve].
+///
+/// ### Properties
+///  - `wavelength` is the mean wavelength of the meanders (unit: L). The default value is 10.0.
+///  - `wavelength_variation` is the relative random variation of the wavelength. The default value is 0.2.
+///  - `amplitude` is the amplitude of the meanders (unit: L). The default value is 3.0.
+///  - `max_gradient` is the maximum mean gradient of the reaches to meander. The default value is 0.05.
+///  - `cutoff_distance` is the width of the neck below which the loops are cut off (unit: L). If `None`, no loops are cut off.
+///  - `channel_width` is the width of the carved channel (unit: L). The default value is 1.0.
+///  - `channel_depth` is the depth of the carved channel (unit: L). The default value is 0.1.
+///  - `seed` is the seed of the random numbers. The default value is 0.
+#[derive(Debug, Clone)]
+pub struct MeanderGenerator2D {
+    wavelength: Length,
+    wavelength_variation: f64,
+    amplitude: Length,
+    max_gradient: f64,
+    cutoff_distance: Option<Length>,
+    channel_width: Length,
+    channel_depth: Length,
+    seed: u64,
+}
+
+impl Default for MeanderGenerator2D {
+    fn default() -> Self {
+        Self {
+            wavelength: 10.0,
+            wavelength_variation: 0.2,
+            amplitude: 3.0,
+            max_gradient: 0.05,
+            cutoff_distance: None,
+            channel_width: 1.0,
+            channel_depth: 0.1,
+            seed: 0,
+        }
+    }
+}
+
+impl MeanderGenerator2D {
+    pub fn set_wavelength(mut self, wavelength: Length) -> Self {
+        self.wavelength = wavelength;
+        self
+    }
+
+    pub fn set_wavelength_variation(mut self, wavelength_variation: f64) -> Self {
+        self.wavelength_variation = wavelength_variation.clamp(0.0, 0.9);
+        self
+    }
+
+    pub fn set_amplitude(mut self, amplitude: Length) -> Self {
+        self.amplitude = amplitude;
+        self
+    }
+
+    pub fn set_max_gradient(mut self, max_gradient: f64) -> Self {
+        self.max_gradient = max_gradient;
+        self
+    }
+
+    pub fn set_cutoff_distance(mut self, cutoff_distance: Option<Length>) -> Self {
+        self.cutoff_distance = cutoff_distance;
+        self
+    }
+
+    pub fn set_channel_width(mut self, channel_width: Length) -> Self {
+        self.channel_width = channel_width;
+        self
+    }
+
+    pub fn set_channel_depth(mut self, channel_depth: Length) -> Self {
+        self.channel_depth = channel_depth;
+        self
+    }
+
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Generate the meandering channels for the low-gradient reaches among `rivers`.
+    pub fn generate(&self, rivers: &[River2D]) -> Vec<MeanderedRiver2D> {
+        if self.wavelength <= 0.0 {
+            return Vec::new();
+        }
+        let mut rng: StdRng = SeedableRng::seed_from_u64(self.seed);
+        rivers
+            .iter()
+            .enumerate()
+            .filter(|(_, river)| river.gradient() < self.max_gradient)
+            .map(|(k, river)| {
+                let centerline = self.displace(river.points(), &mut rng);
+                let (centerline, oxbows) = self.cut_off_loops(centerline);
+                MeanderedRiver2D {
+                    river: k,
+                    centerline,
+                    oxbows,
+                }
+            })
+            .collect()
+    }
+
+    /// Carve the meandering channels into the elevations of the terrain, and return the modified elevations.
+    ///
+    /// The sites within the half of `channel_width` from the centerlines are lowered by up to `channel_depth` with a parabolic cross section.
+    pub fn carve(&self, terrain: &Terrain2D, meanders: &[MeanderedRiver2D]) -> Vec<Elevation> {
+        let half_width = self.channel_width * 0.5;
+        terrain
+            .sites()
+            .iter()
+            .zip(terrain.elevations().iter())
+            .map(|(site, &elevation)| {
+                let distance = meanders
+                    .iter()
+                    .map(|meander| distance_to_polyline(site, &meander.centerline))
+                    .fold(f64::INFINITY, f64::min);
+                if half_width > 0.0 && distance < half_width {
+                    let r = distance / half_width;
+                    elevation - self.channel_depth * (1.0 - r * r)
+                } else {
+                    elevation
+                }
+            })
+            .collect()
+    }
+
+    /// Displace the polyline sideways by the sine wave.
+    fn displace(&self, points: &[Site2D], rng: &mut StdRng) -> Vec<Site2D> {
+        let length = polyline_length(points);
+        let spacing = self.wavelength / POINTS_PER_WAVELENGTH as f64;
+        let num_points = ((length / spacing).ceil() as usize).max(1);
+        let resampled = (0..=num_points)
+            .map(|k| point_at(points, length * k as f64 / num_points as f64))
+            .collect::<Vec<_>>();
+
+        let mut phase = rng.gen::<f64>() * std::f64::consts::TAU;
+        let mut wavenumber = self.random_wavenumber(rng);
+        (0..=num_points)
+            .map(|k| {
+                let s = length * k as f64 / num_points as f64;
+                let prev = resampled[k.saturating_sub(1)];
+                let next = resampled[(k + 1).min(num_points)];
+                let (tx, ty) = (next.x - prev.x, next.y - prev.y);
+                let norm = (tx * tx + ty * ty).sqrt();
+                let (nx, ny) = if norm > 0.0 {
+                    (-ty / norm, tx / norm)
+                } else {
+                    (0.0, 0.0)
+                };
+
+                // taper the amplitude over a wavelength from both ends
+                let taper = (s.min(length - s) / self.wavelength).clamp(0.0, 1.0);
+                let offset = self.amplitude * taper * phase.sin();
+
+                let prev_phase = phase;
+                phase += wavenumber * length / num_points as f64;
+                // vary the wavelength at each crossing of the centerline
+                if (prev_phase / std::f64::consts::PI).floor()
+                    != (phase / std::f64::consts::PI).floor()
+                {
+                    wavenumber = self.random_wavenumber(rng);
+                }
+
+                let base = resampled[k];
+                Site2D::new(base.x + nx * offset, base.y + ny * offset)
+            })
+            .collect()
+    }
+
+    fn random_wavenumber(&self, rng: &mut StdRng) -> f64 {
+        let variation = (rng.gen::<f64>() * 2.0 - 1.0) * self.wavelength_variation;
+        std::f64::consts::TAU / (self.wavelength * (1.0 + variation))
+    }
+
+    /// Cut off the loops of the centerline whose necks are narrower than `cutoff_distance`, returning the remaining centerline and the loops.
+    ///
+    /// Walking down the centerline, each loop is cut at its first neck: the first later point within `cutoff_distance`
+    /// after the centerline has run at least `cutoff_distance * π` (a semicircle around the neck). The centerline is not changed
+    /// if `cutoff_distance` is not set.
+    pub fn cut_off_loops(&self, mut centerline: Vec<Site2D>) -> (Vec<Site2D>, Vec<Vec<Site2D>>) {
+        let cutoff_distance = match self.cutoff_distance {
+            Some(cutoff_distance) => cutoff_distance,
+            None => return (centerline, Vec::new()),
+        };
+        let mut oxbows = Vec::new();
+        let mut i = 0;
+        while i < centerline.len() {
+            // only the loops much longer than the neck are regarded as meander loops
+            let mut arc_length = 0.0;
+            let mut cutoff = None;
+            for j in i + 1..centerline.len() {
+                arc_length += centerline[j - 1].distance(&centerline[j]);
+                if arc_length > cutoff_distance * std::f64::consts::PI
+                    && centerline[i].distance(&centerline[j]) < cutoff_distance
+                {
+                    cutoff = Some(j);
+                    break;
+                }
+            }
+            if let Some(j) = cutoff {
+                let oxbow = centerline.drain(i + 1..j).collect::<Vec<_>>();
+                oxbows.push(oxbow);
+            }
+            i += 1;
+        }
+        (centerline, oxbows)
+    }
+}
+
+fn polyline_length(points: &[Site2D]) -> Length {
+    points
+        .windows(2)
+        .map(|segment| segment[0].distance(&segment[1]))
+        .sum()
+}
+
+/// The point at the distance `s` along the polyline.
+fn point_at(points: &[Site2D], s: Length) -> Site2D {
+    let mut remaining = s;
+    for segment in points.windows(2) {
+        let length = segment[0].distance(&segment[1]);
+        if remaining <= length && length > 0.0 {
+            let t = remaining / length;
+            return Site2D::new(
+                segment[0].x + (segment[1].x - segment[0].x) * t,
+                segment[0].y + (segment[1].y - segment[0].y) * t,
+            );
+        }
+        remaining -= length;
+    }
+    points.last().copied().unwrap_or_default()
+}
+
+fn distance_to_polyline(site: &Site2D, points: &[Site2D]) -> Length {
+    points
+        .windows(2)
+        .map(|segment| {
+            let (a, b) = (segment[0], segment[1]);
+            let (dx, dy) = (b.x - a.x, b.y - a.y);
+            let squared_length = dx * dx + dy * dy;
+            let t = if squared_length > 0.0 {
+                (((site.x - a.x) * dx + (site.y - a.y) * dy) / squared_length).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            site.distance(&Site2D::new(a.x + t * dx, a.y + t * dy))
+        })
+        .fold(f64::INFINITY, f64::min)
+}
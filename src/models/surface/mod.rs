@@ -1,7 +1,44 @@
 //! 2D surface model
+pub mod basin;
+pub mod biome;
+pub mod boundary;
 pub mod builder;
+pub mod channel;
+pub mod collider;
+pub mod diff;
+pub mod drainage_density;
+pub mod edit;
+pub mod estuary;
+pub mod fault;
+pub mod landmark;
+pub mod lod;
+pub mod meander;
 pub mod model;
+pub mod parameter_preset;
+pub mod path;
+pub mod placer;
+pub mod preset;
+pub mod quantized;
+pub mod random_field;
+pub mod raster;
+pub mod raster_writer;
+pub mod region;
+pub mod river;
+pub mod settlement;
+pub mod shoreline;
 pub mod sites;
+pub mod slope_area;
+pub mod solar;
+pub mod splat;
+pub mod surface_type;
 pub mod terrain;
+pub mod tiles;
+pub mod voxel;
+pub mod waterfall;
+pub mod wind;
 
+mod cells;
+mod index;
 mod interpolator;
+mod resample;
+mod triangulation;
@@ -1,11 +1,19 @@
+use std::sync::Arc;
+
 use terrain_graph::edge_attributed_undirected::EdgeAttributedUndirectedGraph;
 
 use crate::core::{
+    distance::DistanceField,
+    fields::SiteFields,
+    network::DrainageNetwork,
     traits::Model,
     units::{Area, Elevation, Length},
 };
 
-use super::{interpolator::TerrainInterpolator2D, sites::Site2D, terrain::Terrain2D};
+use super::{
+    boundary::BoundaryConditions2D, index::SiteIndex2D, interpolator::TerrainInterpolator2D,
+    sites::Site2D, terrain::Terrain2D,
+};
 
 /// A 2D vector representation of the terrain network.
 ///
@@ -14,28 +22,103 @@ use super::{interpolator::TerrainInterpolator2D, sites::Site2D, terrain::Terrain
 /// - `areas` is the areas of each site.
 /// - `graph` is the graph representing the conecctions between sites.
 /// - `default_outlets` is the set of indices of sites that are set as outlets by default.
+///   These are the sites on the convex hull in counterclockwise order, except the ones on the sides which are not the base level.
+/// - `hull` is the sites on the convex hull in counterclockwise order.
+/// - `triangles` is the Delaunay triangles of the sites.
+/// - `cells` is the vertices of the Voronoi cell of each site, clipped by the bounding box.
+/// - `boundary_conditions` is the conditions of the sides of the domain (see [BoundaryConditions2D]).
+/// - `bounds` is the bounding rectangle of the sites, as the pair of the minimum and the maximum corners.
 #[derive(Clone)]
 pub struct TerrainModel2D {
     sites: Vec<Site2D>,
     areas: Vec<Area>,
     graph: EdgeAttributedUndirectedGraph<Length>,
     default_outlets: Vec<usize>,
+    hull: Vec<usize>,
+    triangles: Vec<[usize; 3]>,
+    cells: Vec<Vec<Site2D>>,
+    boundary_conditions: BoundaryConditions2D,
+    bounds: (Site2D, Site2D),
+    index: Arc<SiteIndex2D>,
 }
 
 impl TerrainModel2D {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         sites: Vec<Site2D>,
         areas: Vec<Area>,
         graph: EdgeAttributedUndirectedGraph<Length>,
         default_outlets: Vec<usize>,
+        hull: Vec<usize>,
+        triangles: Vec<[usize; 3]>,
+        cells: Vec<Vec<Site2D>>,
+        boundary_conditions: BoundaryConditions2D,
+        bounds: (Site2D, Site2D),
     ) -> Self {
+        let index = Arc::new(SiteIndex2D::new(&sites));
         Self {
             sites,
             areas,
             graph,
             default_outlets,
+            hull,
+            triangles,
+            cells,
+            boundary_conditions,
+            bounds,
+            index,
         }
     }
+
+    pub(crate) fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    pub(crate) fn hull(&self) -> &[usize] {
+        &self.hull
+    }
+
+    /// The vertices of the Voronoi cell of each site, clipped by the bounding box.
+    ///
+    /// The vertices of each cell are in counterclockwise order without repeating the first vertex,
+    /// and the area of the polygon is the area of the site.
+    pub fn cells(&self) -> &[Vec<Site2D>] {
+        &self.cells
+    }
+
+    /// The vertices of the Voronoi cell of the site `i`, or `None` if the index is out of range.
+    pub fn cell(&self, i: usize) -> Option<&[Site2D]> {
+        self.cells.get(i).map(|cell| cell.as_slice())
+    }
+
+    /// The index of the nearest site to the given site, that is, the site whose Voronoi cell contains it.
+    ///
+    /// This returns `None` if the model has no sites.
+    pub fn nearest_site(&self, site: &Site2D) -> Option<usize> {
+        self.index.nearest(site)
+    }
+
+    /// The indices of the `k` nearest sites to the given site, from the nearest.
+    ///
+    /// This returns all the sites if `k` is larger than the number of the sites.
+    pub fn k_nearest(&self, site: &Site2D, k: usize) -> Vec<usize> {
+        self.index.k_nearest(site, k)
+    }
+
+    /// The distances of the sites along the edges of the graph from the nearest of `seeds` (see [DistanceField]),
+    /// up to `max_distance` (use infinity for no limit).
+    pub fn distance_field(&self, seeds: &[usize], max_distance: Length) -> DistanceField {
+        DistanceField::new(&self.graph, seeds, max_distance)
+    }
+
+    pub fn boundary_conditions(&self) -> &BoundaryConditions2D {
+        &self.boundary_conditions
+    }
+
+    /// The bounding rectangle of the sites, as the pair of the minimum and the maximum corners.
+    pub fn bounds(&self) -> (Site2D, Site2D) {
+        self.bounds
+    }
 }
 
 impl Model<Site2D, Terrain2D> for TerrainModel2D {
@@ -65,5 +148,17 @@ impl Model<Site2D, Terrain2D> for TerrainModel2D {
             elevations.to_vec(),
             TerrainInterpolator2D::new(&self.sites),
         )
+        .set_triangles(self.triangles.clone())
+    }
+
+    fn create_terrain_from_output(
+        &self,
+        elevations: &[Elevation],
+        fields: &SiteFields,
+        network: &DrainageNetwork,
+    ) -> Terrain2D {
+        self.create_terrain_from_result(elevations)
+            .set_fields(fields.clone())
+            .set_network(network.clone())
     }
 }
@@ -0,0 +1,164 @@
+use crate::core::{
+    parameters::{ParameterField, TopographicalParameters},
+    traits::Site,
+    units::{Erodibility, Slope, UpliftRate},
+};
+
+use super::sites::Site2D;
+
+/// The kinds of the landscapes of [ParameterPreset2D].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LandscapeKind {
+    /// An old and stable continental interior: slow uplift, resistant rocks and gentle slopes.
+    Craton,
+    /// A young mountain range: rapid uplift along a ridge through the center of the domain, and steep slopes.
+    YoungOrogen,
+    /// A badlands: soft, highly erodible rocks dissected into dense networks of gullies with steep walls.
+    Badlands,
+    /// A volcanic island: the uplift of a cone at the center of the domain, surrounded by the ocean.
+    VolcanicIsland,
+}
+
+/// The curated parameter fields of the typical landscapes, to get good-looking terrains on the first run.
+///
+/// Each preset is a [ParameterField] which can be passed to `TerrainGenerator::set_parameter_field`,
+/// and its values can be tweaked by the setters after choosing the kind of the landscape.
+/// The spatial patterns (the ridge of the orogen and the cone of the island) are placed relative to the bounding box,
+/// which should be the one of the model.
+///
+/// | kind | uplift rate | erodibility | maximum slope |
+/// | --- | --- | --- | --- |
+/// | [LandscapeKind::Craton] | 0.2 | 0.5 | 15° |
+/// | [LandscapeKind::YoungOrogen] | 0.5 to 2.5 at the ridge | 1.0 | 40° |
+/// | [LandscapeKind::Badlands] | 1.0 | 4.0 | 60° |
+/// | [LandscapeKind::VolcanicIsland] | 0.0 to 3.0 at the summit | 0.6 | 35° |
+///
+/// ### Properties
+///  - `kind` is the kind of the landscape (see [LandscapeKind]).
+///  - `bound_min` and `bound_max` are the bounding rectangle of the model. The default value is from (0, 0) to (100, 100).
+///  - `uplift_rate` is the uplift rate (unit: L/T), the maximum one for the orogen and the island. The default value depends on the kind.
+///  - `erodibility` is the erodibility. The default value depends on the kind.
+///  - `max_slope` is the maximum slope (unit: rad). The default value depends on the kind.
+#[derive(Debug, Clone)]
+pub struct ParameterPreset2D {
+    kind: LandscapeKind,
+    bound_min: Site2D,
+    bound_max: Site2D,
+    uplift_rate: UpliftRate,
+    erodibility: Erodibility,
+    max_slope: Option<Slope>,
+}
+
+/// The ratio of the uplift rate of the foreland to the one at the ridge of the orogen.
+const FORELAND_UPLIFT_RATIO: f64 = 0.2;
+
+/// The half width of the ridge of the orogen relative to the height of the domain.
+const RIDGE_WIDTH_RATIO: f64 = 0.25;
+
+/// The radius of the volcanic island relative to the half of the shorter side of the domain.
+const ISLAND_RADIUS_RATIO: f64 = 0.7;
+
+impl ParameterPreset2D {
+    /// Create the preset of the kind of the landscape with its default values.
+    pub fn new(kind: LandscapeKind) -> Self {
+        let (uplift_rate, erodibility, max_slope_degrees) = match kind {
+            LandscapeKind::Craton => (0.2, 0.5, 15.0_f64),
+            LandscapeKind::YoungOrogen => (2.5, 1.0, 40.0),
+            LandscapeKind::Badlands => (1.0, 4.0, 60.0),
+            LandscapeKind::VolcanicIsland => (3.0, 0.6, 35.0),
+        };
+        Self {
+            kind,
+            bound_min: Site2D::new(0.0, 0.0),
+            bound_max: Site2D::new(100.0, 100.0),
+            uplift_rate,
+            erodibility,
+            max_slope: Some(max_slope_degrees.to_radians()),
+        }
+    }
+
+    pub fn craton() -> Self {
+        Self::new(LandscapeKind::Craton)
+    }
+
+    pub fn young_orogen() -> Self {
+        Self::new(LandscapeKind::YoungOrogen)
+    }
+
+    pub fn badlands() -> Self {
+        Self::new(LandscapeKind::Badlands)
+    }
+
+    pub fn volcanic_island() -> Self {
+        Self::new(LandscapeKind::VolcanicIsland)
+    }
+
+    pub fn set_bounding_box(mut self, bound_min: Site2D, bound_max: Site2D) -> Self {
+        self.bound_min = bound_min;
+        self.bound_max = bound_max;
+        self
+    }
+
+    pub fn set_uplift_rate(mut self, uplift_rate: UpliftRate) -> Self {
+        self.uplift_rate = uplift_rate;
+        self
+    }
+
+    pub fn set_erodibility(mut self, erodibility: Erodibility) -> Self {
+        self.erodibility = erodibility;
+        self
+    }
+
+    pub fn set_max_slope(mut self, max_slope: Option<Slope>) -> Self {
+        self.max_slope = max_slope;
+        self
+    }
+
+    pub fn kind(&self) -> LandscapeKind {
+        self.kind
+    }
+
+    /// The parameters of the site.
+    pub fn parameters_at(&self, site: &Site2D) -> TopographicalParameters {
+        let center = Site2D::new(
+            (self.bound_min.x + self.bound_max.x) * 0.5,
+            (self.bound_min.y + self.bound_max.y) * 0.5,
+        );
+        let parameters = TopographicalParameters::default()
+            .set_erodibility(self.erodibility)
+            .set_max_slope(self.max_slope);
+        match self.kind {
+            LandscapeKind::Craton | LandscapeKind::Badlands => {
+                parameters.set_uplift_rate(self.uplift_rate)
+            }
+            LandscapeKind::YoungOrogen => {
+                // the uplift decays from the ridge along the x axis to the foreland
+                let width = (self.bound_max.y - self.bound_min.y) * RIDGE_WIDTH_RATIO;
+                let distance = (site.y - center.y).abs() / width.max(f64::EPSILON);
+                let ridge = (-distance * distance).exp();
+                parameters.set_uplift_rate(
+                    self.uplift_rate
+                        * (FORELAND_UPLIFT_RATIO + (1.0 - FORELAND_UPLIFT_RATIO) * ridge),
+                )
+            }
+            LandscapeKind::VolcanicIsland => {
+                let radius = (self.bound_max.x - self.bound_min.x)
+                    .min(self.bound_max.y - self.bound_min.y)
+                    * 0.5
+                    * ISLAND_RADIUS_RATIO;
+                let factor = 1.0 - site.distance(&center) / radius.max(f64::EPSILON);
+                if factor > 0.0 {
+                    parameters.set_uplift_rate(self.uplift_rate * factor)
+                } else {
+                    parameters.set_is_outlet(true)
+                }
+            }
+        }
+    }
+}
+
+impl ParameterField<Site2D> for ParameterPreset2D {
+    fn at(&self, _index: usize, site: &Site2D) -> TopographicalParameters {
+        self.parameters_at(site)
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::BinaryHeap;
+
+use crate::core::{
+    distance::QueueItem,
+    traits::{Model, Site},
+    units::{Area, Elevation, Length},
+};
+
+use super::{model::TerrainModel2D, sites::Site2D, terrain::Terrain2D};
+
+/// A least-cost path over the sites, such as a road or a trail.
+///
+/// ### Properties
+///  - `sites` is the indices of the sites along the path from the start to the goal.
+///  - `points` is the positions of the sites.
+///  - `cost` is the total cost of the path (see [PathFinder2D]).
+#[derive(Debug, Clone)]
+pub struct Path2D {
+    pub sites: Vec<usize>,
+    pub points: Vec<Site2D>,
+    pub cost: f64,
+}
+
+impl Path2D {
+    /// The length of the path along its sites.
+    pub fn length(&self) -> Length {
+        self.points
+            .windows(2)
+            .map(|segment| segment[0].distance(&segment[1]))
+            .sum()
+    }
+}
+
+/// Provides a routing of least-cost paths over the graph of the sites, respecting the slopes and the rivers of the terrain (A* search).
+///
+/// The cost of moving along an edge of the length `l` with the slope `s` (the difference of the elevations divided by `l`,
+/// either uphill or downhill) is `l * (1 + slope_weight * s)`. The edges steeper than `max_slope` are impassable,
+/// and so are the sites below `sea_level`. Moving into a river site, whose drainage area is not less than `min_drainage_area`,
+/// costs `river_crossing_cost` additionally, unless the edge follows the channel (one end is the receiver of the other).
+///
+/// ### Properties
+///  - `slope_weight` is the weight of the slope on the cost. The default value is 10.0.
+///  - `max_slope` is the slope above which the edges are impassable. If `None`, all the slopes are passable.
+///  - `sea_level` is the elevation below which the sites are impassable (unit: L). If `None`, all the sites are passable.
+///  - `min_drainage_area` is the drainage area above which the sites are rivers (unit: L^2). The default value is 100.0.
+///  - `river_crossing_cost` is the additional cost of crossing a river. The default value is 10.0.
+#[derive(Debug, Clone)]
+pub struct PathFinder2D {
+    slope_weight: f64,
+    max_slope: Option<f64>,
+    sea_level: Option<Elevation>,
+    min_drainage_area: Area,
+    river_crossing_cost: f64,
+}
+
+impl Default for PathFinder2D {
+    fn default() -> Self {
+        Self {
+            slope_weight: 10.0,
+            max_slope: None,
+            sea_level: None,
+            min_drainage_area: 100.0,
+            river_crossing_cost: 10.0,
+        }
+    }
+}
+
+impl PathFinder2D {
+    pub fn set_slope_weight(mut self, slope_weight: f64) -> Self {
+        self.slope_weight = slope_weight.max(0.0);
+        self
+    }
+
+    pub fn set_max_slope(mut self, max_slope: Option<f64>) -> Self {
+        self.max_slope = max_slope;
+        self
+    }
+
+    pub fn set_sea_level(mut self, sea_level: Option<Elevation>) -> Self {
+        self.sea_level = sea_level;
+        self
+    }
+
+    pub fn set_min_drainage_area(mut self, min_drainage_area: Area) -> Self {
+        self.min_drainage_area = min_drainage_area;
+        self
+    }
+
+    pub fn set_river_crossing_cost(mut self, river_crossing_cost: f64) -> Self {
+        self.river_crossing_cost = river_crossing_cost.max(0.0);
+        self
+    }
+
+    /// Find the least-cost path from the site `start` to the site `goal` over the terrain generated from the model.
+    ///
+    /// This returns `None` if the goal is unreachable, either of the sites is out of range,
+    /// or the terrain does not have the same sites as the model.
+    pub fn find_path(
+        &self,
+        model: &TerrainModel2D,
+        terrain: &Terrain2D,
+        start: usize,
+        goal: usize,
+    ) -> Option<Path2D> {
+        let num = model.num();
+        let sites = model.sites();
+        let elevations = terrain.elevations();
+        if start >= num || goal >= num || elevations.len() != num {
+            return None;
+        }
+        let is_passable = |i: usize| self.sea_level.is_none_or(|sea| elevations[i] >= sea);
+        if !is_passable(start) || !is_passable(goal) {
+            return None;
+        }
+        let network = terrain.network();
+        let is_river =
+            |i: usize| !network.is_empty() && network.drainage_areas()[i] >= self.min_drainage_area;
+        let follows_channel = |i: usize, j: usize| {
+            !network.is_empty() && (network.receivers()[i] == j || network.receivers()[j] == i)
+        };
+        let edge_cost = |i: usize, j: usize, length: Length| {
+            let slope = if length > 0.0 {
+                (elevations[j] - elevations[i]).abs() / length
+            } else {
+                0.0
+            };
+            if self.max_slope.is_some_and(|max_slope| slope > max_slope) {
+                return None;
+            }
+            let crossing = if is_river(j) && !(is_river(i) && follows_channel(i, j)) {
+                self.river_crossing_cost
+            } else {
+                0.0
+            };
+            Some(length * (1.0 + self.slope_weight * slope) + crossing)
+        };
+        // the straight distance never overestimates the cost, since each edge costs at least its length
+        let heuristic = |i: usize| sites[i].distance(&sites[goal]);
+
+        let mut costs = vec![f64::INFINITY; num];
+        let mut previous = vec![usize::MAX; num];
+        let mut queue = BinaryHeap::new();
+        costs[start] = 0.0;
+        queue.push(QueueItem(start, heuristic(start)));
+        while let Some(QueueItem(i, estimate)) = queue.pop() {
+            if i == goal {
+                break;
+            }
+            if estimate > costs[i] + heuristic(i) {
+                continue;
+            }
+            model.graph().neighbors_of(i).iter().for_each(|ja| {
+                if !is_passable(ja.0) {
+                    return;
+                }
+                let cost = match edge_cost(i, ja.0, ja.1) {
+                    Some(cost) => costs[i] + cost,
+                    None => return,
+                };
+                if cost < costs[ja.0] {
+                    costs[ja.0] = cost;
+                    previous[ja.0] = i;
+                    queue.push(QueueItem(ja.0, cost + heuristic(ja.0)));
+                }
+            });
+        }
+        if !costs[goal].is_finite() {
+            return None;
+        }
+
+        let mut path = vec![goal];
+        while let Some(&i) = path.last() {
+            if i == start {
+                break;
+            }
+            path.push(previous[i]);
+        }
+        path.reverse();
+        Some(Path2D {
+            points: path.iter().map(|&i| sites[i]).collect(),
+            sites: path,
+            cost: costs[goal],
+        })
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    core::{
+        fields::{DEPOSIT_THICKNESS, SEDIMENT_FLUX},
+        units::Area,
+    },
+    lem::processes::deposition::DepositionProcess,
+};
+
+use super::{sites::Site2D, terrain::Terrain2D};
+
+/// The kind of a placer deposit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacerKind {
+    /// On the inner side of a bend of a channel, where the flow slows down.
+    PointBar,
+    /// On the sediment deposited where a channel loses its transport capacity.
+    AlluvialFan,
+    /// At the foot of a knickpoint, where the slope of a channel abruptly decreases.
+    KnickpointLag,
+}
+
+/// A proposed location of a placer deposit.
+///
+/// ### Properties
+///  - `kind` is the kind of the deposit.
+///  - `site` is the index of the site of the deposit.
+///  - `position` is the position of the site.
+///  - `density` is the relative density of the resource, from 0.0 to 1.0.
+#[derive(Debug, Clone)]
+pub struct PlacerDeposit2D {
+    pub kind: PlacerKind,
+    pub site: usize,
+    pub position: Site2D,
+    pub density: f64,
+}
+
+/// Provides a proposal of the locations of placer deposits, the heavy minerals concentrated by the flow, from the history of the simulation.
+///
+/// The density of each deposit is the product of its grade and its trapping.
+/// The grade is the fraction of the sediment coming from `source_region`, taken from the fields of the provenance
+/// attached by [DepositionProcess] with the source regions. If `source_region` is `None`, the grade is 1.0.
+/// The trapping is:
+///  - for alluvial fans, the thickness of the deposits ([DEPOSIT_THICKNESS]) relative to the thickest deposit.
+///  - for point bars, `(1 - cos θ) / 2` where θ is the deviation of the channel at the bend, multiplied by the relative sediment flux.
+///  - for knickpoint lags, `1 - S_d / S_u` where `S_u` and `S_d` are the slopes upstream and downstream, multiplied by the relative sediment flux.
+///
+/// The sediment flux is taken from the field [SEDIMENT_FLUX], or the drainage area is used instead if the terrain does not have the field.
+///
+/// ### Properties
+///  - `min_drainage_area` is the drainage area above which the sites are regarded as channels (unit: L^2). The default value is 100.0.
+///  - `min_bend_angle` is the minimum deviation of the channel at point bars (unit: rad). The default value is π/4.
+///  - `knickpoint_ratio` is the minimum ratio of the slope upstream to the slope downstream at knickpoints. The default value is 3.0.
+///  - `source_region` is the index of the source region bearing the resource. The default value is `None`.
+///  - `min_density` is the density below which the deposits are discarded. The default value is 0.01.
+#[derive(Debug, Clone)]
+pub struct PlacerDetector2D {
+    min_drainage_area: Area,
+    min_bend_angle: f64,
+    knickpoint_ratio: f64,
+    source_region: Option<usize>,
+    min_density: f64,
+}
+
+impl Default for PlacerDetector2D {
+    fn default() -> Self {
+        Self {
+            min_drainage_area: 100.0,
+            min_bend_angle: std::f64::consts::FRAC_PI_4,
+            knickpoint_ratio: 3.0,
+            source_region: None,
+            min_density: 0.01,
+        }
+    }
+}
+
+impl PlacerDetector2D {
+    pub fn set_min_drainage_area(mut self, min_drainage_area: Area) -> Self {
+        self.min_drainage_area = min_drainage_area;
+        self
+    }
+
+    pub fn set_min_bend_angle(mut self, min_bend_angle: f64) -> Self {
+        self.min_bend_angle = min_bend_angle;
+        self
+    }
+
+    pub fn set_knickpoint_ratio(mut self, knickpoint_ratio: f64) -> Self {
+        self.knickpoint_ratio = knickpoint_ratio;
+        self
+    }
+
+    pub fn set_source_region(mut self, source_region: Option<usize>) -> Self {
+        self.source_region = source_region;
+        self
+    }
+
+    pub fn set_min_density(mut self, min_density: f64) -> Self {
+        self.min_density = min_density;
+        self
+    }
+
+    /// Propose the placer deposits on the terrain, sorted from the densest.
+    ///
+    /// This returns an empty list if the terrain has no drainage network.
+    pub fn detect(&self, terrain: &Terrain2D) -> Vec<PlacerDeposit2D> {
+        let network = terrain.network();
+        if network.is_empty() {
+            return Vec::new();
+        }
+        let sites = terrain.sites();
+        let elevations = terrain.elevations();
+        let receivers = network.receivers();
+        let drainage_areas = network.drainage_areas();
+        let donors = network.donors();
+        let fields = terrain.fields();
+
+        let ratio = |numerator: f64, denominator: f64| {
+            if denominator > 0.0 && numerator.is_finite() {
+                (numerator / denominator).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+        let max_of = |values: &[f64]| {
+            values
+                .iter()
+                .filter(|v| v.is_finite())
+                .fold(0.0_f64, |max, &v| max.max(v))
+        };
+
+        let fluxes = fields.get(SEDIMENT_FLUX).unwrap_or(drainage_areas);
+        let max_flux = max_of(fluxes);
+        let (source_deposits, source_fluxes) = match self.source_region {
+            Some(region) => (
+                fields.get(&DepositionProcess::provenance_field(region)),
+                fields.get(&DepositionProcess::sediment_flux_field(region)),
+            ),
+            None => (None, None),
+        };
+        let flux_grade = |i: usize| match (self.source_region, source_fluxes) {
+            (None, _) => 1.0,
+            (Some(_), Some(source_fluxes)) => ratio(source_fluxes[i], fluxes[i]),
+            (Some(_), None) => 0.0,
+        };
+
+        let mut deposits = Vec::new();
+
+        // alluvial fans
+        if let Some(thickness) = fields.get(DEPOSIT_THICKNESS) {
+            let max_thickness = max_of(thickness);
+            (0..sites.len())
+                .filter(|&i| thickness[i] > 0.0)
+                .for_each(|i| {
+                    let grade = match (self.source_region, source_deposits) {
+                        (None, _) => 1.0,
+                        (Some(_), Some(source_deposits)) => ratio(source_deposits[i], thickness[i]),
+                        (Some(_), None) => 0.0,
+                    };
+                    deposits.push((
+                        PlacerKind::AlluvialFan,
+                        i,
+                        grade * ratio(thickness[i], max_thickness),
+                    ));
+                });
+        }
+
+        // point bars and knickpoint lags
+        let min_cos = self.min_bend_angle.cos();
+        (0..sites.len())
+            .filter(|&i| drainage_areas[i] >= self.min_drainage_area && receivers[i] != i)
+            .for_each(|i| {
+                // the main channel upstream
+                let upstream = donors[i]
+                    .iter()
+                    .filter(|&&k| drainage_areas[k] >= self.min_drainage_area)
+                    .max_by(|&&a, &&b| drainage_areas[a].total_cmp(&drainage_areas[b]));
+                let upstream = match upstream {
+                    Some(&k) => k,
+                    None => return,
+                };
+                let j = receivers[i];
+                let (ux, uy) = (
+                    sites[i].x - sites[upstream].x,
+                    sites[i].y - sites[upstream].y,
+                );
+                let (dx, dy) = (sites[j].x - sites[i].x, sites[j].y - sites[i].y);
+                let (lu, ld) = ((ux * ux + uy * uy).sqrt(), (dx * dx + dy * dy).sqrt());
+                if lu <= 0.0 || ld <= 0.0 {
+                    return;
+                }
+                let relative_flux = ratio(fluxes[i], max_flux);
+
+                let cos = (ux * dx + uy * dy) / (lu * ld);
+                if cos <= min_cos {
+                    deposits.push((
+                        PlacerKind::PointBar,
+                        i,
+                        flux_grade(i) * (1.0 - cos) / 2.0 * relative_flux,
+                    ));
+                }
+
+                let upstream_slope = (elevations[upstream] - elevations[i]) / lu;
+                let downstream_slope = ((elevations[i] - elevations[j]) / ld).max(0.0);
+                if upstream_slope > 0.0
+                    && upstream_slope >= self.knickpoint_ratio * downstream_slope
+                {
+                    deposits.push((
+                        PlacerKind::KnickpointLag,
+                        i,
+                        flux_grade(i) * (1.0 - downstream_slope / upstream_slope) * relative_flux,
+                    ));
+                }
+            });
+
+        let mut deposits = deposits
+            .into_iter()
+            .filter(|&(_, _, density)| density >= self.min_density && density > 0.0)
+            .map(|(kind, site, density)| PlacerDeposit2D {
+                kind,
+                site,
+                position: sites[site],
+                density,
+            })
+            .collect::<Vec<_>>();
+        deposits.sort_by(|a, b| b.density.total_cmp(&a.density));
+        deposits
+    }
+}
@@ -0,0 +1,476 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use thiserror::Error;
+
+use crate::{
+    core::{
+        parameters::TopographicalParameters,
+        traits::{Model, Site},
+        units::{Erodibility, Length, Step, UpliftRate},
+    },
+    lem::generator::{GenerationError, TerrainGenerator},
+};
+
+use super::{
+    builder::{ModelBuilderError, TerrainModel2DBulider},
+    model::TerrainModel2D,
+    sites::Site2D,
+    terrain::Terrain2D,
+};
+
+#[derive(Error, Debug)]
+pub enum PresetError {
+    #[error("Failed to build the model: {0}")]
+    ModelBuilder(#[from] ModelBuilderError),
+    #[error("Failed to generate the terrain: {0}")]
+    Generation(#[from] GenerationError),
+}
+
+/// The number of the harmonics of the random perturbation of coastlines.
+const NUM_COAST_HARMONICS: usize = 6;
+
+/// Build a model of randomly distributed sites with the sites along the bounding box.
+fn build_model(
+    bound_min: Site2D,
+    bound_max: Site2D,
+    num: usize,
+    rng: &mut StdRng,
+) -> Result<TerrainModel2D, ModelBuilderError> {
+    let sites = (0..num)
+        .map(|_| {
+            Site2D::new(
+                rng.gen_range(bound_min.x..bound_max.x),
+                rng.gen_range(bound_min.y..bound_max.y),
+            )
+        })
+        .collect::<Vec<_>>();
+    TerrainModel2DBulider::default()
+        .set_sites(sites)
+        .set_bounding_box(Some(bound_min), Some(bound_max))
+        .relaxate_sites(1)?
+        .add_edge_sites(None, None)?
+        .build()
+}
+
+/// The mask of the sites on the boundary of the model, which are always the ocean.
+fn boundary_mask(model: &TerrainModel2D) -> Vec<bool> {
+    let mut is_boundary = vec![false; model.num()];
+    model
+        .default_outlets()
+        .iter()
+        .for_each(|&i| is_boundary[i] = true);
+    is_boundary
+}
+
+/// An island with a randomly perturbed coastline.
+struct Island {
+    center: Site2D,
+    radius: Length,
+    harmonics: Vec<(f64, f64)>,
+}
+
+impl Island {
+    /// The elevation factor of the site, 1.0 at the center and 0.0 at the coastline (negative in the ocean).
+    fn factor(&self, site: &Site2D, roughness: f64) -> f64 {
+        let angle = (site.y - self.center.y).atan2(site.x - self.center.x);
+        let perturbation = self
+            .harmonics
+            .iter()
+            .enumerate()
+            .map(|(k, &(amplitude, phase))| amplitude * ((k + 2) as f64 * angle + phase).sin())
+            .sum::<f64>();
+        let radius = self.radius * (1.0 + roughness * perturbation).max(0.1);
+        1.0 - site.distance(&self.center) / radius
+    }
+}
+
+/// Provides a one-call generation of islands and archipelagos with realistic rivers.
+///
+/// The islands are placed at random in the domain, and each of them has a coastline perturbed randomly by `coast_roughness`.
+/// The sites outside the islands are set as outlets (ocean), which stay at the elevation 0.0 up to a negligible perturbation,
+/// and the uplift rate of the land rises towards the center of each island up to `max_uplift_rate`.
+///
+/// ### Properties
+///  - `bound_min` and `bound_max` are the bounding rectangle of the domain. The default value is from (0, 0) to (100, 100).
+///  - `num_sites` is the number of the sites. The default value is 10000.
+///  - `island_count` is the number of the islands. The default value is 1.
+///  - `island_radius` is the mean radius of the islands (unit: L). The default value is 30.0.
+///  - `radius_variation` is the relative random variation of the radius. The default value is 0.3.
+///  - `coast_roughness` is the relative random perturbation of the coastlines. The default value is 0.2.
+///  - `max_uplift_rate` is the uplift rate at the center of the islands (unit: L/T). The default value is 1.0.
+///  - `erodibility` is the erodibility of the land. The default value is 1.0.
+///  - `max_iteration` is the maximum number of iterations of the generation. The default value is 100.
+///  - `seed` is the seed of the random numbers. The default value is 0.
+#[derive(Debug, Clone)]
+pub struct IslandPreset2D {
+    bound_min: Site2D,
+    bound_max: Site2D,
+    num_sites: usize,
+    island_count: usize,
+    island_radius: Length,
+    radius_variation: f64,
+    coast_roughness: f64,
+    max_uplift_rate: UpliftRate,
+    erodibility: Erodibility,
+    max_iteration: Step,
+    seed: u64,
+}
+
+impl Default for IslandPreset2D {
+    fn default() -> Self {
+        Self {
+            bound_min: Site2D::new(0.0, 0.0),
+            bound_max: Site2D::new(100.0, 100.0),
+            num_sites: 10000,
+            island_count: 1,
+            island_radius: 30.0,
+            radius_variation: 0.3,
+            coast_roughness: 0.2,
+            max_uplift_rate: 1.0,
+            erodibility: 1.0,
+            max_iteration: 100,
+            seed: 0,
+        }
+    }
+}
+
+impl IslandPreset2D {
+    pub fn set_bounding_box(mut self, bound_min: Site2D, bound_max: Site2D) -> Self {
+        self.bound_min = bound_min;
+        self.bound_max = bound_max;
+        self
+    }
+
+    pub fn set_num_sites(mut self, num_sites: usize) -> Self {
+        self.num_sites = num_sites;
+        self
+    }
+
+    pub fn set_island_count(mut self, island_count: usize) -> Self {
+        self.island_count = island_count;
+        self
+    }
+
+    pub fn set_island_radius(mut self, island_radius: Length) -> Self {
+        self.island_radius = island_radius;
+        self
+    }
+
+    pub fn set_radius_variation(mut self, radius_variation: f64) -> Self {
+        self.radius_variation = radius_variation.clamp(0.0, 0.9);
+        self
+    }
+
+    pub fn set_coast_roughness(mut self, coast_roughness: f64) -> Self {
+        self.coast_roughness = coast_roughness;
+        self
+    }
+
+    pub fn set_max_uplift_rate(mut self, max_uplift_rate: UpliftRate) -> Self {
+        self.max_uplift_rate = max_uplift_rate;
+        self
+    }
+
+    pub fn set_erodibility(mut self, erodibility: Erodibility) -> Self {
+        self.erodibility = erodibility;
+        self
+    }
+
+    pub fn set_max_iteration(mut self, max_iteration: Step) -> Self {
+        self.max_iteration = max_iteration;
+        self
+    }
+
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Build the model and the topographical parameters of the islands without generating the terrain.
+    ///
+    /// This is useful to customize the generator (e.g. adding processes) before the generation.
+    pub fn build(&self) -> Result<(TerrainModel2D, Vec<TopographicalParameters>), PresetError> {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(self.seed);
+        let model = build_model(self.bound_min, self.bound_max, self.num_sites, &mut rng)?;
+
+        // keep the islands away from the boundary so that they are surrounded by the ocean
+        let margin = Site2D::new(
+            (self.bound_max.x - self.bound_min.x) * 0.1,
+            (self.bound_max.y - self.bound_min.y) * 0.1,
+        );
+        let islands = (0..self.island_count)
+            .map(|_| Island {
+                center: Site2D::new(
+                    rng.gen_range(self.bound_min.x + margin.x..self.bound_max.x - margin.x),
+                    rng.gen_range(self.bound_min.y + margin.y..self.bound_max.y - margin.y),
+                ),
+                radius: self.island_radius
+                    * (1.0 + (rng.gen::<f64>() * 2.0 - 1.0) * self.radius_variation),
+                harmonics: (0..NUM_COAST_HARMONICS)
+                    .map(|k| {
+                        (
+                            rng.gen::<f64>() / (k + 1) as f64,
+                            rng.gen::<f64>() * std::f64::consts::TAU,
+                        )
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+
+        let is_boundary = boundary_mask(&model);
+
+        let parameters = model
+            .sites()
+            .iter()
+            .enumerate()
+            .map(|(i, site)| {
+                let factor = islands
+                    .iter()
+                    .map(|island| island.factor(site, self.coast_roughness))
+                    .fold(f64::NEG_INFINITY, f64::max);
+                if factor > 0.0 && !is_boundary[i] {
+                    TopographicalParameters::default()
+                        .set_erodibility(self.erodibility)
+                        .set_uplift_rate(self.max_uplift_rate * factor.sqrt())
+                } else {
+                    TopographicalParameters::default().set_is_outlet(true)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok((model, parameters))
+    }
+
+    /// Generate the terrain of the islands.
+    pub fn generate(&self) -> Result<Terrain2D, PresetError> {
+        let (model, parameters) = self.build()?;
+        Ok(TerrainGenerator::default()
+            .set_model(model)
+            .set_parameters(parameters)
+            .set_max_iteration(self.max_iteration)
+            .generate()?)
+    }
+}
+
+/// A tectonic plate.
+struct Plate {
+    center: Site2D,
+    velocity: Site2D,
+    is_oceanic: bool,
+    uplift_rate: UpliftRate,
+    erodibility: Erodibility,
+}
+
+/// Provides a one-call generation of continents shaped by tectonic plates.
+///
+/// The domain is partitioned into the Voronoi cells of randomly placed plates. Each plate is either oceanic or continental,
+/// and has its own uplift rate, erodibility and a random direction of motion.
+/// The sites of the oceanic plates are set as outlets (ocean). On the continental plates, the uplift rate is boosted
+/// near the boundaries where the plates converge, by `boundary_uplift_rate` times the convergence speed
+/// (from 0.0 to 2.0) decaying exponentially with the distance from the boundary by `boundary_width`,
+/// so that mountain ranges rise along the collision zones.
+///
+/// ### Properties
+///  - `bound_min` and `bound_max` are the bounding rectangle of the domain. The default value is from (0, 0) to (100, 100).
+///  - `num_sites` is the number of the sites. The default value is 10000.
+///  - `num_plates` is the number of the plates. The default value is 8.
+///  - `ocean_ratio` is the ratio of the oceanic plates. At least one plate is always continental. The default value is 0.3.
+///  - `uplift_rate` is the mean uplift rate of the continental plates (unit: L/T). The default value is 0.2.
+///  - `uplift_variation` is the relative random variation of the uplift rate of the plates. The default value is 0.5.
+///  - `boundary_uplift_rate` is the additional uplift rate at the convergent boundaries per unit convergence speed (unit: L/T). The default value is 1.0.
+///  - `boundary_width` is the distance over which the boundary uplift decays by 1/e (unit: L). The default value is 5.0.
+///  - `erodibility` is the mean erodibility of the plates. The default value is 1.0.
+///  - `erodibility_variation` is the relative random variation of the erodibility of the plates. The default value is 0.3.
+///  - `max_iteration` is the maximum number of iterations of the generation. The default value is 100.
+///  - `seed` is the seed of the random numbers. The default value is 0.
+#[derive(Debug, Clone)]
+pub struct ContinentPreset2D {
+    bound_min: Site2D,
+    bound_max: Site2D,
+    num_sites: usize,
+    num_plates: usize,
+    ocean_ratio: f64,
+    uplift_rate: UpliftRate,
+    uplift_variation: f64,
+    boundary_uplift_rate: UpliftRate,
+    boundary_width: Length,
+    erodibility: Erodibility,
+    erodibility_variation: f64,
+    max_iteration: Step,
+    seed: u64,
+}
+
+impl Default for ContinentPreset2D {
+    fn default() -> Self {
+        Self {
+            bound_min: Site2D::new(0.0, 0.0),
+            bound_max: Site2D::new(100.0, 100.0),
+            num_sites: 10000,
+            num_plates: 8,
+            ocean_ratio: 0.3,
+            uplift_rate: 0.2,
+            uplift_variation: 0.5,
+            boundary_uplift_rate: 1.0,
+            boundary_width: 5.0,
+            erodibility: 1.0,
+            erodibility_variation: 0.3,
+            max_iteration: 100,
+            seed: 0,
+        }
+    }
+}
+
+impl ContinentPreset2D {
+    pub fn set_bounding_box(mut self, bound_min: Site2D, bound_max: Site2D) -> Self {
+        self.bound_min = bound_min;
+        self.bound_max = bound_max;
+        self
+    }
+
+    pub fn set_num_sites(mut self, num_sites: usize) -> Self {
+        self.num_sites = num_sites;
+        self
+    }
+
+    pub fn set_num_plates(mut self, num_plates: usize) -> Self {
+        self.num_plates = num_plates.max(1);
+        self
+    }
+
+    pub fn set_ocean_ratio(mut self, ocean_ratio: f64) -> Self {
+        self.ocean_ratio = ocean_ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn set_uplift_rate(mut self, uplift_rate: UpliftRate) -> Self {
+        self.uplift_rate = uplift_rate;
+        self
+    }
+
+    pub fn set_uplift_variation(mut self, uplift_variation: f64) -> Self {
+        self.uplift_variation = uplift_variation.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn set_boundary_uplift_rate(mut self, boundary_uplift_rate: UpliftRate) -> Self {
+        self.boundary_uplift_rate = boundary_uplift_rate;
+        self
+    }
+
+    pub fn set_boundary_width(mut self, boundary_width: Length) -> Self {
+        self.boundary_width = boundary_width;
+        self
+    }
+
+    pub fn set_erodibility(mut self, erodibility: Erodibility) -> Self {
+        self.erodibility = erodibility;
+        self
+    }
+
+    pub fn set_erodibility_variation(mut self, erodibility_variation: f64) -> Self {
+        self.erodibility_variation = erodibility_variation.clamp(0.0, 0.9);
+        self
+    }
+
+    pub fn set_max_iteration(mut self, max_iteration: Step) -> Self {
+        self.max_iteration = max_iteration;
+        self
+    }
+
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Build the model and the topographical parameters of the plates without generating the terrain.
+    ///
+    /// This is useful to customize the generator (e.g. adding processes) before the generation.
+    pub fn build(&self) -> Result<(TerrainModel2D, Vec<TopographicalParameters>), PresetError> {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(self.seed);
+        let model = build_model(self.bound_min, self.bound_max, self.num_sites, &mut rng)?;
+
+        let num_plates = self.num_plates.max(1);
+        let num_oceanic =
+            ((num_plates as f64 * self.ocean_ratio).round() as usize).min(num_plates - 1);
+        let plates = (0..num_plates)
+            .map(|k| {
+                let direction = rng.gen::<f64>() * std::f64::consts::TAU;
+                Plate {
+                    center: Site2D::new(
+                        rng.gen_range(self.bound_min.x..self.bound_max.x),
+                        rng.gen_range(self.bound_min.y..self.bound_max.y),
+                    ),
+                    velocity: Site2D::new(direction.cos(), direction.sin()),
+                    is_oceanic: k < num_oceanic,
+                    uplift_rate: self.uplift_rate
+                        * (1.0 + (rng.gen::<f64>() * 2.0 - 1.0) * self.uplift_variation),
+                    erodibility: self.erodibility
+                        * (1.0 + (rng.gen::<f64>() * 2.0 - 1.0) * self.erodibility_variation),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let is_boundary = boundary_mask(&model);
+
+        let parameters = model
+            .sites()
+            .iter()
+            .enumerate()
+            .map(|(i, site)| {
+                // the plate containing the site and the nearest neighboring plate
+                let (mut first, mut second) = (0, None);
+                (1..num_plates).for_each(|k| {
+                    let distance = site.squared_distance(&plates[k].center);
+                    if distance < site.squared_distance(&plates[first].center) {
+                        second = Some(first);
+                        first = k;
+                    } else if second
+                        .map(|s| distance < site.squared_distance(&plates[s].center))
+                        .unwrap_or(true)
+                    {
+                        second = Some(k);
+                    }
+                });
+                let plate = &plates[first];
+                if plate.is_oceanic || is_boundary[i] {
+                    return TopographicalParameters::default().set_is_outlet(true);
+                }
+
+                let boundary_uplift_rate = second
+                    .map(|s| {
+                        let other = &plates[s];
+                        let separation = plate.center.distance(&other.center).max(f64::EPSILON);
+                        let normal = Site2D::new(
+                            (other.center.x - plate.center.x) / separation,
+                            (other.center.y - plate.center.y) / separation,
+                        );
+                        // the distance from the site to the bisector of the two plates
+                        let distance = (site.squared_distance(&other.center)
+                            - site.squared_distance(&plate.center))
+                            / (2.0 * separation);
+                        let convergence = (plate.velocity.x - other.velocity.x) * normal.x
+                            + (plate.velocity.y - other.velocity.y) * normal.y;
+                        self.boundary_uplift_rate
+                            * convergence.max(0.0)
+                            * (-distance / self.boundary_width.max(f64::EPSILON)).exp()
+                    })
+                    .unwrap_or(0.0);
+
+                TopographicalParameters::default()
+                    .set_erodibility(plate.erodibility)
+                    .set_uplift_rate(plate.uplift_rate + boundary_uplift_rate)
+            })
+            .collect::<Vec<_>>();
+
+        Ok((model, parameters))
+    }
+
+    /// Generate the terrain of the continents.
+    pub fn generate(&self) -> Result<Terrain2D, PresetError> {
+        let (model, parameters) = self.build()?;
+        Ok(TerrainGenerator::default()
+            .set_model(model)
+            .set_parameters(parameters)
+            .set_max_iteration(self.max_iteration)
+            .generate()?)
+    }
+}
@@ -0,0 +1,70 @@
+use crate::core::units::Elevation;
+
+/// Elevations quantized into 16-bit unsigned integers, as expected by heightmap formats and game engines.
+///
+/// The elevation is restored by `offset + value * scale`. The minimum elevation maps to 0 and the maximum to `u16::MAX`,
+/// so the error of each elevation is at most `scale / 2`.
+///
+/// ### Properties
+///  - `values` is the quantized value of each elevation.
+///  - `scale` is the elevation per unit of the quantized value (unit: L). This is 0.0 if all the elevations are equal.
+///  - `offset` is the elevation of the quantized value 0, the minimum elevation (unit: L).
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizedElevations {
+    pub values: Vec<u16>,
+    pub scale: Elevation,
+    pub offset: Elevation,
+}
+
+impl QuantizedElevations {
+    /// Quantize the elevations over their range. Non-finite elevations are ignored for the range and mapped to 0.
+    pub fn from_elevations(elevations: &[Elevation]) -> Self {
+        let (min, max) = elevations
+            .iter()
+            .filter(|e| e.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &e| {
+                (min.min(e), max.max(e))
+            });
+        if min > max {
+            return Self::from_range(elevations, 0.0, 0.0);
+        }
+        Self::from_range(elevations, min, max)
+    }
+
+    /// Quantize the elevations over the fixed range from `min` to `max` (unit: L), clamping the elevations out of the range.
+    ///
+    /// This is useful to share the same scale between terrains, e.g. the tiles of a large terrain.
+    pub fn from_range(elevations: &[Elevation], min: Elevation, max: Elevation) -> Self {
+        let scale = (max - min).max(0.0) / u16::MAX as f64;
+        let values = elevations
+            .iter()
+            .map(|&e| {
+                if scale > 0.0 && e.is_finite() {
+                    ((e - min) / scale).round().clamp(0.0, u16::MAX as f64) as u16
+                } else {
+                    0
+                }
+            })
+            .collect();
+        Self {
+            values,
+            scale,
+            offset: min,
+        }
+    }
+
+    /// The restored elevation of the `i`-th value.
+    pub fn elevation(&self, i: usize) -> Option<Elevation> {
+        self.values
+            .get(i)
+            .map(|&value| self.offset + value as f64 * self.scale)
+    }
+
+    /// The restored elevations.
+    pub fn elevations(&self) -> Vec<Elevation> {
+        self.values
+            .iter()
+            .map(|&value| self.offset + value as f64 * self.scale)
+            .collect()
+    }
+}
@@ -0,0 +1,109 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::core::units::Length;
+
+use super::sites::Site2D;
+
+/// Provides a Gaussian random field, the spatially correlated random values with the Gaussian covariance
+/// `variance * exp(-r^2 / (2 * correlation_length^2))` at the distance `r`.
+///
+/// The field is approximated by the sum of `num_modes` cosine waves whose wave vectors are drawn from the spectrum of the covariance
+/// (the spectral method), so it can be evaluated at any position without a grid. The field is fully determined by `seed`.
+///
+/// This is intended to express the heterogeneity of the parameters, e.g. the erodibility of each site as `base * exp(value)`.
+///
+/// ### Properties
+///  - `mean` is the mean of the field. The default value is 0.0.
+///  - `variance` is the variance of the field. The default value is 1.0.
+///  - `correlation_length` is the length over which the values are correlated (unit: L). The default value is 10.0.
+///  - `num_modes` is the number of the cosine waves. The default value is 256.
+///  - `seed` is the seed of the field. The default value is 0.
+#[derive(Debug, Clone)]
+pub struct GaussianRandomField2D {
+    mean: f64,
+    variance: f64,
+    correlation_length: Length,
+    num_modes: usize,
+    seed: u64,
+}
+
+impl Default for GaussianRandomField2D {
+    fn default() -> Self {
+        Self {
+            mean: 0.0,
+            variance: 1.0,
+            correlation_length: 10.0,
+            num_modes: 256,
+            seed: 0,
+        }
+    }
+}
+
+impl GaussianRandomField2D {
+    pub fn set_mean(mut self, mean: f64) -> Self {
+        self.mean = mean;
+        self
+    }
+
+    pub fn set_variance(mut self, variance: f64) -> Self {
+        self.variance = variance.max(0.0);
+        self
+    }
+
+    pub fn set_correlation_length(mut self, correlation_length: Length) -> Self {
+        self.correlation_length = correlation_length;
+        self
+    }
+
+    pub fn set_num_modes(mut self, num_modes: usize) -> Self {
+        self.num_modes = num_modes.max(1);
+        self
+    }
+
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The wave vectors and the phases of the cosine waves.
+    fn modes(&self) -> Vec<([f64; 2], f64)> {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(self.seed);
+        let scale = 1.0 / self.correlation_length.max(f64::EPSILON);
+        (0..self.num_modes)
+            .map(|_| {
+                // the pair of the standard normal values by the Box-Muller transform
+                let radius = (-2.0 * (1.0 - rng.gen::<f64>()).ln()).sqrt();
+                let angle = rng.gen::<f64>() * std::f64::consts::TAU;
+                let wave = [radius * angle.cos() * scale, radius * angle.sin() * scale];
+                let phase = rng.gen::<f64>() * std::f64::consts::TAU;
+                (wave, phase)
+            })
+            .collect()
+    }
+
+    fn evaluate(&self, modes: &[([f64; 2], f64)], site: &Site2D) -> f64 {
+        let amplitude = (2.0 * self.variance / modes.len() as f64).sqrt();
+        self.mean
+            + amplitude
+                * modes
+                    .iter()
+                    .map(|(wave, phase)| (wave[0] * site.x + wave[1] * site.y + phase).cos())
+                    .sum::<f64>()
+    }
+
+    /// Get the value of the field at the site.
+    ///
+    /// Use [GaussianRandomField2D::values] to evaluate the field at many sites.
+    pub fn value(&self, site: &Site2D) -> f64 {
+        self.evaluate(&self.modes(), site)
+    }
+
+    /// Get the values of the field at the sites.
+    pub fn values(&self, sites: &[Site2D]) -> Vec<f64> {
+        let modes = self.modes();
+        sites
+            .iter()
+            .map(|site| self.evaluate(&modes, site))
+            .collect()
+    }
+}
@@ -0,0 +1,502 @@
+use crate::core::scale::VerticalScale;
+
+use std::{io, ops::Range};
+
+use super::{
+    channel::{ChannelCarver2D, HydraulicGeometry2D},
+    index::SiteIndex2D,
+    raster_writer::RowWriter,
+    sites::Site2D,
+    surface_type::SurfaceClassifier2D,
+    terrain::Terrain2D,
+};
+
+/// The number of the rows computed by each thread at a time while writing the rows.
+const ROWS_PER_THREAD: usize = 16;
+
+/// A grid of values rasterized from a terrain.
+///
+/// The pixels are stored in the row-major order, and the row `y` = 0 is at the side of `bound_min.y`.
+/// The pixels outside the terrain are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Raster2D {
+    width: usize,
+    height: usize,
+    values: Vec<Option<f64>>,
+}
+
+impl Raster2D {
+    pub fn new(width: usize, height: usize, values: Vec<Option<f64>>) -> Self {
+        Self {
+            width,
+            height,
+            values,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn values(&self) -> &[Option<f64>] {
+        &self.values
+    }
+
+    /// Get the value of the pixel.
+    pub fn get(&self, x: usize, y: usize) -> Option<f64> {
+        if x < self.width && y < self.height {
+            self.values[y * self.width + x]
+        } else {
+            None
+        }
+    }
+}
+
+/// Provides a rasterization of terrains into grids of pixels.
+///
+/// Each pixel is the average of `supersampling` × `supersampling` samples evenly placed in the pixel,
+/// each of which covers the same area. This removes the faceting of coarse terrains and the aliasing of the ridgelines
+/// of fine terrains rasterized at a low resolution. The samples outside the terrain are excluded from the average.
+///
+/// For the large exports, the rows can be written to a [RowWriter] one by one instead (see [Rasterizer2D::write_rows]).
+///
+/// The rows are computed in parallel by `num_threads` threads, each taking a contiguous range of the rows.
+/// The pixels are computed in the same way regardless of the thread which computes them, so the output is identical for any number of threads.
+///
+/// ### Properties
+///  - `bound_min` and `bound_max` are the bounding rectangle to rasterize. The default value is from (0, 0) to (100, 100).
+///  - `width` and `height` are the number of the pixels. The default value is 500 × 500.
+///  - `supersampling` is the number of the samples per pixel along each axis. The default value is 1 (the center of the pixel).
+///  - `vertical_scale` is the conversion of the rasterized elevations and the hillshades (see [VerticalScale]). The default value is the identity.
+///  - `channel_carver` incises the channels into the rasterized elevations and the hillshades (see [ChannelCarver2D]). The default value is `None`.
+///  - `num_threads` is the number of threads to compute the rows in parallel. The default value is 1.
+#[derive(Debug, Clone)]
+pub struct Rasterizer2D {
+    bound_min: Site2D,
+    bound_max: Site2D,
+    width: usize,
+    height: usize,
+    supersampling: usize,
+    vertical_scale: VerticalScale,
+    channel_carver: Option<ChannelCarver2D>,
+    num_threads: usize,
+}
+
+impl Default for Rasterizer2D {
+    fn default() -> Self {
+        Self {
+            bound_min: Site2D::new(0.0, 0.0),
+            bound_max: Site2D::new(100.0, 100.0),
+            width: 500,
+            height: 500,
+            supersampling: 1,
+            vertical_scale: VerticalScale::default(),
+            channel_carver: None,
+            num_threads: 1,
+        }
+    }
+}
+
+impl Rasterizer2D {
+    pub fn set_bounding_box(mut self, bound_min: Site2D, bound_max: Site2D) -> Self {
+        self.bound_min = bound_min;
+        self.bound_max = bound_max;
+        self
+    }
+
+    pub fn set_size(mut self, width: usize, height: usize) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn set_supersampling(mut self, supersampling: usize) -> Self {
+        self.supersampling = supersampling.max(1);
+        self
+    }
+
+    pub fn set_vertical_scale(mut self, vertical_scale: VerticalScale) -> Self {
+        self.vertical_scale = vertical_scale;
+        self
+    }
+
+    pub fn set_channel_carver(mut self, channel_carver: Option<ChannelCarver2D>) -> Self {
+        self.channel_carver = channel_carver;
+        self
+    }
+
+    pub fn set_num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads.max(1);
+        self
+    }
+
+    /// The bounding rectangle to rasterize.
+    pub fn bounding_box(&self) -> (Site2D, Site2D) {
+        (self.bound_min, self.bound_max)
+    }
+
+    /// The number of the pixels as `(width, height)`.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// The size of a pixel.
+    pub fn pixel_size(&self) -> (f64, f64) {
+        (
+            (self.bound_max.x - self.bound_min.x) / self.width.max(1) as f64,
+            (self.bound_max.y - self.bound_min.y) / self.height.max(1) as f64,
+        )
+    }
+
+    /// The center of the pixel.
+    pub fn pixel_center(&self, x: usize, y: usize) -> Site2D {
+        let (dx, dy) = self.pixel_size();
+        Site2D::new(
+            self.bound_min.x + (x as f64 + 0.5) * dx,
+            self.bound_min.y + (y as f64 + 0.5) * dy,
+        )
+    }
+
+    /// Rasterize the values given by `sample` at the positions.
+    pub fn rasterize(&self, sample: impl Fn(&Site2D) -> Option<f64> + Sync) -> Raster2D {
+        let values = self
+            .compute_rows(0..self.height, |y| self.rasterize_row(y, &sample))
+            .into_iter()
+            .flatten()
+            .collect();
+        Raster2D::new(self.width, self.height, values)
+    }
+
+    /// Rasterize the elevations of the terrain, with the channels carved and converted by the vertical scale.
+    pub fn rasterize_elevations(&self, terrain: &Terrain2D) -> Raster2D {
+        self.rasterize(|site| self.sample_elevation(terrain, site))
+    }
+
+    /// Rasterize the hillshade of the terrain, from 0.0 (facing away from the light) to 1.0 (facing the light).
+    ///
+    /// The light comes from `azimuth` (unit: rad, clockwise from the y axis) at `altitude` above the horizon (unit: rad).
+    /// The normal of each pixel is computed from the differences of the rasterized elevations of the adjacent pixels,
+    /// so the vertical scale exaggerates the shading in the same way as the exported meshes.
+    pub fn rasterize_hillshade(
+        &self,
+        terrain: &Terrain2D,
+        azimuth: f64,
+        altitude: f64,
+    ) -> Raster2D {
+        let elevations = self.rasterize_elevations(terrain);
+        let row = |y: usize| &elevations.values()[y * self.width..(y + 1) * self.width];
+        let values = self
+            .compute_rows(0..self.height, |y| {
+                self.shade_row(
+                    y.checked_sub(1).map(row),
+                    row(y),
+                    (y + 1 < self.height).then(|| row(y + 1)),
+                    azimuth,
+                    altitude,
+                )
+            })
+            .into_iter()
+            .flatten()
+            .collect();
+        Raster2D::new(self.width, self.height, values)
+    }
+
+    /// Rasterize the field of the given name of the terrain.
+    pub fn rasterize_field(&self, terrain: &Terrain2D, name: &str) -> Raster2D {
+        self.rasterize(|site| terrain.get_field(name, site))
+    }
+
+    /// Rasterize the drainage areas of the terrain, producing the image of the river network.
+    ///
+    /// Unlike the interpolated values, each sample takes the drainage area of the site whose Voronoi cell contains it,
+    /// so the narrow channels are not smoothed out between the sites. If `log_scale` is true, the natural logarithm of
+    /// `1 + drainage area` is rasterized instead, which makes the tributaries visible beside the trunk rivers.
+    /// The pixels are `None` outside the terrain, in the cells of the sites without finite drainage areas,
+    /// or if the terrain has no drainage network.
+    pub fn rasterize_drainage_areas(&self, terrain: &Terrain2D, log_scale: bool) -> Raster2D {
+        let drainage_areas = terrain.network().drainage_areas();
+        if drainage_areas.len() != terrain.sites().len() {
+            return Raster2D::new(
+                self.width,
+                self.height,
+                vec![None; self.width * self.height],
+            );
+        }
+        let index = SiteIndex2D::new(terrain.sites());
+        self.rasterize(|site| {
+            terrain.get_elevation(site)?;
+            let area = drainage_areas[index.nearest(site)?];
+            if !area.is_finite() {
+                None
+            } else if log_scale {
+                Some(area.max(0.0).ln_1p())
+            } else {
+                Some(area)
+            }
+        })
+    }
+
+    /// Rasterize the steady discharges of the terrain estimated by `geometry` (see [HydraulicGeometry2D::discharges]).
+    ///
+    /// As with the drainage areas, each sample takes the discharge of the site whose Voronoi cell contains it.
+    /// The pixels are `None` outside the terrain, or if the terrain has no drainage network.
+    pub fn rasterize_discharges(
+        &self,
+        terrain: &Terrain2D,
+        geometry: &HydraulicGeometry2D,
+    ) -> Raster2D {
+        if terrain.network().drainage_areas().len() != terrain.sites().len() {
+            return Raster2D::new(
+                self.width,
+                self.height,
+                vec![None; self.width * self.height],
+            );
+        }
+        let discharges = geometry.discharges(terrain);
+        let index = SiteIndex2D::new(terrain.sites());
+        self.rasterize(|site| {
+            terrain.get_elevation(site)?;
+            Some(discharges[index.nearest(site)?]).filter(|discharge| discharge.is_finite())
+        })
+    }
+
+    /// Rasterize the depths of the water in the channels of `carver` (see [ChannelCarver2D::water_depth]).
+    ///
+    /// With the same carver set by [Rasterizer2D::set_channel_carver], the water surface is the rasterized elevation plus the depth,
+    /// so the renderers can blend the water materials without computing the hydrology again.
+    /// The pixels are 0.0 outside the channels and `None` outside the terrain.
+    pub fn rasterize_water_depths(
+        &self,
+        terrain: &Terrain2D,
+        carver: &ChannelCarver2D,
+    ) -> Raster2D {
+        self.rasterize(|site| {
+            terrain.get_elevation(site)?;
+            Some(self.vertical_scale.apply(carver.water_depth(site)))
+        })
+    }
+
+    /// Rasterize the surface types of the terrain classified by `classifier` (see [SurfaceClassifier2D]),
+    /// where each pixel is the code of the type (see [crate::models::surface::surface_type::SurfaceType::code]).
+    ///
+    /// Each sample takes the type of the site whose Voronoi cell contains it. The pixels are `None` outside the terrain.
+    pub fn rasterize_surface_types(
+        &self,
+        terrain: &Terrain2D,
+        classifier: &SurfaceClassifier2D,
+    ) -> Raster2D {
+        let types = classifier.classify(terrain);
+        let index = SiteIndex2D::new(terrain.sites());
+        self.rasterize(|site| {
+            terrain.get_elevation(site)?;
+            Some(types[index.nearest(site)?].code() as f64)
+        })
+    }
+
+    /// Rasterize the values given by `sample` row by row into `writer`, without holding the whole raster.
+    ///
+    /// The rows are written from the top (the side of `bound_max.y`) to the bottom (see [RowWriter]),
+    /// and the writer is finished after the last row.
+    /// Only the rows computed by the threads at a time are held.
+    pub fn write_rows(
+        &self,
+        sample: impl Fn(&Site2D) -> Option<f64> + Sync,
+        writer: &mut impl RowWriter,
+    ) -> io::Result<()> {
+        self.row_blocks().try_for_each(|ys| {
+            self.compute_rows(ys, |y| self.rasterize_row(y, &sample))
+                .iter()
+                .rev()
+                .try_for_each(|row| writer.write_row(row))
+        })?;
+        writer.finish()
+    }
+
+    /// Write the elevations of the terrain row by row. See [Rasterizer2D::rasterize_elevations] and [Rasterizer2D::write_rows].
+    pub fn write_elevations(
+        &self,
+        terrain: &Terrain2D,
+        writer: &mut impl RowWriter,
+    ) -> io::Result<()> {
+        self.write_rows(|site| self.sample_elevation(terrain, site), writer)
+    }
+
+    /// Write the hillshade of the terrain row by row. See [Rasterizer2D::rasterize_hillshade] and [Rasterizer2D::write_rows].
+    ///
+    /// Only the rows of the elevations around the rows computed by the threads at a time are held.
+    pub fn write_hillshade(
+        &self,
+        terrain: &Terrain2D,
+        azimuth: f64,
+        altitude: f64,
+        writer: &mut impl RowWriter,
+    ) -> io::Result<()> {
+        let sample = |site: &Site2D| self.sample_elevation(terrain, site);
+        // the rows of the elevations from the row `base` upward, including the two rows above the current block
+        let mut base = self.height;
+        let mut elevations: Vec<Vec<Option<f64>>> = Vec::new();
+        self.row_blocks().try_for_each(|ys| {
+            let lowest = ys.start.saturating_sub(1);
+            if lowest < base {
+                let mut rows = self.compute_rows(lowest..base, |y| self.rasterize_row(y, &sample));
+                rows.append(&mut elevations);
+                elevations = rows;
+                base = lowest;
+            }
+            let row = |y: usize| elevations[y - base].as_slice();
+            let start = ys.start;
+            self.compute_rows(ys, |y| {
+                self.shade_row(
+                    y.checked_sub(1).map(row),
+                    row(y),
+                    (y + 1 < self.height).then(|| row(y + 1)),
+                    azimuth,
+                    altitude,
+                )
+            })
+            .iter()
+            .rev()
+            .try_for_each(|row| writer.write_row(row))?;
+            // the next block needs only the rows at and below the bottom of this block
+            elevations.truncate(start + 1 - base);
+            Ok::<(), io::Error>(())
+        })?;
+        writer.finish()
+    }
+
+    /// Write the field of the given name of the terrain row by row. See [Rasterizer2D::write_rows].
+    pub fn write_field(
+        &self,
+        terrain: &Terrain2D,
+        name: &str,
+        writer: &mut impl RowWriter,
+    ) -> io::Result<()> {
+        self.write_rows(|site| terrain.get_field(name, site), writer)
+    }
+
+    /// Compute the hillshade of the row of the elevations `row` between the rows `prev` (y - 1) and `next` (y + 1).
+    pub(crate) fn shade_row(
+        &self,
+        prev: Option<&[Option<f64>]>,
+        row: &[Option<f64>],
+        next: Option<&[Option<f64>]>,
+        azimuth: f64,
+        altitude: f64,
+    ) -> Vec<Option<f64>> {
+        let (dx, dy) = self.pixel_size();
+        let light = [
+            altitude.cos() * azimuth.sin(),
+            altitude.cos() * azimuth.cos(),
+            altitude.sin(),
+        ];
+        // the derivative along an axis, using the one-sided difference at the borders of the terrain
+        let derivative =
+            |center: f64, prev: Option<f64>, next: Option<f64>, d: f64| match (prev, next) {
+                (Some(p), Some(n)) => (n - p) / (2.0 * d),
+                (None, Some(n)) => (n - center) / d,
+                (Some(p), None) => (center - p) / d,
+                (None, None) => 0.0,
+            };
+        (0..row.len())
+            .map(|x| {
+                let center = row[x]?;
+                let gx = derivative(
+                    center,
+                    x.checked_sub(1).and_then(|x| row[x]),
+                    row.get(x + 1).copied().flatten(),
+                    dx,
+                );
+                let gy = derivative(
+                    center,
+                    prev.and_then(|prev| prev[x]),
+                    next.and_then(|next| next[x]),
+                    dy,
+                );
+                let norm = (gx * gx + gy * gy + 1.0).sqrt();
+                Some(((-gx * light[0] - gy * light[1] + light[2]) / norm).max(0.0))
+            })
+            .collect()
+    }
+
+    /// Sample the elevation of the terrain, with the channels carved and converted by the vertical scale.
+    fn sample_elevation(&self, terrain: &Terrain2D, site: &Site2D) -> Option<f64> {
+        terrain.get_elevation(site).map(|elevation| {
+            let elevation = match &self.channel_carver {
+                Some(carver) => carver.carve(site, elevation),
+                None => elevation,
+            };
+            self.vertical_scale.apply(elevation)
+        })
+    }
+
+    /// The ranges of the rows written at a time, from the top to the bottom.
+    fn row_blocks(&self) -> impl Iterator<Item = Range<usize>> {
+        let block_size = self.num_threads * ROWS_PER_THREAD;
+        let height = self.height;
+        (0..height.div_ceil(block_size)).map(move |b| {
+            let end = height - b * block_size;
+            end.saturating_sub(block_size)..end
+        })
+    }
+
+    /// Compute the rows `ys` by `row` in parallel, returning them in the ascending order.
+    fn compute_rows<R: Send>(&self, ys: Range<usize>, row: impl Fn(usize) -> R + Sync) -> Vec<R> {
+        let ys = ys.collect::<Vec<_>>();
+        if self.num_threads <= 1 || ys.len() <= 1 {
+            return ys.into_iter().map(row).collect();
+        }
+        let chunk_size = ys.len().div_ceil(self.num_threads);
+        std::thread::scope(|scope| {
+            let handles = ys
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let row = &row;
+                    scope.spawn(move || chunk.iter().map(|&y| row(y)).collect::<Vec<_>>())
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+
+    /// Rasterize a row of the values given by `sample`.
+    pub(crate) fn rasterize_row(
+        &self,
+        y: usize,
+        sample: &impl Fn(&Site2D) -> Option<f64>,
+    ) -> Vec<Option<f64>> {
+        (0..self.width)
+            .map(|x| self.rasterize_pixel(x, y, sample))
+            .collect()
+    }
+
+    fn rasterize_pixel(
+        &self,
+        x: usize,
+        y: usize,
+        sample: &impl Fn(&Site2D) -> Option<f64>,
+    ) -> Option<f64> {
+        let (dx, dy) = self.pixel_size();
+        let n = self.supersampling;
+        let (sum, count) = (0..n)
+            .flat_map(|sy| (0..n).map(move |sx| (sx, sy)))
+            .filter_map(|(sx, sy)| {
+                sample(&Site2D::new(
+                    self.bound_min.x + (x as f64 + (sx as f64 + 0.5) / n as f64) * dx,
+                    self.bound_min.y + (y as f64 + (sy as f64 + 0.5) / n as f64) * dy,
+                ))
+            })
+            .fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+        if count > 0 {
+            Some(sum / count as f64)
+        } else {
+            None
+        }
+    }
+}
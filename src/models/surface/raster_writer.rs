@@ -0,0 +1,323 @@
+//! Encoders of the rasters written row by row, used by the streaming rasterization of [Rasterizer2D].
+//!
+//! The rows are passed to the encoders as soon as they are rasterized, so the full grid of pixels is never held in the memory.
+
+use std::io::{self, Write};
+
+use super::raster::Rasterizer2D;
+
+/// A destination of the rows of a raster.
+///
+/// The rows are written from the top (the side of `bound_max.y`) to the bottom,
+/// which is the order of the rows of the image and the grid formats.
+pub trait RowWriter {
+    /// Write the next row of the pixels. The pixels outside the terrain are `None`.
+    fn write_row(&mut self, row: &[Option<f64>]) -> io::Result<()>;
+
+    /// Finish the output after all the rows are written.
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+/// Check the row against the size of the raster and count it.
+fn check_row(row: &[Option<f64>], width: usize, height: usize, rows: &mut usize) -> io::Result<()> {
+    if row.len() != width {
+        return Err(invalid_input(
+            "The length of the row differs from the width",
+        ));
+    }
+    if *rows >= height {
+        return Err(invalid_input("More rows are written than the height"));
+    }
+    *rows += 1;
+    Ok(())
+}
+
+fn check_finish(height: usize, rows: usize) -> io::Result<()> {
+    if rows != height {
+        return Err(invalid_input("Fewer rows are written than the height"));
+    }
+    Ok(())
+}
+
+fn invalid_input(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+/// Writes the rows as an Esri ASCII grid (`.asc`).
+///
+/// The grid is placed at the bounding box of the rasterizer. If the pixels are not square,
+/// the cell size is written as `dx` and `dy` instead of `cellsize`. The pixels outside the terrain are written as `nodata`.
+pub struct AsciiGridWriter<W: Write> {
+    writer: W,
+    width: usize,
+    height: usize,
+    rows: usize,
+    nodata: f64,
+}
+
+impl<W: Write> AsciiGridWriter<W> {
+    /// Create the writer of the raster of `rasterizer`, writing the header.
+    pub fn new(mut writer: W, rasterizer: &Rasterizer2D, nodata: f64) -> io::Result<Self> {
+        let (width, height) = rasterizer.size();
+        let (bound_min, _) = rasterizer.bounding_box();
+        let (dx, dy) = rasterizer.pixel_size();
+        writeln!(writer, "ncols {}", width)?;
+        writeln!(writer, "nrows {}", height)?;
+        writeln!(writer, "xllcorner {}", bound_min.x)?;
+        writeln!(writer, "yllcorner {}", bound_min.y)?;
+        if dx == dy {
+            writeln!(writer, "cellsize {}", dx)?;
+        } else {
+            writeln!(writer, "dx {}", dx)?;
+            writeln!(writer, "dy {}", dy)?;
+        }
+        writeln!(writer, "NODATA_value {}", nodata)?;
+        Ok(Self {
+            writer,
+            width,
+            height,
+            rows: 0,
+            nodata,
+        })
+    }
+
+    /// Take the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> RowWriter for AsciiGridWriter<W> {
+    fn write_row(&mut self, row: &[Option<f64>]) -> io::Result<()> {
+        check_row(row, self.width, self.height, &mut self.rows)?;
+        row.iter().enumerate().try_for_each(|(x, value)| {
+            if x > 0 {
+                self.writer.write_all(b" ")?;
+            }
+            write!(self.writer, "{}", value.unwrap_or(self.nodata))
+        })?;
+        self.writer.write_all(b"\n")
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        check_finish(self.height, self.rows)?;
+        self.writer.flush()
+    }
+}
+
+/// Writes the rows as a single-channel 32-bit floating point TIFF (`.tif`).
+///
+/// The pixels are stored uncompressed in a single strip followed by the image directory,
+/// so the layout is determined by the size alone and the output needs no seeking.
+/// The pixels outside the terrain are written as NaN.
+pub struct TiffWriter<W: Write> {
+    writer: W,
+    width: usize,
+    height: usize,
+    rows: usize,
+}
+
+impl<W: Write> TiffWriter<W> {
+    /// Create the writer of the raster of `width` × `height` pixels, writing the header.
+    ///
+    /// Returns an error if the raster does not fit in the 4 GiB of a TIFF file.
+    pub fn new(mut writer: W, width: usize, height: usize) -> io::Result<Self> {
+        let data_size = (width as u64) * (height as u64) * 4;
+        if 8 + data_size + Self::DIRECTORY_SIZE > u32::MAX as u64 {
+            return Err(invalid_input("The raster is too large for a TIFF file"));
+        }
+        writer.write_all(b"II*\0")?;
+        writer.write_all(&((8 + data_size) as u32).to_le_bytes())?;
+        Ok(Self {
+            writer,
+            width,
+            height,
+            rows: 0,
+        })
+    }
+
+    /// Take the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    const NUM_ENTRIES: u16 = 10;
+    const DIRECTORY_SIZE: u64 = 2 + Self::NUM_ENTRIES as u64 * 12 + 4;
+
+    fn write_entry(&mut self, tag: u16, field_type: u16, value: u32) -> io::Result<()> {
+        // the SHORT values are left-justified in the 4 bytes of the value
+        let value = if field_type == 3 {
+            (value as u16 as u32).to_le_bytes()
+        } else {
+            value.to_le_bytes()
+        };
+        self.writer.write_all(&tag.to_le_bytes())?;
+        self.writer.write_all(&field_type.to_le_bytes())?;
+        self.writer.write_all(&1u32.to_le_bytes())?;
+        self.writer.write_all(&value)
+    }
+}
+
+impl<W: Write> RowWriter for TiffWriter<W> {
+    fn write_row(&mut self, row: &[Option<f64>]) -> io::Result<()> {
+        check_row(row, self.width, self.height, &mut self.rows)?;
+        let data = row
+            .iter()
+            .flat_map(|value| (value.unwrap_or(f64::NAN) as f32).to_le_bytes())
+            .collect::<Vec<_>>();
+        self.writer.write_all(&data)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        check_finish(self.height, self.rows)?;
+        const SHORT: u16 = 3;
+        const LONG: u16 = 4;
+        let data_size = (self.width * self.height * 4) as u32;
+        self.writer.write_all(&Self::NUM_ENTRIES.to_le_bytes())?;
+        // the entries in the ascending order of the tags
+        self.write_entry(256, LONG, self.width as u32)?; // ImageWidth
+        self.write_entry(257, LONG, self.height as u32)?; // ImageLength
+        self.write_entry(258, SHORT, 32)?; // BitsPerSample
+        self.write_entry(259, SHORT, 1)?; // Compression: none
+        self.write_entry(262, SHORT, 1)?; // PhotometricInterpretation: black is zero
+        self.write_entry(273, LONG, 8)?; // StripOffsets
+        self.write_entry(277, SHORT, 1)?; // SamplesPerPixel
+        self.write_entry(278, LONG, self.height as u32)?; // RowsPerStrip
+        self.write_entry(279, LONG, data_size)?; // StripByteCounts
+        self.write_entry(339, SHORT, 3)?; // SampleFormat: floating point
+        self.writer.write_all(&0u32.to_le_bytes())?;
+        self.writer.flush()
+    }
+}
+
+/// Writes the rows as a 16-bit grayscale PNG (`.png`).
+///
+/// The values from `min` to `max` are mapped linearly to the levels from 1 to 65535, clamping the values outside the range.
+/// The pixels outside the terrain are written as 0.
+/// The image data is stored in uncompressed deflate blocks, one per row, so each row is written as soon as it is given.
+pub struct PngWriter<W: Write> {
+    writer: W,
+    width: usize,
+    height: usize,
+    rows: usize,
+    min: f64,
+    max: f64,
+    adler: (u32, u32),
+}
+
+/// The maximum length of an uncompressed deflate block.
+const MAX_STORED_BLOCK: usize = 65535;
+
+impl<W: Write> PngWriter<W> {
+    /// Create the writer of the raster of `width` × `height` pixels with the range of the values, writing the header.
+    pub fn new(mut writer: W, width: usize, height: usize, min: f64, max: f64) -> io::Result<Self> {
+        if width == 0 || height == 0 || width > i32::MAX as usize || height > i32::MAX as usize {
+            return Err(invalid_input(
+                "The size of the raster is invalid for a PNG file",
+            ));
+        }
+        writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(width as u32).to_be_bytes());
+        header.extend_from_slice(&(height as u32).to_be_bytes());
+        // 16-bit grayscale, deflate, adaptive filtering, no interlace
+        header.extend_from_slice(&[16, 0, 0, 0, 0]);
+        write_chunk(&mut writer, b"IHDR", &header)?;
+        // the zlib header without the preset dictionary
+        write_chunk(&mut writer, b"IDAT", &[0x78, 0x01])?;
+        Ok(Self {
+            writer,
+            width,
+            height,
+            rows: 0,
+            min,
+            max,
+            adler: (1, 0),
+        })
+    }
+
+    /// Take the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn level(&self, value: Option<f64>) -> u16 {
+        match value {
+            Some(value) if value.is_finite() => {
+                let range = self.max - self.min;
+                let t = if range > 0.0 {
+                    ((value - self.min) / range).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                1 + (t * 65534.0).round() as u16
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl<W: Write> RowWriter for PngWriter<W> {
+    fn write_row(&mut self, row: &[Option<f64>]) -> io::Result<()> {
+        check_row(row, self.width, self.height, &mut self.rows)?;
+        // the filter type `None` followed by the big-endian levels
+        let mut scanline = Vec::with_capacity(1 + row.len() * 2);
+        scanline.push(0);
+        row.iter()
+            .for_each(|&value| scanline.extend_from_slice(&self.level(value).to_be_bytes()));
+        self.adler = adler32(self.adler, &scanline);
+
+        let mut data =
+            Vec::with_capacity(scanline.len() + 5 * (scanline.len() / MAX_STORED_BLOCK + 1));
+        scanline.chunks(MAX_STORED_BLOCK).for_each(|block| {
+            let len = block.len() as u16;
+            data.push(0);
+            data.extend_from_slice(&len.to_le_bytes());
+            data.extend_from_slice(&(!len).to_le_bytes());
+            data.extend_from_slice(block);
+        });
+        write_chunk(&mut self.writer, b"IDAT", &data)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        check_finish(self.height, self.rows)?;
+        // the empty final block and the checksum of the zlib stream
+        let (a, b) = self.adler;
+        let mut data = vec![1, 0, 0, 0xff, 0xff];
+        data.extend_from_slice(&((b << 16) | a).to_be_bytes());
+        write_chunk(&mut self.writer, b"IDAT", &data)?;
+        write_chunk(&mut self.writer, b"IEND", &[])?;
+        self.writer.flush()
+    }
+}
+
+fn write_chunk(writer: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    let crc = !crc32(crc32(!0, kind), data);
+    writer.write_all(&crc.to_be_bytes())
+}
+
+fn crc32(mut crc: u32, data: &[u8]) -> u32 {
+    data.iter().for_each(|&byte| {
+        crc ^= byte as u32;
+        (0..8).for_each(|_| {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        });
+    });
+    crc
+}
+
+fn adler32((mut a, mut b): (u32, u32), data: &[u8]) -> (u32, u32) {
+    const MOD_ADLER: u32 = 65521;
+    data.iter().for_each(|&byte| {
+        a = (a + byte as u32) % MOD_ADLER;
+        b = (b + a) % MOD_ADLER;
+    });
+    (a, b)
+}
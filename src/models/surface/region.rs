@@ -0,0 +1,118 @@
+use thiserror::Error;
+
+use crate::core::{
+    parameters::{ParameterError, ParameterSet},
+    traits::Model,
+    units::Elevation,
+};
+
+use super::{index::SiteIndex2D, model::TerrainModel2D, sites::Site2D, terrain::Terrain2D};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RegionError {
+    #[error("The polygon of the region must have at least 3 vertices")]
+    InvalidPolygon,
+    #[error("The number of topographical parameters must be equal to the number of sites")]
+    InvalidNumberOfParameters,
+    #[error("The far-field terrain has no elevation for the site {0}")]
+    MissingFarField(usize),
+    #[error("Failed to freeze the sites outside the region: {0}")]
+    Parameters(#[from] ParameterError),
+}
+
+/// A region of interest to simulate again in detail within a larger terrain.
+///
+/// The sites of a model inside the polygon are simulated, while the sites outside it are frozen at the elevations of
+/// the far-field terrain (such as a previous coarse result of the whole world) as the fixed boundary conditions
+/// (see [ParameterSet::freeze]). To redo a small area of a large world cheaply, build a fine model covering only
+/// the bounding box of the region with a margin, and constrain its parameters by the coarse terrain.
+///
+/// ### Properties
+///  - `polygon` is the vertices of the boundary of the region, in either order without repeating the first vertex.
+#[derive(Debug, Clone)]
+pub struct RegionOfInterest2D {
+    polygon: Vec<Site2D>,
+}
+
+impl RegionOfInterest2D {
+    pub fn new(polygon: Vec<Site2D>) -> Result<Self, RegionError> {
+        if polygon.len() < 3 {
+            return Err(RegionError::InvalidPolygon);
+        }
+        Ok(Self { polygon })
+    }
+
+    pub fn polygon(&self) -> &[Site2D] {
+        &self.polygon
+    }
+
+    /// The axis-aligned bounding box of the polygon as `(bound_min, bound_max)`.
+    pub fn bounding_box(&self) -> (Site2D, Site2D) {
+        self.polygon.iter().fold(
+            (
+                Site2D::new(f64::INFINITY, f64::INFINITY),
+                Site2D::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(min, max), site| {
+                (
+                    Site2D::new(min.x.min(site.x), min.y.min(site.y)),
+                    Site2D::new(max.x.max(site.x), max.y.max(site.y)),
+                )
+            },
+        )
+    }
+
+    /// Whether the site is inside the polygon, by the even-odd rule.
+    pub fn contains(&self, site: &Site2D) -> bool {
+        let num = self.polygon.len();
+        (0..num).fold(false, |inside, k| {
+            let (a, b) = (&self.polygon[k], &self.polygon[(k + 1) % num]);
+            if (a.y > site.y) != (b.y > site.y)
+                && site.x < a.x + (site.y - a.y) / (b.y - a.y) * (b.x - a.x)
+            {
+                !inside
+            } else {
+                inside
+            }
+        })
+    }
+
+    /// The indices of the sites of the model outside the region in the ascending order.
+    pub fn exterior_sites(&self, model: &TerrainModel2D) -> Vec<usize> {
+        model
+            .sites()
+            .iter()
+            .enumerate()
+            .filter(|(_, site)| !self.contains(site))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Freeze the sites of the model outside the region at the elevations of the far-field terrain.
+    ///
+    /// The elevation of each exterior site is interpolated in the far-field terrain, or taken from its nearest site
+    /// if the site is out of the triangles of the far-field terrain. The parameters of the sites inside the region are kept.
+    pub fn constrain(
+        &self,
+        model: &TerrainModel2D,
+        far_field: &Terrain2D,
+        parameters: ParameterSet,
+    ) -> Result<ParameterSet, RegionError> {
+        if parameters.len() != model.num() {
+            return Err(RegionError::InvalidNumberOfParameters);
+        }
+        let index = SiteIndex2D::new(far_field.sites());
+        let exterior = self.exterior_sites(model);
+        let mut elevations: Vec<Elevation> = vec![0.0; model.num()];
+        exterior.iter().try_for_each(|&i| {
+            let site = &model.sites()[i];
+            elevations[i] = far_field
+                .get_elevation(site)
+                .filter(|elevation| elevation.is_finite())
+                .or_else(|| index.nearest(site).map(|j| far_field.elevations()[j]))
+                .ok_or(RegionError::MissingFarField(i))?;
+            Ok::<(), RegionError>(())
+        })?;
+        Ok(parameters.freeze(&exterior, &elevations)?)
+    }
+}
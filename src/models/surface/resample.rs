@@ -0,0 +1,91 @@
+use crate::core::{fields::SiteFields, traits::Model, units::Area};
+
+use super::{index::SiteIndex2D, model::TerrainModel2D, terrain::Terrain2D};
+
+/// The area of each site of the terrain, a third of the areas of the triangles around the site.
+fn site_areas(terrain: &Terrain2D) -> Vec<Area> {
+    let sites = terrain.sites();
+    let mut areas = vec![0.0; sites.len()];
+    terrain.triangles().iter().for_each(|&[a, b, c]| {
+        let area = ((sites[b].x - sites[a].x) * (sites[c].y - sites[a].y)
+            - (sites[c].x - sites[a].x) * (sites[b].y - sites[a].y))
+            .abs()
+            / 2.0;
+        [a, b, c].iter().for_each(|&i| areas[i] += area / 3.0);
+    });
+    areas
+}
+
+/// Sample the values of the terrain at the sites of the model.
+///
+/// The value of each site of the model is the mean of the values of the sites of the terrain in its Voronoi cell,
+/// weighted by their areas. If the cell contains no site of the terrain, the value is interpolated linearly
+/// in the triangle of the terrain containing the site, or taken from the nearest site of the terrain outside the triangles.
+pub(super) fn resample(terrain: &Terrain2D, model: &TerrainModel2D) -> Terrain2D {
+    let sites = terrain.sites();
+    let areas = site_areas(terrain);
+    let index = SiteIndex2D::new(sites);
+
+    // the site of the model containing each site of the terrain
+    let targets = sites
+        .iter()
+        .map(|site| model.nearest_site(site))
+        .collect::<Vec<_>>();
+    let mut total_areas = vec![0.0; model.num()];
+    targets.iter().enumerate().for_each(|(i, target)| {
+        if let Some(j) = *target {
+            // the sites without triangles are weighted equally
+            total_areas[j] += areas[i].max(f64::EPSILON);
+        }
+    });
+
+    // the fallback for the empty cells
+    let samples = model
+        .sites()
+        .iter()
+        .enumerate()
+        .map(|(j, site)| {
+            if total_areas[j] > 0.0 {
+                return Vec::new();
+            }
+            match terrain.locate(site) {
+                Some((t, weights)) => terrain.triangles()[t]
+                    .iter()
+                    .copied()
+                    .zip(weights)
+                    .collect(),
+                None => index
+                    .nearest(site)
+                    .map(|i| vec![(i, 1.0)])
+                    .unwrap_or_default(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let resample_values = |values: &[f64]| {
+        let mut resampled = vec![0.0; model.num()];
+        targets.iter().enumerate().for_each(|(i, target)| {
+            if let Some(j) = *target {
+                resampled[j] += values[i] * areas[i].max(f64::EPSILON) / total_areas[j];
+            }
+        });
+        samples.iter().enumerate().for_each(|(j, sample)| {
+            if !sample.is_empty() {
+                resampled[j] = sample.iter().map(|&(i, w)| values[i] * w).sum();
+            }
+        });
+        resampled
+    };
+
+    let elevations = resample_values(terrain.elevations());
+    let mut fields = SiteFields::default();
+    let names = terrain.fields().names().collect::<Vec<_>>();
+    names.into_iter().for_each(|name| {
+        if let Some(values) = terrain.fields().get(name) {
+            fields.insert(name, resample_values(values));
+        }
+    });
+    model
+        .create_terrain_from_result(&elevations)
+        .set_fields(fields)
+}
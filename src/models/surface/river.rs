@@ -0,0 +1,117 @@
+use crate::core::{
+    network::DrainageNetwork,
+    traits::Site,
+    units::{Area, Elevation, Length},
+};
+
+use super::sites::Site2D;
+
+/// A reach of a river, the path of the flow from its head down to the next confluence or the outlet.
+///
+/// ### Properties
+///  - `sites` is the indices of the sites along the reach from upstream to downstream.
+///  - `points` is the positions of the sites.
+///  - `elevations` is the elevations of the sites.
+///  - `drainage_areas` is the drainage areas of the sites (unit: L^2).
+#[derive(Debug, Clone)]
+pub struct River2D {
+    sites: Vec<usize>,
+    points: Vec<Site2D>,
+    elevations: Vec<Elevation>,
+    drainage_areas: Vec<Area>,
+}
+
+impl River2D {
+    /// The river along `path`, the indices of the sites from upstream to downstream.
+    pub(super) fn from_path(
+        sites: &[Site2D],
+        elevations: &[Elevation],
+        network: &DrainageNetwork,
+        path: Vec<usize>,
+    ) -> Self {
+        Self {
+            points: path.iter().map(|&i| sites[i]).collect(),
+            elevations: path.iter().map(|&i| elevations[i]).collect(),
+            drainage_areas: path.iter().map(|&i| network.drainage_areas()[i]).collect(),
+            sites: path,
+        }
+    }
+
+    pub fn sites(&self) -> &[usize] {
+        &self.sites
+    }
+
+    pub fn points(&self) -> &[Site2D] {
+        &self.points
+    }
+
+    pub fn elevations(&self) -> &[Elevation] {
+        &self.elevations
+    }
+
+    pub fn drainage_areas(&self) -> &[Area] {
+        &self.drainage_areas
+    }
+
+    /// The length of the reach along its sites.
+    pub fn length(&self) -> Length {
+        self.points
+            .windows(2)
+            .map(|segment| segment[0].distance(&segment[1]))
+            .sum()
+    }
+
+    /// The mean gradient of the reach, the drop divided by the length.
+    pub fn gradient(&self) -> f64 {
+        let length = self.length();
+        if length <= 0.0 {
+            return 0.0;
+        }
+        let drop = self.elevations.first().unwrap_or(&0.0) - self.elevations.last().unwrap_or(&0.0);
+        drop / length
+    }
+}
+
+/// Extract the reaches of the rivers, the paths through the sites whose drainage area is not less than `min_drainage_area`.
+///
+/// Each reach starts at a channel head or a confluence and ends at the next confluence or the outlet, which is included in both reaches.
+pub(super) fn extract_rivers(
+    sites: &[Site2D],
+    elevations: &[Elevation],
+    network: &DrainageNetwork,
+    min_drainage_area: Area,
+) -> Vec<River2D> {
+    let num = network.receivers().len();
+    let is_channel = (0..num)
+        .map(|i| network.drainage_areas()[i] >= min_drainage_area)
+        .collect::<Vec<_>>();
+    let num_channel_donors = network
+        .donors()
+        .iter()
+        .map(|donors| donors.iter().filter(|&&k| is_channel[k]).count())
+        .collect::<Vec<_>>();
+
+    // reaches start at channel heads and at confluences
+    (0..num)
+        .filter(|&i| is_channel[i] && num_channel_donors[i] != 1)
+        .filter_map(|start| {
+            let mut path = vec![start];
+            let mut i = start;
+            loop {
+                let j = network.receivers()[i];
+                if j == i {
+                    break;
+                }
+                path.push(j);
+                if num_channel_donors[j] > 1 {
+                    break;
+                }
+                i = j;
+            }
+            if path.len() < 2 {
+                return None;
+            }
+            Some(River2D::from_path(sites, elevations, network, path))
+        })
+        .collect()
+}
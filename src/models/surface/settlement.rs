@@ -0,0 +1,170 @@
+use crate::core::{
+    fields::{FLOODPLAIN, SETTLEMENT_SUITABILITY},
+    traits::Model,
+    units::{Area, Elevation, Length},
+};
+
+use super::{model::TerrainModel2D, terrain::Terrain2D};
+
+/// Provides a scoring of the suitability of each site for settlements, as a starting point for placing towns.
+///
+/// The score is the weighted mean of the following factors, each from 0.0 to 1.0:
+///  - the flatness `1 - slope / max_slope`, clamped to be non-negative, where the slope of each site is the mean of
+///    the absolute slopes of its edges.
+///  - the proximity to rivers `exp(-d / river_distance)`, where `d` is the distance along the edges to the nearest site
+///    whose drainage area is not less than `min_drainage_area`.
+///  - the proximity to the coast `exp(-d / coast_distance)`, where `d` is the distance along the edges to the nearest site below `sea_level`.
+///  - the safety from floods `1 - f`, where `f` is the field [FLOODPLAIN] of the terrain (0.0 if the terrain does not have the field).
+///
+/// The sites below `sea_level` are 0.0. If the terrain has no drainage network, the proximity to rivers is 0.0.
+///
+/// ### Properties
+///  - `sea_level` is the sea level (unit: L). The default value is 0.0.
+///  - `max_slope` is the slope at which the flatness becomes 0.0. The default value is 0.2.
+///  - `min_drainage_area` is the drainage area above which the sites are rivers (unit: L^2). The default value is 100.0.
+///  - `river_distance` is the characteristic distance of the proximity to rivers (unit: L). The default value is 10.0.
+///  - `coast_distance` is the characteristic distance of the proximity to the coast (unit: L). The default value is 20.0.
+///  - `slope_weight` is the weight of the flatness. The default value is 1.0.
+///  - `river_weight` is the weight of the proximity to rivers. The default value is 1.0.
+///  - `coast_weight` is the weight of the proximity to the coast. The default value is 0.5.
+///  - `flood_weight` is the weight of the safety from floods. The default value is 1.0.
+#[derive(Debug, Clone)]
+pub struct SettlementSuitability2D {
+    sea_level: Elevation,
+    max_slope: f64,
+    min_drainage_area: Area,
+    river_distance: Length,
+    coast_distance: Length,
+    slope_weight: f64,
+    river_weight: f64,
+    coast_weight: f64,
+    flood_weight: f64,
+}
+
+impl Default for SettlementSuitability2D {
+    fn default() -> Self {
+        Self {
+            sea_level: 0.0,
+            max_slope: 0.2,
+            min_drainage_area: 100.0,
+            river_distance: 10.0,
+            coast_distance: 20.0,
+            slope_weight: 1.0,
+            river_weight: 1.0,
+            coast_weight: 0.5,
+            flood_weight: 1.0,
+        }
+    }
+}
+
+impl SettlementSuitability2D {
+    pub fn set_sea_level(mut self, sea_level: Elevation) -> Self {
+        self.sea_level = sea_level;
+        self
+    }
+
+    pub fn set_max_slope(mut self, max_slope: f64) -> Self {
+        self.max_slope = max_slope.max(f64::EPSILON);
+        self
+    }
+
+    pub fn set_min_drainage_area(mut self, min_drainage_area: Area) -> Self {
+        self.min_drainage_area = min_drainage_area;
+        self
+    }
+
+    pub fn set_river_distance(mut self, river_distance: Length) -> Self {
+        self.river_distance = river_distance.max(f64::EPSILON);
+        self
+    }
+
+    pub fn set_coast_distance(mut self, coast_distance: Length) -> Self {
+        self.coast_distance = coast_distance.max(f64::EPSILON);
+        self
+    }
+
+    pub fn set_slope_weight(mut self, slope_weight: f64) -> Self {
+        self.slope_weight = slope_weight.max(0.0);
+        self
+    }
+
+    pub fn set_river_weight(mut self, river_weight: f64) -> Self {
+        self.river_weight = river_weight.max(0.0);
+        self
+    }
+
+    pub fn set_coast_weight(mut self, coast_weight: f64) -> Self {
+        self.coast_weight = coast_weight.max(0.0);
+        self
+    }
+
+    pub fn set_flood_weight(mut self, flood_weight: f64) -> Self {
+        self.flood_weight = flood_weight.max(0.0);
+        self
+    }
+
+    /// The suitability of each site of the terrain generated from the model.
+    ///
+    /// If the terrain does not have the same sites as the model, all the sites are 0.0.
+    pub fn scores(&self, model: &TerrainModel2D, terrain: &Terrain2D) -> Vec<f64> {
+        let num = model.num();
+        let elevations = terrain.elevations();
+        let total_weight =
+            self.slope_weight + self.river_weight + self.coast_weight + self.flood_weight;
+        if elevations.len() != num || total_weight <= 0.0 {
+            return vec![0.0; elevations.len()];
+        }
+        let graph = model.graph();
+        let network = terrain.network();
+        let rivers = if network.is_empty() {
+            Vec::new()
+        } else {
+            (0..num)
+                .filter(|&i| network.drainage_areas()[i] >= self.min_drainage_area)
+                .collect()
+        };
+        let sea = (0..num)
+            .filter(|&i| elevations[i] < self.sea_level)
+            .collect::<Vec<_>>();
+        let river_field = model.distance_field(&rivers, f64::INFINITY);
+        let coast_field = model.distance_field(&sea, f64::INFINITY);
+        let floodplain = terrain.fields().get(FLOODPLAIN);
+
+        (0..num)
+            .map(|i| {
+                if elevations[i] < self.sea_level {
+                    return 0.0;
+                }
+                let neighbors = graph.neighbors_of(i);
+                let slope = if neighbors.is_empty() {
+                    0.0
+                } else {
+                    neighbors
+                        .iter()
+                        .filter(|ja| ja.1 > 0.0)
+                        .map(|ja| (elevations[ja.0] - elevations[i]).abs() / ja.1)
+                        .sum::<f64>()
+                        / neighbors.len() as f64
+                };
+                let flatness = (1.0 - slope / self.max_slope).max(0.0);
+                let river = (-river_field.distances()[i] / self.river_distance).exp();
+                let coast = (-coast_field.distances()[i] / self.coast_distance).exp();
+                let safety =
+                    1.0 - floodplain.map_or(0.0, |floodplain| floodplain[i].clamp(0.0, 1.0));
+                (self.slope_weight * flatness
+                    + self.river_weight * river
+                    + self.coast_weight * coast
+                    + self.flood_weight * safety)
+                    / total_weight
+            })
+            .collect()
+    }
+
+    /// Attach the scores to the terrain as the field [SETTLEMENT_SUITABILITY].
+    pub fn attach(&self, model: &TerrainModel2D, terrain: Terrain2D) -> Terrain2D {
+        let scores = self.scores(model, &terrain);
+        let mut fields = terrain.fields().clone();
+        fields.insert(SETTLEMENT_SUITABILITY, scores);
+        terrain.set_fields(fields)
+    }
+}
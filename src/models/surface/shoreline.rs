@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use crate::core::{
+    traits::Site,
+    units::{Area, Elevation, Length},
+};
+
+use super::sites::Site2D;
+
+/// A closed coastline, the isocontour of the terrain at the sea level.
+///
+/// The land is always on the left of the coastline, so the coastlines around islands are counterclockwise
+/// and the coastlines around lakes (the sea enclosed by land, including the holes of the islands) are clockwise.
+/// Outside the convex hull of the sites is regarded as the sea, so the coastlines are closed along the hull.
+///
+/// ### Properties
+///  - `points` is the vertices of the coastline without repeating the first vertex.
+#[derive(Debug, Clone)]
+pub struct Shoreline2D {
+    points: Vec<Site2D>,
+}
+
+impl Shoreline2D {
+    pub fn points(&self) -> &[Site2D] {
+        &self.points
+    }
+
+    /// The signed area enclosed by the coastline, positive for islands and negative for lakes (unit: L^2).
+    pub fn signed_area(&self) -> Area {
+        let num = self.points.len();
+        (0..num)
+            .map(|k| {
+                let (a, b) = (&self.points[k], &self.points[(k + 1) % num]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f64>()
+            / 2.0
+    }
+
+    /// Whether the coastline surrounds an island (counterclockwise).
+    pub fn is_island(&self) -> bool {
+        self.signed_area() > 0.0
+    }
+
+    /// Whether the coastline surrounds a lake (clockwise).
+    pub fn is_lake(&self) -> bool {
+        self.signed_area() < 0.0
+    }
+
+    /// The length of the coastline.
+    pub fn length(&self) -> Length {
+        let num = self.points.len();
+        (0..num)
+            .map(|k| self.points[k].distance(&self.points[(k + 1) % num]))
+            .sum()
+    }
+}
+
+/// A vertex of the coastlines, a crossing on an edge of the triangles or a site on the convex hull.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ContourKey {
+    Crossing(usize, usize),
+    Site(usize),
+}
+
+/// Extract the coastlines by marching triangles, where the sites above `sea_level` are the land.
+pub(super) fn extract_shorelines(
+    sites: &[Site2D],
+    elevations: &[Elevation],
+    triangles: &[[usize; 3]],
+    sea_level: Elevation,
+) -> Vec<Shoreline2D> {
+    let is_land = |i: usize| elevations[i] > sea_level;
+    let crossing = |a: usize, b: usize| {
+        let key = ContourKey::Crossing(a.min(b), a.max(b));
+        let t = (sea_level - elevations[a]) / (elevations[b] - elevations[a]);
+        let t = if t.is_finite() {
+            t.clamp(0.0, 1.0)
+        } else {
+            0.5
+        };
+        let point = Site2D::new(
+            sites[a].x + (sites[b].x - sites[a].x) * t,
+            sites[a].y + (sites[b].y - sites[a].y) * t,
+        );
+        (key, point)
+    };
+
+    // the triangles in the counterclockwise order
+    let triangles = triangles
+        .iter()
+        .map(|&[a, b, c]| {
+            let (pa, pb, pc) = (&sites[a], &sites[b], &sites[c]);
+            if (pb.x - pa.x) * (pc.y - pa.y) - (pb.y - pa.y) * (pc.x - pa.x) < 0.0 {
+                [a, c, b]
+            } else {
+                [a, b, c]
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // the segments of the coastlines as (start, end, the position of the start), with the land on the left
+    let mut segments: Vec<(ContourKey, ContourKey, Site2D)> = Vec::new();
+    let mut edge_counts: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+    triangles.iter().for_each(|triangle| {
+        let edges = (0..3).map(|k| (triangle[k], triangle[(k + 1) % 3]));
+        edges.clone().for_each(|(a, b)| {
+            *edge_counts.entry((a.min(b), a.max(b))).or_default() += 1;
+        });
+        let start = edges.clone().find(|&(a, b)| is_land(a) && !is_land(b));
+        let end = edges.clone().find(|&(a, b)| !is_land(a) && is_land(b));
+        if let (Some(start), Some(end)) = (start, end) {
+            let (start, point) = crossing(start.0, start.1);
+            let (end, _) = crossing(end.0, end.1);
+            segments.push((start, end, point));
+        }
+    });
+
+    // close the coastlines along the convex hull, where the interior is on the left of the edges
+    triangles.iter().for_each(|triangle| {
+        (0..3)
+            .map(|k| (triangle[k], triangle[(k + 1) % 3]))
+            .filter(|&(a, b)| edge_counts[&(a.min(b), a.max(b))] == 1)
+            .for_each(|(a, b)| match (is_land(a), is_land(b)) {
+                (true, true) => segments.push((ContourKey::Site(a), ContourKey::Site(b), sites[a])),
+                (true, false) => segments.push((ContourKey::Site(a), crossing(a, b).0, sites[a])),
+                (false, true) => {
+                    let (start, point) = crossing(a, b);
+                    segments.push((start, ContourKey::Site(b), point));
+                }
+                (false, false) => {}
+            });
+    });
+
+    let next = segments
+        .iter()
+        .enumerate()
+        .map(|(s, &(start, _, _))| (start, s))
+        .collect::<BTreeMap<_, _>>();
+    let mut visited = vec![false; segments.len()];
+    (0..segments.len())
+        .filter_map(|first| {
+            if visited[first] {
+                return None;
+            }
+            let mut points = Vec::new();
+            let mut s = first;
+            while !visited[s] {
+                visited[s] = true;
+                points.push(segments[s].2);
+                match next.get(&segments[s].1) {
+                    Some(&t) => s = t,
+                    None => break,
+                }
+            }
+            (points.len() >= 3).then_some(Shoreline2D { points })
+        })
+        .collect()
+}
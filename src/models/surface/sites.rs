@@ -1,4 +1,5 @@
 use naturalneighbor::Point;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use crate::core::{traits::Site, units::Length};
 
@@ -32,4 +33,236 @@ impl Site for Site2D {
     fn squared_distance(&self, other: &Self) -> Length {
         (self.x - other.x).powi(2) + (self.y - other.y).powi(2)
     }
+
+    /// The direction counterclockwise from the x axis.
+    fn direction(&self, other: &Self) -> Option<f64> {
+        Some((other.y - self.y).atan2(other.x - self.x))
+    }
+}
+
+/// The distribution of the sites generated by [SiteGenerator2D].
+#[derive(Debug, Clone, Copy)]
+pub enum SiteDistribution2D {
+    /// The sites are uniformly random.
+    Uniform,
+    /// The sites are on a square grid, each displaced randomly by up to `jitter` times the spacing of the grid.
+    /// `jitter` is clamped from 0.0 to 0.5.
+    JitteredGrid { jitter: f64 },
+    /// The sites are on a hexagonal lattice, where each site is equidistant from its six neighbors.
+    HexLattice,
+    /// The sites are random but no two sites are closer than a minimum distance (Bridson's algorithm).
+    /// The minimum distance is chosen so that the number of the sites is close to the requested number.
+    PoissonDisk,
+    /// The sites are random with the density `1 / (1 + (d / falloff)^2)`, where `d` is the distance from `center`.
+    RadialFalloff { center: Site2D, falloff: Length },
+}
+
+/// Provides a deterministic generation of the sites in the bounding rectangle.
+///
+/// ### Properties
+///  - `bound_min` and `bound_max` are the bounding rectangle of the sites. The default values are (0.0, 0.0) and (100.0, 100.0).
+///  - `num_sites` is the number of the sites. The lattices have approximately this number of the sites,
+///    and `PoissonDisk` has at most this number of the sites. The default value is 1000.
+///  - `distribution` is the distribution of the sites (see [SiteDistribution2D]). The default value is `Uniform`.
+///  - `seed` is the seed of the random numbers. The default value is 0.
+#[derive(Debug, Clone)]
+pub struct SiteGenerator2D {
+    bound_min: Site2D,
+    bound_max: Site2D,
+    num_sites: usize,
+    distribution: SiteDistribution2D,
+    seed: u64,
+}
+
+impl Default for SiteGenerator2D {
+    fn default() -> Self {
+        Self {
+            bound_min: Site2D::new(0.0, 0.0),
+            bound_max: Site2D::new(100.0, 100.0),
+            num_sites: 1000,
+            distribution: SiteDistribution2D::Uniform,
+            seed: 0,
+        }
+    }
+}
+
+impl SiteGenerator2D {
+    pub fn set_bounding_box(mut self, bound_min: Site2D, bound_max: Site2D) -> Self {
+        self.bound_min = bound_min;
+        self.bound_max = bound_max;
+        self
+    }
+
+    pub fn set_num_sites(mut self, num_sites: usize) -> Self {
+        self.num_sites = num_sites;
+        self
+    }
+
+    pub fn set_distribution(mut self, distribution: SiteDistribution2D) -> Self {
+        self.distribution = distribution;
+        self
+    }
+
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn bounding_box(&self) -> (Site2D, Site2D) {
+        (self.bound_min, self.bound_max)
+    }
+
+    /// Generate the sites. The same settings always generate the same sites.
+    pub fn generate(&self) -> Vec<Site2D> {
+        let width = self.bound_max.x - self.bound_min.x;
+        let height = self.bound_max.y - self.bound_min.y;
+        if self.num_sites == 0 || width <= 0.0 || height <= 0.0 {
+            return Vec::new();
+        }
+        let mut rng: StdRng = SeedableRng::seed_from_u64(self.seed);
+        let random_site = |rng: &mut StdRng| {
+            Site2D::new(
+                self.bound_min.x + rng.gen::<f64>() * width,
+                self.bound_min.y + rng.gen::<f64>() * height,
+            )
+        };
+        let area = width * height;
+
+        match self.distribution {
+            SiteDistribution2D::Uniform => {
+                (0..self.num_sites).map(|_| random_site(&mut rng)).collect()
+            }
+            SiteDistribution2D::JitteredGrid { jitter } => {
+                let jitter = jitter.clamp(0.0, 0.5);
+                let spacing = (area / self.num_sites as f64).sqrt();
+                let (nx, ny) = (
+                    ((width / spacing).round() as usize).max(1),
+                    ((height / spacing).round() as usize).max(1),
+                );
+                let (dx, dy) = (width / nx as f64, height / ny as f64);
+                (0..ny)
+                    .flat_map(|iy| (0..nx).map(move |ix| (ix, iy)))
+                    .map(|(ix, iy)| {
+                        let (jx, jy) = (
+                            (rng.gen::<f64>() * 2.0 - 1.0) * jitter,
+                            (rng.gen::<f64>() * 2.0 - 1.0) * jitter,
+                        );
+                        Site2D::new(
+                            self.bound_min.x + (ix as f64 + 0.5 + jx) * dx,
+                            self.bound_min.y + (iy as f64 + 0.5 + jy) * dy,
+                        )
+                    })
+                    .collect()
+            }
+            SiteDistribution2D::HexLattice => {
+                // each site occupies the area of `spacing^2 * sqrt(3) / 2`
+                let spacing = (2.0 * area / (3.0_f64.sqrt() * self.num_sites as f64)).sqrt();
+                let row_height = spacing * 3.0_f64.sqrt() / 2.0;
+                let ny = ((height / row_height).round() as usize).max(1);
+                let nx = ((width / spacing).round() as usize).max(1);
+                let (dx, dy) = (width / nx as f64, height / ny as f64);
+                (0..ny)
+                    .flat_map(|iy| (0..nx).map(move |ix| (ix, iy)))
+                    .map(|(ix, iy)| {
+                        let offset = if iy % 2 == 0 { 0.25 } else { 0.75 };
+                        Site2D::new(
+                            self.bound_min.x + (ix as f64 + offset) * dx,
+                            self.bound_min.y + (iy as f64 + 0.5) * dy,
+                        )
+                    })
+                    .collect()
+            }
+            SiteDistribution2D::PoissonDisk => self.poisson_disk(&mut rng),
+            SiteDistribution2D::RadialFalloff { center, falloff } => {
+                let mut sites = Vec::with_capacity(self.num_sites);
+                while sites.len() < self.num_sites {
+                    let site = random_site(&mut rng);
+                    let r = if falloff > 0.0 {
+                        site.distance(&center) / falloff
+                    } else {
+                        0.0
+                    };
+                    if rng.gen::<f64>() < 1.0 / (1.0 + r * r) {
+                        sites.push(site);
+                    }
+                }
+                sites
+            }
+        }
+    }
+
+    /// The maximal Poisson disk sampling by Bridson's algorithm.
+    fn poisson_disk(&self, rng: &mut StdRng) -> Vec<Site2D> {
+        const NUM_CANDIDATES: usize = 30;
+        let width = self.bound_max.x - self.bound_min.x;
+        let height = self.bound_max.y - self.bound_min.y;
+        // the maximal sampling has about 0.7 sites per the square of the minimum distance
+        let radius = (0.7 * width * height / self.num_sites as f64).sqrt();
+        let cell_size = radius / 2.0_f64.sqrt();
+        let (nx, ny) = (
+            (width / cell_size).ceil() as usize,
+            (height / cell_size).ceil() as usize,
+        );
+        let cell_of = |site: &Site2D| {
+            (
+                (((site.x - self.bound_min.x) / cell_size) as usize).min(nx - 1),
+                (((site.y - self.bound_min.y) / cell_size) as usize).min(ny - 1),
+            )
+        };
+        let mut grid: Vec<Option<usize>> = vec![None; nx * ny];
+        let mut sites = Vec::new();
+        let mut active = Vec::new();
+
+        let first = Site2D::new(
+            self.bound_min.x + rng.gen::<f64>() * width,
+            self.bound_min.y + rng.gen::<f64>() * height,
+        );
+        let (cx, cy) = cell_of(&first);
+        grid[cy * nx + cx] = Some(0);
+        sites.push(first);
+        active.push(0);
+
+        while !active.is_empty() && sites.len() < self.num_sites {
+            let k = rng.gen_range(0..active.len());
+            let origin = sites[active[k]];
+            let candidate = (0..NUM_CANDIDATES).find_map(|_| {
+                let angle = rng.gen::<f64>() * std::f64::consts::TAU;
+                let distance = radius * (1.0 + rng.gen::<f64>());
+                let site = Site2D::new(
+                    origin.x + distance * angle.cos(),
+                    origin.y + distance * angle.sin(),
+                );
+                if site.x < self.bound_min.x
+                    || site.x >= self.bound_max.x
+                    || site.y < self.bound_min.y
+                    || site.y >= self.bound_max.y
+                {
+                    return None;
+                }
+                let (cx, cy) = cell_of(&site);
+                let is_far = (cy.saturating_sub(2)..(cy + 3).min(ny)).all(|gy| {
+                    (cx.saturating_sub(2)..(cx + 3).min(nx)).all(|gx| match grid[gy * nx + gx] {
+                        Some(j) => sites[j].distance(&site) >= radius,
+                        None => true,
+                    })
+                });
+                if is_far {
+                    Some((site, cx, cy))
+                } else {
+                    None
+                }
+            });
+            match candidate {
+                Some((site, cx, cy)) => {
+                    grid[cy * nx + cx] = Some(sites.len());
+                    active.push(sites.len());
+                    sites.push(site);
+                }
+                None => {
+                    active.swap_remove(k);
+                }
+            }
+        }
+        sites
+    }
 }
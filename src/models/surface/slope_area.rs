@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use crate::core::{traits::Site, units::Area};
+
+use super::terrain::Terrain2D;
+
+/// The result of the regression of the channel slope against the drainage area, `S = steepness * A^-concavity`.
+///
+/// ### Properties
+///  - `outlet` is the index of the outlet of the drainage basin, or `None` for the whole terrain.
+///  - `num_sites` is the number of the channel sites used for the regression.
+///  - `concavity` is the concavity index θ, which equals `m / n` of the stream power law at the steady state.
+///  - `steepness` is the steepness index `k_s`, which equals `(U / K)^(1/n)` at the steady state.
+///  - `r_squared` is the coefficient of determination of the regression over the bins.
+#[derive(Debug, Clone)]
+pub struct SlopeAreaRegression {
+    pub outlet: Option<usize>,
+    pub num_sites: usize,
+    pub concavity: f64,
+    pub steepness: f64,
+    pub r_squared: f64,
+}
+
+/// Provides a slope-area analysis of the channels of the terrain.
+///
+/// The channel sites with the positive slope to their receivers are binned by the logarithm of the drainage area,
+/// and the mean logarithm of the slope of each bin is regressed against the mean logarithm of the area by least squares.
+/// This verifies that the generated terrain reproduces the exponents of the stream power law.
+///
+/// ### Properties
+///  - `min_drainage_area` is the drainage area above which the sites are regarded as channels (unit: L^2). The default value is 100.0.
+///  - `num_bins` is the number of the bins of the logarithm of the drainage area. The default value is 10.
+///  - `min_sites` is the minimum number of the channel sites of a drainage basin to be analyzed. The default value is 10.
+#[derive(Debug, Clone)]
+pub struct SlopeAreaAnalysis2D {
+    min_drainage_area: Area,
+    num_bins: usize,
+    min_sites: usize,
+}
+
+impl Default for SlopeAreaAnalysis2D {
+    fn default() -> Self {
+        Self {
+            min_drainage_area: 100.0,
+            num_bins: 10,
+            min_sites: 10,
+        }
+    }
+}
+
+impl SlopeAreaAnalysis2D {
+    pub fn set_min_drainage_area(mut self, min_drainage_area: Area) -> Self {
+        self.min_drainage_area = min_drainage_area;
+        self
+    }
+
+    pub fn set_num_bins(mut self, num_bins: usize) -> Self {
+        self.num_bins = num_bins.max(2);
+        self
+    }
+
+    pub fn set_min_sites(mut self, min_sites: usize) -> Self {
+        self.min_sites = min_sites;
+        self
+    }
+
+    /// The pairs of the logarithm of the drainage area and the slope of the channel sites, with the outlet of their basins.
+    fn samples(&self, terrain: &Terrain2D) -> Vec<(usize, f64, f64)> {
+        let network = terrain.network();
+        let sites = terrain.sites();
+        let elevations = terrain.elevations();
+        let receivers = network.receivers();
+        let drainage_areas = network.drainage_areas();
+
+        // the outlet of each site, found by following the receivers with memoization
+        let mut outlets = vec![usize::MAX; receivers.len()];
+        (0..receivers.len()).for_each(|start| {
+            let mut path = Vec::new();
+            let mut i = start;
+            while outlets[i] == usize::MAX && receivers[i] != i {
+                path.push(i);
+                i = receivers[i];
+            }
+            let outlet = if outlets[i] == usize::MAX {
+                i
+            } else {
+                outlets[i]
+            };
+            outlets[i] = outlet;
+            path.into_iter().for_each(|k| outlets[k] = outlet);
+        });
+
+        (0..receivers.len())
+            .filter_map(|i| {
+                let j = receivers[i];
+                if j == i || drainage_areas[i] < self.min_drainage_area {
+                    return None;
+                }
+                let distance = sites[i].distance(&sites[j]);
+                let slope = (elevations[i] - elevations[j]) / distance;
+                if distance > 0.0 && slope > 0.0 && drainage_areas[i].is_finite() {
+                    Some((outlets[i], drainage_areas[i].ln(), slope.ln()))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Regress the binned samples of the logarithms of the area and the slope.
+    fn regress(
+        &self,
+        outlet: Option<usize>,
+        samples: &[(f64, f64)],
+    ) -> Option<SlopeAreaRegression> {
+        let (min, max) = samples
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), s| {
+                (min.min(s.0), max.max(s.0))
+            });
+        if samples.is_empty() || max <= min {
+            return None;
+        }
+        let width = (max - min) / self.num_bins as f64;
+        let mut bins = vec![(0.0, 0.0, 0usize); self.num_bins];
+        samples.iter().for_each(|&(log_area, log_slope)| {
+            let b = (((log_area - min) / width) as usize).min(self.num_bins - 1);
+            bins[b].0 += log_area;
+            bins[b].1 += log_slope;
+            bins[b].2 += 1;
+        });
+        let points = bins
+            .into_iter()
+            .filter(|bin| bin.2 > 0)
+            .map(|(x, y, n)| (x / n as f64, y / n as f64))
+            .collect::<Vec<_>>();
+        if points.len() < 2 {
+            return None;
+        }
+
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let sxx = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum::<f64>();
+        let sxy = points
+            .iter()
+            .map(|p| (p.0 - mean_x) * (p.1 - mean_y))
+            .sum::<f64>();
+        let syy = points.iter().map(|p| (p.1 - mean_y).powi(2)).sum::<f64>();
+        let gradient = sxy / sxx;
+        let intercept = mean_y - gradient * mean_x;
+        let r_squared = if syy > 0.0 {
+            (sxy * sxy) / (sxx * syy)
+        } else {
+            1.0
+        };
+
+        Some(SlopeAreaRegression {
+            outlet,
+            num_sites: samples.len(),
+            concavity: -gradient,
+            steepness: intercept.exp(),
+            r_squared,
+        })
+    }
+
+    /// Analyze each drainage basin with at least `min_sites` channel sites, in the order of the index of the outlets.
+    ///
+    /// This returns an empty list if the terrain has no drainage network.
+    pub fn analyze_basins(&self, terrain: &Terrain2D) -> Vec<SlopeAreaRegression> {
+        if terrain.network().is_empty() {
+            return Vec::new();
+        }
+        let mut basins = BTreeMap::new();
+        self.samples(terrain)
+            .into_iter()
+            .for_each(|(outlet, log_area, log_slope)| {
+                basins
+                    .entry(outlet)
+                    .or_insert_with(Vec::new)
+                    .push((log_area, log_slope));
+            });
+        basins
+            .into_iter()
+            .filter(|(_, points)| points.len() >= self.min_sites.max(1))
+            .filter_map(|(outlet, points)| self.regress(Some(outlet), &points))
+            .collect()
+    }
+
+    /// Analyze all the channel sites of the terrain together.
+    ///
+    /// This returns `None` if the terrain has no drainage network or the drainage areas of the channels are not spread.
+    pub fn analyze(&self, terrain: &Terrain2D) -> Option<SlopeAreaRegression> {
+        if terrain.network().is_empty() {
+            return None;
+        }
+        let points = self
+            .samples(terrain)
+            .into_iter()
+            .map(|s| (s.1, s.2))
+            .collect::<Vec<_>>();
+        self.regress(None, &points)
+    }
+}
@@ -0,0 +1,176 @@
+use std::f64::consts::PI;
+
+use crate::core::{
+    fields::SOLAR_EXPOSURE,
+    units::{Elevation, Length},
+};
+
+use super::{sites::Site2D, terrain::Terrain2D};
+
+/// The obliquity of the ecliptic (unit: rad).
+const OBLIQUITY: f64 = 23.44 * PI / 180.0;
+
+/// The number of the samples of the hour angle in a day.
+const NUM_HOURS: usize = 48;
+
+/// The declinations of the sun sampled over a year (the solstices and the equinoxes).
+const DECLINATIONS: [f64; 4] = [-OBLIQUITY, 0.0, OBLIQUITY, 0.0];
+
+/// Provides a proxy of the annual insolation of each site from its slope, aspect and latitude.
+///
+/// The gradient of each site is estimated by the central differences of the interpolated elevations at `sample_distance`,
+/// where the x axis points to the east and the y axis points to the north.
+/// The aspect is binned into `aspect_bins` sectors (no binning if 0), so that the exposure is uniform over the slopes
+/// facing roughly the same direction, which is what vegetation and snow placement usually expect.
+/// The insolation is the direct irradiance on the surface integrated over the daytime at the solstices and the equinoxes,
+/// ignoring the atmosphere and the shadows cast by the surrounding terrain (see [super::wind::WindExposure2D] for the horizon angles).
+/// It is normalized by the insolation of a flat surface on the equator.
+///
+/// ### Properties
+///  - `latitude` is the latitude at `y = 0` (unit: degree, positive in the northern hemisphere). The default value is 45.0.
+///  - `latitude_gradient` is the increase of the latitude per unit distance along the y axis (unit: degree/L). The default value is 0.0.
+///  - `sample_distance` is the distance of the samples for the gradient (unit: L). The default value is 1.0.
+///  - `aspect_bins` is the number of the sectors of the aspect. The default value is 8.
+#[derive(Debug, Clone)]
+pub struct SolarExposure2D {
+    latitude: f64,
+    latitude_gradient: f64,
+    sample_distance: Length,
+    aspect_bins: usize,
+}
+
+impl Default for SolarExposure2D {
+    fn default() -> Self {
+        Self {
+            latitude: 45.0,
+            latitude_gradient: 0.0,
+            sample_distance: 1.0,
+            aspect_bins: 8,
+        }
+    }
+}
+
+impl SolarExposure2D {
+    pub fn set_latitude(mut self, latitude: f64) -> Self {
+        self.latitude = latitude;
+        self
+    }
+
+    pub fn set_latitude_gradient(mut self, latitude_gradient: f64) -> Self {
+        self.latitude_gradient = latitude_gradient;
+        self
+    }
+
+    pub fn set_sample_distance(mut self, sample_distance: Length) -> Self {
+        self.sample_distance = sample_distance;
+        self
+    }
+
+    pub fn set_aspect_bins(mut self, aspect_bins: usize) -> Self {
+        self.aspect_bins = aspect_bins;
+        self
+    }
+
+    /// The slope (unit: rad) and the aspect (unit: rad, clockwise from the north) of each site.
+    ///
+    /// The aspect is the direction the slope faces, and it is binned by `aspect_bins`. The aspect of a flat site is 0.0.
+    pub fn slopes_and_aspects(&self, terrain: &Terrain2D) -> Vec<(f64, f64)> {
+        terrain
+            .sites()
+            .iter()
+            .zip(terrain.elevations().iter())
+            .map(|(site, &elevation)| {
+                let (gx, gy) = self.gradient(terrain, site, elevation);
+                let slope = gx.hypot(gy).atan();
+                if slope <= 0.0 {
+                    return (0.0, 0.0);
+                }
+                // the slope faces the opposite of the gradient
+                let aspect = (-gx).atan2(-gy).rem_euclid(2.0 * PI);
+                (slope, self.bin_aspect(aspect))
+            })
+            .collect()
+    }
+
+    /// The relative annual insolation of each site.
+    pub fn exposures(&self, terrain: &Terrain2D) -> Vec<f64> {
+        let reference = insolation(0.0, 0.0, 0.0);
+        self.slopes_and_aspects(terrain)
+            .iter()
+            .zip(terrain.sites().iter())
+            .map(|(&(slope, aspect), site)| {
+                let latitude = (self.latitude + self.latitude_gradient * site.y).clamp(-90.0, 90.0);
+                insolation(latitude.to_radians(), slope, aspect) / reference
+            })
+            .collect()
+    }
+
+    /// Attach the exposures to the terrain as the field [SOLAR_EXPOSURE].
+    pub fn attach(&self, terrain: Terrain2D) -> Terrain2D {
+        let exposures = self.exposures(&terrain);
+        let mut fields = terrain.fields().clone();
+        fields.insert(SOLAR_EXPOSURE, exposures);
+        terrain.set_fields(fields)
+    }
+
+    /// The gradient of the elevation at the site, using one-sided differences where the samples are outside the terrain.
+    fn gradient(&self, terrain: &Terrain2D, site: &Site2D, elevation: Elevation) -> (f64, f64) {
+        let d = self.sample_distance.max(f64::EPSILON);
+        let derivative = |dx: f64, dy: f64| {
+            let forward = terrain
+                .get_elevation(&Site2D::new(site.x + dx, site.y + dy))
+                .filter(|e| e.is_finite());
+            let backward = terrain
+                .get_elevation(&Site2D::new(site.x - dx, site.y - dy))
+                .filter(|e| e.is_finite());
+            match (forward, backward) {
+                (Some(f), Some(b)) => (f - b) / (2.0 * d),
+                (Some(f), None) => (f - elevation) / d,
+                (None, Some(b)) => (elevation - b) / d,
+                (None, None) => 0.0,
+            }
+        };
+        (derivative(d, 0.0), derivative(0.0, d))
+    }
+
+    fn bin_aspect(&self, aspect: f64) -> f64 {
+        if self.aspect_bins == 0 {
+            return aspect;
+        }
+        let width = 2.0 * PI / self.aspect_bins as f64;
+        ((aspect / width).round() % self.aspect_bins as f64) * width
+    }
+}
+
+/// The direct irradiance on the surface averaged over the hours of the sampled days.
+fn insolation(latitude: f64, slope: f64, aspect: f64) -> f64 {
+    // the unit normal of the surface in (east, north, up)
+    let normal = [
+        slope.sin() * aspect.sin(),
+        slope.sin() * aspect.cos(),
+        slope.cos(),
+    ];
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let total = DECLINATIONS
+        .iter()
+        .map(|&declination| {
+            let (sin_dec, cos_dec) = f64::sin_cos(declination);
+            (0..NUM_HOURS)
+                .map(|k| {
+                    let hour_angle = (k as f64 + 0.5) / NUM_HOURS as f64 * 2.0 * PI - PI;
+                    let (sin_h, cos_h) = hour_angle.sin_cos();
+                    let sun = [
+                        -cos_dec * sin_h,
+                        cos_lat * sin_dec - sin_lat * cos_dec * cos_h,
+                        sin_lat * sin_dec + cos_lat * cos_dec * cos_h,
+                    ];
+                    if sun[2] <= 0.0 {
+                        return 0.0;
+                    }
+                    (normal[0] * sun[0] + normal[1] * sun[1] + normal[2] * sun[2]).max(0.0)
+                })
+                .sum::<f64>()
+        })
+        .sum::<f64>();
+    total / (DECLINATIONS.len() * NUM_HOURS) as f64
+}
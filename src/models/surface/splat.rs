@@ -0,0 +1,229 @@
+use crate::core::{
+    fields::{CHANNEL_STEEPNESS, DEPOSIT_THICKNESS, FLOODPLAIN},
+    units::{Area, Elevation, Length},
+};
+
+use super::{raster::Rasterizer2D, terrain::Terrain2D};
+
+/// The number of the channels of the splat maps: rock, sediment, wetland and soil.
+pub const NUM_SPLAT_CHANNELS: usize = 4;
+
+/// A multi-channel image of the weights of the materials of a terrain.
+///
+/// The channels are rock, sediment, wetland and soil in this order, and the weights of each pixel sum to 1.0.
+/// The pixels are stored in the row-major order, and the row `y` = 0 is at the side of `bound_min.y` as in [super::raster::Raster2D].
+/// The pixels outside the terrain are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplatMap2D {
+    width: usize,
+    height: usize,
+    weights: Vec<Option<[f64; NUM_SPLAT_CHANNELS]>>,
+}
+
+impl SplatMap2D {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn weights(&self) -> &[Option<[f64; NUM_SPLAT_CHANNELS]>] {
+        &self.weights
+    }
+
+    /// Get the weights of the pixel.
+    pub fn get(&self, x: usize, y: usize) -> Option<[f64; NUM_SPLAT_CHANNELS]> {
+        if x < self.width && y < self.height {
+            self.weights[y * self.width + x]
+        } else {
+            None
+        }
+    }
+
+    /// Encode the weights as 8-bit RGBA pixels (rock, sediment, wetland, soil) with the top row (the side of `bound_max.y`) first,
+    /// which is the layout of the splat textures of most engines. The pixels outside the terrain are all 0.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        (0..self.height)
+            .rev()
+            .flat_map(|y| self.weights[y * self.width..(y + 1) * self.width].iter())
+            .flat_map(|weights| {
+                weights
+                    .unwrap_or_default()
+                    .map(|weight| (weight.clamp(0.0, 1.0) * 255.0).round() as u8)
+            })
+            .collect()
+    }
+}
+
+/// Provides a generation of the splat maps of a terrain from the fields of the simulation.
+///
+/// The raw weights of each site are:
+///  - rock: the steepness `(s - min_rock_slope) / (max_rock_slope - min_rock_slope)` clamped from 0.0 to 1.0, where `s` is the slope of the site
+///    (the mean gradient of the triangles around it), or the field [CHANNEL_STEEPNESS] multiplied by `erosion_weight` if it is larger,
+///    so the bedrock is exposed on the cliffs and on the channels incised by the erosion.
+///  - sediment: the field [DEPOSIT_THICKNESS] divided by `sediment_thickness` and clamped to 1.0, or the field [FLOODPLAIN] if it is larger.
+///    The sites below `sea_level` are covered by the sediment.
+///  - wetland: the flatness `1 - rock` of the sites whose drainage area is not less than `min_drainage_area`, 0.0 elsewhere.
+///    The interpolation into the pixels spreads the wetlands from the channels to their banks.
+///  - soil: `1 - max(rock, sediment, wetland)`, so the soil covers the rest.
+///
+/// The raw weights are interpolated at each sample of the rasterizer and normalized to sum to 1.0.
+/// The fields missing from the terrain are regarded as 0.0, and the wetlands require the drainage network.
+///
+/// ### Properties
+///  - `min_rock_slope` is the slope at which the rock starts to appear. The default value is 0.3.
+///  - `max_rock_slope` is the slope at which the rock covers the surface. The default value is 0.8.
+///  - `erosion_weight` is the factor multiplied to the field [CHANNEL_STEEPNESS] for the rock. The default value is 1.0.
+///  - `sediment_thickness` is the thickness of the deposits covering the surface (unit: L). The default value is 1.0.
+///  - `min_drainage_area` is the drainage area above which the sites are channels (unit: L^2). The default value is 100.0.
+///  - `sea_level` is the sea level (unit: L). The default value is 0.0.
+#[derive(Debug, Clone)]
+pub struct SplatGenerator2D {
+    min_rock_slope: f64,
+    max_rock_slope: f64,
+    erosion_weight: f64,
+    sediment_thickness: Length,
+    min_drainage_area: Area,
+    sea_level: Elevation,
+}
+
+impl Default for SplatGenerator2D {
+    fn default() -> Self {
+        Self {
+            min_rock_slope: 0.3,
+            max_rock_slope: 0.8,
+            erosion_weight: 1.0,
+            sediment_thickness: 1.0,
+            min_drainage_area: 100.0,
+            sea_level: 0.0,
+        }
+    }
+}
+
+impl SplatGenerator2D {
+    pub fn set_min_rock_slope(mut self, min_rock_slope: f64) -> Self {
+        self.min_rock_slope = min_rock_slope;
+        self
+    }
+
+    pub fn set_max_rock_slope(mut self, max_rock_slope: f64) -> Self {
+        self.max_rock_slope = max_rock_slope;
+        self
+    }
+
+    pub fn set_erosion_weight(mut self, erosion_weight: f64) -> Self {
+        self.erosion_weight = erosion_weight.max(0.0);
+        self
+    }
+
+    pub fn set_sediment_thickness(mut self, sediment_thickness: Length) -> Self {
+        self.sediment_thickness = sediment_thickness.max(f64::EPSILON);
+        self
+    }
+
+    pub fn set_min_drainage_area(mut self, min_drainage_area: Area) -> Self {
+        self.min_drainage_area = min_drainage_area;
+        self
+    }
+
+    pub fn set_sea_level(mut self, sea_level: Elevation) -> Self {
+        self.sea_level = sea_level;
+        self
+    }
+
+    /// The slope of each site, the mean of the gradients of the triangles around it weighted by their areas.
+    ///
+    /// The sites not in any triangle are 0.0.
+    pub fn slopes(&self, terrain: &Terrain2D) -> Vec<f64> {
+        let sites = terrain.sites();
+        let elevations = terrain.elevations();
+        let mut sums = vec![(0.0, 0.0); sites.len()];
+        terrain.triangles().iter().for_each(|&[a, b, c]| {
+            let (pa, pb, pc) = (&sites[a], &sites[b], &sites[c]);
+            let det = (pb.x - pa.x) * (pc.y - pa.y) - (pb.y - pa.y) * (pc.x - pa.x);
+            if det == 0.0 {
+                return;
+            }
+            let (da, db, dc) = (elevations[a], elevations[b], elevations[c]);
+            let gx = ((db - da) * (pc.y - pa.y) - (dc - da) * (pb.y - pa.y)) / det;
+            let gy = ((dc - da) * (pb.x - pa.x) - (db - da) * (pc.x - pa.x)) / det;
+            let gradient = gx.hypot(gy);
+            if !gradient.is_finite() {
+                return;
+            }
+            let area = det.abs() / 2.0;
+            [a, b, c].iter().for_each(|&i| {
+                sums[i].0 += gradient * area;
+                sums[i].1 += area;
+            });
+        });
+        sums.iter()
+            .map(|&(sum, area)| if area > 0.0 { sum / area } else { 0.0 })
+            .collect()
+    }
+
+    /// The raw weights of the channels of each site before the normalization.
+    pub fn site_weights(&self, terrain: &Terrain2D) -> Vec<[f64; NUM_SPLAT_CHANNELS]> {
+        let elevations = terrain.elevations();
+        let fields = terrain.fields();
+        let network = terrain.network();
+        let slopes = self.slopes(terrain);
+        let steepness = fields.get(CHANNEL_STEEPNESS);
+        let thickness = fields.get(DEPOSIT_THICKNESS);
+        let floodplain = fields.get(FLOODPLAIN);
+        let slope_range = (self.max_rock_slope - self.min_rock_slope).max(f64::EPSILON);
+        (0..elevations.len())
+            .map(|i| {
+                if elevations[i] < self.sea_level {
+                    return [0.0, 1.0, 0.0, 0.0];
+                }
+                let rock = ((slopes[i] - self.min_rock_slope) / slope_range)
+                    .clamp(0.0, 1.0)
+                    .max(steepness.map_or(0.0, |s| (s[i] * self.erosion_weight).clamp(0.0, 1.0)));
+                let sediment = thickness
+                    .map_or(0.0, |t| (t[i] / self.sediment_thickness).clamp(0.0, 1.0))
+                    .max(floodplain.map_or(0.0, |f| f[i].clamp(0.0, 1.0)));
+                let is_channel =
+                    !network.is_empty() && network.drainage_areas()[i] >= self.min_drainage_area;
+                let wetland = if is_channel { 1.0 - rock } else { 0.0 };
+                let soil = 1.0 - rock.max(sediment).max(wetland);
+                [rock, sediment, wetland, soil]
+            })
+            .collect()
+    }
+
+    /// Generate the splat map of the terrain in the grid of the rasterizer.
+    ///
+    /// The supersampling of the rasterizer is applied to each channel; the channel carver and the vertical scale are not used.
+    pub fn generate(&self, rasterizer: &Rasterizer2D, terrain: &Terrain2D) -> SplatMap2D {
+        let site_weights = self.site_weights(terrain);
+        let channels = (0..NUM_SPLAT_CHANNELS)
+            .map(|c| {
+                let values = site_weights.iter().map(|w| w[c]).collect::<Vec<_>>();
+                rasterizer.rasterize(|site| terrain.interpolate(&values, site))
+            })
+            .collect::<Vec<_>>();
+        let (width, height) = rasterizer.size();
+        let weights = (0..width * height)
+            .map(|k| {
+                let mut weights = [0.0; NUM_SPLAT_CHANNELS];
+                for (c, channel) in channels.iter().enumerate() {
+                    weights[c] = channel.values()[k]?.max(0.0);
+                }
+                let sum = weights.iter().sum::<f64>();
+                if sum > 0.0 {
+                    Some(weights.map(|w| w / sum))
+                } else {
+                    Some([0.0, 0.0, 0.0, 1.0])
+                }
+            })
+            .collect();
+        SplatMap2D {
+            width,
+            height,
+            weights,
+        }
+    }
+}
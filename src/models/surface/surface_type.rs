@@ -0,0 +1,147 @@
+use crate::core::{
+    fields::ICE,
+    units::{Area, Elevation, Length},
+};
+
+use super::terrain::Terrain2D;
+
+/// A type of the surface of a site.
+///
+/// Each type has a stable code (its discriminant), which is used as the value of the rasterized classification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SurfaceType {
+    Land = 0,
+    River = 1,
+    Lake = 2,
+    Ocean = 3,
+    Ice = 4,
+}
+
+impl SurfaceType {
+    /// The code of the type.
+    pub fn code(&self) -> u8 {
+        *self as u8
+    }
+
+    /// The type of the code, or `None` if the code is unknown.
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(SurfaceType::Land),
+            1 => Some(SurfaceType::River),
+            2 => Some(SurfaceType::Lake),
+            3 => Some(SurfaceType::Ocean),
+            4 => Some(SurfaceType::Ice),
+            _ => None,
+        }
+    }
+}
+
+/// Provides a classification of the surface of the terrain into land, river, lake, ocean and ice in one pass.
+///
+/// The type of each site is decided in the following order of precedence:
+///  - [SurfaceType::Ocean] if the site is below `sea_level`.
+///  - [SurfaceType::Ice] if the field [ICE] of the terrain marks the site (see [crate::lem::processes::ice::IceProcess]),
+///    or the site is not below `snowline`. The frozen lakes and rivers are also ice.
+///  - [SurfaceType::Lake] if the water level of the site (see [SurfaceClassifier2D::water_levels]) is deeper than `min_lake_depth`.
+///  - [SurfaceType::River] if the drainage area of the site is not less than `min_drainage_area`,
+///    the same threshold as `Terrain2D::extract_rivers`.
+///  - [SurfaceType::Land] otherwise.
+///
+/// The lakes and the rivers require the drainage network of the terrain; without it, such sites are land.
+///
+/// ### Properties
+///  - `sea_level` is the sea level (unit: L). The default value is 0.0.
+///  - `min_drainage_area` is the drainage area above which the sites are rivers (unit: L^2). The default value is 100.0.
+///  - `min_lake_depth` is the depth of the water above which the sites are lakes (unit: L). The default value is 0.0.
+///  - `snowline` is the elevation above which the sites are covered by ice (unit: L). If `None`, only the field [ICE] is used.
+#[derive(Debug, Clone)]
+pub struct SurfaceClassifier2D {
+    sea_level: Elevation,
+    min_drainage_area: Area,
+    min_lake_depth: Length,
+    snowline: Option<Elevation>,
+}
+
+impl Default for SurfaceClassifier2D {
+    fn default() -> Self {
+        Self {
+            sea_level: 0.0,
+            min_drainage_area: 100.0,
+            min_lake_depth: 0.0,
+            snowline: None,
+        }
+    }
+}
+
+impl SurfaceClassifier2D {
+    pub fn set_sea_level(mut self, sea_level: Elevation) -> Self {
+        self.sea_level = sea_level;
+        self
+    }
+
+    pub fn set_min_drainage_area(mut self, min_drainage_area: Area) -> Self {
+        self.min_drainage_area = min_drainage_area;
+        self
+    }
+
+    pub fn set_min_lake_depth(mut self, min_lake_depth: Length) -> Self {
+        self.min_lake_depth = min_lake_depth.max(0.0);
+        self
+    }
+
+    pub fn set_snowline(mut self, snowline: Option<Elevation>) -> Self {
+        self.snowline = snowline;
+        self
+    }
+
+    /// The level of the water surface of each site (unit: L).
+    ///
+    /// The drainage network routes the flow out of the depressions across their lowest passes, so the water fills
+    /// each depression up to the highest site on the path to the outlet. The sites out of the depressions are at their elevations.
+    /// If the terrain has no drainage network, all the sites are at their elevations.
+    pub fn water_levels(&self, terrain: &Terrain2D) -> Vec<Elevation> {
+        let mut levels = terrain.elevations().to_vec();
+        let network = terrain.network();
+        if network.is_empty() {
+            return levels;
+        }
+        // the receivers come before their donors
+        network.order().iter().for_each(|&i| {
+            let j = network.receivers()[i];
+            if j != i && levels[j] > levels[i] {
+                levels[i] = levels[j];
+            }
+        });
+        levels
+    }
+
+    /// Classify the surface type of each site.
+    pub fn classify(&self, terrain: &Terrain2D) -> Vec<SurfaceType> {
+        let elevations = terrain.elevations();
+        let network = terrain.network();
+        let levels = self.water_levels(terrain);
+        let ice = terrain.fields().get(ICE);
+        (0..elevations.len())
+            .map(|i| {
+                if elevations[i] < self.sea_level {
+                    SurfaceType::Ocean
+                } else if ice.is_some_and(|ice| ice[i] > 0.5)
+                    || self
+                        .snowline
+                        .is_some_and(|snowline| elevations[i] >= snowline)
+                {
+                    SurfaceType::Ice
+                } else if levels[i] - elevations[i] > self.min_lake_depth {
+                    SurfaceType::Lake
+                } else if !network.is_empty()
+                    && network.drainage_areas()[i] >= self.min_drainage_area
+                {
+                    SurfaceType::River
+                } else {
+                    SurfaceType::Land
+                }
+            })
+            .collect()
+    }
+}
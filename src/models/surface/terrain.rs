@@ -1,6 +1,20 @@
-use crate::core::units::Elevation;
+use crate::core::{
+    fields::SiteFields,
+    network::DrainageNetwork,
+    units::{Area, Elevation, Length},
+};
 
-use super::{interpolator::TerrainInterpolator2D, sites::Site2D};
+use super::{
+    interpolator::TerrainInterpolator2D,
+    model::TerrainModel2D,
+    quantized::QuantizedElevations,
+    resample::resample,
+    river::{extract_rivers, River2D},
+    shoreline::{extract_shorelines, Shoreline2D},
+    sites::Site2D,
+    triangulation::Triangulation2D,
+    voxel::{voxelize, VoxelColumns2D},
+};
 
 /// Represents the result of terrain generation includeing the pair of sites and result Elevations.
 /// Terrain2D also provides a method for query the interpolated elevations.
@@ -8,6 +22,9 @@ use super::{interpolator::TerrainInterpolator2D, sites::Site2D};
 pub struct Terrain2D {
     sites: Vec<Site2D>,
     elevations: Vec<Elevation>,
+    fields: SiteFields,
+    network: DrainageNetwork,
+    triangulation: Triangulation2D,
     interpolator: TerrainInterpolator2D,
 }
 
@@ -20,10 +37,28 @@ impl Terrain2D {
         Self {
             sites,
             elevations,
+            fields: SiteFields::default(),
+            network: DrainageNetwork::default(),
+            triangulation: Triangulation2D::default(),
             interpolator,
         }
     }
 
+    pub(crate) fn set_fields(mut self, fields: SiteFields) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    pub(crate) fn set_network(mut self, network: DrainageNetwork) -> Self {
+        self.network = network;
+        self
+    }
+
+    pub(crate) fn set_triangles(mut self, triangles: Vec<[usize; 3]>) -> Self {
+        self.triangulation = Triangulation2D::new(triangles);
+        self
+    }
+
     pub fn sites(&self) -> &[Site2D] {
         &self.sites
     }
@@ -32,8 +67,94 @@ impl Terrain2D {
         &self.elevations
     }
 
+    /// Get the elevations quantized into 16-bit unsigned integers over their range (see [QuantizedElevations]).
+    pub fn quantized_elevations(&self) -> QuantizedElevations {
+        QuantizedElevations::from_elevations(&self.elevations)
+    }
+
+    /// Get the additional fields produced by the simulation (see [SiteFields]).
+    pub fn fields(&self) -> &SiteFields {
+        &self.fields
+    }
+
+    /// Get the drainage network produced by the simulation (see [DrainageNetwork]).
+    ///
+    /// This is empty if the terrain is not created by the simulation.
+    pub fn network(&self) -> &DrainageNetwork {
+        &self.network
+    }
+
+    /// Get the Delaunay triangles of the sites, as the triples of the indices of the sites.
+    ///
+    /// This is empty if the terrain is not created from a model.
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        self.triangulation.triangles()
+    }
+
+    /// Locate the triangle containing the site, returning the index of the triangle in [Terrain2D::triangles]
+    /// and the barycentric coordinates of the site for the vertices of the triangle.
+    ///
+    /// This returns `None` if the site is outside the convex hull of the sites.
+    pub fn locate(&self, site: &Site2D) -> Option<(usize, [f64; 3])> {
+        self.triangulation.locate(&self.sites, site)
+    }
+
     /// Get interpolated elevation.
     pub fn get_elevation(&self, site: &Site2D) -> Option<Elevation> {
         self.interpolator.interpolate(&self.elevations, site)
     }
+
+    /// Get the interpolated value of the field of the given name.
+    pub fn get_field(&self, name: &str, site: &Site2D) -> Option<f64> {
+        self.interpolator.interpolate(self.fields.get(name)?, site)
+    }
+
+    /// Get the interpolated value of `values`, which is indexed by the sites.
+    pub(crate) fn interpolate(&self, values: &[f64], site: &Site2D) -> Option<f64> {
+        self.interpolator.interpolate(values, site)
+    }
+
+    /// Extract the reaches of the rivers whose drainage area is not less than `min_drainage_area` (see [River2D]).
+    ///
+    /// This returns an empty list if the terrain has no drainage network.
+    pub fn extract_rivers(&self, min_drainage_area: Area) -> Vec<River2D> {
+        if self.network.is_empty() {
+            return Vec::new();
+        }
+        extract_rivers(
+            &self.sites,
+            &self.elevations,
+            &self.network,
+            min_drainage_area,
+        )
+    }
+
+    /// Extract the closed coastlines at `sea_level` by marching triangles over [Terrain2D::triangles] (see [Shoreline2D]).
+    ///
+    /// The coastlines around islands are counterclockwise and the ones around lakes are clockwise.
+    /// This returns an empty list if the terrain has no triangles.
+    pub fn shorelines(&self, sea_level: Elevation) -> Vec<Shoreline2D> {
+        extract_shorelines(
+            &self.sites,
+            &self.elevations,
+            self.triangulation.triangles(),
+            sea_level,
+        )
+    }
+
+    /// Sample the terrain at the sites of another model, such as a coarser model for multi-resolution workflows.
+    ///
+    /// The elevation and the fields of each site of the model are the means of the ones of the sites of the terrain
+    /// in its Voronoi cell, weighted by their areas. The cells containing no sites of the terrain are interpolated linearly.
+    /// The drainage network is not carried over.
+    pub fn resample_to(&self, model: &TerrainModel2D) -> Terrain2D {
+        resample(self, model)
+    }
+
+    /// Convert the terrain into the columns of cubic voxels of `cell_size` (see [VoxelColumns2D]).
+    ///
+    /// The height of each column is the elevation at its center divided by `cell_size`, clamped to `max_height` voxels.
+    pub fn voxelize(&self, cell_size: Length, max_height: u32) -> VoxelColumns2D {
+        voxelize(self, cell_size, max_height)
+    }
 }
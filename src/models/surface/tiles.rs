@@ -0,0 +1,166 @@
+use crate::core::{scale::VerticalScale, units::Elevation};
+
+use super::{channel::ChannelCarver2D, sites::Site2D, terrain::Terrain2D};
+
+/// A tile of a terrain mesh.
+///
+/// ### Properties
+///  - `x` and `y` are the column and the row of the tile. The row `y` = 0 is at the side of `bound_min.y`.
+///  - `vertices` is the position (x, y, elevation) of each vertex, in the row-major order of the grid of the tile.
+///    The elevation is converted by the vertical scale of the exporter.
+///  - `indices` is the indices of the vertices of the triangles, three per triangle in counterclockwise order.
+///  - `bound_min` and `bound_max` are the axis-aligned bounding box of the vertices of the tile.
+#[derive(Debug, Clone)]
+pub struct TerrainTile2D {
+    pub x: usize,
+    pub y: usize,
+    pub vertices: Vec<[f64; 3]>,
+    pub indices: Vec<u32>,
+    pub bound_min: [f64; 3],
+    pub bound_max: [f64; 3],
+}
+
+/// Provides an export of a terrain as a grid of mesh tiles without cracks between them.
+///
+/// The terrain is sampled on a regular grid of vertices covering the bounding rectangle, and the grid is split into tiles
+/// of `resolution` × `resolution` quads. The adjacent tiles share the vertices on their borders, which are sampled only once,
+/// so the borders match exactly. The vertices outside the terrain (or where the interpolation fails) have the elevation 0.0, and the triangles touching them are omitted.
+///
+/// ### Properties
+///  - `bound_min` and `bound_max` are the bounding rectangle to export. The default value is from (0, 0) to (100, 100).
+///  - `num_tiles` is the number of the tiles along the x and y axes. The default value is 4 × 4.
+///  - `resolution` is the number of the quads of a tile along each axis. The default value is 64.
+///  - `vertical_scale` is the conversion of the elevations of the vertices (see [VerticalScale]). The default value is the identity.
+///  - `channel_carver` incises the channels into the vertices (see [ChannelCarver2D]). The default value is `None`.
+#[derive(Debug, Clone)]
+pub struct TileExporter2D {
+    bound_min: Site2D,
+    bound_max: Site2D,
+    num_tiles: (usize, usize),
+    resolution: usize,
+    vertical_scale: VerticalScale,
+    channel_carver: Option<ChannelCarver2D>,
+}
+
+impl Default for TileExporter2D {
+    fn default() -> Self {
+        Self {
+            bound_min: Site2D::new(0.0, 0.0),
+            bound_max: Site2D::new(100.0, 100.0),
+            num_tiles: (4, 4),
+            resolution: 64,
+            vertical_scale: VerticalScale::default(),
+            channel_carver: None,
+        }
+    }
+}
+
+impl TileExporter2D {
+    pub fn set_bounding_box(mut self, bound_min: Site2D, bound_max: Site2D) -> Self {
+        self.bound_min = bound_min;
+        self.bound_max = bound_max;
+        self
+    }
+
+    pub fn set_num_tiles(mut self, num_tiles_x: usize, num_tiles_y: usize) -> Self {
+        self.num_tiles = (num_tiles_x.max(1), num_tiles_y.max(1));
+        self
+    }
+
+    pub fn set_resolution(mut self, resolution: usize) -> Self {
+        self.resolution = resolution.max(1);
+        self
+    }
+
+    pub fn set_vertical_scale(mut self, vertical_scale: VerticalScale) -> Self {
+        self.vertical_scale = vertical_scale;
+        self
+    }
+
+    pub fn set_channel_carver(mut self, channel_carver: Option<ChannelCarver2D>) -> Self {
+        self.channel_carver = channel_carver;
+        self
+    }
+
+    /// Export the tiles in the row-major order.
+    pub fn export(&self, terrain: &Terrain2D) -> Vec<TerrainTile2D> {
+        let (num_tiles_x, num_tiles_y) = self.num_tiles;
+        let res = self.resolution;
+        let (grid_x, grid_y) = (num_tiles_x * res + 1, num_tiles_y * res + 1);
+        let position = |gx: usize, gy: usize| {
+            Site2D::new(
+                self.bound_min.x
+                    + (self.bound_max.x - self.bound_min.x) * gx as f64 / (grid_x - 1) as f64,
+                self.bound_min.y
+                    + (self.bound_max.y - self.bound_min.y) * gy as f64 / (grid_y - 1) as f64,
+            )
+        };
+        // the whole grid is sampled at once so that the shared vertices are identical
+        let grid: Vec<Option<Elevation>> = (0..grid_y)
+            .flat_map(|gy| (0..grid_x).map(move |gx| (gx, gy)))
+            .map(|(gx, gy)| {
+                let site = position(gx, gy);
+                terrain
+                    .get_elevation(&site)
+                    .filter(|elevation| elevation.is_finite())
+                    .map(|elevation| match &self.channel_carver {
+                        Some(carver) => carver.carve(&site, elevation),
+                        None => elevation,
+                    })
+                    .map(|elevation| self.vertical_scale.apply(elevation))
+            })
+            .collect();
+
+        (0..num_tiles_y)
+            .flat_map(|ty| (0..num_tiles_x).map(move |tx| (tx, ty)))
+            .map(|(tx, ty)| {
+                let (x0, y0) = (tx * res, ty * res);
+                let sample = |i: usize, j: usize| grid[(y0 + j) * grid_x + (x0 + i)];
+                let vertices = (0..=res)
+                    .flat_map(|j| (0..=res).map(move |i| (i, j)))
+                    .map(|(i, j)| {
+                        let site = position(x0 + i, y0 + j);
+                        [site.x, site.y, sample(i, j).unwrap_or(0.0)]
+                    })
+                    .collect::<Vec<_>>();
+
+                let index = |i: usize, j: usize| (j * (res + 1) + i) as u32;
+                let indices = (0..res)
+                    .flat_map(|j| (0..res).map(move |i| (i, j)))
+                    .flat_map(|(i, j)| {
+                        [
+                            [(i, j), (i + 1, j), (i + 1, j + 1)],
+                            [(i, j), (i + 1, j + 1), (i, j + 1)],
+                        ]
+                    })
+                    .filter(|triangle| triangle.iter().all(|&(i, j)| sample(i, j).is_some()))
+                    .flat_map(|triangle| triangle.map(|(i, j)| index(i, j)))
+                    .collect::<Vec<_>>();
+
+                let (bound_min, bound_max) = vertices
+                    .iter()
+                    .enumerate()
+                    .filter(|(k, _)| sample(k % (res + 1), k / (res + 1)).is_some())
+                    .fold(
+                        ([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]),
+                        |(mut min, mut max), (_, vertex)| {
+                            (0..3).for_each(|d| {
+                                min[d] = min[d].min(vertex[d]);
+                                max[d] = max[d].max(vertex[d]);
+                            });
+                            (min, max)
+                        },
+                    );
+
+                TerrainTile2D {
+                    x: tx,
+                    y: ty,
+                    vertices,
+                    indices,
+                    bound_min,
+                    bound_max,
+                }
+            })
+            .collect()
+    }
+}
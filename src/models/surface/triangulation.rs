@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+
+use super::sites::Site2D;
+
+/// The Delaunay triangles of the sites with the adjacency of the triangles, for locating the triangle containing a site.
+#[derive(Clone, Default)]
+pub(crate) struct Triangulation2D {
+    triangles: Vec<[usize; 3]>,
+    /// The triangle across the edge opposite to each vertex of each triangle.
+    adjacency: Vec<[Option<usize>; 3]>,
+}
+
+impl Triangulation2D {
+    pub(crate) fn new(triangles: Vec<[usize; 3]>) -> Self {
+        let key = |a: usize, b: usize| if a < b { (a, b) } else { (b, a) };
+        let mut edges: BTreeMap<(usize, usize), Vec<(usize, usize)>> = BTreeMap::new();
+        triangles.iter().enumerate().for_each(|(t, triangle)| {
+            (0..3).for_each(|k| {
+                edges
+                    .entry(key(triangle[(k + 1) % 3], triangle[(k + 2) % 3]))
+                    .or_default()
+                    .push((t, k));
+            });
+        });
+        let mut adjacency = vec![[None; 3]; triangles.len()];
+        edges.values().for_each(|sides| {
+            if let [(t0, k0), (t1, k1)] = sides.as_slice() {
+                adjacency[*t0][*k0] = Some(*t1);
+                adjacency[*t1][*k1] = Some(*t0);
+            }
+        });
+        Self {
+            triangles,
+            adjacency,
+        }
+    }
+
+    pub(crate) fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// The barycentric coordinates of the site in the triangle, or `None` if the triangle is degenerate.
+    fn barycentric(&self, sites: &[Site2D], t: usize, site: &Site2D) -> Option<[f64; 3]> {
+        let [a, b, c] = self.triangles[t].map(|i| sites[i]);
+        let det = (b.y - c.y) * (a.x - c.x) + (c.x - b.x) * (a.y - c.y);
+        if det == 0.0 {
+            return None;
+        }
+        let w0 = ((b.y - c.y) * (site.x - c.x) + (c.x - b.x) * (site.y - c.y)) / det;
+        let w1 = ((c.y - a.y) * (site.x - c.x) + (a.x - c.x) * (site.y - c.y)) / det;
+        Some([w0, w1, 1.0 - w0 - w1])
+    }
+
+    /// Locate the triangle containing the site by walking across the triangles toward it,
+    /// returning the index of the triangle and the barycentric coordinates of the site in the triangle.
+    ///
+    /// This returns `None` if the site is outside the convex hull of the sites.
+    pub(crate) fn locate(&self, sites: &[Site2D], site: &Site2D) -> Option<(usize, [f64; 3])> {
+        const EPSILON: f64 = 1e-12;
+        let mut t = 0;
+        for _ in 0..self.triangles.len() {
+            let weights = match self.barycentric(sites, t, site) {
+                Some(weights) => weights,
+                None => break,
+            };
+            // cross the edge on the farthest side from the site
+            let (k, &w) = weights
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(b.1))?;
+            if w >= -EPSILON {
+                return Some((t, weights));
+            }
+            t = self.adjacency[t][k]?;
+        }
+        // the walk failed on a degenerate triangle, so search all the triangles
+        (0..self.triangles.len()).find_map(|t| {
+            self.barycentric(sites, t, site)
+                .filter(|weights| weights.iter().all(|&w| w >= -EPSILON))
+                .map(|weights| (t, weights))
+        })
+    }
+}
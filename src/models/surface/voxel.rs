@@ -0,0 +1,168 @@
+use crate::core::{
+    fields::{DEPOSIT_THICKNESS, ICE, LAVA_FLOW, SOIL_THICKNESS},
+    units::Length,
+};
+
+use super::{sites::Site2D, terrain::Terrain2D};
+
+/// The material of a voxel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VoxelMaterial {
+    Bedrock,
+    Sediment,
+    Soil,
+    Lava,
+    Ice,
+}
+
+/// The columns of voxels of a terrain for block-based engines, created by [Terrain2D::voxelize].
+///
+/// The columns are on a grid of `cell_size` covering the sites, and each column is solid from the elevation 0.0
+/// up to its height. The voxels are cubes, so the height of each voxel is also `cell_size`.
+///
+/// ### Properties
+///  - `bound_min` is the corner of the grid at the column (0, 0).
+///  - `cell_size` is the size of a voxel (unit: L).
+///  - `width` and `depth` are the number of the columns along the x and y axes.
+///  - `heights` is the number of the solid voxels of each column, in the row-major order.
+///  - `layers` is the materials of each column from the top as the pairs of the material and the number of the voxels,
+///    if the terrain has any field of the stratigraphy ([SOIL_THICKNESS], [DEPOSIT_THICKNESS], [LAVA_FLOW] or [ICE]).
+#[derive(Debug, Clone)]
+pub struct VoxelColumns2D {
+    pub bound_min: Site2D,
+    pub cell_size: Length,
+    pub width: usize,
+    pub depth: usize,
+    pub heights: Vec<u32>,
+    pub layers: Option<Vec<Vec<(VoxelMaterial, u32)>>>,
+}
+
+impl VoxelColumns2D {
+    /// The number of the solid voxels of the column.
+    pub fn height(&self, x: usize, y: usize) -> Option<u32> {
+        if x < self.width && y < self.depth {
+            Some(self.heights[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// The material of the voxel at the `level`-th layer from the bottom of the column, or `None` if the voxel is empty.
+    ///
+    /// If the columns have no layers, all the solid voxels are [VoxelMaterial::Bedrock].
+    pub fn material(&self, x: usize, y: usize, level: u32) -> Option<VoxelMaterial> {
+        let height = self.height(x, y)?;
+        if level >= height {
+            return None;
+        }
+        let Some(layers) = &self.layers else {
+            return Some(VoxelMaterial::Bedrock);
+        };
+        let mut top = height;
+        layers[y * self.width + x]
+            .iter()
+            .find(|(_, thickness)| {
+                top -= thickness;
+                level >= top
+            })
+            .map(|(material, _)| *material)
+    }
+}
+
+pub(super) fn voxelize(terrain: &Terrain2D, cell_size: Length, max_height: u32) -> VoxelColumns2D {
+    let cell_size = cell_size.max(f64::EPSILON);
+    let (bound_min, bound_max) = terrain.sites().iter().fold(
+        (
+            Site2D::new(f64::INFINITY, f64::INFINITY),
+            Site2D::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+        ),
+        |(min, max), site| {
+            (
+                Site2D::new(min.x.min(site.x), min.y.min(site.y)),
+                Site2D::new(max.x.max(site.x), max.y.max(site.y)),
+            )
+        },
+    );
+    if terrain.sites().is_empty() {
+        return VoxelColumns2D {
+            bound_min: Site2D::new(0.0, 0.0),
+            cell_size,
+            width: 0,
+            depth: 0,
+            heights: Vec::new(),
+            layers: None,
+        };
+    }
+    let width = ((bound_max.x - bound_min.x) / cell_size).ceil().max(1.0) as usize;
+    let depth = ((bound_max.y - bound_min.y) / cell_size).ceil().max(1.0) as usize;
+    let centers = (0..depth)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            Site2D::new(
+                bound_min.x + (x as f64 + 0.5) * cell_size,
+                bound_min.y + (y as f64 + 0.5) * cell_size,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let to_voxels = |length: f64| (length / cell_size).round().clamp(0.0, max_height as f64) as u32;
+    let heights = centers
+        .iter()
+        .map(|center| {
+            terrain
+                .get_elevation(center)
+                .filter(|elevation| elevation.is_finite())
+                .map(to_voxels)
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    let has_layers = [SOIL_THICKNESS, DEPOSIT_THICKNESS, LAVA_FLOW, ICE]
+        .iter()
+        .any(|name| terrain.fields().get(name).is_some());
+    let layers = has_layers.then(|| {
+        let field = |name: &str, center: &Site2D| {
+            terrain
+                .get_field(name, center)
+                .filter(|value| value.is_finite())
+                .unwrap_or(0.0)
+        };
+        centers
+            .iter()
+            .zip(heights.iter())
+            .map(|(center, &height)| {
+                // the surface covers are a voxel thick, and the layers are clipped at the bottom of the column
+                let mut remaining = height;
+                let mut layers = Vec::new();
+                let mut push = |material: VoxelMaterial, thickness: u32| {
+                    let thickness = thickness.min(remaining);
+                    if thickness > 0 {
+                        layers.push((material, thickness));
+                        remaining -= thickness;
+                    }
+                };
+                push(VoxelMaterial::Ice, (field(ICE, center) > 0.5) as u32);
+                push(VoxelMaterial::Lava, (field(LAVA_FLOW, center) > 0.5) as u32);
+                push(
+                    VoxelMaterial::Soil,
+                    to_voxels(field(SOIL_THICKNESS, center)),
+                );
+                push(
+                    VoxelMaterial::Sediment,
+                    to_voxels(field(DEPOSIT_THICKNESS, center)),
+                );
+                push(VoxelMaterial::Bedrock, u32::MAX);
+                layers
+            })
+            .collect()
+    });
+
+    VoxelColumns2D {
+        bound_min,
+        cell_size,
+        width,
+        depth,
+        heights,
+        layers,
+    }
+}
@@ -0,0 +1,159 @@
+use crate::core::{
+    traits::Site,
+    units::{Area, Elevation, Length},
+};
+
+use super::{sites::Site2D, terrain::Terrain2D};
+
+/// The kind of a steep segment of a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaterfallKind {
+    Rapid,
+    Waterfall,
+}
+
+/// A steep segment of a channel, from its top to its bottom along the flow.
+///
+/// ### Properties
+///  - `kind` is whether the segment is a rapid or a waterfall.
+///  - `top` and `bottom` are the indices of the sites at the top and the bottom of the segment.
+///  - `top_position` and `bottom_position` are the positions of those sites.
+///  - `drop` is the difference of the elevations between the top and the bottom (unit: L).
+///  - `length` is the length of the segment along the flow (unit: L).
+///  - `discharge` is the drainage area at the top of the segment, as the discharge from upstream (unit: L^2).
+#[derive(Debug, Clone)]
+pub struct Waterfall2D {
+    pub kind: WaterfallKind,
+    pub top: usize,
+    pub bottom: usize,
+    pub top_position: Site2D,
+    pub bottom_position: Site2D,
+    pub drop: Elevation,
+    pub length: Length,
+    pub discharge: Area,
+}
+
+/// Provides a detection of waterfalls and rapids on the channels of the terrain.
+///
+/// The consecutive edges of the channels steeper than `rapid_slope` are merged into a segment as long as the segment
+/// is shorter than `max_length`. The segment is classified as a waterfall if its mean slope is not less than `waterfall_slope`,
+/// and as a rapid otherwise.
+///
+/// ### Properties
+///  - `min_drainage_area` is the drainage area above which the sites are regarded as channels (unit: L^2). The default value is 100.0.
+///  - `rapid_slope` is the minimum slope of rapids (unit: rad). The default value is π/36.
+///  - `waterfall_slope` is the minimum slope of waterfalls (unit: rad). The default value is π/6.
+///  - `max_length` is the maximum length of a segment (unit: L). The default value is 10.0.
+#[derive(Debug, Clone)]
+pub struct WaterfallDetector2D {
+    min_drainage_area: Area,
+    rapid_slope: f64,
+    waterfall_slope: f64,
+    max_length: Length,
+}
+
+impl Default for WaterfallDetector2D {
+    fn default() -> Self {
+        Self {
+            min_drainage_area: 100.0,
+            rapid_slope: std::f64::consts::PI / 36.0,
+            waterfall_slope: std::f64::consts::FRAC_PI_6,
+            max_length: 10.0,
+        }
+    }
+}
+
+impl WaterfallDetector2D {
+    pub fn set_min_drainage_area(mut self, min_drainage_area: Area) -> Self {
+        self.min_drainage_area = min_drainage_area;
+        self
+    }
+
+    pub fn set_rapid_slope(mut self, rapid_slope: f64) -> Self {
+        self.rapid_slope = rapid_slope;
+        self
+    }
+
+    pub fn set_waterfall_slope(mut self, waterfall_slope: f64) -> Self {
+        self.waterfall_slope = waterfall_slope;
+        self
+    }
+
+    pub fn set_max_length(mut self, max_length: Length) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Detect the waterfalls and rapids on the terrain.
+    ///
+    /// This returns an empty list if the terrain has no drainage network.
+    pub fn detect(&self, terrain: &Terrain2D) -> Vec<Waterfall2D> {
+        let network = terrain.network();
+        if network.is_empty() {
+            return Vec::new();
+        }
+        let sites = terrain.sites();
+        let elevations = terrain.elevations();
+        let receivers = network.receivers();
+        let drainage_areas = network.drainage_areas();
+        let rapid_gradient = self.rapid_slope.tan();
+        let waterfall_gradient = self.waterfall_slope.tan();
+
+        let is_steep = |i: usize| {
+            let j = receivers[i];
+            if j == i || drainage_areas[i] < self.min_drainage_area {
+                return false;
+            }
+            let distance = sites[i].distance(&sites[j]);
+            distance > 0.0 && (elevations[i] - elevations[j]) / distance >= rapid_gradient
+        };
+
+        let steep = (0..sites.len()).map(is_steep).collect::<Vec<_>>();
+        let mut has_steep_donor = vec![false; sites.len()];
+        (0..sites.len()).for_each(|i| {
+            if steep[i] {
+                has_steep_donor[receivers[i]] = true;
+            }
+        });
+
+        (0..sites.len())
+            .filter(|&i| steep[i] && !has_steep_donor[i])
+            .flat_map(|start| {
+                // split the run of the steep edges into segments shorter than `max_length`
+                let mut segments = Vec::new();
+                let (mut top, mut i, mut length) = (start, start, 0.0);
+                while steep[i] {
+                    let j = receivers[i];
+                    let distance = sites[i].distance(&sites[j]);
+                    if length > 0.0 && length + distance > self.max_length {
+                        segments.push((top, i, length));
+                        top = i;
+                        length = 0.0;
+                    }
+                    length += distance;
+                    i = j;
+                }
+                segments.push((top, i, length));
+                segments
+            })
+            .map(|(top, bottom, length)| {
+                let drop = elevations[top] - elevations[bottom];
+                let kind = if drop / length >= waterfall_gradient {
+                    WaterfallKind::Waterfall
+                } else {
+                    WaterfallKind::Rapid
+                };
+                Waterfall2D {
+                    kind,
+                    top,
+                    bottom,
+                    top_position: sites[top],
+                    bottom_position: sites[bottom],
+                    drop,
+                    length,
+                    discharge: drainage_areas[top],
+                }
+            })
+            .collect()
+    }
+}
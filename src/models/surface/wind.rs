@@ -0,0 +1,123 @@
+use crate::core::units::{Elevation, Length};
+
+use super::{sites::Site2D, terrain::Terrain2D};
+
+/// Provides the windward exposure and the rain shadow of the terrain for a prevailing wind.
+///
+/// For each site, the elevations are sampled upwind every `sample_interval` up to `search_distance`.
+///  - The exposure is the negative of the maximum upwind horizon angle (unit: rad): positive on the exposed
+///    windward slopes and ridges, and negative in the sheltered leeward sites.
+///  - The rain shadow is the height of the upwind barrier above the site, decaying exponentially with the distance by `shadow_decay`,
+///    and normalized by `shadow_height` into the range [0, 1].
+///
+/// ### Properties
+///  - `wind_direction` is the direction the wind blows towards (unit: rad, counterclockwise from the x axis). The default value is 0.0.
+///  - `search_distance` is the maximum upwind distance to sample (unit: L). The default value is 20.0.
+///  - `sample_interval` is the interval of the samples (unit: L). The default value is 1.0.
+///  - `shadow_decay` is the distance over which the effect of the barrier decays by 1/e (unit: L). The default value is 10.0.
+///  - `shadow_height` is the barrier height producing the full rain shadow (unit: L). The default value is 1.0.
+#[derive(Debug, Clone)]
+pub struct WindExposure2D {
+    wind_direction: f64,
+    search_distance: Length,
+    sample_interval: Length,
+    shadow_decay: Length,
+    shadow_height: Elevation,
+}
+
+impl Default for WindExposure2D {
+    fn default() -> Self {
+        Self {
+            wind_direction: 0.0,
+            search_distance: 20.0,
+            sample_interval: 1.0,
+            shadow_decay: 10.0,
+            shadow_height: 1.0,
+        }
+    }
+}
+
+impl WindExposure2D {
+    pub fn set_wind_direction(mut self, wind_direction: f64) -> Self {
+        self.wind_direction = wind_direction;
+        self
+    }
+
+    pub fn set_search_distance(mut self, search_distance: Length) -> Self {
+        self.search_distance = search_distance;
+        self
+    }
+
+    pub fn set_sample_interval(mut self, sample_interval: Length) -> Self {
+        self.sample_interval = sample_interval;
+        self
+    }
+
+    pub fn set_shadow_decay(mut self, shadow_decay: Length) -> Self {
+        self.shadow_decay = shadow_decay;
+        self
+    }
+
+    pub fn set_shadow_height(mut self, shadow_height: Elevation) -> Self {
+        self.shadow_height = shadow_height;
+        self
+    }
+
+    /// The windward exposure of each site (unit: rad).
+    pub fn exposures(&self, terrain: &Terrain2D) -> Vec<f64> {
+        self.scan(terrain, |elevation, samples| {
+            let horizon = samples
+                .iter()
+                .map(|&(distance, upwind)| ((upwind - elevation) / distance).atan())
+                .fold(f64::NEG_INFINITY, f64::max);
+            if horizon.is_finite() {
+                -horizon
+            } else {
+                0.0
+            }
+        })
+    }
+
+    /// The rain shadow index of each site, from 0.0 (no shadow) to 1.0 (full shadow).
+    pub fn rain_shadows(&self, terrain: &Terrain2D) -> Vec<f64> {
+        self.scan(terrain, |elevation, samples| {
+            let barrier = samples
+                .iter()
+                .map(|&(distance, upwind)| {
+                    (upwind - elevation) * (-distance / self.shadow_decay.max(f64::EPSILON)).exp()
+                })
+                .fold(0.0, f64::max);
+            (barrier / self.shadow_height.max(f64::EPSILON)).clamp(0.0, 1.0)
+        })
+    }
+
+    /// Evaluate `f` with the elevation of each site and the upwind samples of the distances and the elevations.
+    fn scan<F>(&self, terrain: &Terrain2D, f: F) -> Vec<f64>
+    where
+        F: Fn(Elevation, &[(Length, Elevation)]) -> f64,
+    {
+        let (dx, dy) = (self.wind_direction.cos(), self.wind_direction.sin());
+        let num_samples = if self.sample_interval > 0.0 {
+            (self.search_distance / self.sample_interval).floor() as usize
+        } else {
+            0
+        };
+        terrain
+            .sites()
+            .iter()
+            .zip(terrain.elevations().iter())
+            .map(|(site, &elevation)| {
+                let samples = (1..=num_samples)
+                    .map_while(|k| {
+                        let distance = self.sample_interval * k as f64;
+                        let upwind = Site2D::new(site.x - dx * distance, site.y - dy * distance);
+                        terrain
+                            .get_elevation(&upwind)
+                            .map(|upwind| (distance, upwind))
+                    })
+                    .collect::<Vec<_>>();
+                f(elevation, &samples)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,168 @@
+//! Support for the regression tests of the pipelines based on fastlem.
+//!
+//! This module is available with the feature `test-util`.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::{core::units::Elevation, lem::record::digest_elevations};
+
+/// The first line of the golden files.
+const GOLDEN_MAGIC: &str = "FLEMGOLD1";
+
+/// The environment variable to overwrite the golden files with the current elevations.
+pub const UPDATE_GOLDENS_ENV: &str = "FASTLEM_UPDATE_GOLDENS";
+
+#[derive(Error, Debug)]
+pub enum GoldenError {
+    #[error("Failed to access the golden file: {0}")]
+    Io(#[from] io::Error),
+    #[error("The golden file is malformed")]
+    InvalidFormat,
+    #[error("The number of the elevations is {actual}, but the golden has {expected}")]
+    LengthMismatch { expected: usize, actual: usize },
+    #[error("The elevation of the site {index} is {actual}, but the golden is {expected}")]
+    ElevationMismatch {
+        index: usize,
+        expected: Elevation,
+        actual: Elevation,
+    },
+}
+
+/// The result of [GoldenTerrain::check] which did not fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoldenStatus {
+    /// The elevations matched the golden.
+    Matched,
+    /// The golden file did not exist or was requested to be updated, so the elevations were written as the golden.
+    Written,
+}
+
+/// Calculate the digest of the elevations rounded to the multiples of `tolerance`.
+///
+/// The elevations differing less than `tolerance` usually have the same digest,
+/// but the ones around the middle of the multiples may be rounded differently.
+/// If `tolerance` is not positive, the digest of the exact bit patterns is calculated.
+pub fn hash_elevations(elevations: &[Elevation], tolerance: f64) -> u64 {
+    if tolerance <= 0.0 {
+        return digest_elevations(elevations);
+    }
+    let rounded = elevations
+        .iter()
+        .map(|e| (e / tolerance).round() * tolerance)
+        .collect::<Vec<_>>();
+    digest_elevations(&rounded)
+}
+
+/// Provides a comparison of the elevations of a terrain with the golden stored in a file.
+///
+/// If the golden file does not exist, or the environment variable [UPDATE_GOLDENS_ENV] is set,
+/// the elevations are written as the golden instead.
+/// The golden file is a text file with the digest of the elevations and the elevation of each site in a line.
+///
+/// ### Properties
+///  - `path` is the path of the golden file.
+///  - `tolerance` is the maximum absolute difference of the elevations regarded as equal (unit: L). The default value is 1e-6.
+#[derive(Debug, Clone)]
+pub struct GoldenTerrain {
+    path: PathBuf,
+    tolerance: Elevation,
+}
+
+impl GoldenTerrain {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            tolerance: 1e-6,
+        }
+    }
+
+    pub fn set_tolerance(mut self, tolerance: Elevation) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Compare the elevations with the golden, or write them as the golden if there is none.
+    pub fn check(&self, elevations: &[Elevation]) -> Result<GoldenStatus, GoldenError> {
+        if std::env::var_os(UPDATE_GOLDENS_ENV).is_some() || !self.path.exists() {
+            self.write(elevations)?;
+            return Ok(GoldenStatus::Written);
+        }
+        let (digest, expected) = self.read()?;
+        if expected.len() != elevations.len() {
+            return Err(GoldenError::LengthMismatch {
+                expected: expected.len(),
+                actual: elevations.len(),
+            });
+        }
+        if digest == digest_elevations(elevations) {
+            return Ok(GoldenStatus::Matched);
+        }
+        match expected.iter().zip(elevations.iter()).position(|(e, a)| {
+            let difference = (e - a).abs();
+            difference.is_nan() || difference > self.tolerance
+        }) {
+            Some(index) => Err(GoldenError::ElevationMismatch {
+                index,
+                expected: expected[index],
+                actual: elevations[index],
+            }),
+            None => Ok(GoldenStatus::Matched),
+        }
+    }
+
+    /// Compare the elevations with the golden and panic if they do not match.
+    pub fn assert_matches(&self, elevations: &[Elevation]) {
+        if let Err(error) = self.check(elevations) {
+            panic!("golden {}: {}", self.path.display(), error);
+        }
+    }
+
+    /// Write the elevations as the golden.
+    pub fn write(&self, elevations: &[Elevation]) -> Result<(), GoldenError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut writer = io::BufWriter::new(fs::File::create(&self.path)?);
+        writeln!(writer, "{}", GOLDEN_MAGIC)?;
+        writeln!(
+            writer,
+            "{} {:016x}",
+            elevations.len(),
+            digest_elevations(elevations)
+        )?;
+        for elevation in elevations {
+            writeln!(writer, "{}", elevation)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read the digest and the elevations of the golden.
+    fn read(&self) -> Result<(u64, Vec<Elevation>), GoldenError> {
+        let text = fs::read_to_string(&self.path)?;
+        let mut lines = text.lines();
+        if lines.next() != Some(GOLDEN_MAGIC) {
+            return Err(GoldenError::InvalidFormat);
+        }
+        let header = lines.next().ok_or(GoldenError::InvalidFormat)?;
+        let (len, digest) = header.split_once(' ').ok_or(GoldenError::InvalidFormat)?;
+        let len = len
+            .parse::<usize>()
+            .map_err(|_| GoldenError::InvalidFormat)?;
+        let digest = u64::from_str_radix(digest, 16).map_err(|_| GoldenError::InvalidFormat)?;
+        let elevations = lines
+            .map(|line| line.parse::<Elevation>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| GoldenError::InvalidFormat)?;
+        if elevations.len() != len {
+            return Err(GoldenError::InvalidFormat);
+        }
+        Ok((digest, elevations))
+    }
+}
@@ -0,0 +1,55 @@
+use fastlem::core::adjacency::Adjacency;
+use fastlem::core::traits::Model;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_adjacency() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let graph = model.graph();
+    let adjacency = Adjacency::from_graph(graph);
+    assert_eq!(adjacency.num_sites(), model.num());
+
+    // the neighbors keep the order of the graph
+    let mut num_half_edges = 0;
+    (0..model.num()).for_each(|i| {
+        let neighbors = graph.neighbors_of(i);
+        num_half_edges += neighbors.len();
+        assert_eq!(adjacency.neighbors_of(i).len(), neighbors.len());
+        adjacency
+            .iter_neighbors(i)
+            .zip(neighbors.iter())
+            .for_each(|((j, length), ja)| {
+                assert_eq!(j, ja.0);
+                assert_eq!(length, ja.1);
+            });
+    });
+    assert_eq!(adjacency.num_half_edges(), num_half_edges);
+
+    // the twins connect the same sites in the opposite direction
+    (0..model.num()).for_each(|i| {
+        adjacency.half_edges_of(i).for_each(|k| {
+            let twin = adjacency.twin(k);
+            assert_eq!(adjacency.twin(twin), k);
+            assert_eq!(adjacency.target(twin), i);
+            assert!(adjacency.half_edges_of(adjacency.target(k)).contains(&twin));
+            assert_eq!(adjacency.length(twin), adjacency.length(k));
+        });
+    });
+
+    // the lookup of the edges agrees with the graph
+    (0..model.num()).for_each(|i| {
+        graph.neighbors_of(i).iter().for_each(|ja| {
+            assert_eq!(adjacency.length_between(i, ja.0), Some(ja.1));
+        });
+    });
+    let far = (0..model.num())
+        .find(|&j| j != 0 && !graph.has_edge(0, j).0)
+        .unwrap();
+    assert_eq!(adjacency.find(0, far), None);
+}
@@ -0,0 +1,90 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::{GenerationError, TerrainGenerator};
+use fastlem::lem::processes::diffusion::HillslopeDiffusionProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_aging() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let parameters = vec![
+        TopographicalParameters::default()
+            .set_max_slope(Some(std::f64::consts::FRAC_PI_4));
+        num
+    ];
+    let generator = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(parameters)
+        .set_max_iteration(20);
+    let terrain = generator.clone().generate().unwrap();
+    let max = |elevations: &[f64]| elevations.iter().cloned().fold(0.0, f64::max);
+
+    // the peaks are lowered more by the more intensive aging
+    let aged = generator.clone().age(terrain.elevations(), 1.0).unwrap();
+    let older = generator.clone().age(terrain.elevations(), 3.0).unwrap();
+    assert!(aged.elevations().iter().all(|e| e.is_finite()));
+    assert!(max(aged.elevations()) < max(terrain.elevations()));
+    assert!(max(older.elevations()) < max(aged.elevations()));
+
+    // the parameters are optional
+    let defaulted = TerrainGenerator::default()
+        .set_model(model.clone())
+        .age(terrain.elevations(), 1.0)
+        .unwrap();
+    assert!(max(defaulted.elevations()) < max(terrain.elevations()));
+
+    // no aging without the intensity
+    let unchanged = generator.clone().age(terrain.elevations(), 0.0).unwrap();
+    assert_eq!(unchanged.elevations(), terrain.elevations());
+
+    assert!(matches!(
+        generator.age(&terrain.elevations()[1..], 1.0),
+        Err(GenerationError::InvalidNumberOfElevations)
+    ));
+}
+
+#[test]
+fn test_hillslope_diffusion() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // a plateau without the fluvial erosion is rounded by the diffusion
+    let outlets = model.default_outlets().to_vec();
+    let generate = |diffusivity: f64| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(
+                (0..num)
+                    .map(|i| {
+                        TopographicalParameters::default()
+                            .set_base_elevation(if outlets.contains(&i) { 0.0 } else { 10.0 })
+                            .set_erodibility(0.0)
+                            .set_uplift_rate(0.0)
+                    })
+                    .collect(),
+            )
+            .set_time_step(Some(1.0))
+            .set_max_iteration(10)
+            .add_process(HillslopeDiffusionProcess::default().set_diffusivity(diffusivity))
+            .generate()
+            .unwrap()
+    };
+    let still = generate(0.0);
+    let diffused = generate(1.0);
+    let sum = |elevations: &[f64]| elevations.iter().sum::<f64>();
+    assert!(diffused
+        .elevations()
+        .iter()
+        .all(|e| e.is_finite() && *e <= 10.0 + 1e-9));
+    assert!(sum(diffused.elevations()) < sum(still.elevations()));
+}
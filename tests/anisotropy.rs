@@ -0,0 +1,66 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::record::SimulationRecord;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_anisotropic_erodibility() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .relaxate_sites(1)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let generate = |parameters: TopographicalParameters| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![parameters; num])
+            .set_max_iteration(50)
+            .generate()
+            .unwrap()
+    };
+    let isotropic = generate(TopographicalParameters::default());
+    // the ratio 1.0 is isotropic
+    assert_eq!(
+        generate(TopographicalParameters::default().set_anisotropy(1.0, 1.0)).elevations(),
+        isotropic.elevations()
+    );
+    // the flow across the grain along the x axis erodes less, so the terrain is higher,
+    // and the sites draining across the grain are raised the most
+    let anisotropic = generate(TopographicalParameters::default().set_anisotropy(0.0, 0.1));
+    let mean = |elevations: &[f64]| elevations.iter().sum::<f64>() / elevations.len() as f64;
+    assert!(mean(anisotropic.elevations()) > mean(isotropic.elevations()));
+    let sites = anisotropic.sites();
+    let network = anisotropic.network();
+    let (along, across): (Vec<_>, Vec<_>) = (0..num)
+        .filter(|&i| !network.is_outlet(i))
+        .map(|i| {
+            let j = network.receivers()[i];
+            let (dx, dy) = (sites[j].x - sites[i].x, sites[j].y - sites[i].y);
+            let gradient =
+                (anisotropic.elevations()[i] - anisotropic.elevations()[j]) / dx.hypot(dy);
+            (dx.abs() > dy.abs(), gradient)
+        })
+        .partition(|&(is_along, _)| is_along);
+    let mean_gradient = |v: &[(bool, f64)]| v.iter().map(|&(_, g)| g).sum::<f64>() / v.len() as f64;
+    assert!(mean_gradient(&across) > mean_gradient(&along));
+
+    let (_, record) = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(
+            (0..num)
+                .map(|i| TopographicalParameters::default().set_anisotropy(i as f64 * 0.01, 0.3))
+                .collect(),
+        )
+        .set_max_iteration(20)
+        .generate_with_record()
+        .unwrap();
+    let mut buf = Vec::new();
+    record.write_to(&mut buf).unwrap();
+    let restored = SimulationRecord::read_from(buf.as_slice()).unwrap();
+    assert!(restored.replay().unwrap().is_identical());
+}
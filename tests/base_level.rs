@@ -0,0 +1,83 @@
+use fastlem::core::fields::{TERRACE_AGE, TERRACE_LEVEL};
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::{base_level::BaseLevelProcess, terrace::TerraceProcess};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D, terrain::Terrain2D};
+extern crate fastlem;
+
+#[test]
+fn test_base_level_schedule() {
+    let process = BaseLevelProcess::default().set_schedule(vec![(20, -10.0), (10, 0.0)]);
+    assert_eq!(process.offset(0), 0.0);
+    assert_eq!(process.offset(10), 0.0);
+    assert_eq!(process.offset(15), -5.0);
+    assert_eq!(process.offset(20), -10.0);
+    assert_eq!(process.offset(100), -10.0);
+    assert_eq!(BaseLevelProcess::default().offset(5), 0.0);
+}
+
+#[test]
+fn test_base_level_incision() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let (drop, drop_step, num_iterations) = (5.0, 30, 60);
+    let generator = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(num_iterations);
+    let stable = generator
+        .clone()
+        .add_process(BaseLevelProcess::default())
+        .add_process(TerraceProcess::default())
+        .generate()
+        .unwrap();
+    let dropped = generator
+        .add_process(
+            BaseLevelProcess::default()
+                .set_schedule(vec![(drop_step, 0.0), (drop_step + 1, -drop)]),
+        )
+        .add_process(TerraceProcess::default())
+        .generate()
+        .unwrap();
+
+    // the outlets fall by the drop
+    model.default_outlets().iter().for_each(|&i| {
+        assert!((dropped.elevations()[i] - stable.elevations()[i] + drop).abs() < 1e-9);
+    });
+
+    // the incision propagates from the outlets up the channels
+    let channels = (0..num)
+        .filter(|&i| {
+            !model.default_outlets().contains(&i) && dropped.network().drainage_areas()[i] > 200.0
+        })
+        .collect::<Vec<_>>();
+    assert!(!channels.is_empty());
+    let incised = channels
+        .iter()
+        .filter(|&&i| dropped.elevations()[i] < stable.elevations()[i] - 1.0)
+        .count();
+    assert!(incised * 2 > channels.len());
+
+    // the floodplains abandoned by the incision are left as terraces above the channels
+    let young_terraces = |terrain: &Terrain2D| {
+        let terrace_age = terrain.fields().get(TERRACE_AGE).unwrap();
+        (0..num)
+            .filter(|&i| {
+                terrace_age[i] > 0.0 && terrace_age[i] <= (num_iterations - drop_step) as f64
+            })
+            .collect::<Vec<_>>()
+    };
+    let terraces = young_terraces(&dropped);
+    assert!(terraces.len() > young_terraces(&stable).len());
+    let terrace_level = dropped.fields().get(TERRACE_LEVEL).unwrap();
+    assert!(terraces
+        .iter()
+        .all(|&i| terrace_level[i] > dropped.elevations()[dropped.network().receivers()[i]]));
+}
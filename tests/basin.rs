@@ -0,0 +1,75 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::basin::BasinPolygonizer2D;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_basin_polygons_with_holes() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .add_edge_sites(None, None)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // a disk enclosed by the rest of the domain
+    let center = Site2D { x: 50.0, y: 50.0 };
+    let labels = model
+        .sites()
+        .iter()
+        .map(|site| {
+            let (dx, dy) = (site.x - center.x, site.y - center.y);
+            if (dx * dx + dy * dy).sqrt() < 20.0 {
+                1
+            } else {
+                0
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let polygons = BasinPolygonizer2D::default().polygonize(&model, &labels);
+    let outer = polygons.iter().filter(|p| p.label == 0).collect::<Vec<_>>();
+    let inner = polygons.iter().filter(|p| p.label == 1).collect::<Vec<_>>();
+    assert_eq!(outer.len(), 1);
+    assert_eq!(inner.len(), 1);
+    assert_eq!(outer[0].holes.len(), 1);
+    assert!(inner[0].holes.is_empty());
+
+    // the polygons tile the domain and the disk fills the hole
+    let disk_area = std::f64::consts::PI * 20.0 * 20.0;
+    assert!((inner[0].area() - disk_area).abs() < disk_area * 0.1);
+    let total = outer[0].area() + inner[0].area();
+    assert!((total - 100.0 * 100.0).abs() < 1.0);
+}
+
+#[test]
+fn test_drainage_basin_polygons() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .add_edge_sites(None, None)
+        .unwrap()
+        .build()
+        .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); model.num()])
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+
+    let polygons = BasinPolygonizer2D::default().polygonize_terrain(&model, &terrain);
+    assert!(!polygons.is_empty());
+    let total = polygons.iter().map(|p| p.area()).sum::<f64>();
+    assert!((total - 100.0 * 100.0).abs() < 1.0);
+
+    let filtered = BasinPolygonizer2D::default()
+        .set_min_area(100.0)
+        .polygonize_terrain(&model, &terrain);
+    assert!(filtered.iter().all(|p| p.area() >= 100.0));
+}
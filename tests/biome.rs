@@ -0,0 +1,87 @@
+use fastlem::core::traits::Model;
+use fastlem::models::surface::biome::{wetness_indices, Biome, BiomeClassifier2D};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_biome_table() {
+    let table = [
+        // (temperature, precipitation, biome)
+        (-20.0, 1000.0, Biome::Ice),
+        (-10.0, 1000.0, Biome::Tundra),
+        (-5.0, 200.0, Biome::Tundra),
+        (-5.0, 300.0, Biome::BorealForest),
+        (0.0, 1000.0, Biome::BorealForest),
+        (5.0, 100.0, Biome::Desert),
+        (10.0, 250.0, Biome::TemperateGrassland),
+        (10.0, 749.0, Biome::TemperateGrassland),
+        (10.0, 750.0, Biome::TemperateForest),
+        (15.0, 1999.0, Biome::TemperateForest),
+        (15.0, 2000.0, Biome::TemperateRainforest),
+        (20.0, 499.0, Biome::Desert),
+        (25.0, 500.0, Biome::Savanna),
+        (25.0, 1500.0, Biome::TropicalSeasonalForest),
+        (25.0, 2499.0, Biome::TropicalSeasonalForest),
+        (30.0, 2500.0, Biome::TropicalRainforest),
+    ];
+    table
+        .iter()
+        .for_each(|&(temperature, precipitation, biome)| {
+            assert_eq!(
+                Biome::classify(temperature, precipitation),
+                biome,
+                "temperature: {}, precipitation: {}",
+                temperature,
+                precipitation
+            );
+        });
+}
+
+#[test]
+fn test_biome_classifier() {
+    let sites = vec![
+        Site2D { x: 1.0, y: 1.0 },
+        Site2D { x: 9.0, y: 1.0 },
+        Site2D { x: 1.0, y: 10.0 },
+        Site2D { x: 9.0, y: 10.0 },
+        Site2D { x: 5.0, y: 50.0 },
+    ];
+    let model = TerrainModel2DBulider::default()
+        .set_sites(sites)
+        .set_bounding_box(
+            Some(Site2D { x: 0.0, y: 0.0 }),
+            Some(Site2D { x: 10.0, y: 60.0 }),
+        )
+        .build()
+        .unwrap();
+    let terrain = model.create_terrain_from_result(&[-1.0, 0.0, 1000.0, 0.0, 0.0]);
+
+    // without the drainage network the moisture is the precipitation itself
+    assert!(wetness_indices(&terrain).iter().all(|&w| w == 0.0));
+
+    let classifier = BiomeClassifier2D::default()
+        .set_lapse_rate(0.02)
+        .set_latitude_gradient(0.5)
+        .set_precipitation(Some(vec![3000.0, 3000.0, 1000.0, 100.0, 1000.0]));
+    let temperatures = classifier.temperatures(&terrain);
+    assert_eq!(temperatures, vec![24.5, 24.5, 0.0, 20.0, 0.0]);
+    assert_eq!(
+        classifier.classify(&terrain),
+        vec![
+            Biome::Ocean,
+            Biome::TropicalRainforest,
+            Biome::BorealForest,
+            Biome::Desert,
+            Biome::BorealForest,
+        ]
+    );
+
+    // the sites are wetlands only with the threshold of the wetness
+    assert!(classifier
+        .clone()
+        .set_wetland_wetness(Some(-1.0))
+        .classify(&terrain)
+        .iter()
+        .skip(1)
+        .all(|&biome| biome == Biome::Wetland));
+}
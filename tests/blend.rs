@@ -0,0 +1,70 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_blend() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let outlets = model.default_outlets().to_vec();
+
+    // an authored dome
+    let authored = model
+        .sites()
+        .iter()
+        .map(|site| (30.0 - 0.5 * (site.x - 50.0).hypot(site.y - 50.0)).max(0.0))
+        .collect::<Vec<_>>();
+    let generate = |weight: f64, time_step: Option<f64>| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(
+                authored
+                    .iter()
+                    .map(|&elevation| {
+                        TopographicalParameters::default().set_blend(weight, elevation)
+                    })
+                    .collect(),
+            )
+            .set_time_step(time_step)
+            .set_max_iteration(20)
+            .generate()
+            .unwrap()
+    };
+
+    for time_step in [None, Some(1.0)] {
+        // no blending with the weight 1.0
+        let simulated = generate(1.0, time_step);
+        let plain = TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![TopographicalParameters::default(); num])
+            .set_time_step(time_step)
+            .set_max_iteration(20)
+            .generate()
+            .unwrap();
+        assert_eq!(simulated.elevations(), plain.elevations());
+
+        // the authored surface with the weight 0.0
+        let authored_only = generate(0.0, time_step);
+        (0..num)
+            .filter(|i| !outlets.contains(i))
+            .for_each(|i| assert!((authored_only.elevations()[i] - authored[i]).abs() < 1e-9));
+
+        // the blended surface lies between them
+        let blended = generate(0.5, time_step);
+        let distance = |elevations: &[f64]| {
+            elevations
+                .iter()
+                .zip(&authored)
+                .map(|(e, a)| (e - a).abs())
+                .sum::<f64>()
+        };
+        assert!(blended.elevations().iter().all(|e| e.is_finite()));
+        assert!(distance(blended.elevations()) < distance(simulated.elevations()));
+    }
+}
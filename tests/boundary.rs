@@ -0,0 +1,98 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::boundary::{BoundaryCondition, BoundaryConditions2D};
+use fastlem::models::surface::builder::{ModelBuilderError, TerrainModel2DBulider};
+use fastlem::models::surface::sites::Site2D;
+extern crate fastlem;
+
+fn builder() -> TerrainModel2DBulider {
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    TerrainModel2DBulider::from_random_sites(1000, bound_min, bound_max)
+        .add_edge_sites(None, None)
+        .unwrap()
+}
+
+#[test]
+fn test_wall_boundaries() {
+    let model = builder()
+        .set_boundary_conditions(
+            BoundaryConditions2D::uniform(BoundaryCondition::Wall)
+                .set_bottom(BoundaryCondition::BaseLevel),
+        )
+        .build()
+        .unwrap();
+    assert!(!model.default_outlets().is_empty());
+    assert!(model
+        .default_outlets()
+        .iter()
+        .all(|&i| model.sites()[i].y < 1e-9));
+
+    let num = model.num();
+    let sites = model.sites().to_vec();
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(50)
+        .generate()
+        .unwrap();
+    // all the water drains through the bottom side
+    let network = terrain.network();
+    (0..num)
+        .filter(|&i| network.is_outlet(i))
+        .for_each(|i| assert!(sites[i].y < 1e-9));
+}
+
+#[test]
+fn test_periodic_boundaries() {
+    let model = builder()
+        .set_boundary_conditions(
+            BoundaryConditions2D::default()
+                .set_left(BoundaryCondition::Periodic)
+                .set_right(BoundaryCondition::Periodic),
+        )
+        .build()
+        .unwrap();
+    // the outlets are only on the bottom and top sides
+    assert!(model.default_outlets().iter().all(|&i| {
+        let site = model.sites()[i];
+        site.y.min(100.0 - site.y) <= site.x.min(100.0 - site.x)
+    }));
+    // the left and right sides are connected across the domain
+    let graph = model.graph();
+    let wrapped = (0..model.num())
+        .flat_map(|i| graph.neighbors_of(i).iter().map(move |ja| (i, ja.0, ja.1)))
+        .filter(|&(i, j, distance)| {
+            let (a, b) = (model.sites()[i], model.sites()[j]);
+            (a.x - b.x).abs() > 50.0 && distance < 10.0
+        })
+        .count();
+    assert!(wrapped > 0);
+
+    let num = model.num();
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(50)
+        .generate()
+        .unwrap();
+    assert!(terrain.elevations().iter().all(|e| e.is_finite()));
+}
+
+#[test]
+fn test_invalid_boundaries() {
+    let unpaired = builder()
+        .set_boundary_conditions(
+            BoundaryConditions2D::default().set_left(BoundaryCondition::Periodic),
+        )
+        .build();
+    assert!(matches!(
+        unpaired,
+        Err(ModelBuilderError::InvalidBoundaryConditions)
+    ));
+    let closed = builder()
+        .set_boundary_conditions(BoundaryConditions2D::uniform(BoundaryCondition::Wall))
+        .build();
+    assert!(matches!(closed, Err(ModelBuilderError::NoOutlets)));
+}
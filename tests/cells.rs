@@ -0,0 +1,31 @@
+use fastlem::core::traits::Model;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_cell_polygons() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    assert_eq!(model.cells().len(), model.num());
+    assert!(model.cell(model.num()).is_none());
+
+    // the area of each polygon is the area of the site
+    model.cells().iter().enumerate().for_each(|(i, cell)| {
+        assert!(cell.len() >= 3);
+        let area = (0..cell.len())
+            .map(|k| {
+                let (a, b) = (&cell[k], &cell[(k + 1) % cell.len()]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f64>()
+            .abs()
+            / 2.0;
+        assert!((area - model.areas()[i]).abs() <= area * 1e-9);
+        assert_eq!(model.cell(i).unwrap().len(), cell.len());
+    });
+}
@@ -0,0 +1,154 @@
+use fastlem::models::surface::channel::{ChannelCarver2D, HydraulicGeometry2D};
+use fastlem::models::surface::preset::IslandPreset2D;
+use fastlem::models::surface::raster::Rasterizer2D;
+use fastlem::models::surface::tiles::TileExporter2D;
+extern crate fastlem;
+
+#[test]
+fn test_hydraulic_geometry() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+
+    let geometry = HydraulicGeometry2D::default().set_precipitation(2.0);
+    let channels = geometry.estimate(&terrain, 50.0);
+    let network = terrain.network();
+    assert_eq!(channels.len(), terrain.sites().len());
+    assert!(channels.iter().any(|channel| channel.is_some()));
+
+    (0..channels.len()).for_each(|i| {
+        let drainage_area = network.drainage_areas()[i];
+        match channels[i] {
+            Some(channel) => {
+                assert!(drainage_area >= 50.0);
+                assert_eq!(channel.discharge, drainage_area * 2.0);
+                assert!((channel.width - 0.05 * channel.discharge.sqrt()).abs() < 1e-9);
+                // the channels widen and deepen downstream
+                let j = network.receivers()[i];
+                if let Some(downstream) = channels[j] {
+                    assert!(downstream.width >= channel.width);
+                    assert!(downstream.depth >= channel.depth);
+                }
+            }
+            None => assert!(drainage_area < 50.0 || drainage_area.is_nan()),
+        }
+    });
+}
+
+#[test]
+fn test_channel_carving() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+
+    let geometry = HydraulicGeometry2D::default().set_width_coefficient(0.3);
+    let carver = ChannelCarver2D::new(&terrain, &geometry, 50.0);
+    assert!(carver.num_segments() > 0);
+
+    let rasterizer = Rasterizer2D::default().set_size(100, 100);
+    let plain = rasterizer.rasterize_elevations(&terrain);
+    let carved = rasterizer
+        .set_channel_carver(Some(carver.clone()))
+        .rasterize_elevations(&terrain);
+    // the carving only lowers the elevations, and some pixels on the channels are lowered
+    let mut num_lowered = 0;
+    plain
+        .values()
+        .iter()
+        .zip(carved.values().iter())
+        .for_each(|(a, b)| match (a, b) {
+            (Some(a), Some(b)) => {
+                assert!(b <= a);
+                if b < a {
+                    num_lowered += 1;
+                }
+            }
+            _ => assert_eq!(a.is_some(), b.is_some()),
+        });
+    assert!(num_lowered > 0);
+
+    // the center of a channel is lowered by its depth
+    let channels = geometry.estimate(&terrain, 50.0);
+    let river = terrain
+        .extract_rivers(50.0)
+        .into_iter()
+        .find(|river| river.sites().len() >= 2)
+        .unwrap();
+    let i = river.sites()[0];
+    let depth = channels[i].unwrap().depth;
+    let elevation = terrain.elevations()[i];
+    let carved = carver.carve(&terrain.sites()[i], elevation);
+    assert!(carved <= elevation - depth + 1e-9);
+
+    let tiles = TileExporter2D::default()
+        .set_resolution(8)
+        .set_channel_carver(Some(carver))
+        .export(&terrain);
+    assert_eq!(tiles.len(), 16);
+}
+
+#[test]
+fn test_water_depth_and_discharge() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+
+    let geometry = HydraulicGeometry2D::default().set_precipitation(2.0);
+    let network = terrain.network();
+    let discharges = geometry.discharges(&terrain);
+    assert_eq!(discharges.len(), terrain.sites().len());
+    discharges
+        .iter()
+        .zip(network.drainage_areas().iter())
+        .for_each(|(&discharge, &drainage_area)| {
+            if drainage_area.is_finite() {
+                assert_eq!(discharge, (drainage_area * 2.0).max(0.0));
+            }
+        });
+
+    // only the channels have water
+    let channels = geometry.estimate(&terrain, 50.0);
+    let depths = geometry.water_depths(&terrain, 50.0);
+    depths
+        .iter()
+        .zip(channels.iter())
+        .for_each(|(&depth, channel)| match channel {
+            Some(channel) => assert_eq!(depth, channel.depth),
+            None => assert_eq!(depth, 0.0),
+        });
+    assert!(depths.iter().any(|&depth| depth > 0.0));
+
+    // the water at the center of a channel is as deep as the channel
+    let carver = ChannelCarver2D::new(&terrain, &geometry, 50.0);
+    let river = terrain
+        .extract_rivers(50.0)
+        .into_iter()
+        .find(|river| river.sites().len() >= 2)
+        .unwrap();
+    let i = river.sites()[0];
+    assert!(carver.water_depth(&terrain.sites()[i]) >= depths[i] - 1e-9);
+
+    let rasterizer = Rasterizer2D::default().set_size(100, 100);
+    let elevations = rasterizer.rasterize_elevations(&terrain);
+    let discharge_raster = rasterizer.rasterize_discharges(&terrain, &geometry);
+    let depth_raster = rasterizer.rasterize_water_depths(&terrain, &carver);
+    assert_eq!(discharge_raster.values().len(), 100 * 100);
+    assert_eq!(depth_raster.values().len(), 100 * 100);
+    elevations
+        .values()
+        .iter()
+        .zip(discharge_raster.values().iter())
+        .zip(depth_raster.values().iter())
+        .for_each(|((elevation, discharge), depth)| {
+            assert_eq!(elevation.is_some(), depth.is_some());
+            assert!(discharge.is_none_or(|discharge| discharge >= 0.0));
+            assert!(depth.is_none_or(|depth| depth >= 0.0));
+        });
+    assert!(depth_raster
+        .values()
+        .iter()
+        .any(|depth| depth.is_some_and(|depth| depth > 0.0)));
+}
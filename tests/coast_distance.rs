@@ -0,0 +1,85 @@
+use fastlem::core::fields::{COAST_DISTANCE, CONTINENTALITY};
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::{Model, Site};
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_coast_distance() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(10)
+        .generate()
+        .unwrap();
+    let distances = terrain.fields().get(COAST_DISTANCE).unwrap();
+    let continentalities = terrain.fields().get(CONTINENTALITY).unwrap();
+
+    let sites = model.sites();
+    let is_outlet = (0..num)
+        .map(|i| terrain.network().is_outlet(i))
+        .collect::<Vec<_>>();
+    let coast = (0..num)
+        .filter(|&i| {
+            !is_outlet[i]
+                && model
+                    .graph()
+                    .neighbors_of(i)
+                    .iter()
+                    .any(|ja| is_outlet[ja.0])
+        })
+        .collect::<Vec<_>>();
+    assert!(!coast.is_empty());
+
+    // the distance is 0.0 on the outlets and on the coast next to them
+    (0..num)
+        .filter(|&i| is_outlet[i])
+        .for_each(|i| assert_eq!(distances[i], 0.0));
+    coast.iter().for_each(|&i| assert_eq!(distances[i], 0.0));
+
+    (0..num)
+        .filter(|&i| !is_outlet[i] && distances[i] > 0.0)
+        .for_each(|i| {
+            // the distance along the edges is not shorter than the straight distance to the coast
+            let straight = coast
+                .iter()
+                .map(|&c| sites[i].distance(&sites[c]))
+                .fold(f64::INFINITY, f64::min);
+            assert!(distances[i] >= straight - 1e-9);
+
+            // the distance increases inland by the lengths of the edges
+            let neighbors = model.graph().neighbors_of(i);
+            let via = neighbors
+                .iter()
+                .filter(|ja| !is_outlet[ja.0])
+                .map(|ja| distances[ja.0] + ja.1)
+                .fold(f64::INFINITY, f64::min);
+            assert!((distances[i] - via).abs() < 1e-9);
+        });
+
+    // the interior is farther from the coast than the margins
+    let center = Site2D { x: 50.0, y: 50.0 };
+    let mean_distance = |inner: bool| {
+        let selected = (0..num)
+            .filter(|&i| (sites[i].distance(&center) < 10.0) == inner)
+            .collect::<Vec<_>>();
+        selected.iter().map(|&i| distances[i]).sum::<f64>() / selected.len() as f64
+    };
+    assert!(mean_distance(true) > mean_distance(false) * 1.5);
+
+    // the continentality is the distance normalized by its maximum
+    let max_distance = distances.iter().fold(0.0, |a: f64, &b| a.max(b));
+    assert!(max_distance > 0.0);
+    (0..num).for_each(|i| {
+        assert!((continentalities[i] - distances[i] / max_distance).abs() < 1e-12);
+        assert!((0.0..=1.0).contains(&continentalities[i]));
+    });
+    assert!(continentalities.contains(&1.0));
+}
@@ -0,0 +1,88 @@
+use fastlem::core::scale::VerticalScale;
+use fastlem::models::surface::collider::{ColliderExporter2D, Handedness, UpAxis, Winding};
+use fastlem::models::surface::preset::IslandPreset2D;
+use fastlem::models::surface::sites::Site2D;
+extern crate fastlem;
+
+#[test]
+fn test_collider_export() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+
+    let mesh = ColliderExporter2D::default().export(&terrain);
+    assert!(!mesh.indices.is_empty());
+    assert_eq!(mesh.indices.len() % 3, 0);
+    assert_eq!(mesh.vertices.len(), mesh.sites.len());
+    assert!(mesh
+        .indices
+        .iter()
+        .all(|&k| (k as usize) < mesh.vertices.len()));
+    assert!(mesh.vertices.iter().flatten().all(|v| v.is_finite()));
+    mesh.sites
+        .iter()
+        .zip(mesh.vertices.iter())
+        .for_each(|(&i, vertex)| {
+            let site = &terrain.sites()[i];
+            assert_eq!(
+                *vertex,
+                [site.x as f32, site.y as f32, terrain.elevations()[i] as f32]
+            );
+        });
+
+    [UpAxis::Y, UpAxis::Z].iter().for_each(|&up_axis| {
+        [Handedness::Right, Handedness::Left]
+            .iter()
+            .for_each(|&handedness| {
+                [Winding::CounterClockwise, Winding::Clockwise]
+                    .iter()
+                    .for_each(|&winding| {
+                        let mesh = ColliderExporter2D::default()
+                            .set_up_axis(up_axis)
+                            .set_handedness(handedness)
+                            .set_winding(winding)
+                            .export(&terrain);
+                        // the normals by the cross product point up for the front faces of a right-handed system
+                        let expected = match (handedness, winding) {
+                            (Handedness::Right, Winding::CounterClockwise)
+                            | (Handedness::Left, Winding::Clockwise) => 1.0,
+                            _ => -1.0,
+                        };
+                        let up = match up_axis {
+                            UpAxis::Y => 1,
+                            UpAxis::Z => 2,
+                        };
+                        mesh.indices.chunks(3).for_each(|triangle| {
+                            let [a, b, c] = [0, 1, 2]
+                                .map(|k| mesh.vertices[triangle[k] as usize].map(|v| v as f64));
+                            let (u, v) = (
+                                [b[0] - a[0], b[1] - a[1], b[2] - a[2]],
+                                [c[0] - a[0], c[1] - a[1], c[2] - a[2]],
+                            );
+                            let normal = [
+                                u[1] * v[2] - u[2] * v[1],
+                                u[2] * v[0] - u[0] * v[2],
+                                u[0] * v[1] - u[1] * v[0],
+                            ];
+                            assert!(normal[up] * expected > 0.0);
+                        });
+                    });
+            });
+    });
+
+    // the north of the model is the forward of Unity (+z) and glTF (-z)
+    let exporter = ColliderExporter2D::default()
+        .set_up_axis(UpAxis::Y)
+        .set_origin(Site2D::new(10.0, 20.0))
+        .set_horizontal_scale(2.0)
+        .set_vertical_scale(VerticalScale::default().set_exaggeration(3.0));
+    let site = Site2D::new(11.0, 23.0);
+    assert_eq!(exporter.convert(&site, 1.0), [2.0, 3.0, -6.0]);
+    assert_eq!(
+        exporter
+            .set_handedness(Handedness::Left)
+            .convert(&site, 1.0),
+        [2.0, 3.0, 6.0]
+    );
+}
@@ -0,0 +1,64 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::core::units::Step;
+use fastlem::lem::events::SimulationEvent;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{
+    builder::TerrainModel2DBulider, model::TerrainModel2D, sites::Site2D,
+};
+extern crate fastlem;
+
+/// Generate terrain and return the number of iterations with the elevations.
+fn generate(
+    model: &TerrainModel2D,
+    uplift_rate: f64,
+    time_step: Option<f64>,
+    convergence_tolerance: Option<f64>,
+) -> (Step, Vec<f64>) {
+    let num = model.sites().len();
+    let mut steps = 0;
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![
+            TopographicalParameters::default()
+                .set_uplift_rate(uplift_rate);
+            num
+        ])
+        .set_time_step(time_step)
+        .set_max_iteration(2000)
+        .set_convergence_tolerance(convergence_tolerance)
+        .generate_with_events(|event| {
+            if let SimulationEvent::Finished { step } = event {
+                steps = step;
+            }
+        })
+        .unwrap();
+    (steps, terrain.elevations().to_vec())
+}
+
+#[test]
+fn test_relative_convergence() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // the transient terrain approaches the steady state but rarely stops changing exactly
+    let (exact_steps, _) = generate(&model, 1.0, Some(1.0), None);
+    let (steps, elevations) = generate(&model, 1.0, Some(1.0), Some(1e-6));
+    assert!(steps < exact_steps);
+    assert!(elevations.iter().all(|e| e.is_finite()));
+
+    // the tolerance is relative to the relief, so the convergence does not depend on the scale of the elevations
+    let (large_steps, _) = generate(&model, 1000.0, Some(1.0), Some(1e-6));
+    let (small_steps, _) = generate(&model, 0.001, Some(1.0), Some(1e-6));
+    assert_eq!(large_steps, steps);
+    assert_eq!(small_steps, steps);
+
+    // the steady state converges exactly, so the tolerance stops it no later
+    let (exact_steps, _) = generate(&model, 1.0, None, None);
+    let (steps, _) = generate(&model, 1.0, None, Some(1e-6));
+    assert!(steps <= exact_steps);
+}
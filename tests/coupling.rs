@@ -0,0 +1,53 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_coupling() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let generator = || {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![TopographicalParameters::default(); num])
+            .set_time_step(Some(1.0))
+            .set_max_iteration(20)
+    };
+
+    // the coupling which does nothing keeps the result
+    let plain = generator().generate().unwrap();
+    let mut steps = Vec::new();
+    let observed = generator()
+        .generate_with_coupling(|state| {
+            assert_eq!(state.drainage_areas.len(), num);
+            steps.push(state.step);
+        })
+        .unwrap();
+    assert_eq!(plain.elevations(), observed.elevations());
+    assert!(steps
+        .iter()
+        .enumerate()
+        .all(|(k, &step)| step as usize == k + 1));
+    assert!(!steps.is_empty());
+
+    // the external model lowers the terrain and stops the uplift
+    let mut lowered = 0;
+    let coupled = generator()
+        .generate_with_coupling(|state| {
+            state.elevations.iter_mut().for_each(|e| *e *= 0.5);
+            state
+                .parameters
+                .iter_mut()
+                .for_each(|param| *param = param.clone().set_uplift_rate(0.0));
+            lowered += 1;
+        })
+        .unwrap();
+    assert_eq!(lowered, steps.len());
+    let max = |elevations: &[f64]| elevations.iter().fold(0.0_f64, |max, &e| max.max(e));
+    assert!(max(coupled.elevations()) < max(plain.elevations()));
+}
@@ -0,0 +1,66 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::{Model, Site};
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::crater::{Crater, CraterProcess};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_crater() {
+    let num = 4000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let center = Site2D { x: 50.0, y: 50.0 };
+    let site = (0..num)
+        .min_by(|&a, &b| {
+            model.sites()[a]
+                .distance(&center)
+                .total_cmp(&model.sites()[b].distance(&center))
+        })
+        .unwrap();
+
+    let (diameter, depth, rim_height) = (30.0, 6.0, 1.2);
+    let radius = diameter * 0.5;
+    let generator = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(1);
+    let original = generator.clone().generate().unwrap();
+    // the steps start from 1
+    let terrain = generator
+        .add_process(
+            CraterProcess::default().add_crater(
+                Crater::new(site, 1, diameter)
+                    .set_depth(depth)
+                    .set_rim_height(rim_height),
+            ),
+        )
+        .generate()
+        .unwrap();
+    let change = |i: usize| terrain.elevations()[i] - original.elevations()[i];
+    let distance = |i: usize| model.sites()[i].distance(&model.sites()[site]);
+
+    // the floor of the bowl is at the depth below the original surface
+    assert!((change(site) + depth).abs() < 1e-9);
+    (0..num)
+        .filter(|&i| distance(i) < radius * 0.5)
+        .for_each(|i| assert!(change(i) < 0.0));
+
+    // the rim is the highest, and the ejecta blanket thins out up to the range
+    let highest = (0..num)
+        .max_by(|&a, &b| change(a).total_cmp(&change(b)))
+        .unwrap();
+    assert!(change(highest) <= rim_height + 1e-9);
+    assert!(change(highest) > rim_height * 0.5);
+    assert!((distance(highest) - radius).abs() < radius * 0.2);
+    (0..num)
+        .filter(|&i| distance(i) > radius * 1.5 && distance(i) <= radius * 3.0)
+        .for_each(|i| assert!(change(i) >= 0.0 && change(i) < rim_height / 1.5f64.powi(3)));
+    (0..num)
+        .filter(|&i| distance(i) > radius * 3.0)
+        .for_each(|i| assert_eq!(change(i), 0.0));
+}
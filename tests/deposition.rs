@@ -0,0 +1,219 @@
+use fastlem::core::fields::{SiteFields, DEPOSIT_THICKNESS, SEDIMENT_FLUX};
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::{Model, Site};
+use fastlem::lem::process::{Process, SimulationState};
+use fastlem::lem::processes::deposition::DepositionProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_fan_at_slope_break() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let sites = model.sites();
+    let areas = model.areas();
+
+    // a steep channel from the site `source` breaks into the apex of a cone at the site `apex`
+    let apex = model.nearest_site(&Site2D { x: 50.0, y: 50.0 }).unwrap();
+    let source = model.graph().neighbors_of(apex)[0].0;
+    let mut elevations = (0..num)
+        .map(|i| -2.0 * sites[i].distance(&sites[apex]))
+        .collect::<Vec<_>>();
+    elevations[source] = 10.0;
+
+    // each site drains to its lowest neighbor, and only the channel is erodible
+    let mut receivers = (0..num)
+        .map(|i| {
+            model
+                .graph()
+                .neighbors_of(i)
+                .iter()
+                .map(|ja| ja.0)
+                .filter(|&j| elevations[j] < elevations[i])
+                .min_by(|&j, &k| elevations[j].total_cmp(&elevations[k]))
+                .unwrap_or(i)
+        })
+        .collect::<Vec<_>>();
+    receivers[source] = apex;
+    let mut drainage_areas = areas.to_vec();
+    drainage_areas[source] = 25.0;
+    let mut parameters = (0..num)
+        .map(|i| {
+            TopographicalParameters::default().set_erodibility(if i == source { 1.0 } else { 0.0 })
+        })
+        .collect::<Vec<_>>();
+
+    let spreading_distance = 5.0;
+    let original_elevations = elevations.clone();
+    let mut fields = SiteFields::default();
+    let mut state = SimulationState {
+        step: 1,
+        time_step: 1.0,
+        areas,
+        graph: model.graph(),
+        receivers: &receivers,
+        fallback_distance: 1.0,
+        drainage_areas: &drainage_areas,
+        elevations: &mut elevations,
+        parameters: &mut parameters,
+        fields: &mut fields,
+    };
+    DepositionProcess::default()
+        .set_spreading_distance(spreading_distance)
+        .apply(&mut state);
+
+    let thickness = fields.get(DEPOSIT_THICKNESS).unwrap();
+    let fluxes = fields.get(SEDIMENT_FLUX).unwrap();
+    let distances = model
+        .distance_field(&[apex], f64::INFINITY)
+        .distances()
+        .to_vec();
+
+    // the whole sediment from the channel is deposited around the apex
+    let slope = 10.0 / model.graph().has_edge(source, apex).1;
+    let supply = (25.0f64).sqrt() * slope * areas[source];
+    let deposited = (0..num).map(|i| thickness[i] * areas[i]).sum::<f64>();
+    assert!((deposited - supply).abs() < supply * 1e-9);
+    assert!(fluxes[apex] < supply * 1e-9);
+
+    (0..num).for_each(|i| {
+        assert!(thickness[i] >= 0.0);
+        if i == apex || i == source {
+            assert_eq!(thickness[i], 0.0);
+        } else if distances[i] < spreading_distance && receivers[i] != i {
+            // the fan spreads radially over the lower sites within the distance, thinning away from the apex
+            assert!(thickness[i] > 0.0);
+            assert!(elevations[i] <= elevations[apex]);
+        } else if distances[i] > spreading_distance {
+            // only the rounding error of the sediment continues downstream
+            assert!(thickness[i] * areas[i] < supply * 1e-9);
+        }
+    });
+    let near = (0..num)
+        .filter(|&i| i != apex && i != source && distances[i] < spreading_distance * 0.5)
+        .collect::<Vec<_>>();
+    let far = (0..num)
+        .filter(|&i| distances[i] > spreading_distance * 0.5 && distances[i] < spreading_distance)
+        .collect::<Vec<_>>();
+    let mean =
+        |sites: &[usize]| sites.iter().map(|&i| thickness[i]).sum::<f64>() / sites.len() as f64;
+    assert!(mean(&near) > mean(&far));
+
+    // the fan extends in all directions from the apex
+    let direction = |i: usize| {
+        let (dx, dy) = (sites[i].x - sites[apex].x, sites[i].y - sites[apex].y);
+        (dx > 0.0, dy > 0.0)
+    };
+    [(false, false), (false, true), (true, false), (true, true)]
+        .iter()
+        .for_each(|&quadrant| {
+            assert!((0..num).any(|i| thickness[i] > 0.0 && direction(i) == quadrant));
+        });
+    (0..num).for_each(|i| assert!(elevations[i] >= original_elevations[i]));
+}
+
+#[test]
+fn test_delta_at_coastal_outlet() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let sites = model.sites();
+    let areas = model.areas();
+    let sea_level = 0.0;
+
+    // the sea in the west and the land rising to the east
+    let mut elevations = sites
+        .iter()
+        .map(|site| {
+            if site.x < 50.0 {
+                -1.0
+            } else {
+                (site.x - 50.0) * 0.5 + 0.1
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // a river from the site `source` on the land flows into the outlet `mouth` on the coast,
+    // next to which is another outlet `other`
+    let mouth = model.nearest_site(&Site2D { x: 45.0, y: 50.0 }).unwrap();
+    let other = model.graph().neighbors_of(mouth)[0].0;
+    let source = model.nearest_site(&Site2D { x: 55.0, y: 50.0 }).unwrap();
+    elevations[source] = 20.0;
+    let receivers = (0..num)
+        .map(|i| if i == other { i } else { mouth })
+        .collect::<Vec<_>>();
+    let is_outlet = |i: usize| receivers[i] == i;
+    assert!(is_outlet(mouth) && is_outlet(other) && elevations[other] < sea_level);
+
+    let mut drainage_areas = areas.to_vec();
+    drainage_areas[source] = 10000.0;
+    let mut parameters = (0..num)
+        .map(|i| {
+            TopographicalParameters::default().set_erodibility(if i == source { 1.0 } else { 0.0 })
+        })
+        .collect::<Vec<_>>();
+
+    let spreading_distance = 5.0;
+    let original_elevations = elevations.clone();
+    let mut fields = SiteFields::default();
+    let mut state = SimulationState {
+        step: 1,
+        time_step: 1.0,
+        areas,
+        graph: model.graph(),
+        receivers: &receivers,
+        fallback_distance: 10.0,
+        drainage_areas: &drainage_areas,
+        elevations: &mut elevations,
+        parameters: &mut parameters,
+        fields: &mut fields,
+    };
+    DepositionProcess::default()
+        .set_spreading_distance(spreading_distance)
+        .set_sea_level(Some(sea_level))
+        .apply(&mut state);
+
+    let thickness = fields.get(DEPOSIT_THICKNESS).unwrap();
+    let fluxes = fields.get(SEDIMENT_FLUX).unwrap();
+    let distances = model
+        .distance_field(&[mouth], f64::INFINITY)
+        .distances()
+        .to_vec();
+
+    // the outlets receive no deposits
+    assert_eq!(thickness[mouth], 0.0);
+    assert_eq!(thickness[other], 0.0);
+
+    (0..num).for_each(|i| {
+        if thickness[i] > 0.0 {
+            // the deposits are only in the sea near the mouth, and never rise above the sea level
+            assert!(original_elevations[i] < sea_level);
+            assert!(distances[i] <= spreading_distance);
+            assert!(elevations[i] <= sea_level + 1e-12);
+        } else {
+            assert_eq!(thickness[i], 0.0);
+        }
+    });
+
+    // the supply exceeds the room below the sea level, so the sea near the mouth is filled up to the level
+    // and the rest of the sediment leaves the domain at the mouth
+    let filled = (0..num)
+        .filter(|&i| {
+            !is_outlet(i) && original_elevations[i] < sea_level && distances[i] < spreading_distance
+        })
+        .collect::<Vec<_>>();
+    assert!(!filled.is_empty());
+    filled
+        .iter()
+        .for_each(|&i| assert!((elevations[i] - sea_level).abs() < 1e-12));
+    let deposited = (0..num).map(|i| thickness[i] * areas[i]).sum::<f64>();
+    assert!(fluxes[mouth] > 0.0);
+    assert!((deposited + fluxes[mouth] - fluxes[source]).abs() < fluxes[source] * 1e-9);
+}
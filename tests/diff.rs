@@ -0,0 +1,63 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::events::SimulationEvent;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::diff::{TerrainDiff2D, TerrainDiffError};
+use fastlem::models::surface::raster::Rasterizer2D;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_terrain_diff() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let mut snapshots = Vec::new();
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(10)
+        .set_snapshot_interval(Some(5))
+        .generate_with_events(|event| {
+            if let SimulationEvent::SnapshotReady { elevations, .. } = event {
+                snapshots.push(elevations);
+            }
+        })
+        .unwrap();
+    assert_eq!(snapshots.len(), 2);
+
+    // the terrain rises by the uplift between the snapshots
+    let diff = TerrainDiff2D::between_elevations(&terrain, &snapshots[0], &snapshots[1]).unwrap();
+    let summary = diff.summary();
+    assert_eq!(diff.changes().len(), num);
+    assert!(summary.mean_change > 0.0);
+    assert!(summary.max_rise >= summary.mean_absolute_change);
+    assert!(summary.num_changed > 0);
+    let (i, site) = (num / 2, terrain.sites()[num / 2]);
+    assert!((diff.get_change(&site).unwrap() - diff.changes()[i]).abs() < 1e-9);
+
+    // no change between the same terrains
+    let same = TerrainDiff2D::between(&terrain, &terrain).unwrap();
+    assert_eq!(same.summary().num_changed, 0);
+    assert_eq!(same.summary().max_fall, 0.0);
+
+    let rasterizer = Rasterizer2D::default()
+        .set_bounding_box(bound_min, bound_max)
+        .set_size(20, 10);
+    let raster = diff.rasterize(&rasterizer);
+    assert_eq!((raster.width(), raster.height()), (20, 10));
+    assert!(raster.values().iter().flatten().any(|c| *c > 0.0));
+
+    let mut image = Vec::new();
+    diff.write_image(&rasterizer, &mut image).unwrap();
+    assert_eq!(&image[..8], b"\x89PNG\r\n\x1a\n");
+
+    assert!(matches!(
+        TerrainDiff2D::between_elevations(&terrain, &snapshots[0][1..], &snapshots[1]),
+        Err(TerrainDiffError::InvalidNumberOfElevations)
+    ));
+}
@@ -0,0 +1,97 @@
+use fastlem::core::distance::DistanceField;
+use fastlem::core::traits::Model;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_distance_field() {
+    let num = 1000;
+    let model = TerrainModel2DBulider::from_random_sites(
+        num,
+        Site2D::new(0.0, 0.0),
+        Site2D::new(100.0, 100.0),
+    )
+    .build()
+    .unwrap();
+    let graph = model.graph();
+    let seeds = [
+        model.nearest_site(&Site2D::new(20.0, 20.0)).unwrap(),
+        model.nearest_site(&Site2D::new(80.0, 70.0)).unwrap(),
+    ];
+    let field = model.distance_field(&seeds, f64::INFINITY);
+    let distances = field.distances();
+    assert_eq!(distances.len(), num);
+    assert!(distances.iter().all(|d| d.is_finite()));
+    seeds.iter().for_each(|&seed| {
+        assert_eq!(distances[seed], 0.0);
+        assert_eq!(field.nearest_seeds()[seed], Some(seed));
+    });
+
+    // the distance from the seeds is the minimum of the distances from each seed
+    let fields = seeds.map(|seed| DistanceField::new(graph, &[seed], f64::INFINITY));
+    (0..num).for_each(|i| {
+        let nearest = fields[0].distances()[i].min(fields[1].distances()[i]);
+        assert_eq!(distances[i], nearest);
+        let seed = field.nearest_seeds()[i].unwrap();
+        let k = seeds.iter().position(|&s| s == seed).unwrap();
+        assert_eq!(fields[k].distances()[i], distances[i]);
+        // no edge is a shortcut
+        graph.neighbors_of(i).iter().for_each(|ja| {
+            assert!(distances[ja.0] <= distances[i] + ja.1 + 1e-9);
+        });
+        // the distance along the edges is not shorter than the straight line
+        let straight = seeds
+            .iter()
+            .map(|&s| {
+                let (a, b) = (&model.sites()[i], &model.sites()[s]);
+                ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+            })
+            .fold(f64::INFINITY, f64::min);
+        assert!(distances[i] >= straight - 1e-9);
+    });
+
+    // the reached sites are in the order of the distance
+    assert_eq!(field.reached().len(), num);
+    assert!(field.reached().windows(2).all(|w| w[0].1 <= w[1].1));
+
+    // the sites farther than the maximum distance are not reached, and the invalid seeds are ignored
+    let field = DistanceField::new(graph, &[seeds[0], num + 10], 10.0);
+    (0..num).for_each(|i| {
+        let distance = field.distances()[i];
+        assert_eq!(distance <= 10.0, field.nearest_seeds()[i].is_some());
+        assert!(distance <= 10.0 || distance.is_infinite());
+    });
+    assert!(field.reached().len() < num);
+
+    let field = DistanceField::new(graph, &[], f64::INFINITY);
+    assert!(field.reached().is_empty());
+    assert!(field.distances().iter().all(|d| d.is_infinite()));
+}
+
+#[test]
+fn test_distance_field_with_repeated_seeds() {
+    let num = 1000;
+    let model = TerrainModel2DBulider::from_random_sites(
+        num,
+        Site2D::new(0.0, 0.0),
+        Site2D::new(100.0, 100.0),
+    )
+    .build()
+    .unwrap();
+    let a = model.nearest_site(&Site2D::new(20.0, 20.0)).unwrap();
+    let b = model.nearest_site(&Site2D::new(80.0, 70.0)).unwrap();
+
+    let unique = model.distance_field(&[a, b], 30.0);
+    let repeated = model.distance_field(&[a, b, a, a, b], 30.0);
+    assert_eq!(repeated.distances(), unique.distances());
+    assert_eq!(repeated.nearest_seeds(), unique.nearest_seeds());
+
+    // each site is reached only once
+    let reached = repeated.into_reached();
+    let mut sites = reached.iter().map(|&(i, _)| i).collect::<Vec<_>>();
+    sites.sort_unstable();
+    sites.dedup();
+    assert_eq!(sites.len(), reached.len());
+    assert_eq!(reached.len(), unique.reached().len());
+    assert_eq!(reached.iter().filter(|&&(_, d)| d == 0.0).count(), 2);
+}
@@ -0,0 +1,45 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::drainage_density::DrainageDensityAnalysis2D;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_drainage_density_curve() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(50)
+        .generate()
+        .unwrap();
+
+    let curve = DrainageDensityAnalysis2D::default()
+        .set_num_thresholds(10)
+        .analyze(&terrain);
+    assert_eq!(curve.len(), 10);
+    assert!(curve[0].density > 0.0);
+    curve.windows(2).for_each(|pair| {
+        assert!(pair[0].threshold <= pair[1].threshold);
+        assert!(pair[0].channel_length >= pair[1].channel_length);
+        assert!(pair[0].density >= pair[1].density);
+    });
+
+    // every site drains to some receiver or an outlet, so the smallest threshold covers the whole network
+    // and the density is roughly the inverse of the spacing of the sites
+    let spacing = (100.0 * 100.0 / num as f64).sqrt();
+    assert!(curve[0].density > 0.3 / spacing && curve[0].density < 3.0 / spacing);
+
+    // explicit thresholds are sorted
+    let curve = DrainageDensityAnalysis2D::default()
+        .set_thresholds(Some(vec![1000.0, 10.0, f64::MAX]))
+        .analyze(&terrain);
+    assert_eq!(curve.len(), 3);
+    assert_eq!(curve[0].threshold, 10.0);
+    assert_eq!(curve[2].channel_length, 0.0);
+}
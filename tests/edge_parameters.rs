@@ -0,0 +1,70 @@
+use fastlem::core::parameters::{EdgeParameters, TopographicalParameters};
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::{GenerationError, TerrainGenerator};
+use fastlem::lem::record::SimulationRecord;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_edge_parameters() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .relaxate_sites(1)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // a fault gouge along the line x = 50
+    let in_fault = |i: usize| (model.sites()[i].x - 50.0).abs() < 3.0;
+    let fault_edges = (0..num)
+        .flat_map(|i| {
+            model
+                .graph()
+                .neighbors_of(i)
+                .iter()
+                .map(move |ja| (i, ja.0))
+                .collect::<Vec<_>>()
+        })
+        .filter(|&(i, j)| i < j && in_fault(i) && in_fault(j))
+        .collect::<Vec<_>>();
+    assert!(!fault_edges.is_empty());
+
+    let generator = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(50);
+    let faulted = fault_edges
+        .iter()
+        .fold(generator.clone(), |generator, &(i, j)| {
+            generator.set_edge_parameters(
+                i,
+                j,
+                EdgeParameters::default().set_erodibility_factor(10.0),
+            )
+        });
+
+    let mean_in_fault = |elevations: &[f64]| {
+        let values = (0..num)
+            .filter(|&i| in_fault(i))
+            .map(|i| elevations[i])
+            .collect::<Vec<_>>();
+        values.iter().sum::<f64>() / values.len() as f64
+    };
+    let (terrain, record) = faulted.generate_with_record().unwrap();
+    let original = generator.clone().generate().unwrap();
+    // the gouge is eroded into a valley
+    assert!(mean_in_fault(terrain.elevations()) < mean_in_fault(original.elevations()));
+
+    let mut buf = Vec::new();
+    record.write_to(&mut buf).unwrap();
+    let restored = SimulationRecord::read_from(buf.as_slice()).unwrap();
+    assert!(restored.replay().unwrap().is_identical());
+
+    // the edge must exist
+    let result = generator
+        .set_edge_parameters(0, 0, EdgeParameters::default())
+        .generate();
+    assert!(matches!(result, Err(GenerationError::InvalidEdge(0, 0))));
+}
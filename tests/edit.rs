@@ -0,0 +1,137 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::builder::ModelBuilderError;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_insert_and_remove_sites() {
+    let num = 500;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let labels = (0..num).map(|i| i as f64).collect::<Vec<_>>();
+
+    // refine a region
+    let inserted = (0..20)
+        .map(|k| Site2D {
+            x: 40.0 + (k % 5) as f64 * 2.0,
+            y: 40.0 + (k / 5) as f64 * 2.0,
+        })
+        .collect::<Vec<_>>();
+    let (refined, mapping) = model.insert_sites(&inserted).unwrap();
+    assert_eq!(refined.num(), num + inserted.len());
+    assert_eq!(refined.bounds().1.x, 100.0);
+    assert_eq!(refined.sites()[num].x, inserted[0].x);
+    assert_eq!(mapping.previous_index(3), Some(3));
+    assert_eq!(mapping.previous_index(num), None);
+    let remapped = mapping.remap(&labels);
+    assert_eq!(remapped.len(), refined.num());
+    assert_eq!(remapped[3], 3.0);
+    let nearest = model.nearest_site(&inserted[0]).unwrap();
+    assert_eq!(remapped[num], nearest as f64);
+
+    // the edited model can be simulated with the remapped parameters
+    let terrain = TerrainGenerator::default()
+        .set_model(refined.clone())
+        .set_parameters(mapping.remap(&vec![TopographicalParameters::default(); num]))
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+    assert_eq!(terrain.elevations().len(), refined.num());
+
+    // remove the inserted sites again
+    let indices = (num..refined.num()).collect::<Vec<_>>();
+    let (restored, mapping) = refined.remove_sites(&indices).unwrap();
+    assert_eq!(restored.num(), num);
+    assert_eq!(mapping.new_index(num), None);
+    assert_eq!(mapping.new_index(10), Some(10));
+    assert_eq!(mapping.previous_index(10), Some(10));
+    assert_eq!(restored.areas()[10], model.areas()[10]);
+
+    // remove a site in the middle
+    let (removed, mapping) = model.remove_sites(&[5, 5, num + 100]).unwrap();
+    assert_eq!(removed.num(), num - 1);
+    assert_eq!(mapping.new_index(5), None);
+    assert_eq!(mapping.new_index(6), Some(5));
+    assert_eq!(mapping.remap(&labels)[5], 6.0);
+
+    assert!(matches!(
+        model.insert_sites(&[Site2D { x: 200.0, y: 0.0 }]),
+        Err(ModelBuilderError::SiteOutOfBounds)
+    ));
+}
+
+#[test]
+fn test_merge_models() {
+    let coarse = TerrainModel2DBulider::from_random_sites(
+        500,
+        Site2D { x: 0.0, y: 0.0 },
+        Site2D { x: 100.0, y: 100.0 },
+    )
+    .build()
+    .unwrap();
+    let inset = TerrainModel2DBulider::from_random_sites(
+        400,
+        Site2D { x: 40.0, y: 40.0 },
+        Site2D { x: 60.0, y: 60.0 },
+    )
+    .build()
+    .unwrap();
+
+    let (merged, coarse_mapping, inset_mapping) = coarse.merge(&inset).unwrap();
+    let covered = coarse
+        .sites()
+        .iter()
+        .filter(|site| site.x >= 40.0 && site.x <= 60.0 && site.y >= 40.0 && site.y <= 60.0)
+        .count();
+    assert_eq!(merged.num(), coarse.num() - covered + inset.num());
+    assert_eq!(merged.bounds().0.x, 0.0);
+    assert_eq!(merged.bounds().1.y, 100.0);
+
+    // each merged site comes from exactly one of the models
+    (0..merged.num()).for_each(|i| {
+        let from_coarse = coarse_mapping.previous_index(i);
+        let from_inset = inset_mapping.previous_index(i);
+        assert!(from_coarse.is_some() != from_inset.is_some());
+        if let Some(j) = from_inset {
+            assert_eq!(merged.sites()[i].x, inset.sites()[j].x);
+            assert_eq!(inset_mapping.new_index(j), Some(i));
+        }
+    });
+
+    // the outlets of the inset are inside the merged domain
+    assert!(merged.default_outlets().iter().all(|&i| {
+        let site = merged.sites()[i];
+        !(site.x > 40.0 && site.x < 60.0 && site.y > 40.0 && site.y < 60.0)
+    }));
+
+    // the parameters of the models are combined
+    let coarse_labels = vec![1.0; coarse.num()];
+    let inset_labels = vec![2.0; inset.num()];
+    let (from_coarse, from_inset) = (
+        coarse_mapping.remap(&coarse_labels),
+        inset_mapping.remap(&inset_labels),
+    );
+    let labels = (0..merged.num())
+        .map(|i| {
+            if coarse_mapping.previous_index(i).is_some() {
+                from_coarse[i]
+            } else {
+                from_inset[i]
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(labels.iter().filter(|&&l| l == 2.0).count(), inset.num());
+
+    let terrain = TerrainGenerator::default()
+        .set_model(merged.clone())
+        .set_parameters(vec![TopographicalParameters::default(); merged.num()])
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+    assert_eq!(terrain.elevations().len(), merged.num());
+}
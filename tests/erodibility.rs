@@ -0,0 +1,66 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::{GenerationError, TerrainGenerator};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_non_erodible_sites() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // a resistant ridge of bedrock across the domain
+    let parameters = model
+        .sites()
+        .iter()
+        .map(|site| {
+            let erodibility = if (site.x - 50.0).abs() < 3.0 {
+                0.0
+            } else {
+                1.0
+            };
+            TopographicalParameters::default().set_erodibility(erodibility)
+        })
+        .collect::<Vec<_>>();
+
+    for time_step in [None, Some(1.0)] {
+        let terrain = TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(parameters.clone())
+            .set_time_step(time_step)
+            .set_max_iteration(20)
+            .set_debug_checks(true)
+            .generate()
+            .unwrap();
+        assert!(terrain.elevations().iter().all(|e| e.is_finite()));
+    }
+}
+
+#[test]
+fn test_negative_erodibility() {
+    let num = 100;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // NaN is rejected as well as the negative values
+    [-1.0, f64::NAN].iter().for_each(|&erodibility| {
+        let mut parameters = vec![TopographicalParameters::default(); num];
+        parameters[7] = TopographicalParameters::default().set_erodibility(erodibility);
+        let result = TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(parameters)
+            .set_max_iteration(1)
+            .generate();
+        assert!(matches!(
+            result,
+            Err(GenerationError::InvalidErodibility(7))
+        ));
+    });
+}
@@ -0,0 +1,87 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::estuary::EstuaryDetector2D;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_estuary_at_river_mouth() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(50)
+        .generate()
+        .unwrap();
+    let elevations = terrain.elevations();
+    let receivers = terrain.network().receivers();
+    let drainage_areas = terrain.network().drainage_areas();
+
+    // the mouth of the largest river, flowing into an outlet on the coast
+    let mouth = (0..num)
+        .filter(|&i| receivers[i] != i)
+        .max_by(|&i, &j| drainage_areas[i].total_cmp(&drainage_areas[j]))
+        .unwrap();
+    assert_eq!(receivers[receivers[mouth]], receivers[mouth]);
+    assert!(elevations[mouth] > 0.0);
+
+    // nothing is drowned at the sea level of the simulation
+    assert!(EstuaryDetector2D::default()
+        .detect(&model, &terrain)
+        .is_empty());
+
+    // the sea rises over the mouth and floods up the valley
+    let sea_level = elevations[mouth] + 1.0;
+    let detector = EstuaryDetector2D::default().set_sea_level(sea_level);
+    let ocean = detector.ocean_mask(&model, &terrain);
+    model
+        .default_outlets()
+        .iter()
+        .for_each(|&i| assert!(ocean[i]));
+    (0..num).for_each(|i| {
+        if ocean[i] {
+            assert!(elevations[i] <= sea_level);
+        } else if elevations[i] < sea_level {
+            // the sites below the sea level are flooded only if they are connected to the ocean
+            assert!(model.graph().neighbors_of(i).iter().all(|ja| !ocean[ja.0]));
+        }
+    });
+
+    let estuaries = detector.detect(&model, &terrain);
+    let mut drowned = vec![false; num];
+    estuaries.iter().for_each(|estuary| {
+        assert!(!estuary.outlines.is_empty());
+        assert!(estuary.sites.iter().any(|&i| drainage_areas[i] >= 100.0));
+        estuary.sites.iter().for_each(|&i| {
+            assert!(ocean[i] && elevations[i] > 0.0);
+            assert!(!drowned[i]);
+            drowned[i] = true;
+        });
+    });
+    let estuary = estuaries
+        .iter()
+        .find(|estuary| estuary.sites.contains(&mouth))
+        .unwrap();
+    // the drowned valley includes the channel upstream of the mouth below the sea level
+    (0..num)
+        .filter(|&i| receivers[i] == mouth && elevations[i] < sea_level && ocean[i])
+        .for_each(|i| assert!(estuary.sites.contains(&i)));
+
+    // the rise does not drown anything below the previous sea level
+    assert!(detector
+        .clone()
+        .set_previous_sea_level(Some(sea_level))
+        .detect(&model, &terrain)
+        .is_empty());
+    // the drowned sites without valleys are not estuaries
+    assert!(detector
+        .set_min_drainage_area(f64::MAX)
+        .detect(&model, &terrain)
+        .is_empty());
+}
@@ -0,0 +1,93 @@
+use std::sync::{Arc, Mutex};
+
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::{FallbackDistance, TerrainGenerator};
+use fastlem::lem::process::{Process, SimulationState};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+/// A process recording the fallback distance passed to the processes.
+struct FallbackProbe(Arc<Mutex<Vec<f64>>>);
+
+impl Process for FallbackProbe {
+    fn name(&self) -> &str {
+        "fallback_probe"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        self.0.lock().unwrap().push(state.fallback_distance);
+    }
+}
+
+#[test]
+fn test_fallback_distance() {
+    let num = 500;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D {
+        x: 1000.0,
+        y: 1000.0,
+    };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let (sum, count) = (0..num)
+        .flat_map(|i| model.graph().neighbors_of(i).iter())
+        .fold((0.0, 0), |(sum, count), ja| (sum + ja.1, count + 1));
+    let mean_edge_length = sum / count as f64;
+    // the domain is far from the unit scale, so the fallback must follow it
+    assert!(mean_edge_length > 10.0);
+
+    let generate = |fallback_distance: FallbackDistance| {
+        let probe = Arc::new(Mutex::new(Vec::new()));
+        let terrain = TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![TopographicalParameters::default(); num])
+            .set_max_iteration(3)
+            .set_fallback_distance(fallback_distance)
+            .add_process(FallbackProbe(probe.clone()))
+            .generate()
+            .unwrap();
+        let distances = probe.lock().unwrap().clone();
+        (terrain, distances)
+    };
+
+    let (default_terrain, distances) = generate(FallbackDistance::default());
+    assert_eq!(distances.len(), 3);
+    assert!(distances
+        .iter()
+        .all(|&d| (d - mean_edge_length).abs() < 1e-9 * mean_edge_length));
+
+    let (_, distances) = generate(FallbackDistance::Fixed(2.5));
+    assert!(distances.iter().all(|&d| d == 2.5));
+
+    // the stream tree of a valid model only routes the flow along the edges, so the strict mode succeeds
+    // and the fallback does not change the result
+    let (strict_terrain, _) = generate(FallbackDistance::Strict);
+    assert_eq!(default_terrain.elevations(), strict_terrain.elevations());
+}
+
+#[test]
+fn test_fallback_distance_manifest() {
+    let num = 100;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let parameters = vec![TopographicalParameters::default(); num];
+    let generator = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(parameters.clone())
+        .set_max_iteration(1)
+        .set_fallback_distance(FallbackDistance::Fixed(3.0));
+    let manifest = generator.to_manifest().unwrap();
+    assert_eq!(manifest.fallback_distance, FallbackDistance::Fixed(3.0));
+
+    let rebuilt = TerrainGenerator::from_manifest(&manifest)
+        .set_model(model)
+        .set_parameters(parameters);
+    assert_eq!(rebuilt.to_manifest().unwrap(), manifest);
+}
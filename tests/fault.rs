@@ -0,0 +1,85 @@
+use fastlem::core::fields::FAULT_THROW;
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::fault::FaultProcess;
+use fastlem::models::surface::{
+    builder::TerrainModel2DBulider, fault::FaultTrace2D, sites::Site2D,
+};
+extern crate fastlem;
+
+#[test]
+fn test_fault_trace() {
+    // the trace runs to the north, so the hanging wall is on the west
+    let trace = FaultTrace2D::new(vec![
+        Site2D { x: 50.0, y: 0.0 },
+        Site2D { x: 50.0, y: 100.0 },
+    ]);
+    let sites = [
+        Site2D { x: 10.0, y: 50.0 },
+        Site2D { x: 45.0, y: 50.0 },
+        Site2D { x: 55.0, y: 50.0 },
+        Site2D { x: 90.0, y: 50.0 },
+    ];
+    assert_eq!(
+        trace.hanging_wall(&sites, None),
+        vec![true, true, false, false]
+    );
+    assert_eq!(
+        trace.hanging_wall(&sites, Some(10.0)),
+        vec![false, true, false, false]
+    );
+    assert_eq!(
+        FaultTrace2D::new(vec![Site2D { x: 50.0, y: 0.0 }]).hanging_wall(&sites, None),
+        vec![false; 4]
+    );
+}
+
+#[test]
+fn test_fault() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let trace = FaultTrace2D::new(vec![
+        Site2D { x: 50.0, y: 0.0 },
+        Site2D { x: 50.0, y: 100.0 },
+    ]);
+    let hanging_wall = trace.hanging_wall(model.sites(), None);
+
+    let (slip_rate, num_iterations) = (0.5, 10);
+    let generator = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(num_iterations);
+    let original = generator.clone().generate().unwrap();
+    let terrain = generator
+        .add_process(FaultProcess::new(hanging_wall.clone(), slip_rate))
+        .generate()
+        .unwrap();
+
+    // the hanging wall accumulates the throw except at the outlets, and the footwall does not move
+    let throw = terrain.fields().get(FAULT_THROW).unwrap();
+    let is_outlet = |i: usize| model.default_outlets().contains(&i);
+    (0..num).for_each(|i| {
+        let expected = if hanging_wall[i] && !is_outlet(i) {
+            slip_rate * num_iterations as f64
+        } else {
+            0.0
+        };
+        assert!((throw[i] - expected).abs() < 1e-9);
+    });
+
+    // the scarp raises the hanging wall above the footwall
+    let mean_rise = |on_hanging_wall: bool| {
+        let rises = (0..num)
+            .filter(|&i| hanging_wall[i] == on_hanging_wall && !is_outlet(i))
+            .map(|i| terrain.elevations()[i] - original.elevations()[i])
+            .collect::<Vec<_>>();
+        rises.iter().sum::<f64>() / rises.len() as f64
+    };
+    assert!(mean_rise(true) > mean_rise(false) + 1.0);
+}
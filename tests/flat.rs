@@ -0,0 +1,125 @@
+use fastlem::core::adjacency::Adjacency;
+use fastlem::core::traits::{Model, Site};
+use fastlem::lem::phases::{accumulate_drainage_areas, construct_stream_tree};
+use fastlem::models::surface::{
+    builder::TerrainModel2DBulider, model::TerrainModel2D, sites::Site2D,
+};
+extern crate fastlem;
+
+const PLATEAU_ELEVATION: f64 = 10.0;
+const PLATEAU_EDGE: f64 = 70.0;
+
+fn create_model(num: usize) -> TerrainModel2D {
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap()
+}
+
+/// The outlets along the eastern edge, below the plateau.
+fn eastern_outlets(model: &TerrainModel2D) -> Vec<usize> {
+    (0..model.num())
+        .filter(|&i| model.sites()[i].x >= 98.0)
+        .collect()
+}
+
+#[test]
+fn test_flat_plateau() {
+    let model = create_model(3000);
+    // a round plateau falling off to all sides
+    let center = Site2D { x: 50.0, y: 50.0 };
+    let radius = 25.0;
+    let elevations = model
+        .sites()
+        .iter()
+        .map(|site| PLATEAU_ELEVATION - (site.distance(&center) - radius).max(0.0))
+        .collect::<Vec<_>>();
+    let adjacency = Adjacency::from_graph(model.graph());
+    let receivers = construct_stream_tree(&elevations, &adjacency, model.default_outlets());
+
+    // the flow across the plateau heads to its nearest edge instead of wandering
+    (0..model.num())
+        .filter(|&i| elevations[i] == PLATEAU_ELEVATION)
+        .for_each(|i| {
+            let mut length = 0.0;
+            let mut j = i;
+            while elevations[j] == PLATEAU_ELEVATION {
+                assert_ne!(receivers[j], j);
+                length += model.sites()[j].distance(&model.sites()[receivers[j]]);
+                j = receivers[j];
+            }
+            let straight = radius - model.sites()[i].distance(&center);
+            assert!(
+                length <= straight * 1.5 + 5.0,
+                "site {}: {} along the flow, {} straight",
+                i,
+                length,
+                straight
+            );
+        });
+}
+
+#[test]
+fn test_flat_valley_floor() {
+    let model = create_model(3000);
+    // a flat floor between the walls rising to the north and the south, draining to the east
+    let elevations = model
+        .sites()
+        .iter()
+        .map(|site| {
+            if site.x >= PLATEAU_EDGE {
+                PLATEAU_ELEVATION - (site.x - PLATEAU_EDGE)
+            } else {
+                PLATEAU_ELEVATION + ((site.y - 50.0).abs() - 20.0).max(0.0)
+            }
+        })
+        .collect::<Vec<_>>();
+    let adjacency = Adjacency::from_graph(model.graph());
+    let receivers = construct_stream_tree(&elevations, &adjacency, &eastern_outlets(&model));
+    let drainage_areas = accumulate_drainage_areas(&receivers, model.areas(), &adjacency);
+
+    // the flow is pushed away from the walls, so the channel leaves the floor in the middle
+    let channel = (0..model.num())
+        .filter(|&i| elevations[i] == PLATEAU_ELEVATION)
+        .max_by(|&a, &b| drainage_areas[a].total_cmp(&drainage_areas[b]))
+        .unwrap();
+    let floor_area = (0..model.num())
+        .filter(|&i| elevations[i] == PLATEAU_ELEVATION)
+        .map(|i| model.areas()[i])
+        .sum::<f64>();
+    assert!(
+        (model.sites()[channel].y - 50.0).abs() < 3.0,
+        "channel at {:?}",
+        model.sites()[channel]
+    );
+    assert!(drainage_areas[channel] > floor_area * 0.5);
+}
+
+#[test]
+fn test_flat_closed() {
+    let model = create_model(1000);
+    // a flat depression without any drain is connected to the outlets as a lake
+    let elevations = model
+        .sites()
+        .iter()
+        .map(|site| {
+            let r = site.distance(&Site2D { x: 50.0, y: 50.0 });
+            if r < 20.0 {
+                0.0
+            } else {
+                r - 20.0
+            }
+        })
+        .collect::<Vec<_>>();
+    let adjacency = Adjacency::from_graph(model.graph());
+    let receivers = construct_stream_tree(&elevations, &adjacency, model.default_outlets());
+    let drainage_areas = accumulate_drainage_areas(&receivers, model.areas(), &adjacency);
+    let total = model.areas().iter().sum::<f64>();
+    let outlets = model
+        .default_outlets()
+        .iter()
+        .map(|&i| drainage_areas[i])
+        .sum::<f64>();
+    assert!((outlets - total).abs() < total * 1e-9);
+}
@@ -0,0 +1,76 @@
+use fastlem::core::parameters::{ParameterError, ParameterSet, TopographicalParameters};
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::diffusion::HillslopeDiffusionProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_frozen_region() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // a plateau of a city in the center of the domain
+    let plateau = 5.0;
+    let region = model
+        .sites()
+        .iter()
+        .enumerate()
+        .filter(|(_, site)| (site.x - 50.0).hypot(site.y - 50.0) < 10.0)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let parameters = ParameterSet::new(num)
+        .freeze(&region, &vec![plateau; num])
+        .unwrap();
+    assert_eq!(parameters.frozen(), region);
+
+    for time_step in [None, Some(1.0)] {
+        let terrain = TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(parameters.clone().into())
+            .set_time_step(time_step)
+            .set_max_iteration(20)
+            .add_process(HillslopeDiffusionProcess::default().set_diffusivity(1.0))
+            .generate()
+            .unwrap();
+        let elevations = terrain.elevations();
+        region
+            .iter()
+            .for_each(|&i| assert_eq!(elevations[i], plateau));
+        assert!(elevations.iter().all(|e| e.is_finite()));
+    }
+
+    // the frozen sites are the internal boundary conditions in the steady state:
+    // the sites draining through the low frozen basin are measured from it
+    let free = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+    let frozen = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(
+            ParameterSet::new(num)
+                .freeze(&region, &vec![0.0; num])
+                .unwrap()
+                .into(),
+        )
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+    let lowered = (0..num)
+        .filter(|i| !region.contains(i))
+        .filter(|&i| frozen.elevations()[i] < free.elevations()[i] - 1.0)
+        .count();
+    assert!(lowered > 0);
+
+    assert_eq!(
+        ParameterSet::new(3).freeze(&[1], &[0.0]).unwrap_err(),
+        ParameterError::SiteOutOfRange(1)
+    );
+}
@@ -0,0 +1,109 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::events::SimulationEvent;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_generation_task() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let task = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .generate_in_thread();
+
+    let terrain = task.wait().unwrap();
+    assert_eq!(terrain.elevations().len(), num);
+    assert!(terrain.elevations().iter().all(|e| e.is_finite()));
+}
+
+#[test]
+fn test_generation_progress() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let mut steps = Vec::new();
+    TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(3)
+        .generate_with_progress(|progress| steps.push(progress.step))
+        .unwrap();
+
+    assert!(!steps.is_empty() && steps.len() <= 3);
+    assert_eq!(steps[0], 1);
+}
+
+#[test]
+fn test_generation_events() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let task = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_snapshot_interval(Some(1))
+        .generate_in_thread();
+
+    let mut snapshots = 0;
+    let mut finished = false;
+    for event in task.events().iter() {
+        match event {
+            SimulationEvent::SnapshotReady { elevations, .. } => {
+                assert_eq!(elevations.len(), num);
+                snapshots += 1;
+            }
+            SimulationEvent::Finished { .. } => finished = true,
+            _ => {}
+        }
+    }
+
+    assert!(finished);
+    assert!(snapshots > 0);
+    assert!(task.wait().is_ok());
+}
+
+#[test]
+fn test_generation_estimate() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let mut progresses = Vec::new();
+    TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(10)
+        .generate_with_progress(|progress| progresses.push(progress.clone()))
+        .unwrap();
+
+    progresses.iter().for_each(|progress| {
+        // the estimate never exceeds the remaining iterations until the maximum
+        let remaining_steps = progress.remaining_steps.unwrap();
+        assert!(remaining_steps <= 10 - progress.step);
+        assert!(progress.remaining_time.is_some());
+        if progress.num_changed == 0 {
+            assert_eq!(remaining_steps, 0);
+        }
+    });
+    assert!(progresses
+        .windows(2)
+        .all(|pair| pair[0].elapsed <= pair[1].elapsed));
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "test-util")]
+
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+use fastlem::test_util::{hash_elevations, GoldenError, GoldenStatus, GoldenTerrain};
+extern crate fastlem;
+
+fn generate_elevations() -> Vec<f64> {
+    let num = 500;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(20)
+        .generate()
+        .unwrap()
+        .elevations()
+        .to_vec()
+}
+
+#[test]
+fn test_golden_terrain() {
+    let path = std::env::temp_dir()
+        .join(format!("fastlem-golden-{}", std::process::id()))
+        .join("terrain.golden");
+    let _ = std::fs::remove_file(&path);
+    let elevations = generate_elevations();
+
+    // the first check writes the golden
+    let golden = GoldenTerrain::new(&path).set_tolerance(1e-3);
+    assert_eq!(golden.check(&elevations).unwrap(), GoldenStatus::Written);
+    assert_eq!(golden.check(&elevations).unwrap(), GoldenStatus::Matched);
+    golden.assert_matches(&generate_elevations());
+
+    // the differences within the tolerance are accepted
+    let mut perturbed = elevations.clone();
+    perturbed[3] += 5e-4;
+    assert_eq!(golden.check(&perturbed).unwrap(), GoldenStatus::Matched);
+
+    perturbed[7] += 1.0;
+    assert!(matches!(
+        golden.check(&perturbed),
+        Err(GoldenError::ElevationMismatch { index: 7, .. })
+    ));
+    assert!(matches!(
+        golden.check(&elevations[1..]),
+        Err(GoldenError::LengthMismatch { .. })
+    ));
+
+    std::fs::write(&path, "not a golden").unwrap();
+    assert!(matches!(
+        golden.check(&elevations),
+        Err(GoldenError::InvalidFormat)
+    ));
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn test_hash_elevations() {
+    let elevations = vec![1.0, 2.0, 3.0];
+    assert_eq!(
+        hash_elevations(&elevations, 0.1),
+        hash_elevations(&[1.001, 1.999, 3.0], 0.1)
+    );
+    assert_ne!(
+        hash_elevations(&elevations, 0.1),
+        hash_elevations(&[1.5, 2.0, 3.0], 0.1)
+    );
+    assert_ne!(
+        hash_elevations(&elevations, 0.0),
+        hash_elevations(&[1.001, 2.0, 3.0], 0.0)
+    );
+}
@@ -0,0 +1,71 @@
+use fastlem::core::fields::ICE;
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::ice::IceProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_glacial_erosion_above_snow_line() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let generator = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num]);
+
+    // the snow line is at the median elevation of the terrain after the first iteration
+    let first = generator.clone().set_max_iteration(1).generate().unwrap();
+    let mut sorted = first.elevations().to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let snow_line = sorted[num / 2];
+    let ice = IceProcess::default()
+        .set_sea_level_temperature(15.0)
+        .set_lapse_rate(15.0 / snow_line);
+    assert!((ice.snow_line().unwrap() - snow_line).abs() < 1e-9);
+
+    // the ice covering the terrain in the first iteration changes the erosion in the second one
+    let generator = generator.set_max_iteration(2);
+    let reference = generator.clone().generate().unwrap();
+    let masked = generator
+        .clone()
+        .add_process(ice.clone())
+        .generate()
+        .unwrap();
+    let glaciated = generator
+        .add_process(ice.set_erodibility_factor(4.0))
+        .generate()
+        .unwrap();
+
+    // the sites are covered by ice exactly above the snow line
+    [&masked, &glaciated].iter().for_each(|terrain| {
+        let cover = terrain.fields().get(ICE).unwrap();
+        (0..num).for_each(|i| {
+            assert_eq!(cover[i] > 0.0, terrain.elevations()[i] > snow_line);
+        });
+    });
+
+    // with the default factor the process only outputs the mask
+    assert_eq!(masked.elevations(), reference.elevations());
+
+    // the glaciers erode the sites above the snow line and leave the sites below it as they are
+    assert_eq!(
+        glaciated.network().receivers(),
+        reference.network().receivers()
+    );
+    let (mut covered, mut lowering) = (0, 0.0);
+    (0..num).for_each(|i| {
+        let change = glaciated.elevations()[i] - reference.elevations()[i];
+        if first.elevations()[i] > snow_line {
+            assert!(change <= 1e-9);
+            covered += 1;
+            lowering -= change;
+        } else {
+            assert!(change.abs() < 1e-9);
+        }
+    });
+    assert!(lowering / covered as f64 > 0.1 * snow_line);
+}
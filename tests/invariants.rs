@@ -0,0 +1,59 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::{GenerationError, TerrainGenerator};
+use fastlem::lem::process::{Process, SimulationState};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+/// A process corrupting the elevation of a site with NaN in the given iteration.
+struct CorruptingProcess {
+    site: usize,
+    step: u32,
+}
+
+impl Process for CorruptingProcess {
+    fn name(&self) -> &str {
+        "corrupting"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        if state.step == self.step {
+            state.elevations[self.site] = f64::NAN;
+        }
+    }
+}
+
+#[test]
+fn test_invariant_violated() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let site = num / 2;
+    let generator = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(5)
+        .add_process(CorruptingProcess { site, step: 3 });
+
+    // the corrupted elevation is reported with the site and the iteration instead of producing the terrain
+    match generator.clone().set_debug_checks(true).generate() {
+        Err(GenerationError::InvariantViolated {
+            step,
+            site: violated,
+            ..
+        }) => {
+            assert_eq!(violated, site);
+            assert_eq!(step, 3);
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("the corrupted elevation is not detected"),
+    }
+
+    // without the checks the generation goes on
+    assert!(!matches!(
+        generator.set_debug_checks(false).generate(),
+        Err(GenerationError::InvariantViolated { .. })
+    ));
+}
@@ -0,0 +1,194 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::{Jitter, JitterDistribution, TerrainGenerator};
+use fastlem::lem::record::SimulationRecord;
+use fastlem::models::surface::{
+    builder::TerrainModel2DBulider, model::TerrainModel2D, sites::Site2D,
+};
+extern crate fastlem;
+
+const BASE_ELEVATION: f64 = 1000.0;
+
+fn create_model(num: usize) -> TerrainModel2D {
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap()
+}
+
+/// The perturbation of the initial elevations, which are not changed without any iteration.
+fn perturbation(model: &TerrainModel2D, jitter: Jitter, seed: u64) -> Vec<f64> {
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![
+            TopographicalParameters::default()
+                .set_base_elevation(BASE_ELEVATION);
+            model.num()
+        ])
+        .set_max_iteration(0)
+        .set_seed(seed)
+        .set_jitter(jitter)
+        .generate()
+        .unwrap();
+    terrain
+        .elevations()
+        .iter()
+        .map(|&elevation| elevation - BASE_ELEVATION)
+        .collect()
+}
+
+fn statistics(values: &[f64]) -> (f64, f64) {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    (mean, variance.sqrt())
+}
+
+/// The mean of the absolute differences of the perturbation along the edges.
+fn roughness(model: &TerrainModel2D, values: &[f64]) -> f64 {
+    let (sum, count) = (0..model.num())
+        .flat_map(|i| {
+            model
+                .graph()
+                .neighbors_of(i)
+                .iter()
+                .map(move |ja| (i, ja.0))
+        })
+        .fold((0.0, 0), |(sum, count), (i, j)| {
+            (sum + (values[i] - values[j]).abs(), count + 1)
+        });
+    sum / count as f64
+}
+
+#[test]
+fn test_jitter_amplitude() {
+    let model = create_model(1000);
+
+    // the default perturbation is lost by the rounding at the elevation of the plateau
+    let default = perturbation(&model, Jitter::default(), 0);
+    assert!(default.iter().all(|&v| v == 0.0));
+
+    let uniform = perturbation(&model, Jitter::default().set_amplitude(1.0), 0);
+    assert!(uniform.iter().all(|&v| (0.0..1.0).contains(&v)));
+    let mut sorted = uniform.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    sorted.dedup();
+    assert_eq!(sorted.len(), model.num());
+
+    // the same seed reproduces the perturbation, and another seed changes it
+    assert_eq!(
+        perturbation(&model, Jitter::default().set_amplitude(1.0), 0),
+        uniform
+    );
+    assert_ne!(
+        perturbation(&model, Jitter::default().set_amplitude(1.0), 1),
+        uniform
+    );
+}
+
+#[test]
+fn test_jitter_distribution() {
+    let model = create_model(2000);
+    let normal = perturbation(
+        &model,
+        Jitter::default()
+            .set_amplitude(2.0)
+            .set_distribution(JitterDistribution::Normal),
+        0,
+    );
+    let (mean, deviation) = statistics(&normal);
+    assert!(mean.abs() < 0.2, "mean: {}", mean);
+    assert!((deviation - 2.0).abs() < 0.2, "deviation: {}", deviation);
+    assert!(normal.iter().any(|&v| v < 0.0));
+}
+
+#[test]
+fn test_jitter_correlation() {
+    let model = create_model(2000);
+    let jitter = Jitter::default()
+        .set_amplitude(1.0)
+        .set_distribution(JitterDistribution::Normal);
+    let independent = perturbation(&model, jitter, 0);
+    let correlated = perturbation(&model, jitter.set_correlation_length(Some(10.0)), 0);
+
+    // the smoothing keeps the mean and the standard deviation
+    let (mean, deviation) = statistics(&independent);
+    let (correlated_mean, correlated_deviation) = statistics(&correlated);
+    assert!((mean - correlated_mean).abs() < 1e-3);
+    assert!((deviation - correlated_deviation).abs() < 1e-3);
+
+    // the neighbors are closer to each other
+    assert!(roughness(&model, &correlated) < roughness(&model, &independent) * 0.5);
+
+    // the non-positive correlation length is ignored
+    assert_eq!(
+        perturbation(&model, jitter.set_correlation_length(Some(0.0)), 0),
+        independent
+    );
+}
+
+#[test]
+fn test_jitter_frozen_sites() {
+    let model = create_model(500);
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![
+            TopographicalParameters::default()
+                .set_base_elevation(BASE_ELEVATION)
+                .set_is_frozen(true);
+            model.num()
+        ])
+        .set_max_iteration(0)
+        .set_jitter(Jitter::default().set_amplitude(1.0))
+        .generate()
+        .unwrap();
+    assert!(terrain.elevations().iter().all(|&e| e == BASE_ELEVATION));
+}
+
+#[test]
+fn test_jitter_manifest() {
+    let model = create_model(100);
+    let parameters = vec![TopographicalParameters::default(); model.num()];
+    let jitter = Jitter::default()
+        .set_amplitude(0.5)
+        .set_distribution(JitterDistribution::Normal)
+        .set_correlation_length(Some(5.0));
+    let generator = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(parameters.clone())
+        .set_max_iteration(1)
+        .set_jitter(jitter);
+    let manifest = generator.to_manifest().unwrap();
+    assert_eq!(manifest.jitter, jitter);
+
+    let rebuilt = TerrainGenerator::from_manifest(&manifest)
+        .set_model(model)
+        .set_parameters(parameters);
+    assert_eq!(rebuilt.to_manifest().unwrap(), manifest);
+}
+
+#[test]
+fn test_jitter_record() {
+    let model = create_model(500);
+    let (_, record) = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![
+            TopographicalParameters::default()
+                .set_base_elevation(BASE_ELEVATION);
+            model.num()
+        ])
+        .set_max_iteration(5)
+        .set_seed(7)
+        .set_jitter(
+            Jitter::default()
+                .set_amplitude(0.1)
+                .set_correlation_length(Some(10.0)),
+        )
+        .generate_with_record()
+        .unwrap();
+
+    let mut buf = Vec::new();
+    record.write_to(&mut buf).unwrap();
+    let restored = SimulationRecord::read_from(buf.as_slice()).unwrap();
+    assert!(restored.replay().unwrap().is_identical());
+}
@@ -0,0 +1,48 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_junction_consistency() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // an external model digging pits into the terrain
+    let generate = |junction_tolerance: Option<f64>| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![TopographicalParameters::default(); num])
+            .set_time_step(Some(1.0))
+            .set_max_iteration(20)
+            .set_junction_tolerance(junction_tolerance)
+            .generate_with_coupling(|state| {
+                state
+                    .elevations
+                    .iter_mut()
+                    .step_by(7)
+                    .for_each(|e| *e -= 5.0);
+            })
+            .unwrap()
+    };
+    let violations = |terrain: &fastlem::models::surface::terrain::Terrain2D, tolerance: f64| {
+        let receivers = terrain.network().receivers();
+        let elevations = terrain.elevations();
+        (0..num)
+            .filter(|&i| elevations[i] < elevations[receivers[i]] - tolerance)
+            .count()
+    };
+
+    let inconsistent = generate(None);
+    assert!(violations(&inconsistent, 0.0) > 0);
+
+    let consistent = generate(Some(0.0));
+    assert_eq!(violations(&consistent, 0.0), 0);
+
+    let tolerant = generate(Some(1.0));
+    assert_eq!(violations(&tolerant, 1.0), 0);
+}
@@ -0,0 +1,163 @@
+use fastlem::core::fields::{SINKHOLE, SPRING_DISCHARGE, SURFACE_DRAINAGE_AREA, UNDERGROUND_FLOW};
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::{Model, Site};
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_karst() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // a band of soluble rock across the domain
+    let is_soluble = model
+        .sites()
+        .iter()
+        .map(|site| (30.0..60.0).contains(&site.x))
+        .collect::<Vec<_>>();
+    let areas = model.areas().to_vec();
+    let generator = TerrainGenerator::default()
+        .set_model(model)
+        .set_max_iteration(30);
+    let insoluble = generator
+        .clone()
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .generate()
+        .unwrap();
+    let karst = generator
+        .set_parameters(
+            is_soluble
+                .iter()
+                .map(|&soluble| {
+                    TopographicalParameters::default().set_solubility(if soluble {
+                        0.5
+                    } else {
+                        0.0
+                    })
+                })
+                .collect(),
+        )
+        .generate()
+        .unwrap();
+
+    assert!(insoluble.fields().get(SURFACE_DRAINAGE_AREA).is_none());
+
+    let drainage_areas = karst.network().drainage_areas();
+    let surface_drainage_areas = karst.fields().get(SURFACE_DRAINAGE_AREA).unwrap();
+    let underground_flows = karst.fields().get(UNDERGROUND_FLOW).unwrap();
+    (0..num).for_each(|i| {
+        assert!(surface_drainage_areas[i] <= drainage_areas[i] * (1.0 + 1e-12));
+        assert!(
+            (surface_drainage_areas[i] + underground_flows[i] - drainage_areas[i]).abs()
+                < drainage_areas[i] * 1e-9
+        );
+        if !is_soluble[i] && underground_flows[i] > 0.0 {
+            panic!("the flow stays underground at the insoluble site {}", i);
+        }
+    });
+
+    // the channels over the soluble sites lose their flow, so they are less incised
+    let channels = (0..num)
+        .filter(|&i| is_soluble[i] && drainage_areas[i] - areas[i] > 100.0)
+        .collect::<Vec<_>>();
+    assert!(!channels.is_empty());
+    let mean_elevation = |elevations: &[f64]| {
+        channels.iter().map(|&i| elevations[i]).sum::<f64>() / channels.len() as f64
+    };
+    assert!(mean_elevation(karst.elevations()) > mean_elevation(insoluble.elevations()));
+
+    // the soluble sites lose at least their solubility of the inflow into the subsurface
+    assert!(channels
+        .iter()
+        .all(|&i| underground_flows[i] >= (drainage_areas[i] - areas[i]) * 0.5 * (1.0 - 1e-9)));
+
+    // the lost flow emerges as springs at the insoluble sites, and no sinkholes form without the full solubility
+    let springs = karst.fields().get(SPRING_DISCHARGE).unwrap();
+    assert!((0..num).any(|i| !is_soluble[i] && springs[i] > 0.0));
+    assert!(karst
+        .fields()
+        .get(SINKHOLE)
+        .unwrap()
+        .iter()
+        .all(|&s| s == 0.0));
+}
+
+#[test]
+fn test_sinkholes() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // the isolated sites of fully soluble rock nearest to the points of a grid
+    let sites = model.sites().to_vec();
+    let mut is_soluble = vec![false; num];
+    (1..5).for_each(|gx| {
+        (1..5).for_each(|gy| {
+            let point = Site2D {
+                x: gx as f64 * 20.0,
+                y: gy as f64 * 20.0,
+            };
+            let nearest = (0..num)
+                .min_by(|&i, &j| {
+                    sites[i]
+                        .distance(&point)
+                        .total_cmp(&sites[j].distance(&point))
+                })
+                .unwrap();
+            is_soluble[nearest] = true;
+        });
+    });
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_max_iteration(30)
+        .set_parameters(
+            is_soluble
+                .iter()
+                .map(|&soluble| {
+                    TopographicalParameters::default().set_solubility(if soluble {
+                        1.0
+                    } else {
+                        0.0
+                    })
+                })
+                .collect(),
+        )
+        .generate()
+        .unwrap();
+
+    let sinkholes = terrain
+        .fields()
+        .get(SINKHOLE)
+        .unwrap()
+        .iter()
+        .enumerate()
+        .filter(|(_, &s)| s > 0.0)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    assert!(!sinkholes.is_empty());
+
+    let elevations = terrain.elevations();
+    let receivers = terrain.network().receivers();
+    let surface_drainage_areas = terrain.fields().get(SURFACE_DRAINAGE_AREA).unwrap();
+    sinkholes.iter().for_each(|&i| {
+        assert!(is_soluble[i]);
+        // the sinkhole is a local sink of the drainage network, swallowing the inflow from the sites around it
+        assert_eq!(receivers[i], i);
+        assert!((0..num).any(|j| j != i && receivers[j] == i));
+        assert!((surface_drainage_areas[i] - model.areas()[i]).abs() < 1e-9);
+        // the sinkhole is a closed depression, lower than all its neighbors
+        model
+            .graph()
+            .neighbors_of(i)
+            .iter()
+            .for_each(|ja| assert!(elevations[i] < elevations[ja.0]));
+    });
+}
@@ -0,0 +1,95 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::kernels::{
+    downhill_slopes, fast_powf, fast_stream_powers, stream_powers, FAST_POWF_MAX_RELATIVE_ERROR,
+    LANES,
+};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_stream_powers() {
+    // the lengths which are not multiples of the lanes exercise the remainders
+    (0..3 * LANES + 1).for_each(|len| {
+        let flows = (0..len).map(|k| 1.0 + k as f64 * 37.5).collect::<Vec<_>>();
+        [0.5, 0.4, 1.0].iter().for_each(|&m_exp| {
+            let powers = stream_powers(&flows, m_exp);
+            assert_eq!(powers.len(), len);
+            powers.iter().zip(&flows).for_each(|(&power, &flow)| {
+                let expected = flow.powf(m_exp);
+                assert!((power - expected).abs() <= expected * 1e-15);
+            });
+        });
+    });
+}
+
+#[test]
+fn test_downhill_slopes() {
+    let len = 2 * LANES + 3;
+    let elevations = (0..len).map(|k| k as f64).collect::<Vec<_>>();
+    let lower_elevations = vec![2.0; len];
+    let distances = vec![4.0; len];
+    let slopes = downhill_slopes(&elevations, &lower_elevations, &distances);
+    assert_eq!(slopes.len(), len);
+    (0..len).for_each(|k| {
+        assert_eq!(slopes[k], ((k as f64 - 2.0) / 4.0).max(0.0));
+    });
+}
+
+#[test]
+fn test_fast_powf() {
+    let mut x = 1e-300;
+    while x < 1e300 {
+        [0.5, 0.4, 1.0, -1.0, 0.0].iter().for_each(|&y| {
+            let expected = f64::powf(x, y);
+            let power = fast_powf(x, y);
+            if expected.is_normal() {
+                assert!(((power - expected) / expected).abs() < FAST_POWF_MAX_RELATIVE_ERROR);
+            } else {
+                assert_eq!(power, expected);
+            }
+        });
+        x *= 1.37;
+    }
+
+    // the bases out of the range of the approximation fall back to `powf`
+    [0.0, f64::INFINITY, 1e-310].iter().for_each(|&x| {
+        assert_eq!(fast_powf(x, 0.5).to_bits(), x.powf(0.5).to_bits());
+    });
+    // the payloads of NaN may differ between the runtime and the constant folding, so only NaN itself is compared
+    [-1.0, f64::NAN].iter().for_each(|&x| {
+        assert!(fast_powf(x, 0.5).is_nan());
+        assert!(x.powf(0.5).is_nan());
+    });
+    assert_eq!(fast_powf(1e300, 2.0), f64::INFINITY);
+
+    let flows = [0.0, 1.0, 2.5, 1e6, 1e-310];
+    let powers = fast_stream_powers(&flows, 0.5);
+    flows.iter().zip(&powers).for_each(|(&flow, &power)| {
+        assert_eq!(power, fast_powf(flow, 0.5));
+    });
+}
+
+#[test]
+fn test_fast_powf_generation() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let generator = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(10);
+    let exact = generator.clone().generate().unwrap();
+    let fast = generator.set_fast_powf(true).generate().unwrap();
+
+    let max_elevation = exact.elevations().iter().fold(0.0, |a: f64, &b| a.max(b));
+    assert!(max_elevation > 0.0);
+    exact
+        .elevations()
+        .iter()
+        .zip(fast.elevations())
+        .for_each(|(&a, &b)| assert!((a - b).abs() <= max_elevation * 1e-4));
+}
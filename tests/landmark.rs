@@ -0,0 +1,113 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::{Model, Site};
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::landmark::{LandmarkExtractor2D, RiverMeasure};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_landmarks() {
+    let num = 2000;
+    let model = TerrainModel2DBulider::from_random_sites(
+        num,
+        Site2D::new(0.0, 0.0),
+        Site2D::new(100.0, 100.0),
+    )
+    .add_edge_sites(None, None)
+    .unwrap()
+    .build()
+    .unwrap();
+    let num = model.num();
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+    let network = terrain.network();
+    let receivers = network.receivers();
+    let drainage_areas = network.drainage_areas();
+    let elevations = terrain.elevations();
+    let is_channel = |i: usize| drainage_areas[i] >= 50.0;
+
+    let extractor = LandmarkExtractor2D::default()
+        .set_min_drainage_area(50.0)
+        .set_valley_radius(15.0);
+    let landmarks = extractor.extract(&model, &terrain);
+
+    // the longest river is not shorter than the path from any channel site to its outlet
+    let river = landmarks.main_river.unwrap();
+    let sites = river.sites();
+    assert!(sites.len() >= 2);
+    assert!(sites.windows(2).all(|w| receivers[w[0]] == w[1]));
+    assert!(sites.iter().all(|&i| is_channel(i)));
+    assert!(network.is_outlet(*sites.last().unwrap()));
+    (0..num).filter(|&i| is_channel(i)).for_each(|i| {
+        let mut length = 0.0;
+        let mut j = i;
+        while receivers[j] != j && is_channel(receivers[j]) {
+            length += model.sites()[j].distance(&model.sites()[receivers[j]]);
+            j = receivers[j];
+        }
+        assert!(length <= river.length() + 1e-9);
+    });
+
+    // the river of the largest discharge follows the largest tributary
+    let river = extractor
+        .clone()
+        .set_river_measure(RiverMeasure::Discharge)
+        .main_river(&terrain)
+        .unwrap();
+    let sites = river.sites();
+    let outlet = *sites.last().unwrap();
+    assert!(network.is_outlet(outlet));
+    (0..num)
+        .filter(|&i| {
+            network.is_outlet(i) && (0..num).any(|k| k != i && receivers[k] == i && is_channel(k))
+        })
+        .for_each(|i| assert!(drainage_areas[i] <= drainage_areas[outlet]));
+    sites.windows(2).for_each(|w| {
+        (0..num)
+            .filter(|&k| receivers[k] == w[1] && k != w[1] && is_channel(k))
+            .for_each(|k| assert!(drainage_areas[k] <= drainage_areas[w[0]]));
+    });
+
+    // the largest basin drains into the outlet of the largest drainage area
+    let basin = landmarks.largest_basin.unwrap();
+    (0..num)
+        .filter(|&i| network.is_outlet(i))
+        .for_each(|i| assert!(drainage_areas[i] <= drainage_areas[basin.outlet]));
+    assert!((basin.area - drainage_areas[basin.outlet]).abs() < 1e-6 * basin.area);
+    basin.sites.iter().for_each(|&i| {
+        let mut j = i;
+        while receivers[j] != j {
+            j = receivers[j];
+        }
+        assert_eq!(j, basin.outlet);
+    });
+    // the cells of the edge sites are clipped at the bounding box, which is the convex hull
+    let polygon_area = basin.polygons.iter().map(|p| p.area()).sum::<f64>();
+    assert!((polygon_area - basin.area).abs() < 0.01 * basin.area);
+
+    let peak = landmarks.highest_peak.unwrap();
+    assert!(elevations.iter().all(|&e| e <= peak.elevation));
+    assert_eq!(elevations[peak.site], peak.elevation);
+
+    // the depth of the valley is the relief within the radius
+    let valley = landmarks.deepest_valley.unwrap();
+    assert!(is_channel(valley.site));
+    let depth_of = |i: usize| {
+        let field = model.distance_field(&[i], 15.0);
+        field
+            .reached()
+            .iter()
+            .map(|&(k, _)| elevations[k])
+            .fold(elevations[i], f64::max)
+            - elevations[i]
+    };
+    assert!((valley.depth - depth_of(valley.site)).abs() < 1e-9);
+    (0..num)
+        .filter(|&i| is_channel(i))
+        .for_each(|i| assert!(depth_of(i) <= valley.depth + 1e-9));
+    assert!(valley.depth > 0.0);
+}
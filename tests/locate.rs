@@ -0,0 +1,52 @@
+use fastlem::core::traits::Model;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_locate_triangles() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .add_edge_sites(None, None)
+        .unwrap()
+        .build()
+        .unwrap();
+    let elevations = model
+        .sites()
+        .iter()
+        .map(|site| 2.0 * site.x + 3.0 * site.y)
+        .collect::<Vec<_>>();
+    let terrain = model.create_terrain_from_result(&elevations);
+    let triangles = terrain.triangles();
+    assert!(!triangles.is_empty());
+    assert!(triangles.iter().flatten().all(|&i| i < model.num()));
+
+    (0..20).for_each(|ix| {
+        (0..20).for_each(|iy| {
+            let site = Site2D {
+                x: 2.5 + ix as f64 * 5.0,
+                y: 2.5 + iy as f64 * 5.0,
+            };
+            let (t, weights) = terrain.locate(&site).unwrap();
+            assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            assert!(weights.iter().all(|&w| w >= -1e-9));
+
+            // the barycentric coordinates reproduce the position and a linear function
+            let vertices = triangles[t];
+            let (x, y, z) = (0..3).fold((0.0, 0.0, 0.0), |(x, y, z), k| {
+                let s = terrain.sites()[vertices[k]];
+                (
+                    x + weights[k] * s.x,
+                    y + weights[k] * s.y,
+                    z + weights[k] * elevations[vertices[k]],
+                )
+            });
+            assert!((x - site.x).abs() < 1e-6 && (y - site.y).abs() < 1e-6);
+            assert!((z - (2.0 * site.x + 3.0 * site.y)).abs() < 1e-6);
+        });
+    });
+
+    assert!(terrain.locate(&Site2D { x: -10.0, y: 50.0 }).is_none());
+    assert!(terrain.locate(&Site2D { x: 50.0, y: 200.0 }).is_none());
+}
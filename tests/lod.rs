@@ -0,0 +1,56 @@
+use fastlem::core::traits::Model;
+use fastlem::models::surface::lod::LodGenerator2D;
+use fastlem::models::surface::preset::IslandPreset2D;
+extern crate fastlem;
+
+#[test]
+fn test_lod_meshes() {
+    let (model, _) = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .build()
+        .unwrap();
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+
+    let tolerances = vec![0.0, 1e-6, 1.0];
+    let meshes = LodGenerator2D::default()
+        .set_tolerances(tolerances.clone())
+        .generate(&model, &terrain);
+    assert_eq!(meshes.len(), 3);
+
+    meshes
+        .iter()
+        .zip(tolerances.iter())
+        .for_each(|(mesh, &tolerance)| {
+            assert!(mesh.max_error <= tolerance);
+            assert_eq!(mesh.indices.len() % 3, 0);
+            assert_eq!(mesh.vertices.len(), mesh.sites.len());
+            assert!(mesh
+                .indices
+                .iter()
+                .all(|&i| (i as usize) < mesh.vertices.len()));
+            // the hull is kept
+            assert!(model
+                .default_outlets()
+                .iter()
+                .all(|i| mesh.sites.contains(i)));
+        });
+    // the ocean is flat, so the sites are removed with a tiny error
+    assert_eq!(meshes[0].vertices.len(), model.sites().len());
+    assert!(meshes[1].vertices.len() < meshes[0].vertices.len());
+    assert!(meshes[2].vertices.len() < meshes[1].vertices.len());
+
+    // the total area of the triangles is preserved
+    let area = |mesh: &fastlem::models::surface::lod::TerrainMesh2D| {
+        mesh.indices
+            .chunks_exact(3)
+            .map(|t| {
+                let [a, b, c] = [t[0], t[1], t[2]].map(|i| mesh.vertices[i as usize]);
+                ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])) / 2.0
+            })
+            .sum::<f64>()
+    };
+    assert!((area(&meshes[0]) - area(&meshes[2])).abs() < 1e-6);
+}
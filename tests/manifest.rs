@@ -0,0 +1,98 @@
+use fastlem::core::parameters::{EdgeParameters, TopographicalParameters};
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::manifest::ManifestError;
+use fastlem::lem::processes::diffusion::HillslopeDiffusionProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_manifest() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let parameters = vec![TopographicalParameters::default().set_erodibility(0.5); num];
+    let (i, j) = {
+        let graph = fastlem::core::traits::Model::graph(&model);
+        (0, graph.neighbors_of(0)[0].0)
+    };
+
+    let generator = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(parameters.clone())
+        .set_seed(42)
+        .set_time_step(Some(1.0))
+        .set_max_iteration(20)
+        .set_min_parallel_sites(128)
+        .set_edge_parameters(i, j, EdgeParameters::default().set_erodibility_factor(2.0))
+        .add_process(HillslopeDiffusionProcess::default());
+    let manifest = generator.to_manifest().unwrap();
+    assert_eq!(manifest.seed, 42);
+    assert_eq!(manifest.max_iteration, Some(20));
+    assert_eq!(manifest.processes, vec!["hillslope_diffusion".to_string()]);
+
+    // the generator rebuilt from the manifest produces the identical terrain
+    let rebuilt = TerrainGenerator::from_manifest(&manifest)
+        .set_model(model.clone())
+        .set_parameters(parameters.clone())
+        .add_process(HillslopeDiffusionProcess::default());
+    rebuilt.check_manifest(&manifest).unwrap();
+    assert_eq!(rebuilt.to_manifest().unwrap(), manifest);
+    assert_eq!(
+        generator.generate().unwrap().elevations(),
+        rebuilt.clone().generate().unwrap().elevations()
+    );
+
+    // the differences are detected
+    let changed = rebuilt
+        .clone()
+        .set_parameters(vec![TopographicalParameters::default(); num]);
+    assert!(matches!(
+        changed.check_manifest(&manifest),
+        Err(ManifestError::ParametersMismatch)
+    ));
+    let other_model = TerrainModel2DBulider::from_random_sites(num + 1, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let moved = TerrainGenerator::from_manifest(&manifest)
+        .set_model(other_model)
+        .set_parameters(vec![
+            TopographicalParameters::default().set_erodibility(0.5);
+            num + 1
+        ]);
+    assert!(matches!(
+        moved.check_manifest(&manifest),
+        Err(ManifestError::ModelMismatch)
+    ));
+    let without_processes = TerrainGenerator::from_manifest(&manifest)
+        .set_model(model)
+        .set_parameters(parameters);
+    assert!(matches!(
+        without_processes.check_manifest(&manifest),
+        Err(ManifestError::ProcessesMismatch)
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_manifest_serialization() {
+    let num = 100;
+    let model = TerrainModel2DBulider::from_random_sites(
+        num,
+        Site2D { x: 0.0, y: 0.0 },
+        Site2D { x: 100.0, y: 100.0 },
+    )
+    .build()
+    .unwrap();
+    let manifest = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_seed(7)
+        .to_manifest()
+        .unwrap();
+    let json = serde_json::to_string(&manifest).unwrap();
+    let restored: fastlem::lem::manifest::GenerationManifest = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, manifest);
+}
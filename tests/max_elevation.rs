@@ -0,0 +1,72 @@
+use fastlem::core::parameters::{ParameterError, ParameterSet, TopographicalParameters};
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_max_elevation() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let areas = model.areas().to_vec();
+
+    let generate = |ceiling: Option<f64>| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![
+                TopographicalParameters::default()
+                    .set_uplift_rate(5.0)
+                    .set_max_elevation(ceiling);
+                num
+            ])
+            .set_time_step(Some(1.0))
+            .set_max_iteration(10)
+            .generate()
+            .unwrap()
+    };
+    let free = generate(None);
+    let max = free.elevations().iter().cloned().fold(0.0, f64::max);
+    let ceiling = max * 0.5;
+    let capped = generate(Some(ceiling));
+
+    assert!(capped
+        .elevations()
+        .iter()
+        .all(|e| e.is_finite() && *e <= ceiling + 1e-9));
+
+    // the excess is spread instead of clipped: more sites are raised than by clipping
+    let num_high = |elevations: &[f64]| elevations.iter().filter(|e| **e > ceiling * 0.5).count();
+    let clipped = free
+        .elevations()
+        .iter()
+        .map(|e| e.min(ceiling))
+        .collect::<Vec<_>>();
+    assert!(num_high(capped.elevations()) >= num_high(&clipped));
+    let volume = |elevations: &[f64]| {
+        elevations
+            .iter()
+            .zip(&areas)
+            .filter(|(_, a)| a.is_finite())
+            .map(|(e, a)| e * a)
+            .sum::<f64>()
+    };
+    assert!(volume(capped.elevations()) > volume(&clipped));
+
+    // the bounds must be ordered
+    let invalid = ParameterSet::new(3)
+        .set(
+            2,
+            TopographicalParameters::default()
+                .set_min_elevation(Some(10.0))
+                .set_max_elevation(Some(5.0)),
+        )
+        .unwrap();
+    assert_eq!(
+        invalid.validate(),
+        Err(ParameterError::InvalidElevationBounds(2))
+    );
+}
@@ -0,0 +1,126 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Site;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::meander::MeanderGenerator2D;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+/// Points of a loop of radius 3.0 above `center` (or below it if `upward` is false) on a stem whose neck is 0.3 wide.
+fn circular_loop(center: f64, upward: bool) -> Vec<Site2D> {
+    let num = 32;
+    let a = 0.05;
+    let stem = (1..=6).map(|k| k as f64 * 0.5).collect::<Vec<_>>();
+    let circle = (0..num).map(|k| {
+        let angle = -std::f64::consts::FRAC_PI_2
+            - a
+            - (std::f64::consts::TAU - 2.0 * a) * k as f64 / (num - 1) as f64;
+        (center + 3.0 * angle.cos(), 6.3 + 3.0 * angle.sin())
+    });
+    stem.iter()
+        .map(|&y| (center - 0.15, y))
+        .chain(circle)
+        .chain(stem.iter().rev().map(|&y| (center + 0.15, y)))
+        .map(|(x, y)| Site2D {
+            x,
+            y: if upward { y } else { -y },
+        })
+        .collect()
+}
+
+#[test]
+fn test_cut_off_loops() {
+    // a straight channel along y = 0 with a loop on each side whose necks lie next to each other
+    let mut centerline = (0..=18)
+        .map(|k| Site2D {
+            x: k as f64 * 0.5,
+            y: 0.0,
+        })
+        .collect::<Vec<_>>();
+    centerline.extend(circular_loop(10.0, true));
+    centerline.push(Site2D { x: 10.3, y: 0.0 });
+    centerline.extend(circular_loop(10.6, false));
+    centerline.extend((0..=16).map(|k| Site2D {
+        x: 11.6 + k as f64 * 0.5,
+        y: 0.0,
+    }));
+    let num = centerline.len();
+
+    // no loops are cut off without the cutoff distance
+    let (uncut, oxbows) = MeanderGenerator2D::default().cut_off_loops(centerline.clone());
+    assert_eq!(uncut.len(), num);
+    assert!(oxbows.is_empty());
+
+    // each loop is cut at its own neck instead of one wide cutoff spanning both
+    let (cut, oxbows) = MeanderGenerator2D::default()
+        .set_cutoff_distance(Some(1.5))
+        .cut_off_loops(centerline);
+    assert_eq!(oxbows.len(), 2);
+    assert_eq!(
+        cut.len() + oxbows.iter().map(|oxbow| oxbow.len()).sum::<usize>(),
+        num
+    );
+    assert!(cut.iter().all(|site| site.y.abs() < 1.5));
+    assert_eq!(cut[0].distance(&Site2D { x: 0.0, y: 0.0 }), 0.0);
+    assert!(cut[cut.len() - 1].distance(&Site2D { x: 19.6, y: 0.0 }) < 1e-9);
+    // the loop above is cut at its first neck, keeping the end of its stem in the channel
+    assert_eq!(oxbows[0].len(), 6 + 32 + 5);
+    assert!(oxbows[0][0].distance(&Site2D { x: 9.85, y: 0.5 }) < 1e-9);
+    assert!(oxbows[0][42].distance(&Site2D { x: 10.15, y: 1.0 }) < 1e-9);
+    assert!(cut
+        .iter()
+        .any(|site| site.distance(&Site2D { x: 10.15, y: 0.5 }) < 1e-9));
+    // the first oxbow is the loop above and the second one is the loop below
+    assert!(oxbows[0].iter().any(|site| site.y > 9.0));
+    assert!(oxbows[0].iter().all(|site| site.y > -1.5));
+    assert!(oxbows[1].iter().any(|site| site.y < -9.0));
+    assert!(oxbows[1].iter().all(|site| site.y < 1.5));
+}
+
+#[test]
+fn test_meanders_of_extracted_rivers() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(50)
+        .generate()
+        .unwrap();
+
+    let min_drainage_area = 100.0;
+    let rivers = terrain.extract_rivers(min_drainage_area);
+    assert!(!rivers.is_empty());
+    rivers.iter().for_each(|river| {
+        assert!(river.sites().len() >= 2);
+        assert!(river.drainage_areas()[0] >= min_drainage_area);
+        // each reach follows the receivers downstream
+        river.sites().windows(2).for_each(|pair| {
+            assert_eq!(terrain.network().receivers()[pair[0]], pair[1]);
+        });
+        river.elevations().windows(2).for_each(|pair| {
+            assert!(pair[0] >= pair[1]);
+        });
+    });
+
+    let generator = MeanderGenerator2D::default()
+        .set_max_gradient(f64::MAX)
+        .set_cutoff_distance(Some(1.0));
+    let meanders = generator.generate(&rivers);
+    assert!(!meanders.is_empty());
+    meanders.iter().for_each(|meander| {
+        let river = &rivers[meander.river()];
+        // the channel stays connected to the network at both ends
+        let centerline = meander.centerline();
+        assert!(centerline[0].distance(&river.points()[0]) < 1e-9);
+        assert!(
+            centerline[centerline.len() - 1].distance(&river.points()[river.points().len() - 1])
+                < 1e-9
+        );
+        assert!(meander.sinuosity() >= 1.0);
+    });
+    assert!(meanders.iter().any(|meander| meander.sinuosity() > 1.1));
+}
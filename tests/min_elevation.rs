@@ -0,0 +1,113 @@
+use fastlem::core::parameters::{ParameterError, ParameterSet, TopographicalParameters};
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_min_elevation() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let outlets = model.default_outlets().to_vec();
+    let floor = 20.0;
+
+    // the eastern half has a resistant basement
+    let parameters = |with_floor: bool| {
+        model
+            .sites()
+            .iter()
+            .enumerate()
+            .map(|(i, site)| {
+                TopographicalParameters::default()
+                    .set_base_elevation(if outlets.contains(&i) { 0.0 } else { 30.0 })
+                    .set_min_elevation(if with_floor && site.x > 50.0 {
+                        Some(floor)
+                    } else {
+                        None
+                    })
+            })
+            .collect::<Vec<_>>()
+    };
+    let generate = |with_floor: bool| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(parameters(with_floor))
+            .set_time_step(Some(1.0))
+            .set_max_iteration(30)
+            .generate()
+            .unwrap()
+    };
+
+    let floored = generate(true);
+    let free = generate(false);
+    let sites = model.sites();
+    (0..num)
+        .filter(|i| !outlets.contains(i) && sites[*i].x > 50.0)
+        .for_each(|i| assert!(floored.elevations()[i] >= floor));
+    assert!((0..num)
+        .filter(|i| !outlets.contains(i) && sites[*i].x > 50.0)
+        .any(|i| free.elevations()[i] < floor));
+
+    // the floor must be finite
+    let invalid = ParameterSet::new(3)
+        .set(
+            1,
+            TopographicalParameters::default().set_min_elevation(Some(f64::NAN)),
+        )
+        .unwrap();
+    assert_eq!(invalid.validate(), Err(ParameterError::NonFiniteValue(1)));
+}
+
+#[test]
+fn test_min_elevation_in_steady_state() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // the rivers flow to the western coast across a resistant band
+    let in_band = |site: &Site2D| site.x > 40.0 && site.x < 60.0;
+    let parameters = |floor: Option<f64>| {
+        model
+            .sites()
+            .iter()
+            .map(|site| {
+                TopographicalParameters::default()
+                    .set_is_outlet(site.x < 3.0)
+                    .set_min_elevation(floor.filter(|_| in_band(site)))
+            })
+            .collect::<Vec<_>>()
+    };
+    let generate = |floor: Option<f64>| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(parameters(floor))
+            .set_max_iteration(30)
+            .generate()
+            .unwrap()
+    };
+
+    // the floor is above most of the terrain without it
+    let free = generate(None);
+    let mut sorted = free.elevations().to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let floor = sorted[num * 9 / 10];
+
+    let floored = generate(Some(floor));
+    let sites = model.sites();
+    let elevations = floored.elevations();
+    let receivers = floored.network().receivers();
+    (0..num)
+        .filter(|&i| receivers[i] != i && in_band(&sites[i]))
+        .for_each(|i| assert!(elevations[i] >= floor));
+    // the sites upstream of the raised sites are not left below them
+    (0..num)
+        .filter(|&i| receivers[i] != i)
+        .for_each(|i| assert!(elevations[i] >= elevations[receivers[i]]));
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "mmap")]
+
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::storage::MappedElevations;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_mapped_elevations() {
+    let directory = std::env::temp_dir().join("fastlem_test_mapped_elevations");
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("elevations.flem");
+
+    let mut mapped = MappedElevations::create(&path, 4).unwrap();
+    assert_eq!(&mapped[..], &[0.0; 4]);
+    mapped.copy_from_slice(&[1.0, 2.5, -3.0, 4.0]);
+    mapped.flush().unwrap();
+    drop(mapped);
+
+    let mapped = MappedElevations::open(&path).unwrap();
+    assert_eq!(&mapped[..], &[1.0, 2.5, -3.0, 4.0]);
+    drop(mapped);
+
+    // the files without the magic are rejected
+    let invalid = directory.join("invalid.flem");
+    std::fs::write(&invalid, [0u8; 16]).unwrap();
+    assert!(MappedElevations::open(&invalid).is_err());
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn test_mapped_simulation() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let parameters = vec![TopographicalParameters::default(); model.num()];
+
+    let directory = std::env::temp_dir().join("fastlem_test_mapped_simulation");
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    let storage = directory.join("elevations.flem");
+    let snapshots = directory.join("snapshots");
+
+    let generator = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(parameters)
+        .set_max_iteration(10)
+        .set_snapshot_interval(Some(5));
+    let in_memory = generator.clone().generate().unwrap();
+    let mapped = generator
+        .set_elevation_storage(Some(&storage))
+        .set_snapshot_directory(Some(&snapshots))
+        .generate()
+        .unwrap();
+
+    // the storage does not affect the result
+    assert_eq!(in_memory.elevations(), mapped.elevations());
+
+    // the storage keeps the latest elevations
+    let stored = MappedElevations::open(&storage).unwrap();
+    assert_eq!(&stored[..], mapped.elevations());
+
+    // the snapshots are written at the interval
+    let snapshot = MappedElevations::open(snapshots.join("snapshot_00000005.flem")).unwrap();
+    assert_eq!(snapshot.len(), mapped.elevations().len());
+    assert!(snapshots.join("snapshot_00000010.flem").exists());
+    assert!(!snapshots.join("snapshot_00000001.flem").exists());
+
+    drop(stored);
+    drop(snapshot);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,39 @@
+use fastlem::core::traits::{Model, Site};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_nearest_sites() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let sites = model.sites();
+
+    (0..50).for_each(|k| {
+        let query = Site2D {
+            x: (k as f64 * 37.1) % 100.0,
+            y: (k as f64 * 61.7) % 100.0,
+        };
+        let mut expected = (0..sites.len()).collect::<Vec<_>>();
+        expected.sort_by(|&a, &b| {
+            sites[a]
+                .distance(&query)
+                .total_cmp(&sites[b].distance(&query))
+        });
+
+        assert_eq!(model.nearest_site(&query), Some(expected[0]));
+        let nearest = model.k_nearest(&query, 5);
+        assert_eq!(nearest.len(), 5);
+        nearest.iter().zip(expected.iter()).for_each(|(&a, &b)| {
+            assert_eq!(sites[a].distance(&query), sites[b].distance(&query));
+        });
+    });
+
+    assert_eq!(
+        model.k_nearest(&Site2D { x: 0.0, y: 0.0 }, num + 10).len(),
+        model.num()
+    );
+}
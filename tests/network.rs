@@ -0,0 +1,52 @@
+use fastlem::core::network::DrainageNetwork;
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_network_order() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+    let network = terrain.network();
+
+    // every site comes exactly once, after its receiver
+    let order = network.order();
+    assert_eq!(order.len(), num);
+    let mut position = vec![usize::MAX; num];
+    order.iter().enumerate().for_each(|(k, &i)| {
+        assert_eq!(position[i], usize::MAX);
+        position[i] = k;
+    });
+    (0..num).for_each(|i| {
+        let j = network.receivers()[i];
+        if j != i {
+            assert!(position[j] < position[i]);
+        }
+    });
+
+    // the order of the stream tree of the simulation is the one computed from the receivers
+    let rebuilt = DrainageNetwork::new(
+        network.receivers().to_vec(),
+        network.drainage_areas().to_vec(),
+    );
+    assert_eq!(rebuilt.order(), order);
+}
+
+#[test]
+fn test_network_order_with_cycle() {
+    // 0 is the outlet, 1 and 2 drain into it, and 3 and 4 form a cycle
+    let network = DrainageNetwork::new(vec![0, 0, 1, 4, 3], vec![1.0; 5]);
+    assert_eq!(network.order(), &[0, 1, 2]);
+}
@@ -0,0 +1,98 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::regolith::RegolithProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_parallel_determinism() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .add_edge_sites(None, None)
+        .unwrap()
+        .build()
+        .unwrap();
+    let num = model.sites().len();
+    let parameters = (0..num)
+        .map(|i| {
+            TopographicalParameters::default()
+                .set_erodibility(1.0 + (i % 5) as f64 * 0.2)
+                .set_solubility(if i % 13 == 0 { 0.5 } else { 0.0 })
+        })
+        .collect::<Vec<_>>();
+
+    for time_step in [None, Some(0.5)] {
+        let generate = |num_threads: usize| {
+            TerrainGenerator::default()
+                .set_model(model.clone())
+                .set_parameters(parameters.clone())
+                .set_max_iteration(20)
+                .set_time_step(time_step)
+                .set_num_threads(num_threads)
+                .add_process(RegolithProcess::default())
+                .generate_with_record()
+                .unwrap()
+        };
+
+        let (terrain, record) = generate(1);
+        [2, 3, 8].iter().for_each(|&num_threads| {
+            let (parallel_terrain, parallel_record) = generate(num_threads);
+            let bits =
+                |elevations: &[f64]| elevations.iter().map(|e| e.to_bits()).collect::<Vec<_>>();
+            assert_eq!(
+                bits(terrain.elevations()),
+                bits(parallel_terrain.elevations())
+            );
+            assert_eq!(record.digests(), parallel_record.digests());
+        });
+    }
+}
+
+#[test]
+fn test_parallel_accumulation() {
+    let num = 3000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // a single outlet leaves the basins nothing to solve in parallel
+    let outlet = model.default_outlets()[0];
+    let parameters = (0..model.num())
+        .map(|i| TopographicalParameters::default().set_is_outlet(i == outlet))
+        .collect::<Vec<_>>();
+
+    let generate = |num_threads: usize, min_parallel_sites: usize| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(parameters.clone())
+            .set_max_iteration(10)
+            .set_num_threads(num_threads)
+            .set_min_parallel_sites(min_parallel_sites)
+            .generate()
+            .unwrap()
+    };
+    let terrain = generate(1, 1);
+    let bits = |values: &[f64]| values.iter().map(|e| e.to_bits()).collect::<Vec<_>>();
+    [(4, 1), (3, 8), (4, usize::MAX)]
+        .iter()
+        .for_each(|&(num_threads, min_parallel_sites)| {
+            let parallel_terrain = generate(num_threads, min_parallel_sites);
+            assert_eq!(
+                bits(terrain.elevations()),
+                bits(parallel_terrain.elevations())
+            );
+            assert_eq!(
+                bits(terrain.network().drainage_areas()),
+                bits(parallel_terrain.network().drainage_areas())
+            );
+        });
+
+    // all the sites drain into the outlet
+    let total = model.areas().iter().sum::<f64>();
+    assert!((terrain.network().drainage_areas()[outlet] - total).abs() < total * 1e-9);
+}
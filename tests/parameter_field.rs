@@ -0,0 +1,57 @@
+use fastlem::core::parameters::{ParameterSet, TopographicalParameters};
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_parameter_field() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // the uplift rates decreasing to the east
+    let field = |_: usize, site: &Site2D| {
+        TopographicalParameters::default().set_uplift_rate(2.0 - site.x / 100.0)
+    };
+    let parameters = model
+        .sites()
+        .iter()
+        .enumerate()
+        .map(|(i, site)| field(i, site))
+        .collect::<Vec<_>>();
+
+    let generator = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_max_iteration(20);
+    let from_vec = generator
+        .clone()
+        .set_parameters(parameters.clone())
+        .generate()
+        .unwrap();
+    let from_field = generator
+        .clone()
+        .set_parameter_field(field)
+        .generate()
+        .unwrap();
+    assert_eq!(from_vec.elevations(), from_field.elevations());
+
+    // the parameter sets are fields
+    let from_set = generator
+        .clone()
+        .set_parameter_field(ParameterSet::from_parameters(parameters))
+        .generate()
+        .unwrap();
+    assert_eq!(from_vec.elevations(), from_set.elevations());
+
+    // the latest parameters replace the others
+    let replaced = generator
+        .set_parameter_field(field)
+        .set_parameters(vec![TopographicalParameters::default(); model.num()])
+        .generate()
+        .unwrap();
+    assert_ne!(from_vec.elevations(), replaced.elevations());
+}
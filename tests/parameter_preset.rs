@@ -0,0 +1,56 @@
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::parameter_preset::{LandscapeKind, ParameterPreset2D};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_parameter_presets() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let center = Site2D { x: 50.0, y: 50.0 };
+
+    let generate = |preset: ParameterPreset2D| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameter_field(preset.set_bounding_box(bound_min, bound_max))
+            .set_max_iteration(50)
+            .generate()
+            .unwrap()
+    };
+    let max_elevation = |elevations: &[f64]| elevations.iter().fold(0.0, |a: f64, &b| a.max(b));
+
+    let craton = generate(ParameterPreset2D::craton());
+    let orogen = generate(ParameterPreset2D::young_orogen());
+    let badlands = generate(ParameterPreset2D::badlands());
+    let island = generate(ParameterPreset2D::volcanic_island());
+    [&craton, &orogen, &badlands, &island]
+        .iter()
+        .for_each(|terrain| {
+            assert!(terrain.elevations().iter().all(|e| e.is_finite()));
+            assert!(max_elevation(terrain.elevations()) > 0.0);
+        });
+
+    // the orogen rises higher than the craton
+    assert!(max_elevation(orogen.elevations()) > max_elevation(craton.elevations()) * 2.0);
+
+    // the island is surrounded by the ocean, and its summit is near the center
+    let sites = model.sites();
+    let summit = (0..model.num())
+        .max_by(|&a, &b| island.elevations()[a].total_cmp(&island.elevations()[b]))
+        .unwrap();
+    assert!((sites[summit].x - center.x).hypot(sites[summit].y - center.y) < 20.0);
+    (0..model.num())
+        .filter(|&i| (sites[i].x - center.x).hypot(sites[i].y - center.y) > 40.0)
+        .for_each(|i| assert!(island.elevations()[i].abs() < 1e-9));
+
+    // the values can be tweaked
+    let preset = ParameterPreset2D::badlands().set_erodibility(2.0);
+    assert_eq!(preset.kind(), LandscapeKind::Badlands);
+    let flatter = generate(preset);
+    assert!(max_elevation(flatter.elevations()) > max_elevation(badlands.elevations()));
+}
@@ -0,0 +1,88 @@
+use fastlem::core::parameters::{ParameterError, ParameterSet, TopographicalParameters};
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_mark_outlets() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let parameters = ParameterSet::new(model.num())
+        .map(|i, parameters| parameters.set_erodibility(1.0 + (i % 3) as f64))
+        .mark_outlets(model.default_outlets(), -10.0)
+        .unwrap();
+    assert_eq!(parameters.len(), model.num());
+    let mut outlets = model.default_outlets().to_vec();
+    outlets.sort();
+    assert_eq!(parameters.outlets(), outlets);
+    assert!(parameters.validate().is_ok());
+
+    // an outlet out of the range leaves the parameters unchanged
+    assert_eq!(
+        parameters
+            .clone()
+            .mark_outlets(&[0, model.num()], 0.0)
+            .unwrap_err(),
+        ParameterError::SiteOutOfRange(model.num())
+    );
+
+    let outlet = model.default_outlets()[0];
+    let unmarked = parameters.clone().unmark_outlets(&[outlet]).unwrap();
+    assert_eq!(unmarked.outlets().len(), model.default_outlets().len() - 1);
+
+    // the outlets keep their base elevations
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(parameters.into())
+        .generate()
+        .unwrap();
+    model.default_outlets().iter().for_each(|&i| {
+        assert!((terrain.elevations()[i] + 10.0).abs() < 1e-9);
+    });
+}
+
+#[test]
+fn test_validate_parameters() {
+    let parameters = ParameterSet::new(10);
+    assert!(parameters.validate().is_ok());
+
+    let invalid = parameters
+        .clone()
+        .set(3, TopographicalParameters::default().set_erodibility(-1.0))
+        .unwrap();
+    assert_eq!(
+        invalid.validate(),
+        Err(ParameterError::InvalidErodibility(3))
+    );
+
+    let invalid = parameters
+        .clone()
+        .set(
+            5,
+            TopographicalParameters::default().set_max_slope(Some(std::f64::consts::FRAC_PI_2)),
+        )
+        .unwrap();
+    assert_eq!(invalid.validate(), Err(ParameterError::InvalidMaxSlope(5)));
+
+    let invalid = parameters
+        .clone()
+        .set(
+            7,
+            TopographicalParameters::default().set_base_elevation(f64::NAN),
+        )
+        .unwrap();
+    assert_eq!(invalid.validate(), Err(ParameterError::NonFiniteValue(7)));
+
+    assert_eq!(
+        parameters
+            .set(10, TopographicalParameters::default())
+            .unwrap_err(),
+        ParameterError::SiteOutOfRange(10)
+    );
+}
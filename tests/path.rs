@@ -0,0 +1,102 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::path::PathFinder2D;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_path_finding() {
+    let num = 2000;
+    let model = TerrainModel2DBulider::from_random_sites(
+        num,
+        Site2D::new(0.0, 0.0),
+        Site2D::new(100.0, 100.0),
+    )
+    .build()
+    .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+    let start = model.nearest_site(&Site2D::new(10.0, 50.0)).unwrap();
+    let goal = model.nearest_site(&Site2D::new(90.0, 50.0)).unwrap();
+
+    // without the slope and the rivers, the path is the shortest along the edges
+    let path = PathFinder2D::default()
+        .set_slope_weight(0.0)
+        .set_river_crossing_cost(0.0)
+        .find_path(&model, &terrain, start, goal)
+        .unwrap();
+    let distance = model.distance_field(&[start], f64::INFINITY).distances()[goal];
+    assert_eq!(path.sites.first(), Some(&start));
+    assert_eq!(path.sites.last(), Some(&goal));
+    assert!((path.cost - distance).abs() < 1e-9);
+    assert!((path.length() - distance).abs() < 1e-9);
+
+    // the consecutive sites are connected, and the cost is the sum of the costs of the edges
+    let finder = PathFinder2D::default()
+        .set_slope_weight(20.0)
+        .set_min_drainage_area(50.0);
+    let path = finder.find_path(&model, &terrain, start, goal).unwrap();
+    let elevations = terrain.elevations();
+    let network = terrain.network();
+    let is_river = |i: usize| network.drainage_areas()[i] >= 50.0;
+    let cost = path
+        .sites
+        .windows(2)
+        .map(|w| {
+            let (i, j) = (w[0], w[1]);
+            let length = model
+                .graph()
+                .neighbors_of(i)
+                .iter()
+                .find(|ja| ja.0 == j)
+                .unwrap()
+                .1;
+            let slope = (elevations[j] - elevations[i]).abs() / length;
+            let follows = network.receivers()[i] == j || network.receivers()[j] == i;
+            let crossing = if is_river(j) && !(is_river(i) && follows) {
+                10.0
+            } else {
+                0.0
+            };
+            length * (1.0 + 20.0 * slope) + crossing
+        })
+        .sum::<f64>();
+    assert!((path.cost - cost).abs() < 1e-9);
+    assert!(path.length() >= distance - 1e-9);
+
+    // the steep edges are avoided
+    let max_slope = 0.5;
+    if let Some(path) = PathFinder2D::default()
+        .set_max_slope(Some(max_slope))
+        .find_path(&model, &terrain, start, goal)
+    {
+        path.sites.windows(2).for_each(|w| {
+            let (a, b) = (&model.sites()[w[0]], &model.sites()[w[1]]);
+            let length = ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt();
+            assert!((elevations[w[1]] - elevations[w[0]]).abs() / length <= max_slope + 1e-9);
+        });
+    }
+    assert!(PathFinder2D::default()
+        .set_max_slope(Some(0.0))
+        .find_path(&model, &terrain, start, goal)
+        .is_none());
+
+    // the sites below the sea level are impassable
+    let finder = PathFinder2D::default().set_sea_level(Some(f64::INFINITY));
+    assert!(finder.find_path(&model, &terrain, start, goal).is_none());
+    assert!(PathFinder2D::default()
+        .find_path(&model, &terrain, start, num)
+        .is_none());
+
+    // the path to the start itself
+    let path = PathFinder2D::default()
+        .find_path(&model, &terrain, start, start)
+        .unwrap();
+    assert_eq!(path.sites, vec![start]);
+    assert_eq!(path.cost, 0.0);
+}
@@ -0,0 +1,154 @@
+use fastlem::core::adjacency::Adjacency;
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::{GenerationError, TerrainGenerator};
+use fastlem::lem::phases::{
+    accumulate_drainage_areas, construct_stream_tree, sweep_multiple_flow_directions,
+    update_elevations,
+};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_phases() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let parameters = vec![TopographicalParameters::default(); model.num()];
+    let adjacency = Adjacency::from_graph(model.graph());
+
+    // run the phases by hand from the flat terrain
+    let mut elevations = vec![0.0; model.num()];
+    let mut receivers = Vec::new();
+    for _ in 0..50 {
+        receivers = construct_stream_tree(&elevations, &adjacency, model.default_outlets());
+        elevations = update_elevations(
+            &elevations,
+            &receivers,
+            model.areas(),
+            &adjacency,
+            &parameters,
+            None,
+        )
+        .unwrap();
+    }
+    assert!(elevations.iter().all(|&e| e >= 0.0 && e.is_finite()));
+    assert!(elevations.iter().any(|&e| e > 0.0));
+
+    // every site flows to an outlet, so the drainage areas of the outlets sum up to the total area
+    let drainage_areas = accumulate_drainage_areas(&receivers, model.areas(), &adjacency);
+    let total = model.areas().iter().sum::<f64>();
+    let outlets = model
+        .default_outlets()
+        .iter()
+        .map(|&i| drainage_areas[i])
+        .sum::<f64>();
+    assert!((outlets - total).abs() < total * 1e-9);
+    (0..model.num()).for_each(|i| assert!(drainage_areas[i] >= model.areas()[i]));
+
+    // the steady state of the generator is a fixed point of the phases
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(parameters.clone())
+        .generate()
+        .unwrap();
+    let receivers =
+        construct_stream_tree(terrain.elevations(), &adjacency, model.default_outlets());
+    assert_eq!(receivers, terrain.network().receivers());
+    let updated = update_elevations(
+        terrain.elevations(),
+        &receivers,
+        model.areas(),
+        &adjacency,
+        &parameters,
+        None,
+    )
+    .unwrap();
+    assert_eq!(updated, terrain.elevations());
+    // the order of the summation may differ
+    accumulate_drainage_areas(&receivers, model.areas(), &adjacency)
+        .iter()
+        .zip(terrain.network().drainage_areas())
+        .for_each(|(a, b)| assert!((a - b).abs() <= b * 1e-12));
+
+    // the lengths of the inputs are checked
+    let update = |receivers: &[usize], areas: &[f64], parameters: &[TopographicalParameters]| {
+        update_elevations(
+            terrain.elevations(),
+            receivers,
+            areas,
+            &adjacency,
+            parameters,
+            None,
+        )
+    };
+    assert!(matches!(
+        update(&receivers, model.areas(), &parameters[1..]),
+        Err(GenerationError::InvalidNumberOfParameters)
+    ));
+    assert!(matches!(
+        update(&receivers[1..], model.areas(), &parameters),
+        Err(GenerationError::InvalidNumberOfReceivers)
+    ));
+    assert!(matches!(
+        update(&receivers, &model.areas()[1..], &parameters),
+        Err(GenerationError::InvalidNumberOfAreas)
+    ));
+}
+
+#[test]
+fn test_multiple_flow_directions() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .generate()
+        .unwrap();
+    let adjacency = Adjacency::from_graph(model.graph());
+    let elevations = terrain.elevations();
+    let outlets = model.default_outlets();
+    let total = model.areas().iter().sum::<f64>();
+
+    // every site drains to an outlet, so the whole area reaches the outlets in whatever proportions
+    [0.0, 1.1, 4.0].iter().for_each(|&exponent| {
+        let drainage_areas = sweep_multiple_flow_directions(
+            elevations,
+            &adjacency,
+            outlets,
+            model.areas(),
+            exponent,
+        );
+        let reached = outlets.iter().map(|&i| drainage_areas[i]).sum::<f64>();
+        assert!((reached - total).abs() < total * 1e-9);
+        (0..num).for_each(|i| assert!(drainage_areas[i] >= model.areas()[i]));
+    });
+
+    // the flow spreads over the slopes, so the largest drainage area is smaller than along the stream tree
+    let receivers = terrain.network().receivers();
+    let single = accumulate_drainage_areas(receivers, model.areas(), &adjacency);
+    let multiple =
+        sweep_multiple_flow_directions(elevations, &adjacency, outlets, model.areas(), 1.1);
+    let max_of = |areas: &[f64]| {
+        (0..num)
+            .filter(|&i| !outlets.contains(&i))
+            .map(|i| areas[i])
+            .fold(0.0, f64::max)
+    };
+    assert!(max_of(&multiple) < max_of(&single));
+
+    // with a large exponent the flow concentrates to the steepest neighbor, which is the receiver on the slopes
+    let concentrated =
+        sweep_multiple_flow_directions(elevations, &adjacency, outlets, model.areas(), 1e3);
+    let converged = (0..num)
+        .filter(|&i| (concentrated[i] - single[i]).abs() <= single[i] * 1e-6)
+        .count();
+    assert!(converged * 10 > num * 9);
+}
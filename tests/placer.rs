@@ -0,0 +1,70 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::deposition::DepositionProcess;
+use fastlem::models::surface::placer::{PlacerDetector2D, PlacerKind};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_placer_deposits() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let regions = model
+        .sites()
+        .iter()
+        .map(|site| if site.x < 50.0 { 0 } else { 1 })
+        .collect::<Vec<_>>();
+
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(50)
+        .add_process(DepositionProcess::default().set_source_regions(Some(regions)))
+        .generate()
+        .unwrap();
+
+    let deposits = PlacerDetector2D::default()
+        .set_min_drainage_area(20.0)
+        .set_min_density(0.0)
+        .detect(&terrain);
+    assert!(!deposits.is_empty());
+    assert!(deposits
+        .iter()
+        .any(|deposit| deposit.kind == PlacerKind::AlluvialFan));
+    assert!(deposits
+        .iter()
+        .all(|deposit| deposit.density > 0.0 && deposit.density <= 1.0));
+    assert!(deposits
+        .windows(2)
+        .all(|pair| pair[0].density >= pair[1].density));
+
+    // the grade from a region does not exceed the grade of the whole sediment
+    let sourced = (0..2)
+        .map(|region| {
+            PlacerDetector2D::default()
+                .set_min_drainage_area(20.0)
+                .set_min_density(0.0)
+                .set_source_region(Some(region))
+                .detect(&terrain)
+        })
+        .collect::<Vec<_>>();
+    sourced.iter().for_each(|region_deposits| {
+        assert!(region_deposits.len() <= deposits.len());
+        region_deposits.iter().for_each(|deposit| {
+            let whole = deposits
+                .iter()
+                .find(|d| d.site == deposit.site && d.kind == deposit.kind)
+                .unwrap();
+            assert!(deposit.density <= whole.density + 1e-9);
+        });
+    });
+    assert!(sourced
+        .iter()
+        .all(|region_deposits| !region_deposits.is_empty()));
+}
@@ -0,0 +1,88 @@
+use fastlem::core::fields::{PLATE, TECTONIC_UPLIFT};
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::plate::{Plate, PlateProcess};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_plate_kinematics() {
+    // the translation and the rotation make the pure rotation around the shifted center
+    let plate = Plate::new(Site2D { x: 10.0, y: 0.0 })
+        .set_velocity(1.0, 0.5)
+        .set_rotation(Site2D { x: 0.0, y: 0.0 }, 0.1);
+    let dt = 1e-4;
+    let (a, b) = (plate.seed_at(3.0), plate.seed_at(3.0 + dt));
+    let velocity = plate.velocity_at(&a);
+    assert!(((b.x - a.x) / dt - velocity.0).abs() < 1e-3);
+    assert!(((b.y - a.y) / dt - velocity.1).abs() < 1e-3);
+
+    let translated = Plate::new(Site2D { x: 10.0, y: 0.0 }).set_velocity(1.0, 0.5);
+    let seed = translated.seed_at(2.0);
+    assert_eq!((seed.x, seed.y), (12.0, 1.0));
+}
+
+#[test]
+fn test_plate_uplift() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let plates = |speed: f64| {
+        vec![
+            Plate::new(Site2D { x: 25.0, y: 50.0 }).set_velocity(speed, 0.0),
+            Plate::new(Site2D { x: 75.0, y: 50.0 }).set_velocity(-speed, 0.0),
+        ]
+    };
+    let generate = |plates: Vec<Plate>| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![TopographicalParameters::default(); num])
+            .set_time_step(Some(1.0))
+            .set_max_iteration(20)
+            .add_process(PlateProcess::new(model.sites(), plates).set_boundary_width(Some(5.0)))
+            .generate()
+            .unwrap()
+    };
+
+    // the convergent boundary is uplifted, and the interiors of the plates are not
+    // (away from the long edges along the hull, which connect the sites across the boundary)
+    let converging = generate(plates(0.5));
+    let uplift = converging.fields().get(TECTONIC_UPLIFT).unwrap();
+    let plate = converging.fields().get(PLATE).unwrap();
+    let (mut boundary, mut interior) = (0.0, 0.0);
+    model.sites().iter().enumerate().for_each(|(i, site)| {
+        assert!(uplift[i].is_finite());
+        assert_eq!(plate[i], if site.x < 50.0 { 0.0 } else { 1.0 });
+        if (site.x - 50.0).abs() < 5.0 {
+            boundary += uplift[i];
+        } else if (site.x - 50.0).abs() > 20.0 && (site.y - 50.0).abs() < 30.0 {
+            interior += uplift[i].abs();
+        }
+    });
+    assert!(boundary > 0.0);
+    assert!(interior < 1e-9);
+
+    // the divergent boundary subsides, but the uplift rates do not become negative
+    let diverging = generate(plates(-0.5));
+    let subsidence = diverging.fields().get(TECTONIC_UPLIFT).unwrap();
+    assert!(subsidence.iter().all(|u| *u >= -1.0));
+    assert!(subsidence.iter().any(|u| *u < 0.0));
+
+    // the static plates do nothing
+    let still = generate(plates(0.0));
+    let plain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+    assert_eq!(still.elevations(), plain.elevations());
+    let max = |elevations: &[f64]| elevations.iter().cloned().fold(0.0, f64::max);
+    assert!(max(converging.elevations()) > max(plain.elevations()));
+}
@@ -0,0 +1,44 @@
+use fastlem::core::traits::Model;
+use fastlem::models::surface::preset::{ContinentPreset2D, IslandPreset2D};
+extern crate fastlem;
+
+#[test]
+fn test_island_preset() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(2000)
+        .set_island_count(3)
+        .set_island_radius(15.0)
+        .set_seed(1)
+        .generate()
+        .unwrap();
+
+    let elevations = terrain.elevations();
+    assert!(elevations.iter().all(|e| e.is_finite() && *e >= 0.0));
+    assert!(elevations.iter().any(|e| *e > 0.1));
+    assert!(elevations.iter().any(|e| *e < 1e-9));
+
+    // the boundary is the ocean
+    terrain
+        .sites()
+        .iter()
+        .zip(elevations.iter())
+        .filter(|(site, _)| site.x <= 0.0 || site.y <= 0.0 || site.x >= 100.0 || site.y >= 100.0)
+        .for_each(|(_, &elevation)| assert!(elevation < 1e-9));
+}
+
+#[test]
+fn test_continent_preset() {
+    let preset = ContinentPreset2D::default()
+        .set_num_sites(2000)
+        .set_num_plates(6)
+        .set_seed(2);
+
+    let (model, parameters) = preset.build().unwrap();
+    assert_eq!(parameters.len(), model.sites().len());
+
+    let terrain = preset.generate().unwrap();
+    let elevations = terrain.elevations();
+    assert!(elevations.iter().all(|e| e.is_finite() && *e >= 0.0));
+    assert!(elevations.iter().any(|e| *e > 0.1));
+    assert!(elevations.iter().any(|e| *e < 1e-9));
+}
@@ -0,0 +1,100 @@
+use fastlem::core::fields::DEPOSIT_THICKNESS;
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::deposition::DepositionProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_provenance() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let outlets = model.default_outlets().to_vec();
+    let regions = model
+        .sites()
+        .iter()
+        .map(|site| if site.x < 50.0 { 0 } else { 1 })
+        .collect::<Vec<_>>();
+
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(50)
+        .add_process(DepositionProcess::default().set_source_regions(Some(regions)))
+        .generate()
+        .unwrap();
+
+    let fields = terrain.fields();
+    let thickness = fields.get(DEPOSIT_THICKNESS).unwrap();
+    let provenances = (0..2)
+        .map(|r| fields.get(&DepositionProcess::provenance_field(r)).unwrap())
+        .collect::<Vec<_>>();
+    let exported = (0..2)
+        .map(|r| {
+            fields
+                .get(&DepositionProcess::exported_provenance_field(r))
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    // the deposits from the regions make up the whole deposits
+    (0..num).for_each(|i| {
+        let total = provenances[0][i] + provenances[1][i];
+        assert!(provenances.iter().all(|p| p[i] >= 0.0));
+        assert!((total - thickness[i]).abs() <= 1e-9 * thickness[i].abs().max(1.0));
+    });
+    assert!(thickness.iter().any(|h| *h > 0.0));
+
+    // the sediment leaves the domain only at the outlets
+    (0..num).for_each(|i| {
+        exported.iter().for_each(|e| {
+            assert!(e[i] >= 0.0);
+            if !outlets.contains(&i) {
+                assert_eq!(e[i], 0.0);
+            }
+        });
+    });
+    assert!(exported[0].iter().sum::<f64>() > 0.0);
+    assert!(exported[1].iter().sum::<f64>() > 0.0);
+}
+
+#[test]
+fn test_provenance_with_too_few_regions() {
+    let num = 500;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let generator = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(10);
+
+    // the regions not given for all the sites are ignored instead of indexing out of bounds
+    let untracked = generator
+        .clone()
+        .add_process(DepositionProcess::default())
+        .generate()
+        .unwrap();
+    let terrain = generator
+        .add_process(DepositionProcess::default().set_source_regions(Some(vec![0; num / 2])))
+        .generate()
+        .unwrap();
+    assert_eq!(terrain.elevations(), untracked.elevations());
+    assert_eq!(
+        terrain.fields().get(DEPOSIT_THICKNESS),
+        untracked.fields().get(DEPOSIT_THICKNESS)
+    );
+    assert!(terrain
+        .fields()
+        .get(&DepositionProcess::provenance_field(0))
+        .is_none());
+}
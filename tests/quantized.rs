@@ -0,0 +1,27 @@
+use fastlem::models::surface::preset::IslandPreset2D;
+use fastlem::models::surface::quantized::QuantizedElevations;
+extern crate fastlem;
+
+#[test]
+fn test_quantized_elevations() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+    let quantized = terrain.quantized_elevations();
+    assert_eq!(quantized.values.len(), terrain.elevations().len());
+    assert!(quantized.values.contains(&0));
+    assert!(quantized.values.contains(&u16::MAX));
+    quantized
+        .elevations()
+        .iter()
+        .zip(terrain.elevations().iter())
+        .for_each(|(a, b)| assert!((a - b).abs() <= quantized.scale / 2.0 + 1e-12));
+
+    let flat = QuantizedElevations::from_elevations(&[3.0, 3.0]);
+    assert_eq!(flat.values, vec![0, 0]);
+    assert_eq!(flat.elevation(1), Some(3.0));
+
+    let clamped = QuantizedElevations::from_range(&[-1.0, 0.5, 2.0], 0.0, 1.0);
+    assert_eq!(clamped.values, vec![0, 32768, u16::MAX]);
+}
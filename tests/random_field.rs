@@ -0,0 +1,58 @@
+use fastlem::models::surface::random_field::GaussianRandomField2D;
+use fastlem::models::surface::sites::Site2D;
+extern crate fastlem;
+
+#[test]
+fn test_gaussian_random_field() {
+    let field = GaussianRandomField2D::default()
+        .set_mean(2.0)
+        .set_variance(0.25)
+        .set_correlation_length(5.0)
+        .set_seed(7);
+
+    // the sites on a grid much larger than the correlation length
+    let sites = (0..100)
+        .flat_map(|y| {
+            (0..100).map(move |x| Site2D {
+                x: x as f64 * 4.0,
+                y: y as f64 * 4.0,
+            })
+        })
+        .collect::<Vec<_>>();
+    let values = field.values(&sites);
+    assert_eq!(values.len(), sites.len());
+    assert!(values.iter().all(|v| v.is_finite()));
+    assert_eq!(values[123], field.value(&sites[123]));
+
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance =
+        values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / values.len() as f64;
+    assert!((mean - 2.0).abs() < 0.1);
+    assert!((variance - 0.25).abs() < 0.1);
+
+    // the values are correlated only within the correlation length
+    let correlation = |offset: f64| {
+        let shifted = field.values(
+            &sites
+                .iter()
+                .map(|site| Site2D {
+                    x: site.x + offset,
+                    y: site.y,
+                })
+                .collect::<Vec<_>>(),
+        );
+        values
+            .iter()
+            .zip(shifted.iter())
+            .map(|(a, b)| (a - mean) * (b - mean))
+            .sum::<f64>()
+            / values.len() as f64
+            / variance
+    };
+    assert!(correlation(1.0) > 0.9);
+    assert!(correlation(25.0).abs() < 0.2);
+
+    // the field is determined by the seed
+    assert_eq!(values, field.clone().values(&sites));
+    assert_ne!(values, field.set_seed(8).values(&sites));
+}
@@ -0,0 +1,125 @@
+use fastlem::core::scale::{VerticalScale, VerticalUnit};
+use fastlem::core::traits::Site;
+use fastlem::models::surface::preset::IslandPreset2D;
+use fastlem::models::surface::raster::Rasterizer2D;
+extern crate fastlem;
+
+#[test]
+fn test_supersampled_rasterization() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+
+    let rasterizer = Rasterizer2D::default().set_size(20, 10);
+    let raster = rasterizer.rasterize_elevations(&terrain);
+    assert_eq!(raster.values().len(), 200);
+    assert_eq!(
+        raster.get(3, 4),
+        terrain.get_elevation(&rasterizer.pixel_center(3, 4))
+    );
+    assert_eq!(raster.get(20, 0), None);
+
+    // the supersampled pixel is the average of the samples in the pixel
+    let supersampled = rasterizer
+        .clone()
+        .set_supersampling(4)
+        .rasterize_elevations(&terrain);
+    let fine = rasterizer.set_size(80, 40).rasterize_elevations(&terrain);
+    (0..10).for_each(|y| {
+        (0..20).for_each(|x| {
+            let samples = (0..4)
+                .flat_map(|sy| (0..4).map(move |sx| (x * 4 + sx, y * 4 + sy)))
+                .filter_map(|(fx, fy)| fine.get(fx, fy))
+                .collect::<Vec<_>>();
+            let average = samples.iter().sum::<f64>() / samples.len() as f64;
+            assert!((supersampled.get(x, y).unwrap() - average).abs() < 1e-9);
+        });
+    });
+}
+
+#[test]
+fn test_drainage_area_rasterization() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+
+    let rasterizer = Rasterizer2D::default().set_size(50, 50);
+    let raster = rasterizer.rasterize_drainage_areas(&terrain, false);
+    let log_raster = rasterizer.rasterize_drainage_areas(&terrain, true);
+    let areas = terrain.network().drainage_areas();
+    let max_area = areas
+        .iter()
+        .copied()
+        .filter(|a| a.is_finite())
+        .fold(0.0, f64::max);
+
+    let mut count = 0;
+    (0..50).for_each(|y| {
+        (0..50).for_each(|x| {
+            let Some(area) = raster.get(x, y) else {
+                assert_eq!(log_raster.get(x, y), None);
+                return;
+            };
+            count += 1;
+            // each pixel takes the drainage area of the nearest site
+            let center = rasterizer.pixel_center(x, y);
+            let nearest = terrain
+                .sites()
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    a.squared_distance(&center)
+                        .total_cmp(&b.squared_distance(&center))
+                })
+                .unwrap()
+                .0;
+            assert_eq!(area, areas[nearest]);
+            assert!(area.is_finite());
+            assert!(area <= max_area);
+            assert!((log_raster.get(x, y).unwrap() - area.ln_1p()).abs() < 1e-12);
+        });
+    });
+    assert!(count > 0);
+}
+
+#[test]
+fn test_vertical_scale() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+
+    let scale = VerticalScale::default()
+        .set_unit(VerticalUnit::Meters(10.0))
+        .set_exaggeration(2.0);
+    assert_eq!(scale.factor(), 20.0);
+
+    let rasterizer = Rasterizer2D::default().set_size(40, 40);
+    let exaggerated = rasterizer.clone().set_vertical_scale(scale);
+    let raster = rasterizer.rasterize_elevations(&terrain);
+    let scaled = exaggerated.rasterize_elevations(&terrain);
+    raster
+        .values()
+        .iter()
+        .zip(scaled.values().iter())
+        .for_each(|(a, b)| assert_eq!(a.map(|a| a * 20.0), *b));
+
+    // the flat pixels are lit by the sine of the altitude, and the exaggeration increases the contrast
+    let altitude = std::f64::consts::FRAC_PI_4;
+    let shade = rasterizer.rasterize_hillshade(&terrain, 0.0, altitude);
+    let scaled_shade = exaggerated.rasterize_hillshade(&terrain, 0.0, altitude);
+    let variance = |raster: &fastlem::models::surface::raster::Raster2D| {
+        let values = raster.values().iter().flatten().collect::<Vec<_>>();
+        let mean = values.iter().copied().sum::<f64>() / values.len() as f64;
+        values.iter().map(|&v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+    };
+    assert!(shade
+        .values()
+        .iter()
+        .flatten()
+        .all(|&v| (0.0..=1.0).contains(&v)));
+    assert!(variance(&scaled_shade) > variance(&shade));
+    assert!((shade.get(0, 0).unwrap() - altitude.sin()).abs() < 1e-6);
+}
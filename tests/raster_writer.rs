@@ -0,0 +1,201 @@
+use std::io;
+
+use fastlem::models::surface::preset::IslandPreset2D;
+use fastlem::models::surface::raster::Rasterizer2D;
+use fastlem::models::surface::raster_writer::{AsciiGridWriter, PngWriter, RowWriter, TiffWriter};
+extern crate fastlem;
+
+/// Collects the written rows.
+#[derive(Default)]
+struct Rows {
+    rows: Vec<Vec<Option<f64>>>,
+    finished: bool,
+}
+
+impl RowWriter for Rows {
+    fn write_row(&mut self, row: &[Option<f64>]) -> io::Result<()> {
+        self.rows.push(row.to_vec());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.finished = true;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_streamed_rows() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+    let rasterizer = Rasterizer2D::default().set_size(30, 20);
+
+    // the rows are the ones of the raster from the top
+    let raster = rasterizer.rasterize_elevations(&terrain);
+    let mut rows = Rows::default();
+    rasterizer.write_elevations(&terrain, &mut rows).unwrap();
+    assert!(rows.finished);
+    assert_eq!(rows.rows.len(), 20);
+    rows.rows.iter().enumerate().for_each(|(i, row)| {
+        let y = 19 - i;
+        (0..30).for_each(|x| assert_eq!(row[x], raster.get(x, y)));
+    });
+
+    // the streamed hillshade is identical to the one of the whole raster
+    let hillshade = rasterizer.rasterize_hillshade(&terrain, 0.5, 0.8);
+    let mut rows = Rows::default();
+    rasterizer
+        .write_hillshade(&terrain, 0.5, 0.8, &mut rows)
+        .unwrap();
+    assert_eq!(rows.rows.len(), 20);
+    rows.rows.iter().enumerate().for_each(|(i, row)| {
+        let y = 19 - i;
+        (0..30).for_each(|x| assert_eq!(row[x], hillshade.get(x, y)));
+    });
+}
+
+#[test]
+fn test_parallel_rows() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+    let rasterizer = Rasterizer2D::default().set_size(23, 77);
+    let raster = rasterizer.rasterize_elevations(&terrain);
+    let hillshade = rasterizer.rasterize_hillshade(&terrain, 0.5, 0.8);
+
+    // the output is identical regardless of the number of threads, including the rows at the borders of the blocks
+    [2, 3, 8].into_iter().for_each(|num_threads| {
+        let rasterizer = rasterizer.clone().set_num_threads(num_threads);
+        assert_eq!(rasterizer.rasterize_elevations(&terrain), raster);
+        assert_eq!(
+            rasterizer.rasterize_hillshade(&terrain, 0.5, 0.8),
+            hillshade
+        );
+
+        let mut rows = Rows::default();
+        rasterizer.write_elevations(&terrain, &mut rows).unwrap();
+        assert_eq!(rows.rows.len(), 77);
+        rows.rows.iter().enumerate().for_each(|(i, row)| {
+            let y = 76 - i;
+            (0..23).for_each(|x| assert_eq!(row[x], raster.get(x, y)));
+        });
+
+        let mut rows = Rows::default();
+        rasterizer
+            .write_hillshade(&terrain, 0.5, 0.8, &mut rows)
+            .unwrap();
+        assert_eq!(rows.rows.len(), 77);
+        rows.rows.iter().enumerate().for_each(|(i, row)| {
+            let y = 76 - i;
+            (0..23).for_each(|x| assert_eq!(row[x], hillshade.get(x, y)));
+        });
+    });
+}
+
+#[test]
+fn test_ascii_grid_writer() {
+    let rasterizer = Rasterizer2D::default().set_size(4, 2);
+    let mut writer = AsciiGridWriter::new(Vec::new(), &rasterizer, -9999.0).unwrap();
+    rasterizer
+        .write_rows(|site| (site.x < 50.0).then_some(site.y), &mut writer)
+        .unwrap();
+    let text = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+        text,
+        "ncols 4\nnrows 2\nxllcorner 0\nyllcorner 0\ndx 25\ndy 50\nNODATA_value -9999\n\
+         75 75 -9999 -9999\n25 25 -9999 -9999\n"
+    );
+}
+
+#[test]
+fn test_tiff_writer() {
+    let rasterizer = Rasterizer2D::default().set_size(3, 2);
+    let mut writer = TiffWriter::new(Vec::new(), 3, 2).unwrap();
+    rasterizer
+        .write_rows(|site| (site.x > 50.0).then_some(site.x), &mut writer)
+        .unwrap();
+    let data = writer.into_inner();
+    assert_eq!(&data[..4], b"II*\0");
+
+    // the pixels follow the header, and the directory follows the pixels
+    let directory = u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
+    assert_eq!(directory, 8 + 3 * 2 * 4);
+    let num_entries = u16::from_le_bytes(data[directory..directory + 2].try_into().unwrap());
+    assert_eq!(data.len(), directory + 2 + num_entries as usize * 12 + 4);
+    let pixels = data[8..directory]
+        .chunks(4)
+        .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect::<Vec<_>>();
+    let x = 100.0 / 3.0;
+    assert!(pixels[0].is_nan());
+    assert!(pixels[1].is_nan());
+    assert_eq!(pixels[2], (2.5 * x) as f32);
+    assert_eq!(pixels[5], (2.5 * x) as f32);
+}
+
+#[test]
+fn test_png_writer() {
+    let (width, height) = (300, 120);
+    let rasterizer = Rasterizer2D::default().set_size(width, height);
+    let mut writer = PngWriter::new(Vec::new(), width, height, 0.0, 100.0).unwrap();
+    rasterizer
+        .write_rows(|site| (site.y > 10.0).then_some(site.x), &mut writer)
+        .unwrap();
+    let data = writer.into_inner();
+    assert_eq!(&data[..8], b"\x89PNG\r\n\x1a\n");
+
+    // read the chunks, concatenating the zlib stream of the image data
+    let mut stream = Vec::new();
+    let mut offset = 8;
+    let mut kinds = Vec::new();
+    while offset < data.len() {
+        let len = u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
+        let kind = &data[offset + 4..offset + 8];
+        if kind == b"IDAT" {
+            stream.extend_from_slice(&data[offset + 8..offset + 8 + len]);
+        }
+        kinds.push(kind.to_vec());
+        offset += 12 + len;
+    }
+    assert_eq!(offset, data.len());
+    assert_eq!(kinds.first().unwrap(), b"IHDR");
+    assert_eq!(kinds.last().unwrap(), b"IEND");
+
+    // decode the uncompressed deflate blocks
+    assert_eq!(&stream[..2], &[0x78, 0x01]);
+    let mut scanlines = Vec::new();
+    let mut offset = 2;
+    loop {
+        let last = stream[offset] & 1 == 1;
+        let len = u16::from_le_bytes([stream[offset + 1], stream[offset + 2]]) as usize;
+        let nlen = u16::from_le_bytes([stream[offset + 3], stream[offset + 4]]) as usize;
+        assert_eq!(len, !nlen & 0xffff);
+        scanlines.extend_from_slice(&stream[offset + 5..offset + 5 + len]);
+        offset += 5 + len;
+        if last {
+            break;
+        }
+    }
+    assert_eq!(stream.len(), offset + 4);
+    assert_eq!(scanlines.len(), height * (1 + width * 2));
+
+    // the top row is the largest y, and the bottom rows are outside the sampled region
+    let level = |x: usize, row: usize| {
+        let i = row * (1 + width * 2) + 1 + x * 2;
+        u16::from_be_bytes([scanlines[i], scanlines[i + 1]])
+    };
+    assert_eq!(
+        level(0, 0),
+        1 + (65534.0_f64 * (0.5 / width as f64)).round() as u16
+    );
+    assert!(level(width - 1, 0) > level(0, 0));
+    assert_eq!(level(0, height - 1), 0);
+
+    // more rows than the height are rejected
+    let mut writer = PngWriter::new(Vec::new(), 2, 1, 0.0, 1.0).unwrap();
+    writer.write_row(&[None, None]).unwrap();
+    assert!(writer.write_row(&[None, None]).is_err());
+}
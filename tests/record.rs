@@ -0,0 +1,44 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::record::{digest_elevations, SimulationRecord};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_record_and_replay() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let (terrain, record) = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(
+            (0..num)
+                .map(|i| {
+                    TopographicalParameters::default()
+                        .set_erodibility(1.0 + (i % 7) as f64 * 0.1)
+                        .set_max_slope(Some(0.5))
+                })
+                .collect::<_>(),
+        )
+        .set_seed(42)
+        .set_debug_checks(true)
+        .generate_with_record()
+        .unwrap();
+
+    assert_eq!(
+        record.digests().last().copied(),
+        Some(digest_elevations(terrain.elevations()))
+    );
+
+    let mut buf = Vec::new();
+    record.write_to(&mut buf).unwrap();
+    let restored = SimulationRecord::read_from(buf.as_slice()).unwrap();
+
+    let report = restored.replay().unwrap();
+    assert!(report.is_identical());
+    assert_eq!(report.recorded_steps, record.digests().len());
+}
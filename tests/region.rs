@@ -0,0 +1,98 @@
+use fastlem::core::parameters::{ParameterSet, TopographicalParameters};
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::region::{RegionError, RegionOfInterest2D};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_region_polygon() {
+    assert!(matches!(
+        RegionOfInterest2D::new(vec![Site2D::new(0.0, 0.0), Site2D::new(1.0, 0.0)]),
+        Err(RegionError::InvalidPolygon)
+    ));
+
+    // a concave polygon shaped like the letter L
+    let region = RegionOfInterest2D::new(vec![
+        Site2D::new(0.0, 0.0),
+        Site2D::new(2.0, 0.0),
+        Site2D::new(2.0, 1.0),
+        Site2D::new(1.0, 1.0),
+        Site2D::new(1.0, 2.0),
+        Site2D::new(0.0, 2.0),
+    ])
+    .unwrap();
+    assert!(region.contains(&Site2D::new(0.5, 0.5)));
+    assert!(region.contains(&Site2D::new(1.5, 0.5)));
+    assert!(region.contains(&Site2D::new(0.5, 1.5)));
+    assert!(!region.contains(&Site2D::new(1.5, 1.5)));
+    assert!(!region.contains(&Site2D::new(-0.5, 0.5)));
+    let (bound_min, bound_max) = region.bounding_box();
+    assert_eq!((bound_min.x, bound_min.y), (0.0, 0.0));
+    assert_eq!((bound_max.x, bound_max.y), (2.0, 2.0));
+}
+
+#[test]
+fn test_region_of_interest() {
+    // the coarse result of the whole world
+    let num = 1000;
+    let coarse_model = TerrainModel2DBulider::from_random_sites(
+        num,
+        Site2D::new(0.0, 0.0),
+        Site2D::new(100.0, 100.0),
+    )
+    .build()
+    .unwrap();
+    let coarse = TerrainGenerator::default()
+        .set_model(coarse_model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+
+    // the fine model covering the region with a margin
+    let region = RegionOfInterest2D::new(vec![
+        Site2D::new(40.0, 35.0),
+        Site2D::new(65.0, 40.0),
+        Site2D::new(60.0, 65.0),
+        Site2D::new(35.0, 60.0),
+    ])
+    .unwrap();
+    let num = 2000;
+    let model = TerrainModel2DBulider::from_random_sites(
+        num,
+        Site2D::new(30.0, 30.0),
+        Site2D::new(70.0, 70.0),
+    )
+    .build()
+    .unwrap();
+    let parameters = region
+        .constrain(&model, &coarse, ParameterSet::new(num))
+        .unwrap();
+    let exterior = region.exterior_sites(&model);
+    assert_eq!(parameters.frozen(), exterior);
+    assert!(exterior.len() < num);
+
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(parameters.into())
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+    let elevations = terrain.elevations();
+
+    // the exterior keeps the far field, and the interior is simulated on the fine model
+    exterior.iter().for_each(|&i| {
+        let expected = coarse.get_elevation(&model.sites()[i]).unwrap();
+        assert_eq!(elevations[i], expected);
+    });
+    assert!(elevations.iter().all(|e| e.is_finite()));
+    assert!((0..num)
+        .filter(|i| !exterior.contains(i))
+        .any(|i| elevations[i] > 0.0));
+
+    assert!(matches!(
+        region.constrain(&model, &coarse, ParameterSet::new(num + 1)),
+        Err(RegionError::InvalidNumberOfParameters)
+    ));
+}
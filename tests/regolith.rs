@@ -0,0 +1,31 @@
+use fastlem::core::fields::SOIL_THICKNESS;
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::regolith::RegolithProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_regolith() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(50)
+        .add_process(RegolithProcess::default().set_channel_area(Some(200.0)))
+        .generate()
+        .unwrap();
+
+    let soil = terrain.fields().get(SOIL_THICKNESS).unwrap();
+    assert_eq!(soil.len(), num);
+    assert!(soil.iter().all(|h| h.is_finite() && *h >= 0.0));
+    assert!(soil.iter().any(|h| *h > 0.0));
+    assert!(terrain.elevations().iter().all(|e| e.is_finite()));
+}
@@ -0,0 +1,80 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::surface_age::SurfaceAgeProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_resample_between_resolutions() {
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let fine = TerrainModel2DBulider::from_random_sites(4000, bound_min, bound_max)
+        .add_edge_sites(None, None)
+        .unwrap()
+        .build()
+        .unwrap();
+    let coarse = TerrainModel2DBulider::from_random_sites(300, bound_min, bound_max)
+        .add_edge_sites(None, None)
+        .unwrap()
+        .build()
+        .unwrap();
+    let plane = |site: &Site2D| 0.5 * site.x + 0.2 * site.y;
+
+    // downsampling averages the fine sites in each coarse cell
+    let elevations = fine.sites().iter().map(plane).collect::<Vec<_>>();
+    let terrain = fine.create_terrain_from_result(&elevations);
+    let downsampled = terrain.resample_to(&coarse);
+    assert_eq!(downsampled.elevations().len(), coarse.num());
+    let spacing = (100.0 * 100.0 / 300.0_f64).sqrt();
+    coarse.sites().iter().enumerate().for_each(|(j, site)| {
+        assert!((downsampled.elevations()[j] - plane(site)).abs() < spacing);
+    });
+
+    // upsampling interpolates the coarse sites
+    let elevations = coarse.sites().iter().map(plane).collect::<Vec<_>>();
+    let upsampled = coarse
+        .create_terrain_from_result(&elevations)
+        .resample_to(&fine);
+    assert_eq!(upsampled.elevations().len(), fine.num());
+    let max_error = fine
+        .sites()
+        .iter()
+        .enumerate()
+        .map(|(i, site)| (upsampled.elevations()[i] - plane(site)).abs())
+        .fold(0.0, f64::max);
+    assert!(max_error < spacing);
+}
+
+#[test]
+fn test_resample_fields() {
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let fine = TerrainModel2DBulider::from_random_sites(2000, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let coarse = TerrainModel2DBulider::from_random_sites(200, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(fine.clone())
+        .set_parameters(vec![TopographicalParameters::default(); fine.num()])
+        .set_max_iteration(20)
+        .set_time_step(Some(1.0))
+        .add_process(SurfaceAgeProcess::default())
+        .generate()
+        .unwrap();
+
+    let resampled = terrain.resample_to(&coarse);
+    let names = terrain.fields().names().collect::<Vec<_>>();
+    assert!(!names.is_empty());
+    names.iter().for_each(|name| {
+        let values = resampled.fields().get(name).unwrap();
+        assert_eq!(values.len(), coarse.num());
+        assert!(values.iter().all(|v| v.is_finite()));
+    });
+    assert!(resampled.network().is_empty());
+
+    let max = terrain.elevations().iter().fold(0.0, |m: f64, &e| m.max(e));
+    assert!(resampled.elevations().iter().all(|&e| e >= 0.0 && e <= max));
+}
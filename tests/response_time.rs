@@ -0,0 +1,77 @@
+use fastlem::core::fields::{CELERITY, CHANNEL_STEEPNESS, RESPONSE_TIME};
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_response_time_and_steepness() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(50)
+        .generate()
+        .unwrap();
+
+    let network = terrain.network();
+    let response_times = terrain.fields().get(RESPONSE_TIME).unwrap();
+    let steepnesses = terrain.fields().get(CHANNEL_STEEPNESS).unwrap();
+    assert_eq!(response_times.len(), num);
+    assert_eq!(steepnesses.len(), num);
+
+    (0..num).for_each(|i| {
+        let j = network.receivers()[i];
+        assert!(response_times[i].is_finite() && response_times[i] >= 0.0);
+        assert!((0.0..=1.0).contains(&steepnesses[i]));
+        if j == i {
+            assert_eq!(response_times[i], 0.0);
+            assert_eq!(steepnesses[i], 0.0);
+        } else {
+            // the response time grows upstream
+            assert!(response_times[i] > response_times[j]);
+        }
+    });
+    assert!(steepnesses.contains(&1.0));
+}
+
+#[test]
+fn test_celerity() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![
+            TopographicalParameters::default().set_erodibility(2.0);
+            num
+        ])
+        .set_max_iteration(50)
+        .generate()
+        .unwrap();
+
+    let network = terrain.network();
+    let celerities = terrain.fields().get(CELERITY).unwrap();
+    assert_eq!(celerities.len(), num);
+    (0..num).for_each(|i| {
+        let j = network.receivers()[i];
+        if j == i {
+            assert_eq!(celerities[i], 0.0);
+        } else {
+            // `K * A^m` with the default exponent m = 0.5, growing downstream with the drainage area
+            let expected = 2.0 * network.drainage_areas()[i].sqrt();
+            assert!((celerities[i] - expected).abs() < expected * 1e-9);
+            if network.receivers()[j] != j {
+                assert!(celerities[j] > celerities[i]);
+            }
+        }
+    });
+}
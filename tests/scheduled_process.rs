@@ -0,0 +1,78 @@
+use std::sync::{Arc, Mutex};
+
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::units::Step;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::process::{Process, ScheduledProcess, SimulationState};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+/// Records the iterations in which it is applied.
+#[derive(Clone)]
+struct RecordingProcess {
+    steps: Arc<Mutex<Vec<Step>>>,
+}
+
+impl Process for RecordingProcess {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        self.steps.lock().unwrap().push(state.step);
+    }
+
+    fn max_time_step(&self, state: &SimulationState) -> Option<f64> {
+        Some(state.time_step / 2.0)
+    }
+}
+
+#[test]
+fn test_scheduled_process() {
+    let num = 500;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let run = |process: ScheduledProcess<RecordingProcess>, steps: Arc<Mutex<Vec<Step>>>| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![TopographicalParameters::default(); num])
+            .set_time_step(Some(1.0))
+            .set_max_iteration(10)
+            .add_process(process)
+            .generate()
+            .unwrap();
+        let steps = steps.lock().unwrap().clone();
+        steps
+    };
+    let recording = || {
+        let steps = Arc::new(Mutex::new(Vec::new()));
+        (
+            RecordingProcess {
+                steps: steps.clone(),
+            },
+            steps,
+        )
+    };
+
+    // the process is applied in two sub-steps in each active iteration
+    let (process, steps) = recording();
+    let scheduled = ScheduledProcess::new(process).set_active_steps(3..6);
+    assert!(!scheduled.is_active(2));
+    assert!(scheduled.is_active(3));
+    assert!(!scheduled.is_active(6));
+    assert_eq!(scheduled.name(), "recording");
+    assert_eq!(run(scheduled, steps), vec![3, 3, 4, 4, 5, 5]);
+
+    let (process, steps) = recording();
+    let scheduled = ScheduledProcess::new(process)
+        .set_active_steps(4..)
+        .set_activation(|step| step % 2 == 0);
+    assert_eq!(run(scheduled, steps), vec![4, 4, 6, 6, 8, 8, 10, 10]);
+
+    // all the iterations by default
+    let (process, steps) = recording();
+    assert_eq!(run(ScheduledProcess::new(process), steps).len(), 20);
+}
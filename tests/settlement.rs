@@ -0,0 +1,91 @@
+use fastlem::core::fields::SETTLEMENT_SUITABILITY;
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::settlement::SettlementSuitability2D;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_settlement_suitability() {
+    let num = 2000;
+    let model = TerrainModel2DBulider::from_random_sites(
+        num,
+        Site2D::new(0.0, 0.0),
+        Site2D::new(100.0, 100.0),
+    )
+    .build()
+    .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(20)
+        .generate()
+        .unwrap();
+    let elevations = terrain.elevations();
+    let sea_level = 1.0;
+
+    let suitability = SettlementSuitability2D::default().set_sea_level(sea_level);
+    let scores = suitability.scores(&model, &terrain);
+    assert_eq!(scores.len(), num);
+    (0..num).for_each(|i| {
+        assert!((0.0..=1.0).contains(&scores[i]));
+        if elevations[i] < sea_level {
+            assert_eq!(scores[i], 0.0);
+        }
+    });
+    assert!(scores.iter().any(|&score| score > 0.5));
+
+    // each factor alone
+    let only = |suitability: SettlementSuitability2D| {
+        suitability
+            .set_sea_level(sea_level)
+            .set_slope_weight(0.0)
+            .set_river_weight(0.0)
+            .set_coast_weight(0.0)
+            .set_flood_weight(0.0)
+    };
+    let flatness = only(SettlementSuitability2D::default())
+        .set_slope_weight(1.0)
+        .set_max_slope(0.5)
+        .scores(&model, &terrain);
+    (0..num)
+        .filter(|&i| elevations[i] >= sea_level)
+        .for_each(|i| {
+            let neighbors = model.graph().neighbors_of(i);
+            let slope = neighbors
+                .iter()
+                .map(|ja| (elevations[ja.0] - elevations[i]).abs() / ja.1)
+                .sum::<f64>()
+                / neighbors.len() as f64;
+            assert!((flatness[i] - (1.0 - slope / 0.5).max(0.0)).abs() < 1e-9);
+        });
+
+    let proximity = only(SettlementSuitability2D::default())
+        .set_river_weight(1.0)
+        .set_min_drainage_area(100.0)
+        .scores(&model, &terrain);
+    (0..num)
+        .filter(|&i| elevations[i] >= sea_level)
+        .for_each(|i| {
+            if terrain.network().drainage_areas()[i] >= 100.0 {
+                assert_eq!(proximity[i], 1.0);
+            } else {
+                assert!(proximity[i] < 1.0);
+            }
+        });
+
+    // without the field of the floodplain, all the land is safe
+    let safety = only(SettlementSuitability2D::default())
+        .set_flood_weight(1.0)
+        .scores(&model, &terrain);
+    (0..num)
+        .filter(|&i| elevations[i] >= sea_level)
+        .for_each(|i| assert_eq!(safety[i], 1.0));
+
+    let attached = suitability.attach(&model, terrain.clone());
+    assert_eq!(
+        attached.fields().get(SETTLEMENT_SUITABILITY).unwrap(),
+        scores
+    );
+}
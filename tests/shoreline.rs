@@ -0,0 +1,80 @@
+use fastlem::core::traits::Model;
+use fastlem::models::surface::builder::TerrainModel2DBulider;
+use fastlem::models::surface::preset::IslandPreset2D;
+use fastlem::models::surface::sites::Site2D;
+use fastlem::models::surface::terrain::Terrain2D;
+extern crate fastlem;
+
+fn radial_terrain(elevation: impl Fn(f64) -> f64) -> Terrain2D {
+    let model = TerrainModel2DBulider::from_random_sites(
+        4000,
+        Site2D::new(-1.0, -1.0),
+        Site2D::new(1.0, 1.0),
+    )
+    .relaxate_sites(1)
+    .unwrap()
+    .build()
+    .unwrap();
+    let elevations = model
+        .sites()
+        .iter()
+        .map(|site| elevation((site.x * site.x + site.y * site.y).sqrt()))
+        .collect::<Vec<_>>();
+    model.create_terrain_from_result(&elevations)
+}
+
+#[test]
+fn test_island_shoreline() {
+    // a cone whose coastline is the circle of radius 0.5
+    let terrain = radial_terrain(|r| 1.0 - r);
+    let shorelines = terrain.shorelines(0.5);
+    assert_eq!(shorelines.len(), 1);
+    let shoreline = &shorelines[0];
+    assert!(shoreline.is_island());
+    assert!(!shoreline.is_lake());
+    let area = std::f64::consts::PI * 0.25;
+    assert!((shoreline.signed_area() - area).abs() < area * 0.05);
+    assert!((shoreline.length() - std::f64::consts::PI).abs() < 0.1);
+    shoreline.points().iter().for_each(|point| {
+        let r = (point.x * point.x + point.y * point.y).sqrt();
+        assert!((r - 0.5).abs() < 0.05);
+    });
+}
+
+#[test]
+fn test_lake_shoreline() {
+    // a ring of land with a lake inside
+    let terrain = radial_terrain(|r| 1.0 - (r - 0.5).abs() * 4.0);
+    let shorelines = terrain.shorelines(0.0);
+    assert_eq!(shorelines.len(), 2);
+    let islands = shorelines.iter().filter(|s| s.is_island()).count();
+    let lakes = shorelines.iter().filter(|s| s.is_lake()).count();
+    assert_eq!((islands, lakes), (1, 1));
+    // the area of the land is the difference of the circles of radius 0.75 and 0.25
+    let area = shorelines.iter().map(|s| s.signed_area()).sum::<f64>();
+    let expected = std::f64::consts::PI * (0.75 * 0.75 - 0.25 * 0.25);
+    assert!((area - expected).abs() < expected * 0.05);
+}
+
+#[test]
+fn test_shorelines_closed_along_hull() {
+    // all the sites are above the sea level, so the coastline runs along the convex hull
+    let terrain = radial_terrain(|_| 1.0);
+    let shorelines = terrain.shorelines(0.0);
+    assert_eq!(shorelines.len(), 1);
+    assert!(shorelines[0].is_island());
+    assert!((shorelines[0].signed_area() - 4.0).abs() < 0.2);
+
+    // nothing is above the sea level
+    assert!(terrain.shorelines(2.0).is_empty());
+
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+    let shorelines = terrain.shorelines(0.0);
+    assert!(shorelines.iter().any(|s| s.is_island()));
+    shorelines
+        .iter()
+        .for_each(|s| assert!(s.points().len() >= 3 && s.signed_area() != 0.0));
+}
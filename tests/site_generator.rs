@@ -0,0 +1,88 @@
+use fastlem::core::traits::{Model, Site};
+use fastlem::models::surface::builder::TerrainModel2DBulider;
+use fastlem::models::surface::sites::{Site2D, SiteDistribution2D, SiteGenerator2D};
+extern crate fastlem;
+
+fn is_inside(site: &Site2D) -> bool {
+    site.x >= 0.0 && site.x <= 100.0 && site.y >= 0.0 && site.y <= 100.0
+}
+
+fn min_distance(sites: &[Site2D]) -> f64 {
+    (0..sites.len())
+        .flat_map(|i| (i + 1..sites.len()).map(move |j| (i, j)))
+        .map(|(i, j)| sites[i].distance(&sites[j]))
+        .fold(f64::MAX, f64::min)
+}
+
+#[test]
+fn test_site_distributions() {
+    let num = 1000;
+    let distributions = [
+        SiteDistribution2D::Uniform,
+        SiteDistribution2D::JitteredGrid { jitter: 0.3 },
+        SiteDistribution2D::HexLattice,
+        SiteDistribution2D::PoissonDisk,
+        SiteDistribution2D::RadialFalloff {
+            center: Site2D::new(50.0, 50.0),
+            falloff: 20.0,
+        },
+    ];
+    distributions.iter().for_each(|&distribution| {
+        let generator = SiteGenerator2D::default()
+            .set_num_sites(num)
+            .set_distribution(distribution)
+            .set_seed(7);
+        let sites = generator.generate();
+        assert!(sites.len() > num * 8 / 10 && sites.len() <= num * 12 / 10);
+        assert!(sites.iter().all(is_inside));
+
+        // deterministic
+        let again = generator.generate();
+        assert!(sites
+            .iter()
+            .zip(again.iter())
+            .all(|(a, b)| a.x == b.x && a.y == b.y));
+    });
+
+    // the lattices and the Poisson disk sampling keep the sites apart
+    let spacing = (100.0 * 100.0 / num as f64).sqrt();
+    let hex = SiteGenerator2D::default()
+        .set_distribution(SiteDistribution2D::HexLattice)
+        .generate();
+    assert!(min_distance(&hex) > spacing * 0.9);
+    let poisson = SiteGenerator2D::default()
+        .set_distribution(SiteDistribution2D::PoissonDisk)
+        .generate();
+    assert!(min_distance(&poisson) > spacing * 0.5);
+
+    // the falloff concentrates the sites around the center
+    let radial = SiteGenerator2D::default()
+        .set_distribution(SiteDistribution2D::RadialFalloff {
+            center: Site2D::new(50.0, 50.0),
+            falloff: 10.0,
+        })
+        .generate();
+    let near = radial
+        .iter()
+        .filter(|site| site.distance(&Site2D::new(50.0, 50.0)) < 25.0)
+        .count();
+    assert!(near > radial.len() / 2);
+
+    // different seeds generate different sites
+    let a = SiteGenerator2D::default().set_seed(1).generate();
+    let b = SiteGenerator2D::default().set_seed(2).generate();
+    assert!(a[0].x != b[0].x);
+}
+
+#[test]
+fn test_model_from_generator() {
+    let generator = SiteGenerator2D::default()
+        .set_num_sites(500)
+        .set_distribution(SiteDistribution2D::PoissonDisk);
+    let model = TerrainModel2DBulider::from_generator(&generator)
+        .add_edge_sites(None, None)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert!(model.num() > generator.generate().len());
+}
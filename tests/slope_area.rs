@@ -0,0 +1,45 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::slope_area::SlopeAreaAnalysis2D;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_slope_area_regression() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![
+            TopographicalParameters::default()
+                .set_erodibility(0.5)
+                .set_uplift_rate(2.0);
+            num
+        ])
+        .set_max_iteration(50)
+        .generate()
+        .unwrap();
+
+    // the steady state reproduces `S = (U / K) * A^-m` with m = 0.5
+    let analysis = SlopeAreaAnalysis2D::default().set_min_drainage_area(20.0);
+    let whole = analysis.analyze(&terrain).unwrap();
+    assert_eq!(whole.outlet, None);
+    assert!((whole.concavity - 0.5).abs() < 0.05);
+    assert!((whole.steepness / 4.0 - 1.0).abs() < 0.2);
+    assert!(whole.r_squared > 0.95);
+
+    let basins = analysis.analyze_basins(&terrain);
+    assert!(!basins.is_empty());
+    assert!(basins.iter().all(|basin| basin.outlet.is_some()
+        && basin.num_sites >= 10
+        && basin.num_sites <= whole.num_sites));
+    assert!(basins
+        .windows(2)
+        .all(|pair| pair[0].outlet < pair[1].outlet));
+    let largest = basins.iter().max_by_key(|basin| basin.num_sites).unwrap();
+    assert!((largest.concavity - 0.5).abs() < 0.1);
+}
@@ -0,0 +1,60 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::events::SimulationEvent;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::snapshot::SnapshotSequence;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_snapshot_sequence() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let quantum = 1e-4;
+    let mut snapshots = SnapshotSequence::new(quantum);
+    let mut originals = Vec::new();
+    TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(0.1))
+        .set_max_iteration(30)
+        .set_snapshot_interval(Some(3))
+        .generate_with_events(|event| {
+            if let SimulationEvent::SnapshotReady { step, elevations } = event {
+                snapshots.push(step, &elevations);
+                originals.push((step, elevations));
+            }
+        })
+        .unwrap();
+
+    assert_eq!(snapshots.len(), 10);
+    assert!(snapshots.encoded_size() < num * 8 * snapshots.len() / 2);
+
+    let check = |snapshots: &SnapshotSequence| {
+        snapshots.iter().zip(originals.iter()).for_each(
+            |((step, decoded), (original_step, original))| {
+                assert_eq!(step, *original_step);
+                decoded.iter().zip(original.iter()).for_each(|(a, b)| {
+                    assert!((a - b).abs() <= quantum / 2.0 + 1e-12);
+                });
+            },
+        );
+    };
+    check(&snapshots);
+    assert_eq!(snapshots.decode(4), snapshots.iter().nth(4).map(|(_, e)| e));
+
+    let mut buffer = Vec::new();
+    snapshots.write_to(&mut buffer).unwrap();
+    let mut restored = SnapshotSequence::read_from(buffer.as_slice()).unwrap();
+    check(&restored);
+
+    // the restored sequence continues the deltas
+    let last = originals.last().unwrap().1.clone();
+    snapshots.push(31, &last);
+    restored.push(31, &last);
+    assert_eq!(snapshots.decode(10), restored.decode(10));
+}
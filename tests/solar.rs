@@ -0,0 +1,57 @@
+use fastlem::core::fields::SOLAR_EXPOSURE;
+use fastlem::core::traits::Model;
+use fastlem::models::surface::preset::IslandPreset2D;
+use fastlem::models::surface::solar::SolarExposure2D;
+extern crate fastlem;
+
+#[test]
+fn test_solar_exposure() {
+    let (model, _) = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .build()
+        .unwrap();
+    // a plane rising to the north, so that all the sites face the south
+    let elevations = model
+        .sites()
+        .iter()
+        .map(|site| site.y * 0.5)
+        .collect::<Vec<_>>();
+    let terrain = model.create_terrain_from_result(&elevations);
+    let flat = model.create_terrain_from_result(&vec![0.0; elevations.len()]);
+    // the gradients are not exact near the boundary of the terrain
+    let interior = model
+        .sites()
+        .iter()
+        .map(|site| site.x > 5.0 && site.x < 95.0 && site.y > 5.0 && site.y < 95.0)
+        .collect::<Vec<_>>();
+    let compare = |a: &[f64], b: &[f64], f: fn(f64, f64) -> bool| {
+        (0..a.len()).filter(|&i| interior[i]).all(|i| f(a[i], b[i]))
+    };
+
+    let solar = SolarExposure2D::default();
+    let slopes_and_aspects = solar.slopes_and_aspects(&terrain);
+    (0..slopes_and_aspects.len())
+        .filter(|&i| interior[i])
+        .for_each(|i| {
+            let (slope, aspect) = slopes_and_aspects[i];
+            assert!((slope - 0.5_f64.atan()).abs() < 1e-6);
+            assert!((aspect - std::f64::consts::PI).abs() < 1e-6);
+        });
+
+    // the slopes facing the sun receive more than the flat surface in the northern hemisphere, and less in the southern one
+    let north = solar.exposures(&terrain);
+    let north_flat = solar.exposures(&flat);
+    assert!(compare(&north, &north_flat, |s, f| s > f));
+    let south = solar.clone().set_latitude(-45.0).exposures(&terrain);
+    let south_flat = solar.clone().set_latitude(-45.0).exposures(&flat);
+    assert!(compare(&south, &south_flat, |s, f| s < f));
+    // the flat surface on the equator is the reference
+    let equator = solar.clone().set_latitude(0.0).exposures(&flat);
+    assert!(equator.iter().all(|&e| (e - 1.0).abs() < 1e-9));
+
+    let terrain = solar.attach(terrain);
+    assert_eq!(
+        terrain.fields().get(SOLAR_EXPOSURE).unwrap(),
+        north.as_slice()
+    );
+}
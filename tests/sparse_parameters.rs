@@ -0,0 +1,44 @@
+use fastlem::core::parameters::{SparseParameters, TopographicalParameters};
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_sparse_parameters() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let default = TopographicalParameters::default().set_erodibility(0.5);
+    let resistant = TopographicalParameters::default().set_erodibility(0.1);
+    let sparse = SparseParameters::new(default.clone())
+        .set_overrides((0..model.num()).step_by(10).map(|i| (i, resistant.clone())))
+        .mark_outlets(&model.default_outlets()[..3], 0.0);
+    let num_overrides = sparse.num_overrides();
+    assert!(num_overrides >= model.num() / 10);
+
+    let parameters = sparse.to_parameter_set(model.num());
+    assert_eq!(parameters.len(), model.num());
+    assert_eq!(parameters.outlets().len(), 3);
+
+    let generator = TerrainGenerator::default()
+        .set_model(model)
+        .set_max_iteration(20);
+    let from_sparse = generator
+        .clone()
+        .set_parameter_field(sparse.clone())
+        .generate()
+        .unwrap();
+    let from_vec = generator
+        .set_parameters(parameters.into())
+        .generate()
+        .unwrap();
+    assert_eq!(from_sparse.elevations(), from_vec.elevations());
+
+    let removed = sparse.remove_override(10).remove_override(10);
+    assert_eq!(removed.num_overrides(), num_overrides - 1);
+}
@@ -0,0 +1,77 @@
+use fastlem::core::traits::Model;
+use fastlem::models::surface::builder::TerrainModel2DBulider;
+use fastlem::models::surface::preset::IslandPreset2D;
+use fastlem::models::surface::raster::Rasterizer2D;
+use fastlem::models::surface::sites::Site2D;
+use fastlem::models::surface::splat::{SplatGenerator2D, NUM_SPLAT_CHANNELS};
+extern crate fastlem;
+
+#[test]
+fn test_splat_slopes() {
+    // an inclined plane with the gradient 0.5
+    let model = TerrainModel2DBulider::from_random_sites(
+        500,
+        Site2D::new(0.0, 0.0),
+        Site2D::new(10.0, 10.0),
+    )
+    .build()
+    .unwrap();
+    let elevations = model
+        .sites()
+        .iter()
+        .map(|site| 0.3 * site.x + 0.4 * site.y + 1.0)
+        .collect::<Vec<_>>();
+    let terrain = model.create_terrain_from_result(&elevations);
+
+    let generator = SplatGenerator2D::default()
+        .set_min_rock_slope(0.3)
+        .set_max_rock_slope(0.8);
+    generator
+        .slopes(&terrain)
+        .iter()
+        .for_each(|&slope| assert!((slope - 0.5).abs() < 1e-9));
+    generator.site_weights(&terrain).iter().for_each(|w| {
+        assert!((w[0] - 0.4).abs() < 1e-9);
+        assert_eq!(w[1], 0.0);
+        assert_eq!(w[2], 0.0);
+        assert!((w[3] - 0.6).abs() < 1e-9);
+    });
+}
+
+#[test]
+fn test_splat_map() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+    let rasterizer = Rasterizer2D::default().set_size(64, 48);
+    let generator = SplatGenerator2D::default()
+        .set_min_drainage_area(50.0)
+        .set_sea_level(0.01);
+    let splat = generator.generate(&rasterizer, &terrain);
+    assert_eq!((splat.width(), splat.height()), (64, 48));
+    assert_eq!(splat.weights().len(), 64 * 48);
+
+    splat.weights().iter().flatten().for_each(|weights| {
+        assert!(weights.iter().all(|&w| (0.0..=1.0).contains(&w)));
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    });
+    // the sea floor is covered by the sediment, and the wetlands follow the channels
+    (0..NUM_SPLAT_CHANNELS).filter(|&c| c != 0).for_each(|c| {
+        assert!(splat
+            .weights()
+            .iter()
+            .flatten()
+            .any(|weights| weights[c] > 0.5));
+    });
+
+    // the image starts from the top row
+    let rgba = splat.to_rgba8();
+    assert_eq!(rgba.len(), 64 * 48 * 4);
+    let top_left = splat
+        .get(0, 47)
+        .map(|weights| weights.map(|w| (w * 255.0).round() as u8))
+        .unwrap_or_default();
+    assert_eq!(&rgba[0..4], &top_left);
+    assert_eq!(splat.get(64, 0), None);
+}
@@ -0,0 +1,86 @@
+use std::sync::{Arc, Mutex};
+
+use fastlem::core::fields::SOIL_THICKNESS;
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::process::{Process, SimulationState, SubSteppedProcess};
+use fastlem::lem::processes::regolith::RegolithProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+/// Records the time step of each call.
+struct RecordingProcess {
+    time_steps: Arc<Mutex<Vec<f64>>>,
+}
+
+impl Process for RecordingProcess {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    fn apply(&self, state: &mut SimulationState) {
+        self.time_steps.lock().unwrap().push(state.time_step);
+    }
+}
+
+#[test]
+fn test_sub_steps() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let generator = || {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![TopographicalParameters::default(); num])
+            .set_time_step(Some(2.0))
+            .set_max_iteration(10)
+    };
+
+    let time_steps = Arc::new(Mutex::new(Vec::new()));
+    let sub_stepped_time_steps = Arc::new(Mutex::new(Vec::new()));
+    generator()
+        .add_process(RecordingProcess {
+            time_steps: time_steps.clone(),
+        })
+        .add_process(
+            SubSteppedProcess::new(RecordingProcess {
+                time_steps: sub_stepped_time_steps.clone(),
+            })
+            .set_num_sub_steps(4),
+        )
+        .generate()
+        .unwrap();
+    let time_steps = time_steps.lock().unwrap();
+    let sub_stepped_time_steps = sub_stepped_time_steps.lock().unwrap();
+    assert!(!time_steps.is_empty());
+    assert!(time_steps.iter().all(|&dt| dt == 2.0));
+    assert_eq!(sub_stepped_time_steps.len(), time_steps.len() * 4);
+    assert!(sub_stepped_time_steps.iter().all(|&dt| dt == 0.5));
+
+    // the limit of the sub-step is respected
+    let limited_time_steps = Arc::new(Mutex::new(Vec::new()));
+    generator()
+        .add_process(
+            SubSteppedProcess::new(RecordingProcess {
+                time_steps: limited_time_steps.clone(),
+            })
+            .set_max_time_step(Some(0.3)),
+        )
+        .generate()
+        .unwrap();
+    let limited_time_steps = limited_time_steps.lock().unwrap();
+    assert_eq!(limited_time_steps.len(), time_steps.len() * 7);
+    assert!(limited_time_steps.iter().all(|&dt| dt <= 0.3));
+
+    // the fast creep is stable with its own sub-steps
+    let terrain = generator()
+        .add_process(RegolithProcess::default().set_diffusivity(100.0))
+        .generate()
+        .unwrap();
+    let soil = terrain.fields().get(SOIL_THICKNESS).unwrap();
+    assert!(soil.iter().all(|h| h.is_finite() && *h >= 0.0));
+    assert!(terrain.elevations().iter().all(|e| e.is_finite()));
+}
@@ -0,0 +1,40 @@
+use fastlem::core::fields::SURFACE_AGE;
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::surface_age::SurfaceAgeProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_surface_age() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let generate = |min_change: f64| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![TopographicalParameters::default(); num])
+            .set_time_step(Some(1.0))
+            .set_max_iteration(50)
+            .add_process(SurfaceAgeProcess::default().set_min_change(min_change))
+            .generate()
+            .unwrap()
+    };
+
+    // the surfaces are never renewed
+    let terrain = generate(f64::INFINITY);
+    let ages = terrain.fields().get(SURFACE_AGE).unwrap();
+    assert_eq!(ages.len(), num);
+    assert!(ages[0] > 0.0);
+    assert!(ages.iter().all(|age| *age == ages[0]));
+
+    // the surfaces are renewed by the erosion
+    let terrain = generate(1e-3);
+    let renewed = terrain.fields().get(SURFACE_AGE).unwrap();
+    assert!(renewed.iter().all(|age| *age >= 0.0 && *age <= ages[0]));
+    assert!(renewed.iter().any(|age| *age < ages[0]));
+}
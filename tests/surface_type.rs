@@ -0,0 +1,125 @@
+use fastlem::core::parameters::ParameterSet;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::raster::Rasterizer2D;
+use fastlem::models::surface::surface_type::{SurfaceClassifier2D, SurfaceType};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_surface_type_code() {
+    [
+        SurfaceType::Land,
+        SurfaceType::River,
+        SurfaceType::Lake,
+        SurfaceType::Ocean,
+        SurfaceType::Ice,
+    ]
+    .iter()
+    .for_each(|&surface_type| {
+        assert_eq!(
+            SurfaceType::from_code(surface_type.code()),
+            Some(surface_type)
+        );
+    });
+    assert_eq!(SurfaceType::from_code(5), None);
+}
+
+#[test]
+fn test_surface_classification() {
+    // a crater whose rim is at the elevation 30.0 around the center, sloping down into the ocean outside
+    let num = 2000;
+    let model = TerrainModel2DBulider::from_random_sites(
+        num,
+        Site2D::new(0.0, 0.0),
+        Site2D::new(100.0, 100.0),
+    )
+    .build()
+    .unwrap();
+    let center = Site2D::new(50.0, 50.0);
+    let elevations = model
+        .sites()
+        .iter()
+        .map(|site| {
+            let r = ((site.x - center.x).powi(2) + (site.y - center.y).powi(2)).sqrt();
+            if r <= 25.0 {
+                5.0 + r
+            } else {
+                55.0 - r
+            }
+        })
+        .collect::<Vec<_>>();
+    let parameters = ParameterSet::new(num)
+        .freeze(&(0..num).collect::<Vec<_>>(), &elevations)
+        .unwrap();
+    let terrain = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(parameters.into())
+        .set_max_iteration(1)
+        .generate()
+        .unwrap();
+    assert!(!terrain.network().is_empty());
+
+    let classifier = SurfaceClassifier2D::default().set_min_drainage_area(50.0);
+    let levels = classifier.water_levels(&terrain);
+    let types = classifier.classify(&terrain);
+    assert_eq!(types.len(), num);
+
+    // the crater is filled with water up to its rim
+    let bottom = model.nearest_site(&center).unwrap();
+    assert_eq!(types[bottom], SurfaceType::Lake);
+    assert!(levels[bottom] > 25.0 && levels[bottom] <= 30.0);
+    (0..num).for_each(|i| {
+        let elevation = terrain.elevations()[i];
+        assert!(levels[i] >= elevation);
+        match types[i] {
+            SurfaceType::Ocean => assert!(elevation < 0.0),
+            SurfaceType::Lake => assert!(levels[i] > elevation),
+            SurfaceType::River => {
+                assert!(terrain.network().drainage_areas()[i] >= 50.0);
+                assert_eq!(levels[i], elevation);
+            }
+            SurfaceType::Land => assert!(elevation >= 0.0),
+            SurfaceType::Ice => unreachable!(),
+        }
+    });
+    [
+        SurfaceType::Land,
+        SurfaceType::River,
+        SurfaceType::Lake,
+        SurfaceType::Ocean,
+    ]
+    .iter()
+    .for_each(|surface_type| assert!(types.contains(surface_type)));
+
+    // the rim above the snowline is covered by ice
+    let types = classifier
+        .clone()
+        .set_snowline(Some(28.0))
+        .classify(&terrain);
+    (0..num).for_each(|i| {
+        let elevation = terrain.elevations()[i];
+        assert_eq!(types[i] == SurfaceType::Ice, elevation >= 28.0);
+    });
+
+    // the rasterized classification is the codes of the types
+    let raster = Rasterizer2D::default()
+        .set_size(64, 64)
+        .rasterize_surface_types(&terrain, &classifier);
+    assert_eq!(raster.values().len(), 64 * 64);
+    assert!(raster
+        .values()
+        .iter()
+        .flatten()
+        .all(|&code| { code.fract() == 0.0 && SurfaceType::from_code(code as u8).is_some() }));
+    assert!(raster
+        .values()
+        .iter()
+        .any(|&code| code == Some(SurfaceType::Lake.code() as f64)));
+
+    // without the drainage network, there are no lakes and rivers
+    let types = classifier.classify(&model.create_terrain_from_result(&elevations));
+    assert!(types
+        .iter()
+        .all(|&t| t == SurfaceType::Land || t == SurfaceType::Ocean));
+}
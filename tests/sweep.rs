@@ -0,0 +1,49 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::sweep::{ParameterSweep, SweepAxis};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_parameter_sweep() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let generator = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(20);
+
+    let sweep = ParameterSweep::new(generator)
+        .add_axis(SweepAxis::erodibility_factor(vec![0.5, 2.0]))
+        .add_axis(SweepAxis::uplift_rate_factor(vec![1.0, 2.0, 4.0]));
+    assert_eq!(
+        sweep.combinations(),
+        vec![
+            vec![0.5, 1.0],
+            vec![0.5, 2.0],
+            vec![0.5, 4.0],
+            vec![2.0, 1.0],
+            vec![2.0, 2.0],
+            vec![2.0, 4.0],
+        ]
+    );
+
+    let runs = sweep.run().unwrap();
+    assert_eq!(runs.len(), 6);
+
+    // the relief is larger with a lower erodibility and a higher uplift rate
+    assert!(runs[0].summary.relief > runs[3].summary.relief);
+    assert!(runs[2].summary.relief > runs[0].summary.relief);
+
+    // the results are the same in parallel
+    let parallel_runs = sweep.set_num_threads(4).run().unwrap();
+    runs.iter().zip(parallel_runs.iter()).for_each(|(a, b)| {
+        assert_eq!(a.values, b.values);
+        assert_eq!(a.summary, b.summary);
+        assert_eq!(a.terrain.elevations(), b.terrain.elevations());
+    });
+}
@@ -0,0 +1,41 @@
+use fastlem::models::surface::preset::IslandPreset2D;
+use fastlem::models::surface::tiles::TileExporter2D;
+extern crate fastlem;
+
+#[test]
+fn test_tiled_mesh_export() {
+    let terrain = IslandPreset2D::default()
+        .set_num_sites(1000)
+        .generate()
+        .unwrap();
+
+    let resolution = 8;
+    let tiles = TileExporter2D::default()
+        .set_num_tiles(3, 2)
+        .set_resolution(resolution)
+        .export(&terrain);
+    assert_eq!(tiles.len(), 6);
+
+    let row = resolution + 1;
+    tiles.iter().for_each(|tile| {
+        assert_eq!(tile.vertices.len(), row * row);
+        assert!(tile.indices.len() <= resolution * resolution * 6);
+        assert!(tile
+            .vertices
+            .iter()
+            .all(|v| v[0] >= tile.bound_min[0] && v[0] <= tile.bound_max[0]));
+
+        // the right border matches the left border of the next tile
+        if let Some(right) = tiles.iter().find(|t| t.x == tile.x + 1 && t.y == tile.y) {
+            (0..row).for_each(|j| {
+                assert_eq!(tile.vertices[j * row + resolution], right.vertices[j * row]);
+            });
+        }
+        // the top border matches the bottom border of the next tile
+        if let Some(top) = tiles.iter().find(|t| t.x == tile.x && t.y == tile.y + 1) {
+            (0..row).for_each(|i| {
+                assert_eq!(tile.vertices[resolution * row + i], top.vertices[i]);
+            });
+        }
+    });
+}
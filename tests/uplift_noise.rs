@@ -0,0 +1,51 @@
+use fastlem::core::fields::UPLIFT_PERTURBATION;
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::uplift_noise::UpliftNoiseProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_uplift_noise() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    let generate = |process: Option<UpliftNoiseProcess>| {
+        let generator = TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![TopographicalParameters::default(); num])
+            .set_time_step(Some(1.0))
+            .set_max_iteration(20);
+        match process {
+            Some(process) => generator.add_process(process),
+            None => generator,
+        }
+        .generate()
+        .unwrap()
+    };
+
+    let noisy = generate(Some(UpliftNoiseProcess::default().set_amplitude(0.5)));
+    let perturbation = noisy.fields().get(UPLIFT_PERTURBATION).unwrap();
+    assert!(perturbation.iter().all(|p| p.is_finite() && *p >= -1.0));
+    assert!(perturbation.iter().any(|p| *p != 0.0));
+    let mean = perturbation.iter().sum::<f64>() / num as f64;
+    assert!(mean.abs() < 0.05);
+
+    // the noise is reproducible from the seed
+    let again = generate(Some(UpliftNoiseProcess::default().set_amplitude(0.5)));
+    assert_eq!(noisy.elevations(), again.elevations());
+    let reseeded = generate(Some(
+        UpliftNoiseProcess::default().set_amplitude(0.5).set_seed(1),
+    ));
+    assert_ne!(noisy.elevations(), reseeded.elevations());
+
+    // no perturbation without the amplitude
+    let plain = generate(None);
+    let silent = generate(Some(UpliftNoiseProcess::default().set_amplitude(0.0)));
+    assert_eq!(plain.elevations(), silent.elevations());
+    assert_ne!(plain.elevations(), noisy.elevations());
+}
@@ -0,0 +1,66 @@
+use fastlem::core::fields::{DIFFUSIVITY_FACTOR, VEGETATION_COVER};
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::vegetation::VegetationProcess;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_vegetation() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // the western half is wet and vegetated, and the eastern half is dry and bare
+    let is_vegetated = model
+        .sites()
+        .iter()
+        .map(|site| site.x < 50.0)
+        .collect::<Vec<_>>();
+    let precipitation = is_vegetated
+        .iter()
+        .map(|&vegetated| if vegetated { 100.0 } else { 0.0 })
+        .collect::<Vec<_>>();
+
+    let generator = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_max_iteration(50);
+    let bare = generator.clone().generate().unwrap();
+    let terrain = generator
+        .add_process(VegetationProcess::default().set_precipitation(Some(precipitation)))
+        .generate()
+        .unwrap();
+
+    let cover = terrain.fields().get(VEGETATION_COVER).unwrap();
+    (0..num).for_each(|i| {
+        if is_vegetated[i] {
+            assert!(cover[i] > 0.9);
+        } else {
+            assert_eq!(cover[i], 0.0);
+        }
+    });
+    let diffusivity_factor = terrain.fields().get(DIFFUSIVITY_FACTOR).unwrap();
+    assert!((0..num).all(|i| (diffusivity_factor[i] - (1.0 - 0.5 * cover[i])).abs() < 1e-12));
+
+    // the vegetation reduces the erodibility, so the vegetated sites are eroded less and stand higher than without it
+    let mean_ratio = |vegetated: bool| {
+        let ratios = (0..num)
+            .filter(|&i| is_vegetated[i] == vegetated && bare.elevations()[i] > 0.0)
+            .map(|i| terrain.elevations()[i] / bare.elevations()[i])
+            .collect::<Vec<_>>();
+        ratios.iter().sum::<f64>() / ratios.len() as f64
+    };
+    let vegetated_ratio = mean_ratio(true);
+    let bare_ratio = mean_ratio(false);
+    assert!(
+        vegetated_ratio > bare_ratio * 1.2,
+        "vegetated: {}, bare: {}",
+        vegetated_ratio,
+        bare_ratio
+    );
+}
@@ -0,0 +1,112 @@
+use fastlem::core::fields::LAVA_FLOW;
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::{Model, Site};
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::volcano::{Eruption, VolcanoProcess};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_volcano() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+    let center = Site2D { x: 50.0, y: 50.0 };
+    let vent = (0..num)
+        .min_by(|&a, &b| {
+            model.sites()[a]
+                .distance(&center)
+                .total_cmp(&model.sites()[b].distance(&center))
+        })
+        .unwrap();
+
+    let volume = 1000.0;
+    let flank_slope = std::f64::consts::PI / 12.0;
+    let generator = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(2);
+    let original = generator.clone().generate().unwrap();
+    // another vent on the coast, where the outlet is kept as the base level
+    let coastal_vent = model.default_outlets()[0];
+    let terrain = generator
+        .clone()
+        .add_process(
+            VolcanoProcess::default()
+                .add_eruption(
+                    Eruption::new(2, volume)
+                        .set_site(Some(vent))
+                        .set_flank_slope(flank_slope)
+                        .set_lava_length(20.0),
+                )
+                .add_eruption(Eruption::new(2, volume).set_site(Some(coastal_vent))),
+        )
+        .generate()
+        .unwrap();
+
+    // the cone of the volume stands on the vent after the last iteration (the steps start from 1)
+    // V = π r^2 H / 3 where r = H / tan(slope)
+    let gradient = flank_slope.tan();
+    let height = (3.0 * volume * gradient * gradient / std::f64::consts::PI).cbrt();
+    let rise = |i: usize| terrain.elevations()[i] - original.elevations()[i];
+    assert!((rise(vent) - height).abs() < height * 1e-9);
+    (0..num)
+        .filter(|&i| model.sites()[i].distance(&model.sites()[coastal_vent]) > 30.0)
+        .for_each(|i| {
+            let distance = model.sites()[i].distance(&model.sites()[vent]);
+            assert!(rise(i) >= -1e-9 && rise(i) <= (height - gradient * distance).max(0.0) + 1e-9);
+        });
+    model
+        .default_outlets()
+        .iter()
+        .for_each(|&i| assert_eq!(rise(i), 0.0));
+    assert!(model
+        .graph()
+        .neighbors_of(coastal_vent)
+        .iter()
+        .any(|ja| rise(ja.0) > 0.0));
+
+    // the lava runs down from the vent and is hardened, so it is eroded less than the soft lava
+    let lava = terrain.fields().get(LAVA_FLOW).unwrap();
+    assert_eq!(lava[vent], 1.0);
+    let covered = (0..num).filter(|&i| lava[i] > 0.0).collect::<Vec<_>>();
+    assert!(covered.len() > 1);
+    let hardened = generator
+        .clone()
+        .set_max_iteration(20)
+        .add_process(
+            VolcanoProcess::default()
+                .add_eruption(
+                    Eruption::new(1, volume)
+                        .set_site(Some(vent))
+                        .set_lava_length(20.0),
+                )
+                .set_lava_erodibility_factor(0.25),
+        )
+        .generate()
+        .unwrap();
+    let soft = generator
+        .set_max_iteration(20)
+        .add_process(
+            VolcanoProcess::default()
+                .add_eruption(
+                    Eruption::new(1, volume)
+                        .set_site(Some(vent))
+                        .set_lava_length(20.0),
+                )
+                .set_lava_erodibility_factor(1.0),
+        )
+        .generate()
+        .unwrap();
+    let covered = (0..num)
+        .filter(|&i| hardened.fields().get(LAVA_FLOW).unwrap()[i] > 0.0)
+        .collect::<Vec<_>>();
+    let mean = |elevations: &[f64]| {
+        covered.iter().map(|&i| elevations[i]).sum::<f64>() / covered.len() as f64
+    };
+    assert!(mean(hardened.elevations()) > mean(soft.elevations()));
+}
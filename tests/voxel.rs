@@ -0,0 +1,54 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::Model;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::regolith::RegolithProcess;
+use fastlem::models::surface::voxel::VoxelMaterial;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_voxelize() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .add_edge_sites(None, None)
+        .unwrap()
+        .build()
+        .unwrap();
+    let num = model.sites().len();
+
+    let terrain = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(30)
+        .add_process(RegolithProcess::default())
+        .generate()
+        .unwrap();
+
+    let cell_size = 0.5;
+    let max_height = 40;
+    let voxels = terrain.voxelize(cell_size, max_height);
+    assert!(voxels.width >= 200 && voxels.depth >= 200);
+    assert_eq!(voxels.heights.len(), voxels.width * voxels.depth);
+    assert!(voxels.heights.iter().all(|&h| h <= max_height));
+    assert!(voxels.heights.iter().any(|&h| h > 0));
+
+    let layers = voxels.layers.as_ref().unwrap();
+    layers
+        .iter()
+        .zip(voxels.heights.iter())
+        .for_each(|(layers, &height)| {
+            assert_eq!(layers.iter().map(|(_, t)| t).sum::<u32>(), height);
+        });
+
+    let (x, y) = (100, 100);
+    let height = voxels.height(x, y).unwrap();
+    assert_eq!(voxels.material(x, y, height), None);
+    assert_eq!(voxels.material(x, y, 0), Some(VoxelMaterial::Bedrock));
+    assert_eq!(
+        voxels.material(x, y, height - 1),
+        Some(layers[y * voxels.width + x][0].0)
+    );
+}
@@ -0,0 +1,92 @@
+use fastlem::core::fields::{BASEFLOW, DISCHARGE, GROUNDWATER_FLOW, INFILTRATION};
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_infiltration_and_evaporation() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .relaxate_sites(1)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let generate = |parameters: TopographicalParameters| {
+        TerrainGenerator::default()
+            .set_model(model.clone())
+            .set_parameters(vec![parameters; num])
+            .set_max_iteration(50)
+            .generate()
+            .unwrap()
+    };
+    let humid = generate(TopographicalParameters::default());
+    assert!(humid.fields().get(DISCHARGE).is_none());
+
+    let arid = generate(
+        TopographicalParameters::default()
+            .set_infiltration(0.3)
+            .set_evaporation(0.5),
+    );
+    let discharges = arid.fields().get(DISCHARGE).unwrap();
+    let infiltrations = arid.fields().get(INFILTRATION).unwrap();
+    let network = arid.network();
+    (0..num).for_each(|i| {
+        assert!(discharges[i] > 0.0);
+        assert!(discharges[i] <= network.drainage_areas()[i] + 1e-9);
+        assert!(infiltrations[i] >= 0.0);
+    });
+    // the rivers shrink downstream where the losses exceed the local runoff
+    assert!((0..num).any(|i| {
+        let j = network.receivers()[i];
+        j != i && discharges[j] < discharges[i]
+    }));
+    // the smaller discharge erodes less
+    let mean = |elevations: &[f64]| elevations.iter().sum::<f64>() / elevations.len() as f64;
+    assert!(mean(arid.elevations()) > mean(humid.elevations()));
+}
+
+#[test]
+fn test_groundwater_baseflow() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .relaxate_sites(1)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let generator = TerrainGenerator::default()
+        .set_model(model)
+        .set_parameters(vec![
+            TopographicalParameters::default().set_infiltration(0.5);
+            num
+        ])
+        .set_max_iteration(50);
+    let without = generator.clone().generate().unwrap();
+    assert!(without.fields().get(BASEFLOW).is_none());
+
+    let terrain = generator
+        .set_groundwater_transmissivity(Some(10.0))
+        .generate()
+        .unwrap();
+    let infiltrations = terrain.fields().get(INFILTRATION).unwrap();
+    let baseflows = terrain.fields().get(BASEFLOW).unwrap();
+    let groundwater_flows = terrain.fields().get(GROUNDWATER_FLOW).unwrap();
+    let network = terrain.network();
+
+    // all the infiltrated water emerges somewhere, at the latest at the outlets
+    let total_infiltration = infiltrations.iter().sum::<f64>();
+    let total_baseflow = baseflows.iter().sum::<f64>();
+    assert!(total_infiltration > 0.0);
+    assert!((total_infiltration - total_baseflow).abs() < 1e-6 * total_infiltration);
+    // there are springs above the outlets
+    assert!((0..num).any(|i| baseflows[i] > 0.0 && !network.is_outlet(i)));
+    assert!((0..num)
+        .filter(|&i| network.is_outlet(i))
+        .all(|i| groundwater_flows[i] == 0.0));
+}
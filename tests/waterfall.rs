@@ -0,0 +1,93 @@
+use fastlem::core::parameters::TopographicalParameters;
+use fastlem::core::traits::{Model, Site};
+use fastlem::lem::generator::TerrainGenerator;
+use fastlem::lem::processes::base_level::BaseLevelProcess;
+use fastlem::models::surface::waterfall::{Waterfall2D, WaterfallDetector2D, WaterfallKind};
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_waterfalls_at_knickpoint() {
+    let num = 2000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // the base level drops suddenly in the last iteration, leaving a knickpoint at the mouth of every channel
+    let (drop, num_iterations) = (20.0, 30);
+    let generator = TerrainGenerator::default()
+        .set_model(model.clone())
+        .set_parameters(vec![TopographicalParameters::default(); num])
+        .set_time_step(Some(1.0))
+        .set_max_iteration(num_iterations);
+    let stable = generator.clone().generate().unwrap();
+    let dropped = generator
+        .add_process(
+            BaseLevelProcess::default()
+                .set_schedule(vec![(num_iterations - 1, 0.0), (num_iterations, -drop)]),
+        )
+        .generate()
+        .unwrap();
+
+    let detector = WaterfallDetector2D::default();
+    let waterfalls = detector.detect(&dropped);
+    let sites = dropped.sites();
+    let elevations = dropped.elevations();
+    let receivers = dropped.network().receivers();
+    let drainage_areas = dropped.network().drainage_areas();
+
+    // each segment runs down the receivers from its top to its bottom
+    waterfalls.iter().for_each(|waterfall| {
+        assert_eq!(waterfall.top_position.distance(&sites[waterfall.top]), 0.0);
+        assert_eq!(
+            waterfall.bottom_position.distance(&sites[waterfall.bottom]),
+            0.0
+        );
+        assert_eq!(
+            waterfall.drop,
+            elevations[waterfall.top] - elevations[waterfall.bottom]
+        );
+        assert_eq!(waterfall.discharge, drainage_areas[waterfall.top]);
+        assert!(waterfall.discharge >= 100.0);
+        let mut i = waterfall.top;
+        let mut length = 0.0;
+        while i != waterfall.bottom {
+            let j = receivers[i];
+            assert_ne!(i, j);
+            length += sites[i].distance(&sites[j]);
+            i = j;
+        }
+        assert!((waterfall.length - length).abs() < 1e-9);
+    });
+
+    // the channels falling into the dropped outlets end in waterfalls
+    let outlets = model.default_outlets();
+    let mouths = (0..num)
+        .filter(|&i| {
+            receivers[i] != i && outlets.contains(&receivers[i]) && drainage_areas[i] >= 100.0
+        })
+        .collect::<Vec<_>>();
+    assert!(!mouths.is_empty());
+    mouths.iter().for_each(|&i| {
+        let waterfall = waterfalls
+            .iter()
+            .find(|waterfall| waterfall.bottom == receivers[i])
+            .unwrap();
+        assert_eq!(waterfall.kind, WaterfallKind::Waterfall);
+        assert!(waterfall.drop >= drop);
+    });
+
+    // without the drop the mouths are not as steep
+    let stable_waterfalls = detector.detect(&stable);
+    let num_falls_at_outlets = |waterfalls: &[Waterfall2D]| {
+        waterfalls
+            .iter()
+            .filter(|waterfall| {
+                waterfall.kind == WaterfallKind::Waterfall && outlets.contains(&waterfall.bottom)
+            })
+            .count()
+    };
+    assert!(num_falls_at_outlets(&stable_waterfalls) < num_falls_at_outlets(&waterfalls));
+}
@@ -0,0 +1,82 @@
+use fastlem::core::traits::Model;
+use fastlem::models::surface::builder::ModelBuilderError;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+fn count_left(sites: &[Site2D]) -> usize {
+    sites.iter().filter(|site| site.x < 50.0).count()
+}
+
+fn is_near_center(site: &Site2D) -> bool {
+    (site.x - 50.0).powi(2) + (site.y - 50.0).powi(2) < 15.0 * 15.0
+}
+
+#[test]
+fn test_weighted_relaxation() {
+    let num = 1000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let builder = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max);
+    let initial = builder.clone().build().unwrap();
+
+    // the sites gather around the point of interest
+    let weights = initial
+        .sites()
+        .iter()
+        .map(|site| if is_near_center(site) { 10.0 } else { 1.0 })
+        .collect::<Vec<_>>();
+    let weighted = builder
+        .clone()
+        .set_site_weights(Some(weights))
+        .relaxate_sites(5)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(weighted.num(), num);
+    let count_near = |sites: &[Site2D]| sites.iter().filter(|site| is_near_center(site)).count();
+    assert!(count_near(weighted.sites()) as f64 > count_near(initial.sites()) as f64 * 1.2);
+    assert!(weighted
+        .sites()
+        .iter()
+        .all(|site| site.x >= 0.0 && site.x <= 100.0 && site.y >= 0.0 && site.y <= 100.0));
+
+    assert!(matches!(
+        builder
+            .set_site_weights(Some(vec![1.0; num - 1]))
+            .relaxate_sites(1),
+        Err(ModelBuilderError::InvalidWeights)
+    ));
+}
+
+#[test]
+fn test_weighted_refinement() {
+    let num = 200;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let builder = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .add_edge_sites(None, None)
+        .unwrap();
+    let initial = builder.clone().build().unwrap();
+
+    // without the weights, the triangles are refined uniformly
+    let uniform = builder.clone().refine_sites(20.0).unwrap().build().unwrap();
+    assert!(uniform.num() > initial.num());
+
+    // the points of interest on the left side demand more sites
+    let weights = initial
+        .sites()
+        .iter()
+        .map(|site| if site.x < 50.0 { 8.0 } else { 1.0 })
+        .collect::<Vec<_>>();
+    let refined = builder
+        .set_site_weights(Some(weights))
+        .refine_sites(20.0)
+        .unwrap()
+        .build()
+        .unwrap();
+    let (left, right) = (
+        count_left(refined.sites()),
+        refined.num() - count_left(refined.sites()),
+    );
+    assert!(left > right * 3);
+}
@@ -0,0 +1,77 @@
+use fastlem::core::traits::Model;
+use fastlem::models::surface::wind::WindExposure2D;
+use fastlem::models::surface::{builder::TerrainModel2DBulider, sites::Site2D};
+extern crate fastlem;
+
+#[test]
+fn test_rain_shadow_behind_ridge() {
+    let num = 4000;
+    let bound_min = Site2D { x: 0.0, y: 0.0 };
+    let bound_max = Site2D { x: 100.0, y: 100.0 };
+    let model = TerrainModel2DBulider::from_random_sites(num, bound_min, bound_max)
+        .build()
+        .unwrap();
+
+    // a ridge of height 10 along x = 50 on a plain
+    let ridge = |site: &Site2D| (10.0 - (site.x - 50.0).abs()).max(0.0);
+    let elevations = model.sites().iter().map(ridge).collect::<Vec<_>>();
+    let terrain = model.create_terrain_from_result(&elevations);
+
+    // the wind blows from the west
+    let wind = WindExposure2D::default().set_shadow_height(5.0);
+    let exposures = wind.exposures(&terrain);
+    let shadows = wind.rain_shadows(&terrain);
+    assert_eq!(exposures.len(), num);
+    assert_eq!(shadows.len(), num);
+
+    // the orographic precipitation is reduced by the rain shadow
+    let base_precipitation = 1000.0;
+    let precipitation = shadows
+        .iter()
+        .map(|&shadow| base_precipitation * (1.0 - shadow))
+        .collect::<Vec<_>>();
+
+    let sites = model.sites();
+    let (mut windward, mut leeward) = (Vec::new(), Vec::new());
+    (0..num).for_each(|i| {
+        let x = sites[i].x;
+        if x > 42.0 && x < 48.0 && sites[i].y > 10.0 && sites[i].y < 90.0 {
+            windward.push(i);
+        } else if x > 55.0 && x < 65.0 && sites[i].y > 10.0 && sites[i].y < 90.0 {
+            leeward.push(i);
+        }
+    });
+    assert!(!windward.is_empty() && !leeward.is_empty());
+
+    // the windward slope faces the wind and gets the full precipitation
+    windward.iter().for_each(|&i| {
+        assert!(exposures[i] > 0.0);
+        assert!(shadows[i] < 1e-6);
+        assert!(precipitation[i] > base_precipitation * (1.0 - 1e-6));
+    });
+    // the leeward side is sheltered behind the ridge and gets less precipitation
+    leeward.iter().for_each(|&i| {
+        assert!(exposures[i] < 0.0);
+        assert!(shadows[i] > 0.0);
+        assert!(precipitation[i] < base_precipitation);
+    });
+    let mean =
+        |sites: &[usize]| sites.iter().map(|&i| precipitation[i]).sum::<f64>() / sites.len() as f64;
+    assert!(mean(&leeward) < mean(&windward) * 0.8);
+
+    // the shadow fades away far from the ridge
+    let far = (0..num)
+        .filter(|&i| sites[i].x > 90.0 && sites[i].y > 10.0 && sites[i].y < 90.0)
+        .collect::<Vec<_>>();
+    assert!(far.iter().all(|&i| shadows[i] < 1e-6));
+
+    // the wind from the east casts the shadow on the other side
+    let shadows = wind
+        .set_wind_direction(std::f64::consts::PI)
+        .rain_shadows(&terrain);
+    let mean_shadow =
+        |sites: &[usize]| sites.iter().map(|&i| shadows[i]).sum::<f64>() / sites.len() as f64;
+    windward.iter().for_each(|&i| assert!(shadows[i] > 0.0));
+    assert!(mean_shadow(&windward) > 0.3);
+    assert!(mean_shadow(&leeward) < 1e-3);
+}